@@ -0,0 +1,286 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use canopy::coverage::{CoverageCatalog, CoverageHits};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, smoke::SuiteResult};
+
+/// Covered and total counts for one kind of registered item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CoverageTotals {
+    /// Items exercised at least once.
+    pub covered: usize,
+    /// Items registered.
+    pub total: usize,
+}
+
+impl CoverageTotals {
+    /// Return the covered share as a percentage. An empty registry counts as fully covered.
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.covered as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    /// Format as `covered/total (percent%)`.
+    fn summary(&self) -> String {
+        format!("{}/{} ({:.1}%)", self.covered, self.total, self.percent())
+    }
+}
+
+/// Command coverage for one widget owner.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OwnerCoverage {
+    /// Widget owner, or the id prefix for free commands.
+    pub owner: String,
+    /// Covered and total command counts for this owner.
+    pub commands: CoverageTotals,
+    /// Ids of the owner's commands that never dispatched, sorted.
+    pub uncovered: Vec<String>,
+}
+
+/// A registered binding that never fired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct UncoveredBinding {
+    /// Stable binding name: scope, input and path filter.
+    pub key: String,
+    /// User-facing description.
+    pub description: String,
+    /// Whether the binding belongs to the framework rather than the application.
+    pub framework: bool,
+}
+
+/// Registered commands and bindings compared against what a suite exercised.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CoverageReport {
+    /// Command totals across every owner.
+    pub commands: CoverageTotals,
+    /// Binding totals.
+    pub bindings: CoverageTotals,
+    /// Per-owner command coverage, sorted by owner.
+    pub owners: Vec<OwnerCoverage>,
+    /// Bindings that never fired, sorted by key.
+    pub uncovered_bindings: Vec<UncoveredBinding>,
+}
+
+impl CoverageReport {
+    /// Compare a catalog against recorded hits. Hits for items missing from the catalog, such
+    /// as bindings a script registered itself, are ignored.
+    pub fn new(catalog: &CoverageCatalog, hits: &CoverageHits) -> Self {
+        let mut owners: BTreeMap<&str, OwnerCoverage> = BTreeMap::new();
+        for command in &catalog.commands {
+            let owner = owners
+                .entry(command.owner.as_str())
+                .or_insert_with(|| OwnerCoverage {
+                    owner: command.owner.clone(),
+                    commands: CoverageTotals::default(),
+                    uncovered: Vec::new(),
+                });
+            owner.commands.total += 1;
+            if hits.commands.contains_key(&command.id) {
+                owner.commands.covered += 1;
+            } else {
+                owner.uncovered.push(command.id.clone());
+            }
+        }
+        let owners: Vec<_> = owners.into_values().collect();
+        let commands = CoverageTotals {
+            covered: owners.iter().map(|owner| owner.commands.covered).sum(),
+            total: catalog.commands.len(),
+        };
+
+        let uncovered_bindings: Vec<_> = catalog
+            .bindings
+            .iter()
+            .filter(|binding| !hits.bindings.contains_key(&binding.key))
+            .map(|binding| UncoveredBinding {
+                key: binding.key.clone(),
+                description: binding.description.clone(),
+                framework: binding.framework,
+            })
+            .collect();
+        let bindings = CoverageTotals {
+            covered: catalog.bindings.len() - uncovered_bindings.len(),
+            total: catalog.bindings.len(),
+        };
+
+        Self {
+            commands,
+            bindings,
+            owners,
+            uncovered_bindings,
+        }
+    }
+
+    /// Compare a catalog against the hits merged from every script in a suite result.
+    pub fn from_suite(catalog: &CoverageCatalog, result: &SuiteResult) -> Self {
+        let mut hits = CoverageHits::default();
+        for script in &result.scripts {
+            if let Some(coverage) = &script.outcome.coverage {
+                hits.merge(coverage);
+            }
+        }
+        Self::new(catalog, &hits)
+    }
+
+    /// Render a human-readable table: one row per owner with its uncovered commands, then
+    /// totals and the bindings that never fired.
+    pub fn render_table(&self) -> String {
+        let width = self
+            .owners
+            .iter()
+            .map(|owner| owner.owner.len())
+            .chain(["OWNER".len()])
+            .max()
+            .unwrap_or_default();
+        let mut out = format!("{:<width$}  COMMANDS\n", "OWNER");
+        for owner in &self.owners {
+            out.push_str(&format!(
+                "{:<width$}  {}\n",
+                owner.owner,
+                owner.commands.summary()
+            ));
+            for id in &owner.uncovered {
+                out.push_str(&format!("{:<width$}    uncovered: {id}\n", ""));
+            }
+        }
+        out.push_str(&format!(
+            "\ncommands: {}\nbindings: {}\n",
+            self.commands.summary(),
+            self.bindings.summary()
+        ));
+        if !self.uncovered_bindings.is_empty() {
+            out.push_str("uncovered bindings:\n");
+            for binding in &self.uncovered_bindings {
+                let framework = if binding.framework {
+                    " [framework]"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "  {}  {}{framework}\n",
+                    binding.key, binding.description
+                ));
+            }
+        }
+        out
+    }
+
+    /// Write the report as pretty-printed JSON to `path`, creating parent directories.
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use canopy::coverage::{BindingEntry, BindingHit, CommandEntry};
+
+    use super::*;
+
+    fn catalog() -> CoverageCatalog {
+        let command = |id: &str, owner: &str| CommandEntry {
+            id: id.to_string(),
+            owner: owner.to_string(),
+        };
+        let binding = |key: &str, description: &str, framework: bool| BindingEntry {
+            key: key.to_string(),
+            description: description.to_string(),
+            target: "command".to_string(),
+            framework,
+        };
+        CoverageCatalog {
+            commands: vec![
+                command("editor::redo", "editor"),
+                command("editor::save", "editor"),
+                command("editor::undo", "editor"),
+                command("list::select", "list"),
+            ],
+            bindings: vec![
+                binding("default:s", "Save", false),
+                binding("exclusive(help):q", "Close help", true),
+            ],
+        }
+    }
+
+    fn hits(commands: &[&str], bindings: &[&str]) -> CoverageHits {
+        CoverageHits {
+            commands: commands.iter().map(|id| (id.to_string(), 1)).collect(),
+            bindings: bindings
+                .iter()
+                .map(|key| {
+                    (
+                        key.to_string(),
+                        BindingHit {
+                            description: String::new(),
+                            count: 1,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn report_groups_uncovered_commands_by_owner() {
+        let report = CoverageReport::new(
+            &catalog(),
+            &hits(
+                &["editor::save", "list::select", "other::gone"],
+                &["default:s"],
+            ),
+        );
+        assert_eq!(
+            report.commands,
+            CoverageTotals {
+                covered: 2,
+                total: 4
+            }
+        );
+        assert_eq!(report.owners.len(), 2);
+        assert_eq!(report.owners[0].owner, "editor");
+        assert_eq!(
+            report.owners[0].uncovered,
+            vec!["editor::redo".to_string(), "editor::undo".to_string()]
+        );
+        assert!(report.owners[1].uncovered.is_empty());
+        assert_eq!(
+            report.bindings,
+            CoverageTotals {
+                covered: 1,
+                total: 2
+            }
+        );
+        assert_eq!(report.uncovered_bindings.len(), 1);
+        assert_eq!(report.uncovered_bindings[0].key, "exclusive(help):q");
+    }
+
+    #[test]
+    fn table_lists_owners_totals_and_uncovered_bindings() {
+        let report = CoverageReport::new(&catalog(), &hits(&["editor::save"], &[]));
+        let table = report.render_table();
+        assert!(table.contains("editor  1/3 (33.3%)"));
+        assert!(table.contains("uncovered: editor::undo"));
+        assert!(table.contains("list    0/1 (0.0%)"));
+        assert!(table.contains("commands: 1/4 (25.0%)"));
+        assert!(table.contains("bindings: 0/2 (0.0%)"));
+        assert!(table.contains("exclusive(help):q  Close help [framework]"));
+    }
+
+    #[test]
+    fn empty_registries_count_as_covered() {
+        let report = CoverageReport::new(&CoverageCatalog::default(), &CoverageHits::default());
+        assert!((report.commands.percent() - 100.0).abs() < f64::EPSILON);
+        assert!(report.render_table().contains("commands: 0/0 (100.0%)"));
+    }
+}
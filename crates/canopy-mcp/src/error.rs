@@ -36,6 +36,20 @@ pub enum Error {
     /// A smoke suite did not resolve to any Luau scripts.
     #[error("no .luau scripts found under {0}")]
     NoScripts(PathBuf),
+    /// Filters removed every script from a smoke suite.
+    #[error("no .luau scripts under {suite} match {filters}")]
+    NoMatchingScripts {
+        /// Suite directory that was scanned.
+        suite: PathBuf,
+        /// The filters, comma-separated.
+        filters: String,
+    },
+    /// A resource URI that the app does not serve.
+    #[error("unknown resource: {0}")]
+    UnknownResource(String),
+    /// A fuzzing run was configured so that it cannot draw any action.
+    #[error("invalid fuzz configuration: {0}")]
+    Fuzz(String),
 }
 
 impl Error {
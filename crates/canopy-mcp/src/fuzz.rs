@@ -0,0 +1,918 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    result::Result as StdResult,
+    sync::Once,
+    time::Duration,
+};
+
+use canopy::{
+    Canopy,
+    commands::{CommandParamKind, CommandSpec},
+    error::{Error as CanopyError, Result as CanopyResult},
+    event::key::Key,
+    geom::Size,
+    recording::luau_string,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Error, Result,
+    script::{AppEvaluator, HeadlessSession, app_factory},
+};
+
+/// Default number of input sequences to run.
+const DEFAULT_ITERATIONS: usize = 100;
+/// Default number of actions in each input sequence.
+const DEFAULT_STEPS: usize = 50;
+/// Default time limit for one action in milliseconds.
+const DEFAULT_STEP_TIMEOUT_MS: u64 = 1000;
+/// Upper bound on replays spent shrinking one failure.
+const MAX_SHRINK_RUNS: usize = 500;
+/// Named keys drawn for random key input.
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Esc",
+    "Tab",
+    "BackTab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Space",
+    "F1",
+];
+/// Modifier prefixes drawn for random key input; the empty prefix is weighted up.
+const MODIFIERS: &[&str] = &["", "", "", "", "Ctrl+", "Alt+", "Shift+", "Ctrl+Alt+"];
+/// Text drawn for paste input, covering empty, multi-line, wide and control content.
+const PASTE_SAMPLES: &[&str] = &[
+    "",
+    "hello",
+    "   ",
+    "first line\nsecond line",
+    "tab\tseparated",
+    "日本語のテキスト",
+    "🙂🙃👍",
+    "\u{1b}[31mred\u{1b}[0m",
+    "a very long line of pasted text that is wider than most panes and keeps on going past the edge",
+];
+
+/// Relative weights of the action kinds the fuzzer draws.
+///
+/// A weight of zero disables an action kind. Binding and command actions are skipped whenever
+/// the focus has no usable binding or command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FuzzWeights {
+    /// Press a key that has an effective binding at the current focus.
+    pub binding: u32,
+    /// Press a random key, possibly with modifiers.
+    pub key: u32,
+    /// Click at a random screen position.
+    pub click: u32,
+    /// Scroll at a random screen position.
+    pub scroll: u32,
+    /// Paste a sample text into the focused node.
+    pub paste: u32,
+    /// Resize the screen to a random size.
+    pub resize: u32,
+    /// Dispatch a command that is available from the current focus and takes no arguments.
+    pub command: u32,
+}
+
+impl Default for FuzzWeights {
+    fn default() -> Self {
+        Self {
+            binding: 8,
+            key: 3,
+            click: 3,
+            scroll: 2,
+            paste: 1,
+            resize: 1,
+            command: 3,
+        }
+    }
+}
+
+impl FuzzWeights {
+    /// Return the sum of all weights.
+    fn total(&self) -> u64 {
+        [
+            self.binding,
+            self.key,
+            self.click,
+            self.scroll,
+            self.paste,
+            self.resize,
+            self.command,
+        ]
+        .into_iter()
+        .map(u64::from)
+        .sum()
+    }
+}
+
+/// Configuration for a fuzzing run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FuzzConfig {
+    /// Seed for the pseudo-random generator. The same seed and app replay the same run.
+    pub seed: u64,
+    /// Number of input sequences to run, each against a fresh app instance.
+    pub iterations: usize,
+    /// Number of actions in each input sequence.
+    pub steps: usize,
+    /// Optional fixture applied before each sequence.
+    pub fixture: Option<String>,
+    /// Time limit for one action in milliseconds.
+    pub step_timeout_ms: u64,
+    /// Command ids the fuzzer never dispatches, such as commands that launch external programs.
+    pub exclude_commands: Vec<String>,
+    /// Relative weights of the action kinds.
+    pub weights: FuzzWeights,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            iterations: DEFAULT_ITERATIONS,
+            steps: DEFAULT_STEPS,
+            fixture: None,
+            step_timeout_ms: DEFAULT_STEP_TIMEOUT_MS,
+            exclude_commands: Vec::new(),
+            weights: FuzzWeights::default(),
+        }
+    }
+}
+
+impl FuzzConfig {
+    /// Construct a config with a seed and default options.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+}
+
+/// One generated input action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FuzzAction {
+    /// A key press, as a key spec accepted by `canopy.send_key`.
+    Key {
+        /// Key spec.
+        key: String,
+    },
+    /// A left click at screen coordinates.
+    Click {
+        /// Column.
+        x: u32,
+        /// Row.
+        y: u32,
+    },
+    /// A wheel scroll at screen coordinates.
+    Scroll {
+        /// Scroll towards the top of the content when true.
+        up: bool,
+        /// Column.
+        x: u32,
+        /// Row.
+        y: u32,
+    },
+    /// A bracketed paste.
+    Paste {
+        /// Pasted text.
+        text: String,
+    },
+    /// A screen resize.
+    Resize {
+        /// New width in cells.
+        width: u32,
+        /// New height in cells.
+        height: u32,
+    },
+    /// A command dispatch by command id.
+    Command {
+        /// Command id such as `root::quit`.
+        name: String,
+    },
+}
+
+impl FuzzAction {
+    /// Return the Luau source that performs this action and renders its effect.
+    pub fn luau(&self) -> String {
+        let action = match self {
+            Self::Key { key } => format!("canopy.send_key({})", luau_string(key)),
+            Self::Click { x, y } => format!("canopy.send_click({x}, {y})"),
+            Self::Scroll { up, x, y } => {
+                let direction = if *up { "Up" } else { "Down" };
+                format!("canopy.send_scroll(\"{direction}\", {x}, {y})")
+            }
+            Self::Paste { text } => format!("canopy.send_paste({})", luau_string(text)),
+            Self::Resize { width, height } => format!("canopy.resize({width}, {height})"),
+            Self::Command { name } => format!("canopy.cmd({})", luau_string(name)),
+        };
+        format!("{action}\ncanopy.render()\n")
+    }
+}
+
+/// Category of a fuzzing failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The app panicked.
+    Panic,
+    /// A core tree invariant was violated.
+    Invariant,
+    /// An action returned an error.
+    Error,
+}
+
+impl FailureKind {
+    /// Return the stable lowercase name of this failure kind.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Panic => "panic",
+            Self::Invariant => "invariant",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A failing input sequence, shrunk to a minimal reproduction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FuzzFailure {
+    /// Zero-based iteration that found the failure.
+    pub iteration: usize,
+    /// Failure category.
+    pub kind: FailureKind,
+    /// Failure message of the shrunk sequence.
+    pub message: String,
+    /// Number of actions that ran before the original sequence failed, including the failing one.
+    pub original_steps: usize,
+    /// Shrunk action sequence that still fails with the same kind.
+    pub actions: Vec<FuzzAction>,
+    /// Luau script that replays the shrunk sequence.
+    pub repro: String,
+}
+
+/// Result of a fuzzing run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FuzzReport {
+    /// Seed the run started from.
+    pub seed: u64,
+    /// Number of sequences that ran, including a failing one.
+    pub iterations: usize,
+    /// Total number of actions that ran.
+    pub actions: usize,
+    /// The first failure found, if any.
+    pub failure: Option<FuzzFailure>,
+}
+
+impl FuzzReport {
+    /// Return true when no sequence failed.
+    pub fn success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Fuzz fresh headless app instances with random input sequences.
+pub fn run_fuzz(
+    factory: impl Fn() -> Result<Canopy> + Send + Sync + 'static,
+    config: &FuzzConfig,
+) -> Result<FuzzReport> {
+    AppEvaluator::new(app_factory(factory)).fuzz(config)
+}
+
+/// Run a fuzzing campaign and shrink the first failure it finds.
+pub(crate) fn run(evaluator: &AppEvaluator, config: &FuzzConfig) -> Result<FuzzReport> {
+    if config.weights.total() == 0 {
+        return Err(Error::Fuzz(
+            "at least one action weight must be non-zero".into(),
+        ));
+    }
+    let _quiet = QuietPanics::install();
+    let runner = Runner { evaluator, config };
+    let mut rng = Rng::new(config.seed);
+    let mut report = FuzzReport {
+        seed: config.seed,
+        iterations: 0,
+        actions: 0,
+        failure: None,
+    };
+    for iteration in 0..config.iterations {
+        report.iterations += 1;
+        let mut session = runner.session()?;
+        let mut size = evaluator.view_size();
+        let mut actions = Vec::new();
+        for _ in 0..config.steps {
+            let action = runner.choose(&mut rng, &session.canopy, size)?;
+            if let FuzzAction::Resize { width, height } = action {
+                size = Size::new(width, height);
+            }
+            let result = runner.step(&mut session, &action);
+            actions.push(action);
+            report.actions += 1;
+            if let Err(failure) = result {
+                report.failure = Some(runner.shrink(iteration, actions, failure)?);
+                return Ok(report);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Build the Luau repro script for a failing action sequence.
+pub fn repro_script(failure: &FuzzFailure, seed: u64, fixture: Option<&str>) -> String {
+    let mut out = format!(
+        "-- canopy fuzz repro: seed {seed}, iteration {}, {} of {} actions\n",
+        failure.iteration,
+        failure.actions.len(),
+        failure.original_steps
+    );
+    if let Some(fixture) = fixture {
+        out.push_str(&format!("-- fixture: {fixture}\n"));
+    }
+    let mut lines = failure.message.lines();
+    let first = lines.next().unwrap_or_default();
+    out.push_str(&format!("-- {}: {first}\n", failure.kind.as_str()));
+    for line in lines {
+        out.push_str(&format!("--   {line}\n"));
+    }
+    for action in &failure.actions {
+        out.push_str(&action.luau());
+    }
+    out
+}
+
+/// A failure observed while running one action.
+#[derive(Debug, Clone)]
+struct StepFailure {
+    /// Failure category.
+    kind: FailureKind,
+    /// Failure message.
+    message: String,
+}
+
+impl StepFailure {
+    /// Classify an error returned by an action.
+    ///
+    /// Invariant violations raised inside a script reach the host as script errors, so the
+    /// message is checked as well as the variant.
+    fn from_error(error: &CanopyError) -> Self {
+        let message = error.to_string();
+        let kind = if matches!(error, CanopyError::Invariant(_))
+            || message.contains("invariant violation")
+        {
+            FailureKind::Invariant
+        } else {
+            FailureKind::Error
+        };
+        Self { kind, message }
+    }
+
+    /// Describe a caught panic, with the location recorded by the quiet panic hook.
+    fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        let text = payload
+            .downcast_ref::<&str>()
+            .map(|text| (*text).to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "non-string panic payload".to_string());
+        let message = match PANIC_LOCATION.with(RefCell::take) {
+            Some(location) => format!("{text} at {location}"),
+            None => text,
+        };
+        Self {
+            kind: FailureKind::Panic,
+            message,
+        }
+    }
+}
+
+/// Action kinds weighted by [`FuzzWeights`].
+#[derive(Debug, Clone, Copy)]
+enum ActionKind {
+    /// A key with an effective binding.
+    Binding,
+    /// A random key.
+    Key,
+    /// A left click.
+    Click,
+    /// A wheel scroll.
+    Scroll,
+    /// A paste.
+    Paste,
+    /// A resize.
+    Resize,
+    /// A command dispatch.
+    Command,
+}
+
+/// Shared state for generating, running and replaying action sequences.
+struct Runner<'a> {
+    /// Evaluator that builds fresh app instances.
+    evaluator: &'a AppEvaluator,
+    /// Run configuration.
+    config: &'a FuzzConfig,
+}
+
+impl Runner<'_> {
+    /// Build a fresh headless session with the configured fixture applied.
+    fn session(&self) -> Result<HeadlessSession> {
+        HeadlessSession::new(self.evaluator, self.config.fixture.as_deref())
+    }
+
+    /// Run one action and render, catching errors and panics.
+    fn step(
+        &self,
+        session: &mut HeadlessSession,
+        action: &FuzzAction,
+    ) -> StdResult<(), StepFailure> {
+        let script = action.luau();
+        let timeout = Duration::from_millis(self.config.step_timeout_ms.max(1));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            session
+                .canopy
+                .eval_script_value_with_timeout(&script, timeout)?;
+            session.canopy.render(&mut session.backend)
+        }));
+        match result {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(error)) => Err(StepFailure::from_error(&error)),
+            Err(payload) => Err(StepFailure::from_panic(payload.as_ref())),
+        }
+    }
+
+    /// Replay an action sequence against a fresh app, returning the first failure and its index.
+    fn replay(&self, actions: &[FuzzAction]) -> Result<Option<(usize, StepFailure)>> {
+        let mut session = self.session()?;
+        for (index, action) in actions.iter().enumerate() {
+            if let Err(failure) = self.step(&mut session, action) {
+                return Ok(Some((index, failure)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Draw the next action from the app's current focus, bindings and commands.
+    ///
+    /// Binding and command actions are only drawn when the focus offers at least one; when
+    /// every drawable weight is zero the fuzzer falls back to a random key.
+    fn choose(&self, rng: &mut Rng, canopy: &Canopy, size: Size) -> Result<FuzzAction> {
+        let weights = self.config.weights;
+        let keys = binding_keys(canopy)?;
+        let commands = self.commands(canopy);
+        let choices = [
+            (
+                ActionKind::Binding,
+                if keys.is_empty() { 0 } else { weights.binding },
+            ),
+            (ActionKind::Key, weights.key),
+            (ActionKind::Click, weights.click),
+            (ActionKind::Scroll, weights.scroll),
+            (ActionKind::Paste, weights.paste),
+            (ActionKind::Resize, weights.resize),
+            (
+                ActionKind::Command,
+                if commands.is_empty() {
+                    0
+                } else {
+                    weights.command
+                },
+            ),
+        ];
+        let total: u64 = choices.iter().map(|(_, weight)| u64::from(*weight)).sum();
+        let mut roll = if total == 0 {
+            0
+        } else {
+            rng.next_u64() % total
+        };
+        let mut kind = ActionKind::Key;
+        for (choice, weight) in choices {
+            if roll < u64::from(weight) {
+                kind = choice;
+                break;
+            }
+            roll -= u64::from(weight);
+        }
+        let view_size = self.evaluator.view_size();
+        Ok(match kind {
+            ActionKind::Binding => FuzzAction::Key {
+                key: rng.pick(&keys).clone(),
+            },
+            ActionKind::Key => FuzzAction::Key {
+                key: random_key(rng),
+            },
+            ActionKind::Click => FuzzAction::Click {
+                x: rng.below(size.w),
+                y: rng.below(size.h),
+            },
+            ActionKind::Scroll => FuzzAction::Scroll {
+                up: rng.next_u64() % 2 == 0,
+                x: rng.below(size.w),
+                y: rng.below(size.h),
+            },
+            ActionKind::Paste => FuzzAction::Paste {
+                text: (*rng.pick(PASTE_SAMPLES)).to_string(),
+            },
+            ActionKind::Resize => FuzzAction::Resize {
+                width: 1 + rng.below(view_size.w + view_size.w / 2),
+                height: 1 + rng.below(view_size.h + view_size.h / 2),
+            },
+            ActionKind::Command => FuzzAction::Command {
+                name: rng.pick(&commands).clone(),
+            },
+        })
+    }
+
+    /// Return the ids of available commands that take no user arguments.
+    fn commands(&self, canopy: &Canopy) -> Vec<String> {
+        let mut commands: Vec<String> = canopy
+            .command_availability_from_focus()
+            .into_iter()
+            .filter(|availability| {
+                availability.resolution.is_some()
+                    && takes_no_arguments(availability.spec)
+                    && !self
+                        .config
+                        .exclude_commands
+                        .iter()
+                        .any(|name| name == availability.spec.id.0)
+            })
+            .map(|availability| availability.spec.id.0.to_string())
+            .collect();
+        commands.sort();
+        commands
+    }
+
+    /// Shrink a failing sequence by removing chunks of actions while it still fails the same way.
+    fn shrink(
+        &self,
+        iteration: usize,
+        mut actions: Vec<FuzzAction>,
+        mut failure: StepFailure,
+    ) -> Result<FuzzFailure> {
+        let original_steps = actions.len();
+        let mut chunk = actions.len() / 2;
+        let mut runs = 0;
+        while chunk > 0 && runs < MAX_SHRINK_RUNS {
+            let mut start = 0;
+            let mut removed = false;
+            while start < actions.len() && runs < MAX_SHRINK_RUNS {
+                let end = (start + chunk).min(actions.len());
+                let candidate: Vec<FuzzAction> = actions[..start]
+                    .iter()
+                    .chain(&actions[end..])
+                    .cloned()
+                    .collect();
+                if candidate.is_empty() {
+                    break;
+                }
+                runs += 1;
+                match self.replay(&candidate)? {
+                    Some((index, found)) if found.kind == failure.kind => {
+                        actions = candidate;
+                        actions.truncate(index + 1);
+                        failure = found;
+                        removed = true;
+                    }
+                    _ => start += chunk,
+                }
+            }
+            if !removed {
+                chunk /= 2;
+            }
+        }
+        let mut shrunk = FuzzFailure {
+            iteration,
+            kind: failure.kind,
+            message: failure.message,
+            original_steps,
+            actions,
+            repro: String::new(),
+        };
+        shrunk.repro = repro_script(&shrunk, self.config.seed, self.config.fixture.as_deref());
+        Ok(shrunk)
+    }
+}
+
+/// Return true when a command can be dispatched without arguments.
+fn takes_no_arguments(spec: &CommandSpec) -> bool {
+    spec.params
+        .iter()
+        .all(|param| param.kind == CommandParamKind::Injected || param.optional)
+}
+
+/// Return the key specs of the effective bindings at the current focus.
+///
+/// Keys whose printed spec does not parse back to the same key, such as `-`, are skipped
+/// because `canopy.send_key` could not replay them.
+fn binding_keys(canopy: &Canopy) -> CanopyResult<Vec<String>> {
+    Ok(canopy
+        .available_bindings(None)?
+        .bindings
+        .into_iter()
+        .map(|binding| binding.key)
+        .filter_map(|key| {
+            let spec = key.to_string();
+            (Key::parse_spec(&spec) == Ok(key)).then_some(spec)
+        })
+        .collect())
+}
+
+/// Draw a random key spec: a printable character or a named key, with optional modifiers.
+fn random_key(rng: &mut Rng) -> String {
+    let modifier = rng.pick(MODIFIERS);
+    let key = if rng.next_u64() % 2 == 0 {
+        let ch = char::from(b'!' + u8::try_from(rng.below(94)).unwrap_or(0));
+        if ch == '-' || ch == '+' {
+            "=".to_string()
+        } else {
+            ch.to_string()
+        }
+    } else {
+        (*rng.pick(NAMED_KEYS)).to_string()
+    };
+    format!("{modifier}{key}")
+}
+
+/// Small deterministic pseudo-random generator (SplitMix64).
+struct Rng {
+    /// Generator state.
+    state: u64,
+}
+
+impl Rng {
+    /// Seed a generator.
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Return the next pseudo-random value.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a value in `0..bound`, or zero for an empty range.
+    fn below(&mut self, bound: u32) -> u32 {
+        if bound == 0 {
+            return 0;
+        }
+        u32::try_from(self.next_u64() % u64::from(bound)).unwrap_or(0)
+    }
+
+    /// Pick one item from a non-empty slice.
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        let len = u64::try_from(items.len()).unwrap_or(u64::MAX);
+        let index = usize::try_from(self.next_u64() % len).unwrap_or(0);
+        &items[index]
+    }
+}
+
+thread_local! {
+    /// Location of the most recent panic caught on this thread while fuzzing.
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+    /// Whether a fuzzing run on this thread is quieting panic reports.
+    static FUZZING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Installs the fuzzing panic hook once per process.
+static QUIET_HOOK: Once = Once::new();
+
+/// Quiets panic reports on the current thread for the duration of a fuzzing run.
+///
+/// Shrinking replays a panic many times, so while a guard is alive the default hook's stderr
+/// report is replaced by one that only records the panic location for the failure message. The
+/// hook is installed once and passes panics on other threads, or outside a run, to the hook it
+/// replaced, so overlapping runs cannot leave it swallowing reports.
+struct QuietPanics {
+    /// Whether this thread was already fuzzing when the guard was created.
+    was_fuzzing: bool,
+}
+
+impl QuietPanics {
+    /// Quiet panic reports on this thread until the guard drops.
+    fn install() -> Self {
+        QUIET_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                if FUZZING.with(Cell::get) {
+                    let location = info.location().map(ToString::to_string);
+                    PANIC_LOCATION.with(|slot| *slot.borrow_mut() = location);
+                } else {
+                    previous(info);
+                }
+            }));
+        });
+        Self {
+            was_fuzzing: FUZZING.with(|fuzzing| fuzzing.replace(true)),
+        }
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        FUZZING.with(|fuzzing| fuzzing.set(self.was_fuzzing));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use canopy::{ViewContext, command, derive_commands, prelude::*};
+
+    use super::*;
+
+    struct FuzzTarget {
+        explosions: u32,
+    }
+
+    #[derive_commands]
+    impl FuzzTarget {
+        fn new() -> Self {
+            Self { explosions: 0 }
+        }
+
+        #[command]
+        fn tick(&mut self) {}
+
+        #[command]
+        fn explode(&mut self) -> error::Result<()> {
+            self.explosions += 1;
+            if self.explosions >= 3 {
+                return Err(CanopyError::Invalid("boom".into()));
+            }
+            Ok(())
+        }
+    }
+
+    impl Widget for FuzzTarget {
+        fn render(&mut self, _rndr: &mut Render, _ctx: &dyn ViewContext) -> CanopyResult<()> {
+            Ok(())
+        }
+
+        fn accept_focus(&self, _ctx: &dyn ViewContext) -> bool {
+            true
+        }
+
+        fn name(&self) -> NodeName {
+            NodeName::convert("fuzz_target")
+        }
+    }
+
+    impl Loader for FuzzTarget {
+        fn load(cnpy: &mut Canopy) -> CanopyResult<()> {
+            cnpy.add_commands::<Self>()
+        }
+    }
+
+    fn target_app() -> crate::Result<Canopy> {
+        let mut canopy = Canopy::new();
+        FuzzTarget::load(&mut canopy)?;
+        canopy.finalize_api()?;
+        canopy.replace_root(FuzzTarget::new())?;
+        Ok(canopy)
+    }
+
+    fn explode() -> FuzzAction {
+        FuzzAction::Command {
+            name: "fuzz_target::explode".into(),
+        }
+    }
+
+    #[test]
+    fn fuzz_runs_are_reproducible_from_a_seed() -> crate::Result<()> {
+        let mut config = FuzzConfig::new(7);
+        config.iterations = 3;
+        config.steps = 20;
+        config.exclude_commands = vec!["fuzz_target::explode".into()];
+
+        let first = run_fuzz(target_app, &config)?;
+        let second = run_fuzz(target_app, &config)?;
+        assert!(first.success(), "{first:?}");
+        assert_eq!(first.iterations, 3);
+        assert_eq!(first.actions, 60);
+        assert_eq!(first, second);
+        Ok(())
+    }
+
+    #[test]
+    fn fuzz_failures_shrink_to_a_minimal_repro() -> crate::Result<()> {
+        let mut config = FuzzConfig::new(11);
+        config.steps = 40;
+        config.weights = FuzzWeights {
+            binding: 0,
+            key: 2,
+            click: 1,
+            scroll: 1,
+            paste: 1,
+            resize: 1,
+            command: 4,
+        };
+
+        let report = run_fuzz(target_app, &config)?;
+        let failure = report.failure.expect("exploding command should fail");
+        assert_eq!(failure.kind, FailureKind::Error);
+        assert!(failure.message.contains("boom"), "{}", failure.message);
+        assert_eq!(failure.actions, vec![explode(), explode(), explode()]);
+        assert!(failure.original_steps >= 3);
+        let body: String = failure
+            .repro
+            .lines()
+            .filter(|line| !line.starts_with("--"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        assert_eq!(
+            body,
+            "canopy.cmd(\"fuzz_target::explode\")\ncanopy.render()\n".repeat(3)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fuzz_rejects_all_zero_weights() {
+        let mut config = FuzzConfig::new(1);
+        config.weights = FuzzWeights {
+            binding: 0,
+            key: 0,
+            click: 0,
+            scroll: 0,
+            paste: 0,
+            resize: 0,
+            command: 0,
+        };
+        let error = run_fuzz(target_app, &config).unwrap_err();
+        assert!(matches!(error, Error::Fuzz(_)), "{error}");
+    }
+
+    #[test]
+    fn actions_render_as_luau() {
+        let actions = [
+            FuzzAction::Key {
+                key: "Ctrl+s".into(),
+            },
+            FuzzAction::Scroll {
+                up: true,
+                x: 3,
+                y: 4,
+            },
+            FuzzAction::Paste {
+                text: "a \"quoted\"\nline".into(),
+            },
+            FuzzAction::Resize {
+                width: 10,
+                height: 2,
+            },
+        ];
+        let script: String = actions.iter().map(FuzzAction::luau).collect();
+        assert_eq!(
+            script,
+            "canopy.send_key(\"Ctrl+s\")\ncanopy.render()\n\
+             canopy.send_scroll(\"Up\", 3, 4)\ncanopy.render()\n\
+             canopy.send_paste(\"a \\\"quoted\\\"\\nline\")\ncanopy.render()\n\
+             canopy.resize(10, 2)\ncanopy.render()\n"
+        );
+    }
+
+    #[test]
+    fn caught_panics_report_payload_and_location() {
+        let _quiet = QuietPanics::install();
+        let payload = panic::catch_unwind(|| -> u32 { panic!("exploded") }).unwrap_err();
+        let failure = StepFailure::from_panic(payload.as_ref());
+        assert_eq!(failure.kind, FailureKind::Panic);
+        assert!(
+            failure.message.starts_with("exploded at "),
+            "{}",
+            failure.message
+        );
+        assert!(failure.message.contains("fuzz.rs"), "{}", failure.message);
+    }
+
+    #[test]
+    fn quiet_panics_nest_and_stay_on_their_thread() {
+        let outer = QuietPanics::install();
+        let inner = QuietPanics::install();
+        drop(inner);
+        assert!(FUZZING.with(Cell::get));
+        let elsewhere = thread::spawn(|| FUZZING.with(Cell::get)).join().unwrap();
+        assert!(!elsewhere);
+        drop(outer);
+        assert!(!FUZZING.with(Cell::get));
+    }
+}
@@ -3,10 +3,18 @@
 
 //! MCP and smoke-test helpers for canopy applications.
 
+/// Command and binding coverage reports for smoke suites.
+pub mod coverage;
 /// Error types shared across the automation helpers.
 pub mod error;
+/// Randomized input fuzzing with failure shrinking.
+pub mod fuzz;
 /// Shared executable launch harness for app binaries.
 pub mod launch;
+/// JUnit XML, TAP and JSON lines reports for smoke suites.
+pub mod report;
+/// Live app resources served over MCP: screen, focus, API and bindings.
+pub mod resources;
 /// Headless script-evaluation types and helpers.
 pub mod script;
 /// Stdio MCP server wrapper for script automation.
@@ -14,15 +22,25 @@ pub mod server;
 /// Smoke-suite discovery and execution helpers.
 pub mod smoke;
 
+pub use coverage::{CoverageReport, CoverageTotals, OwnerCoverage, UncoveredBinding};
 pub use error::{Error, Result};
+pub use fuzz::{
+    FailureKind, FuzzAction, FuzzConfig, FuzzFailure, FuzzReport, FuzzWeights, repro_script,
+    run_fuzz,
+};
 pub use launch::{LaunchMode, launch};
+pub use report::ReportFormat;
 pub use script::{
     AppEvaluator, BootstrapCommand, BootstrapJournalEntry, BootstrapResponse, ScriptErrorInfo,
     ScriptEvalOutcome, ScriptEvalRequest, ScriptTaskState, ScriptTiming, app_factory,
-    evaluate_live,
+    evaluate_live, evaluate_live_read_only,
+};
+pub use server::{
+    ApplyFixtureRequest, ClientRole, SessionRole, UdsServerHandle, json_tool_result, serve_stdio,
+    serve_uds,
 };
-pub use server::{ApplyFixtureRequest, UdsServerHandle, json_tool_result, serve_stdio, serve_uds};
 pub use smoke::{
-    ScriptResult, ScriptStatus, SuiteConfig, SuiteResult, discover_scripts, fixture_for_script,
-    run_suite,
+    ScriptQueue, ScriptResult, ScriptStatus, SuiteConfig, SuiteResult, discover_scripts,
+    fixture_for_script, run_suite, script_matches_filter, script_relative_name,
+    snapshot_dir_for_script,
 };
@@ -0,0 +1,349 @@
+use std::{fs, path::Path, result::Result as StdResult, str::FromStr};
+
+use canopy::{script::ScriptAssertion, text::xml_escape};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::{
+    Result,
+    script::{ScriptErrorInfo, ScriptTiming},
+    smoke::{ScriptResult, ScriptStatus, SuiteResult, script_relative_name},
+};
+
+/// Machine-readable formats for smoke-suite reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// JUnit XML, as read by CI test dashboards.
+    Junit,
+    /// Test Anything Protocol, version 13.
+    Tap,
+    /// One JSON object per script, one per line.
+    Jsonl,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> StdResult<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "junit" | "xml" => Ok(Self::Junit),
+            "tap" => Ok(Self::Tap),
+            "jsonl" | "json" => Ok(Self::Jsonl),
+            _ => Err(format!(
+                "unknown report format `{value}`; expected junit, tap or jsonl"
+            )),
+        }
+    }
+}
+
+impl ReportFormat {
+    /// Render a suite result, naming scripts by their path under `suite_dir`.
+    pub fn render(self, suite_dir: &Path, result: &SuiteResult) -> Result<String> {
+        match self {
+            Self::Junit => Ok(junit(suite_dir, result)),
+            Self::Tap => Ok(tap(suite_dir, result)),
+            Self::Jsonl => jsonl(suite_dir, result),
+        }
+    }
+
+    /// Render a suite result and write it to `path`, creating parent directories.
+    pub fn write(self, path: &Path, suite_dir: &Path, result: &SuiteResult) -> Result<()> {
+        let report = self.render(suite_dir, result)?;
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, report)?;
+        Ok(())
+    }
+}
+
+/// One JSON lines record.
+#[derive(Serialize)]
+struct JsonlRecord<'a> {
+    /// Script path relative to the suite root.
+    name: String,
+    /// Script path on disk.
+    path: &'a Path,
+    /// Fixture applied before the script, if any.
+    fixture: Option<&'a str>,
+    /// Pass or fail status.
+    status: ScriptStatus,
+    /// Total duration in milliseconds.
+    elapsed_ms: u64,
+    /// Build and execution timings.
+    timing: &'a ScriptTiming,
+    /// Summary failure message.
+    message: Option<&'a str>,
+    /// Assertions recorded by the script.
+    assertions: &'a [ScriptAssertion],
+    /// Log lines emitted by the script.
+    logs: &'a [String],
+    /// Error details when the script failed.
+    error: Option<&'a ScriptErrorInfo>,
+}
+
+/// Render one JSON object per script.
+fn jsonl(suite_dir: &Path, result: &SuiteResult) -> Result<String> {
+    let mut out = String::new();
+    for script in &result.scripts {
+        let record = JsonlRecord {
+            name: script_relative_name(suite_dir, &script.path),
+            path: &script.path,
+            fixture: script.fixture.as_deref(),
+            status: script.status,
+            elapsed_ms: script.elapsed_ms,
+            timing: &script.outcome.timing,
+            message: script.message.as_deref(),
+            assertions: &script.outcome.assertions,
+            logs: &script.outcome.logs,
+            error: script.outcome.error.as_ref(),
+        };
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Render a TAP version 13 stream with a YAML diagnostic block for each failure.
+fn tap(suite_dir: &Path, result: &SuiteResult) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n", result.scripts.len());
+    for (index, script) in result.scripts.iter().enumerate() {
+        let name = script_relative_name(suite_dir, &script.path);
+        let number = index + 1;
+        if script.status == ScriptStatus::Passed {
+            out.push_str(&format!("ok {number} - {name}\n"));
+            continue;
+        }
+        out.push_str(&format!("not ok {number} - {name}\n  ---\n"));
+        // JSON strings are valid YAML scalars and need no further escaping.
+        if let Some(message) = &script.message {
+            out.push_str(&format!("  message: {}\n", json_string(message)));
+        }
+        if let Some(fixture) = &script.fixture {
+            out.push_str(&format!("  fixture: {}\n", json_string(fixture)));
+        }
+        out.push_str(&format!("  elapsed_ms: {}\n", script.elapsed_ms));
+        let failed: Vec<_> = failed_assertions(script).collect();
+        if !failed.is_empty() {
+            out.push_str("  failed_assertions:\n");
+            for message in failed {
+                out.push_str(&format!("    - {}\n", json_string(message)));
+            }
+        }
+        if !script.outcome.logs.is_empty() {
+            out.push_str("  logs:\n");
+            for line in &script.outcome.logs {
+                out.push_str(&format!("    - {}\n", json_string(line)));
+            }
+        }
+        out.push_str("  ...\n");
+    }
+    out
+}
+
+/// Render a JUnit XML document with one test case per script, classed by fixture.
+fn junit(suite_dir: &Path, result: &SuiteResult) -> String {
+    let failures = result
+        .scripts
+        .iter()
+        .filter(|script| script.status == ScriptStatus::Failed)
+        .count();
+    let total_ms = result.scripts.iter().map(|script| script.elapsed_ms).sum();
+    let suite_name = suite_dir.file_name().map_or_else(
+        || "smoke".to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    let counts = format!(
+        "tests=\"{}\" failures=\"{failures}\" errors=\"0\" time=\"{}\"",
+        result.scripts.len(),
+        seconds(total_ms)
+    );
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"{}\" {counts}>\n",
+        xml_escape(&suite_name)
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"{}\" {counts}>\n",
+        xml_escape(&suite_name)
+    ));
+    for script in &result.scripts {
+        let name = script_relative_name(suite_dir, &script.path);
+        let class = script.fixture.as_deref().unwrap_or(&suite_name);
+        out.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\" time=\"{}\">\n",
+            xml_escape(class),
+            xml_escape(&name),
+            seconds(script.elapsed_ms)
+        ));
+        if script.status == ScriptStatus::Failed {
+            let message = script.message.as_deref().unwrap_or("script failed");
+            let kind = script
+                .outcome
+                .error
+                .as_ref()
+                .map_or("assertion", |error| error.error_type.as_str());
+            let mut details = vec![message];
+            details.extend(failed_assertions(script).filter(|failed| *failed != message));
+            out.push_str(&format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                xml_escape(message),
+                xml_escape(kind),
+                xml_escape(&details.join("\n"))
+            ));
+        }
+        if !script.outcome.logs.is_empty() {
+            out.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&script.outcome.logs.join("\n"))
+            ));
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+/// Iterate over the messages of a script's failed assertions.
+fn failed_assertions(script: &ScriptResult) -> impl Iterator<Item = &str> {
+    script
+        .outcome
+        .assertions
+        .iter()
+        .filter(|assertion| !assertion.passed)
+        .map(|assertion| assertion.message.as_str())
+}
+
+/// Format milliseconds as fractional seconds.
+fn seconds(ms: u64) -> String {
+    format!("{}.{:03}", ms / 1000, ms % 1000)
+}
+
+/// Quote a string as JSON.
+fn json_string(value: &str) -> String {
+    JsonValue::from(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::script::{ScriptEvalOutcome, ScriptTaskState};
+
+    fn passed(path: &str, fixture: Option<&str>) -> ScriptResult {
+        ScriptResult::from_outcome(
+            PathBuf::from(path),
+            fixture.map(str::to_string),
+            ScriptEvalOutcome {
+                success: true,
+                state: ScriptTaskState::Completed,
+                value: None,
+                logs: vec!["ready".to_string()],
+                assertions: vec![ScriptAssertion {
+                    passed: true,
+                    message: "list renders".to_string(),
+                }],
+                diagnostics: Vec::new(),
+                timing: ScriptTiming {
+                    build_ms: 2,
+                    exec_ms: 10,
+                    total_ms: 12,
+                },
+                error: None,
+                coverage: None,
+            },
+        )
+    }
+
+    fn failed(path: &str) -> ScriptResult {
+        let mut outcome = ScriptEvalOutcome::error_only(
+            "runtime",
+            "expected <3> & got \"2\"",
+            Vec::new(),
+            ScriptTiming {
+                build_ms: 1,
+                exec_ms: 1_500,
+                total_ms: 1_501,
+            },
+        );
+        outcome.logs = vec!["step 1".to_string()];
+        ScriptResult::from_outcome(PathBuf::from(path), None, outcome)
+    }
+
+    fn suite() -> SuiteResult {
+        SuiteResult {
+            scripts: vec![
+                passed(
+                    "/suite/smoke/with_items/navigation.luau",
+                    Some("with_items"),
+                ),
+                failed("/suite/smoke/bootstrap.luau"),
+            ],
+        }
+    }
+
+    #[test]
+    fn format_names_parse() {
+        assert_eq!("JUnit".parse::<ReportFormat>(), Ok(ReportFormat::Junit));
+        assert_eq!("tap".parse::<ReportFormat>(), Ok(ReportFormat::Tap));
+        assert_eq!("jsonl".parse::<ReportFormat>(), Ok(ReportFormat::Jsonl));
+        assert!("html".parse::<ReportFormat>().is_err());
+    }
+
+    #[test]
+    fn junit_reports_escaped_failures_classed_by_fixture() -> Result<()> {
+        let report = ReportFormat::Junit.render(Path::new("/suite/smoke"), &suite())?;
+        assert!(report.contains(
+            "<testsuite name=\"smoke\" tests=\"2\" failures=\"1\" errors=\"0\" time=\"1.513\">"
+        ));
+        assert!(report.contains(
+            "<testcase classname=\"with_items\" name=\"with_items/navigation.luau\" time=\"0.012\">"
+        ));
+        assert!(report.contains(
+            "<failure message=\"expected &lt;3&gt; &amp; got &quot;2&quot;\" type=\"runtime\">"
+        ));
+        assert!(report.contains("<system-out>step 1</system-out>"));
+        Ok(())
+    }
+
+    #[test]
+    fn tap_marks_failures_with_yaml_diagnostics() -> Result<()> {
+        let report = ReportFormat::Tap.render(Path::new("/suite/smoke"), &suite())?;
+        assert_eq!(
+            report,
+            "TAP version 13\n\
+             1..2\n\
+             ok 1 - with_items/navigation.luau\n\
+             not ok 2 - bootstrap.luau\n  \
+             ---\n  \
+             message: \"expected <3> & got \\\"2\\\"\"\n  \
+             elapsed_ms: 1501\n  \
+             logs:\n    \
+             - \"step 1\"\n  \
+             ...\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn jsonl_writes_one_record_per_script() -> Result<()> {
+        let report = ReportFormat::Jsonl.render(Path::new("/suite/smoke"), &suite())?;
+        let records: Vec<JsonValue> = report
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<serde_json::Result<_>>()?;
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["name"], "with_items/navigation.luau");
+        assert_eq!(records[0]["status"], "Passed");
+        assert_eq!(records[0]["timing"]["exec_ms"], 10);
+        assert_eq!(records[0]["assertions"][0]["message"], "list renders");
+        assert_eq!(records[1]["fixture"], JsonValue::Null);
+        assert_eq!(records[1]["error"]["type"], "runtime");
+        assert_eq!(records[1]["logs"][0], "step 1");
+        Ok(())
+    }
+}
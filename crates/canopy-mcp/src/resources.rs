@@ -0,0 +1,251 @@
+use canopy::{
+    BindingOwner, BindingPhase, Canopy, commands::ArgValue, help::BindingSnapshot,
+    observe::AppChange,
+};
+use serde::Serialize;
+use serde_json::{Value as JsonValue, json};
+
+use crate::{Error, Result};
+
+/// URI of the rendered screen text.
+pub const SCREEN_URI: &str = "canopy://screen";
+/// URI of the focused node, its path and the active input modes.
+pub const FOCUS_URI: &str = "canopy://focus";
+/// URI of the generated Luau API definition.
+pub const API_URI: &str = "canopy://api";
+/// URI of the effective key bindings at the current focus.
+pub const BINDINGS_URI: &str = "canopy://bindings";
+
+/// Description of one resource a live app serves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ResourceInfo {
+    /// Resource URI.
+    pub uri: &'static str,
+    /// Short resource name.
+    pub name: &'static str,
+    /// What the resource holds and when it changes.
+    pub description: &'static str,
+    /// MIME type of the resource text.
+    pub mime_type: &'static str,
+}
+
+/// Every resource a live app serves, in listing order.
+pub const RESOURCES: [ResourceInfo; 4] = [
+    ResourceInfo {
+        uri: SCREEN_URI,
+        name: "screen",
+        description: "Text of the most recently rendered frame. Updated after every frame.",
+        mime_type: "text/plain",
+    },
+    ResourceInfo {
+        uri: FOCUS_URI,
+        name: "focus",
+        description: "Focused node, its path and the active input modes. Updated when focus or \
+            mode changes.",
+        mime_type: "application/json",
+    },
+    ResourceInfo {
+        uri: API_URI,
+        name: "api",
+        description: "Generated Luau API definition for script_eval.",
+        mime_type: "text/x-luau",
+    },
+    ResourceInfo {
+        uri: BINDINGS_URI,
+        name: "bindings",
+        description: "Effective key bindings at the current focus. Updated when focus or mode \
+            changes.",
+        mime_type: "application/json",
+    },
+];
+
+/// Return the description of a resource URI, if the app serves it.
+pub fn resource_info(uri: &str) -> Option<&'static ResourceInfo> {
+    RESOURCES.iter().find(|resource| resource.uri == uri)
+}
+
+/// Read a resource's current text from a live app.
+pub fn read_resource(canopy: &mut Canopy, uri: &str) -> Result<String> {
+    match uri {
+        SCREEN_URI => Ok(canopy
+            .buf()
+            .map(|buf| buf.screen_text())
+            .unwrap_or_default()),
+        FOCUS_URI => {
+            let snapshot = canopy.available_bindings(None)?;
+            Ok(focus_json(canopy, &snapshot).to_string())
+        }
+        API_URI => {
+            canopy.finalize_api()?;
+            Ok(canopy.script_api()?.to_string())
+        }
+        BINDINGS_URI => {
+            let snapshot = canopy.available_bindings(None)?;
+            Ok(bindings_json(&snapshot).to_string())
+        }
+        _ => Err(Error::UnknownResource(uri.to_string())),
+    }
+}
+
+/// Return the resources a change makes stale.
+pub fn changed_resources(change: &AppChange) -> &'static [&'static str] {
+    match change {
+        AppChange::Frame => &[SCREEN_URI],
+        AppChange::Focus(_) | AppChange::Mode(_) => &[FOCUS_URI, BINDINGS_URI],
+        AppChange::Bindings => &[BINDINGS_URI],
+        AppChange::Api => &[API_URI],
+    }
+}
+
+/// Encode the focus resource.
+///
+/// The node uses the same encoding as node values returned from `script_eval`.
+fn focus_json(canopy: &Canopy, snapshot: &BindingSnapshot) -> JsonValue {
+    let node = canopy
+        .focus_id()
+        .and_then(|node| ArgValue::Node(node).to_external_json_value().ok());
+    json!({
+        "node": node,
+        "path": snapshot.focus_path.to_string(),
+        "mode": canopy.input_mode(),
+        "active_modes": snapshot.active_modes,
+    })
+}
+
+/// Encode the bindings resource with the record fields `canopy.available_bindings()` uses.
+fn bindings_json(snapshot: &BindingSnapshot) -> JsonValue {
+    let bindings: Vec<_> = snapshot
+        .bindings
+        .iter()
+        .map(|binding| {
+            json!({
+                "id": binding.id.as_u64(),
+                "input": binding.key.to_string(),
+                "description": binding.description,
+                "owner": match binding.owner {
+                    BindingOwner::Application => "application".to_string(),
+                    BindingOwner::Framework(group) => format!("framework:{group}"),
+                },
+                "scope": binding.scope.label(),
+                "mode": binding.scope.mode(),
+                "path": binding.path_filter,
+                "route_path": binding.route_path.to_string(),
+                "phase": match binding.phase {
+                    BindingPhase::BeforeWidget => "before_widget",
+                    BindingPhase::AfterIgnore => "after_ignore",
+                },
+            })
+        })
+        .collect();
+    json!({
+        "focus_path": snapshot.focus_path.to_string(),
+        "active_modes": snapshot.active_modes,
+        "exclusive_group": snapshot.exclusive_group.map(|group| group.as_str()),
+        "bindings": bindings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use canopy::{
+        ViewContext, command, derive_commands, error::Result as CanopyResult, geom::Line,
+        prelude::*, render::NopBackend,
+    };
+
+    use super::*;
+    use crate::{ScriptEvalRequest, evaluate_live};
+
+    struct Banner;
+
+    #[derive_commands]
+    impl Banner {
+        #[command]
+        fn noop(&self) {}
+    }
+
+    impl Widget for Banner {
+        fn render(&mut self, frame: &mut Render, _ctx: &dyn ViewContext) -> CanopyResult<()> {
+            frame.text("default", Line::new(0, 0, 6), "banner")?;
+            Ok(())
+        }
+
+        fn name(&self) -> NodeName {
+            NodeName::convert("banner")
+        }
+    }
+
+    impl Loader for Banner {
+        fn load(cnpy: &mut Canopy) -> CanopyResult<()> {
+            cnpy.add_commands::<Self>()
+        }
+    }
+
+    fn rendered() -> crate::Result<Canopy> {
+        let mut canopy = Canopy::new();
+        Banner::load(&mut canopy)?;
+        canopy.finalize_api()?;
+        canopy.replace_root(Banner)?;
+        canopy.eval_script(r#"canopy.bind("q", { description = "Quit" }, function() end)"#)?;
+        canopy.set_root_size(Size::new(8, 1))?;
+        canopy.render(&mut NopBackend::new())?;
+        Ok(canopy)
+    }
+
+    #[test]
+    fn reads_every_listed_resource() -> crate::Result<()> {
+        let mut canopy = rendered()?;
+        assert!(read_resource(&mut canopy, SCREEN_URI)?.starts_with("banner"));
+        assert!(read_resource(&mut canopy, API_URI)?.contains("declare banner"));
+
+        let focus: JsonValue = serde_json::from_str(&read_resource(&mut canopy, FOCUS_URI)?)?;
+        assert_eq!(focus["mode"], "default");
+        let focused = evaluate_live(
+            &mut canopy,
+            &ScriptEvalRequest {
+                script: "return canopy.focused()".to_string(),
+                fixture: None,
+                timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
+            },
+        );
+        assert_eq!(Some(&focus["node"]), focused.value.as_ref());
+
+        let bindings: JsonValue = serde_json::from_str(&read_resource(&mut canopy, BINDINGS_URI)?)?;
+        let quit = bindings["bindings"]
+            .as_array()
+            .and_then(|bindings| {
+                bindings
+                    .iter()
+                    .find(|binding| binding["description"] == "Quit")
+            })
+            .expect("quit binding listed");
+        assert_eq!(quit["input"], "q");
+        assert_eq!(quit["owner"], "application");
+
+        for resource in RESOURCES {
+            assert_eq!(resource_info(resource.uri), Some(&resource));
+        }
+        assert!(matches!(
+            read_resource(&mut canopy, "canopy://nope"),
+            Err(Error::UnknownResource(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn changes_map_to_stale_resources() {
+        assert_eq!(changed_resources(&AppChange::Frame), &[SCREEN_URI]);
+        assert_eq!(
+            changed_resources(&AppChange::Mode("insert".to_string())),
+            &[FOCUS_URI, BINDINGS_URI]
+        );
+        assert_eq!(
+            changed_resources(&AppChange::Focus(None)),
+            &[FOCUS_URI, BINDINGS_URI]
+        );
+        assert_eq!(changed_resources(&AppChange::Bindings), &[BINDINGS_URI]);
+        assert_eq!(changed_resources(&AppChange::Api), &[API_URI]);
+    }
+}
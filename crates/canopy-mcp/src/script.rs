@@ -4,19 +4,24 @@ use std::{
 };
 
 use canopy::{
-    Canopy, FixtureInfo,
+    Canopy, FixtureInfo, VirtualClock,
     commands::{ArgValue, CommandDispatchKind, CommandResolution},
+    coverage::{CoverageCatalog, CoverageHits},
     error::{Error as CanopyError, ScriptErrorKind},
     geom::Size,
     render::NopBackend,
     script::{ScriptAssertion, ScriptCheckDiagnostic},
+    snapshot::SnapshotConfig,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use tmcp::{TOOL_ERROR_INTERNAL, schema::CallToolResult, tool_params};
 
-use crate::Result;
+use crate::{
+    Result,
+    fuzz::{self, FuzzConfig, FuzzReport},
+};
 
 /// Shared application factory used by the automation helpers.
 pub type AppFactory = Arc<dyn Fn() -> Result<Canopy> + Send + Sync>;
@@ -50,6 +55,15 @@ pub struct ScriptEvalRequest {
     /// Optional evaluation timeout in milliseconds.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_ms: Option<u64>,
+    /// Optional directory holding the files read by `canopy.assert_snapshot`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_dir: Option<String>,
+    /// Optionally record missing snapshots and rewrite changed ones instead of failing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_snapshots: Option<bool>,
+    /// Optionally record the commands and bindings the script exercises.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<bool>,
 }
 
 /// Timing information for a script evaluation.
@@ -130,6 +144,9 @@ pub struct ScriptEvalOutcome {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Error payload when evaluation fails.
     pub error: Option<ScriptErrorInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Commands and bindings exercised, when the request asked for coverage.
+    pub coverage: Option<CoverageHits>,
 }
 
 /// Compact command availability record returned by bootstrap.
@@ -223,6 +240,7 @@ impl ScriptEvalOutcome {
                 owner: None,
                 message: message.into(),
             }),
+            coverage: None,
         }
     }
 }
@@ -234,6 +252,8 @@ pub struct AppEvaluator {
     factory: AppFactory,
     /// Headless viewport used during rendering and event simulation.
     view_size: Size,
+    /// Whether each app instance runs poll timing on a virtual clock.
+    virtual_clock: bool,
 }
 
 impl AppEvaluator {
@@ -242,6 +262,7 @@ impl AppEvaluator {
         Self {
             factory,
             view_size: DEFAULT_VIEW_SIZE,
+            virtual_clock: false,
         }
     }
 
@@ -251,6 +272,20 @@ impl AppEvaluator {
         self
     }
 
+    /// Run each app instance on a virtual clock, so polls fire only as scripts advance time.
+    ///
+    /// Scripts move time with `canopy.advance_time`, and `canopy.wait_for*` advance it instead
+    /// of sleeping.
+    pub fn with_virtual_clock(mut self) -> Self {
+        self.virtual_clock = true;
+        self
+    }
+
+    /// Return the headless viewport size.
+    pub(crate) fn view_size(&self) -> Size {
+        self.view_size
+    }
+
     /// Render and return the app's Luau API definition.
     pub fn script_api(&self) -> Result<String> {
         let mut canopy = (self.factory)()?;
@@ -258,6 +293,13 @@ impl AppEvaluator {
         Ok(canopy.script_api()?.to_string())
     }
 
+    /// Return every command and binding a fresh app instance registers.
+    pub fn coverage_catalog(&self) -> Result<CoverageCatalog> {
+        let mut canopy = (self.factory)()?;
+        canopy.finalize_api()?;
+        Ok(canopy.coverage_catalog())
+    }
+
     /// Return the evaluator's registered fixture catalog.
     pub fn fixtures(&self) -> Result<Vec<FixtureInfo>> {
         let canopy = (self.factory)()?;
@@ -266,29 +308,33 @@ impl AppEvaluator {
 
     /// Return bootstrap information for a fresh headless app instance.
     pub fn bootstrap(&self) -> Result<BootstrapResponse> {
-        let mut session = HeadlessSession::new(&self.factory, self.view_size, None)?;
+        let mut session = HeadlessSession::new(self, None)?;
         bootstrap_for_canopy(&mut session.canopy)
     }
 
     /// Evaluate a Luau script against a fresh headless app.
     pub fn evaluate(&self, request: &ScriptEvalRequest) -> ScriptEvalOutcome {
         let total_start = Instant::now();
-        let mut session =
-            match HeadlessSession::new(&self.factory, self.view_size, request.fixture.as_deref()) {
-                Ok(session) => session,
-                Err(error) => {
-                    return ScriptEvalOutcome::error_only(
-                        "build",
-                        error.to_string(),
-                        Vec::new(),
-                        ScriptTiming::zero(),
-                    );
-                }
-            };
+        let mut session = match HeadlessSession::new(self, request.fixture.as_deref()) {
+            Ok(session) => session,
+            Err(error) => {
+                return ScriptEvalOutcome::error_only(
+                    "build",
+                    error.to_string(),
+                    Vec::new(),
+                    ScriptTiming::zero(),
+                );
+            }
+        };
         let build_ms = total_start.elapsed().as_millis() as u64;
         let HeadlessSession { canopy, backend } = &mut session;
         evaluate_in(canopy, request, build_ms, total_start, Some(backend))
     }
+
+    /// Fuzz fresh headless app instances with random input sequences.
+    pub fn fuzz(&self, config: &FuzzConfig) -> Result<FuzzReport> {
+        fuzz::run(self, config)
+    }
 }
 
 /// Build a bootstrap payload from a finalized app.
@@ -369,6 +415,33 @@ pub fn evaluate_live(canopy: &mut Canopy, request: &ScriptEvalRequest) -> Script
     evaluate_in(canopy, request, 0, Instant::now(), None)
 }
 
+/// Evaluate a script against a running app on behalf of a read-only observer.
+///
+/// Inspection APIs work as usual, while APIs that mutate the app fail with a `read_only`
+/// error. Snapshot settings belong to the app, so requests that change them are refused, and
+/// snapshot checks compare without writing files even when the app is in update mode.
+pub fn evaluate_live_read_only(
+    canopy: &mut Canopy,
+    request: &ScriptEvalRequest,
+) -> ScriptEvalOutcome {
+    if request.snapshot_dir.is_some() || request.update_snapshots.is_some() {
+        return ScriptEvalOutcome::error_only(
+            "invalid",
+            "read-only sessions cannot change snapshot settings",
+            Vec::new(),
+            ScriptTiming::zero(),
+        );
+    }
+    let request = ScriptEvalRequest {
+        update_snapshots: Some(false),
+        ..request.clone()
+    };
+    canopy.set_script_read_only(true);
+    let outcome = evaluate_live(canopy, &request);
+    canopy.set_script_read_only(false);
+    outcome
+}
+
 /// Typecheck, evaluate, and report one script against an already-built canopy app.
 ///
 /// `render` is supplied for headless evaluation, where nothing else drives the screen after the
@@ -393,6 +466,11 @@ fn evaluate_in(
         TypecheckGate::Failed(outcome) => return *outcome,
     };
 
+    let previous_snapshots = apply_snapshot_options(canopy, request);
+    let record_coverage = request.coverage == Some(true);
+    if record_coverage {
+        canopy.start_coverage();
+    }
     let exec_start = Instant::now();
     let eval_result =
         eval_script_value(canopy, &request.script, request.timeout_ms).and_then(|value| {
@@ -407,10 +485,18 @@ fn evaluate_in(
         exec_ms,
         total_ms: total_start.elapsed().as_millis() as u64,
     };
+    if let Some(previous) = previous_snapshots {
+        canopy.set_snapshot_config(previous);
+    }
     let logs = canopy.take_script_logs();
     let assertions = canopy.take_script_assertions();
+    let coverage = if record_coverage {
+        canopy.take_coverage()
+    } else {
+        None
+    };
 
-    match eval_result {
+    let mut outcome = match eval_result {
         Ok(value) => ScriptEvalOutcome {
             success: true,
             state: ScriptTaskState::Completed,
@@ -420,28 +506,57 @@ fn evaluate_in(
             diagnostics,
             timing,
             error: None,
+            coverage: None,
         },
         Err(error) => failure_with_logs(&error, logs, assertions, diagnostics, timing),
+    };
+    outcome.coverage = coverage;
+    outcome
+}
+
+/// Apply a request's snapshot directory and update mode over the app's snapshot configuration.
+///
+/// Returns the configuration to restore once the evaluation finishes, so a live app keeps its
+/// own settings for later requests.
+fn apply_snapshot_options(
+    canopy: &mut Canopy,
+    request: &ScriptEvalRequest,
+) -> Option<SnapshotConfig> {
+    if request.snapshot_dir.is_none() && request.update_snapshots.is_none() {
+        return None;
+    }
+    let previous = canopy.snapshot_config();
+    let mut config = previous.clone();
+    if let Some(dir) = &request.snapshot_dir {
+        config = config.with_dir(dir);
+    }
+    if let Some(update) = request.update_snapshots {
+        config = config.with_update(update);
     }
+    canopy.set_snapshot_config(config);
+    Some(previous)
 }
 
 /// Headless canopy session used while evaluating one script request.
-struct HeadlessSession {
+pub(crate) struct HeadlessSession {
     /// The app instance under test.
-    canopy: Canopy,
+    pub(crate) canopy: Canopy,
     /// No-op renderer used to drive layout and event dispatch.
-    backend: NopBackend,
+    pub(crate) backend: NopBackend,
 }
 
 impl HeadlessSession {
     /// Build and render a fresh headless canopy session.
-    fn new(factory: &AppFactory, view_size: Size, fixture: Option<&str>) -> Result<Self> {
-        let mut canopy = factory()?;
+    pub(crate) fn new(evaluator: &AppEvaluator, fixture: Option<&str>) -> Result<Self> {
+        let mut canopy = (evaluator.factory)()?;
+        if evaluator.virtual_clock {
+            canopy.use_virtual_clock(VirtualClock::new());
+        }
         canopy.finalize_api()?;
         if let Some(fixture) = fixture {
             canopy.apply_fixture(fixture)?;
         }
-        canopy.set_root_size(view_size)?;
+        canopy.set_root_size(evaluator.view_size)?;
         let mut backend = NopBackend::new();
         canopy.render(&mut backend)?;
         Ok(Self { canopy, backend })
@@ -543,6 +658,7 @@ fn failure_with_logs(
         diagnostics,
         timing,
         error: Some(info),
+        coverage: None,
     }
 }
 
@@ -709,6 +825,9 @@ declare script_target: {
             .to_string(),
             fixture: None,
             timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
         assert!(outcome.success);
         assert_eq!(outcome.state, ScriptTaskState::Completed);
@@ -716,6 +835,71 @@ declare script_target: {
         assert_eq!(outcome.value, Some(JsonValue::from(7)));
     }
 
+    #[test]
+    fn evaluate_records_coverage_on_request() -> crate::Result<()> {
+        let evaluator = AppEvaluator::new(test_factory());
+        let request = ScriptEvalRequest {
+            script: "script_target.set(3) script_target.set(4)".to_string(),
+            fixture: None,
+            timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: Some(true),
+        };
+        let outcome = evaluator.evaluate(&request);
+        assert!(outcome.success);
+        let coverage = outcome.coverage.expect("coverage requested");
+        assert_eq!(coverage.commands.get("script_target::set"), Some(&2));
+        assert!(!coverage.commands.contains_key("script_target::get"));
+
+        let outcome = evaluator.evaluate(&ScriptEvalRequest {
+            coverage: None,
+            ..request
+        });
+        assert!(outcome.coverage.is_none());
+
+        let catalog = evaluator.coverage_catalog()?;
+        assert!(
+            catalog
+                .commands
+                .iter()
+                .any(|command| command.id == "script_target::get"
+                    && command.owner == "script_target")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn virtual_clock_evaluations_advance_time_instead_of_sleeping() {
+        let request = |script: &str| ScriptEvalRequest {
+            script: script.to_string(),
+            fixture: None,
+            timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
+        };
+        let evaluator = AppEvaluator::new(test_factory()).with_virtual_clock();
+        assert!(
+            evaluator
+                .evaluate(&request("canopy.advance_time(250)"))
+                .success
+        );
+
+        // Ten minutes of virtual time pass without any wall-clock wait.
+        let outcome = evaluator.evaluate(&request(
+            "canopy.wait_for(function() return false end, 600000)",
+        ));
+        assert!(!outcome.success);
+        assert!(outcome.timing.total_ms < 60_000);
+
+        let outcome =
+            AppEvaluator::new(test_factory()).evaluate(&request("canopy.advance_time(1)"));
+        assert!(!outcome.success);
+        let message = outcome.error.expect("error info").message;
+        assert!(message.contains("virtual clock"), "{message}");
+    }
+
     #[test]
     fn evaluate_returns_node_handles_as_external_tokens() {
         let evaluator = AppEvaluator::new(test_factory());
@@ -723,6 +907,9 @@ declare script_target: {
             script: "local root = canopy.root()\nprint(root)\nreturn root".to_string(),
             fixture: None,
             timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
 
         assert!(outcome.success);
@@ -743,6 +930,9 @@ declare script_target: {
             .to_string(),
             fixture: Some("seeded".to_string()),
             timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
 
         assert!(outcome.success);
@@ -757,6 +947,9 @@ declare script_target: {
             script: "while true do end".to_string(),
             fixture: None,
             timeout_ms: Some(1),
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
 
         assert!(!outcome.success);
@@ -777,6 +970,9 @@ declare script_target: {
             script: r#"script_target.set("bad")"#.to_string(),
             fixture: None,
             timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
         assert!(!outcome.success);
         assert_eq!(outcome.state, ScriptTaskState::Failed);
@@ -797,6 +993,9 @@ declare script_target: {
             script: r#"canopy.cmd("missing::command")"#.to_string(),
             fixture: None,
             timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         });
 
         assert!(!outcome.success);
@@ -817,6 +1016,9 @@ declare script_target: {
                 script: "return function() end".to_string(),
                 fixture: None,
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             },
         );
 
@@ -842,6 +1044,9 @@ declare script_target: {
                 script: "return script_target.get()".to_string(),
                 fixture: Some("seeded".to_string()),
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             },
         );
 
@@ -867,6 +1072,9 @@ declare script_target: {
                 script: "return script_target.get()".to_string(),
                 fixture: None,
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             },
         );
 
@@ -875,4 +1083,87 @@ declare script_target: {
         assert_eq!(outcome.value, Some(JsonValue::from(31)));
         Ok(())
     }
+
+    #[test]
+    fn evaluate_live_scopes_snapshot_options_to_one_request() -> crate::Result<()> {
+        let mut canopy = (test_factory().as_ref())()?;
+        let dir = tempfile::tempdir()?;
+        let config = SnapshotConfig::new(dir.path().join("app"));
+        canopy.set_snapshot_config(config.clone());
+        let outcome = evaluate_live(
+            &mut canopy,
+            &ScriptEvalRequest {
+                script: "return true".to_string(),
+                fixture: None,
+                timeout_ms: None,
+                snapshot_dir: Some(dir.path().join("request").display().to_string()),
+                update_snapshots: Some(true),
+                coverage: None,
+            },
+        );
+
+        assert!(outcome.success);
+        assert_eq!(canopy.snapshot_config(), config);
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_live_read_only_refuses_mutation() -> crate::Result<()> {
+        let mut canopy = (test_factory().as_ref())()?;
+        let request = |script: &str| ScriptEvalRequest {
+            script: script.to_string(),
+            fixture: None,
+            timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
+        };
+
+        let outcome = evaluate_live_read_only(&mut canopy, &request("return canopy.input_mode()"));
+        assert!(outcome.success);
+
+        let outcome = evaluate_live_read_only(&mut canopy, &request("script_target.set(5)"));
+        assert!(!outcome.success);
+        let error = outcome.error.as_ref().expect("structured error");
+        assert_eq!(error.kind.as_deref(), Some("read_only"));
+
+        let mut snapshot = request("return true");
+        snapshot.update_snapshots = Some(true);
+        let outcome = evaluate_live_read_only(&mut canopy, &snapshot);
+        assert_eq!(
+            outcome.error.map(|error| error.error_type),
+            Some("invalid".to_string())
+        );
+
+        let outcome = evaluate_live(
+            &mut canopy,
+            &request("script_target.set(5) return script_target.get()"),
+        );
+        assert_eq!(outcome.value, Some(JsonValue::from(5)));
+        Ok(())
+    }
+
+    #[test]
+    fn evaluate_live_read_only_never_writes_snapshots() -> crate::Result<()> {
+        let mut canopy = (test_factory().as_ref())()?;
+        let dir = tempfile::tempdir()?;
+        let config = SnapshotConfig::new(dir.path()).with_update(true);
+        canopy.set_snapshot_config(config.clone());
+        let outcome = evaluate_live_read_only(
+            &mut canopy,
+            &ScriptEvalRequest {
+                script: r#"canopy.assert_snapshot("screen")"#.to_string(),
+                fixture: None,
+                timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
+            },
+        );
+
+        assert!(!outcome.success);
+        assert!(!dir.path().join("screen.snap").exists());
+        assert_eq!(canopy.snapshot_config(), config);
+        Ok(())
+    }
 }
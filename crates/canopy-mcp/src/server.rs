@@ -1,21 +1,38 @@
 use std::{
+    collections::BTreeSet,
     fs,
     path::{Path, PathBuf},
-    sync::mpsc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc,
+    },
     thread,
 };
 
-use canopy::{AutomationHandle, Canopy, error::Error as CanopyError};
+use async_trait::async_trait;
+use canopy::{AutomationHandle, Canopy, error::Error as CanopyError, observe::AppChange};
+use futures::{StreamExt, channel::mpsc::UnboundedReceiver};
+use parking_lot::{Mutex, MutexGuard};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tmcp::{Server, ToolError, ToolResult, mcp_server, schema::CallToolResult};
+use tmcp::{
+    Arguments, Server, ServerCtx, ServerHandler, ToolError, ToolResult, mcp_server,
+    schema::{
+        CallToolResult, ClientCapabilities, Cursor, Implementation, InitializeResult,
+        ListResourcesResult, ListToolsResult, ReadResourceResult, Resource, ResourcesCapability,
+        ServerNotification,
+    },
+};
 use tokio::{net::UnixListener, runtime::Builder, sync::oneshot, task::block_in_place};
 
 use crate::{
     Error, Result,
+    fuzz::FuzzConfig,
+    resources::{RESOURCES, changed_resources, read_resource, resource_info},
     script::{
         AppEvaluator, AppFactory, ScriptEvalRequest, app_factory, bootstrap_for_canopy,
-        evaluate_live,
+        evaluate_live, evaluate_live_read_only,
     },
 };
 
@@ -50,13 +67,267 @@ fn canopy_mcp_server(factory: AppFactory) -> CanopyMcpServer {
 /// Live MCP server that proxies tool calls onto a running canopy UI thread.
 #[derive(Clone)]
 struct LiveCanopyMcpServer {
-    /// Handle used to marshal work onto the runloop thread.
+    /// This connection's lane for marshalling work onto the runloop thread.
     automation: AutomationHandle,
+    /// This connection's claim on the controller role.
+    seat: Arc<Seat>,
+}
+
+/// Role of one client connected to a live app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientRole {
+    /// Drives the app: evaluates any script and applies fixtures.
+    Controller,
+    /// Watches the app: evaluates read-only scripts and reads resources.
+    Observer,
+}
+
+/// A connection's role, as reported by the role tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionRole {
+    /// This connection's role.
+    pub role: ClientRole,
+    /// Whether any connection currently holds the controller role.
+    pub controller_connected: bool,
+}
+
+/// The controller role shared by every connection to one live app.
+#[derive(Debug, Default)]
+struct ControllerSeat {
+    /// Id of the connection holding the role, if any.
+    holder: Mutex<Option<u64>>,
+    /// Id handed to the next connection.
+    next_id: AtomicU64,
+}
+
+/// One connection's claim on the controller role, released when the connection closes.
+#[derive(Debug)]
+struct Seat {
+    /// Role shared with the other connections.
+    shared: Arc<ControllerSeat>,
+    /// This connection's id.
+    id: u64,
+}
+
+impl Seat {
+    /// Join a live app, taking the controller role when no other connection holds it.
+    fn join(shared: &Arc<ControllerSeat>) -> Self {
+        let seat = Self {
+            shared: Arc::clone(shared),
+            id: shared.next_id.fetch_add(1, Ordering::SeqCst),
+        };
+        seat.claim();
+        seat
+    }
+
+    /// Lock the id of the connection holding the controller role.
+    fn holder(&self) -> MutexGuard<'_, Option<u64>> {
+        self.shared.holder.lock()
+    }
+
+    /// Take the controller role if it is free, and return this connection's role.
+    fn claim(&self) -> SessionRole {
+        let mut holder = self.holder();
+        if holder.is_none() {
+            *holder = Some(self.id);
+        }
+        drop(holder);
+        self.role()
+    }
+
+    /// Give up the controller role if this connection holds it.
+    fn release(&self) -> SessionRole {
+        let mut holder = self.holder();
+        if *holder == Some(self.id) {
+            *holder = None;
+        }
+        drop(holder);
+        self.role()
+    }
+
+    /// Report this connection's role.
+    fn role(&self) -> SessionRole {
+        let holder = *self.holder();
+        SessionRole {
+            role: if holder == Some(self.id) {
+                ClientRole::Controller
+            } else {
+                ClientRole::Observer
+            },
+            controller_connected: holder.is_some(),
+        }
+    }
+
+    /// Whether this connection holds the controller role.
+    fn is_controller(&self) -> bool {
+        self.role().role == ClientRole::Controller
+    }
+}
+
+impl Drop for Seat {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// One live MCP connection: the live tools plus subscribable app resources.
+struct LiveConnection {
+    /// Tool handlers for the running app.
+    tools: LiveCanopyMcpServer,
+    /// Resource URIs this connection subscribed to.
+    subscriptions: Arc<Mutex<BTreeSet<String>>>,
+    /// Whether this connection already forwards app changes.
+    observing: AtomicBool,
+}
+
+/// Construct the live MCP connection handler for a running canopy app.
+fn live_connection(automation: AutomationHandle, seat: Arc<Seat>) -> LiveConnection {
+    LiveConnection {
+        tools: LiveCanopyMcpServer { automation, seat },
+        subscriptions: Arc::default(),
+        observing: AtomicBool::new(false),
+    }
+}
+
+/// Convert an automation error into an MCP protocol error.
+fn protocol_error(error: &Error) -> tmcp::Error {
+    match error {
+        Error::UnknownResource(_) => tmcp::Error::InvalidParams(error.to_string()),
+        _ => tmcp::Error::InternalError(error.to_string()),
+    }
+}
+
+#[async_trait]
+impl ServerHandler for LiveConnection {
+    async fn initialize(
+        &self,
+        context: &ServerCtx,
+        protocol_version: String,
+        capabilities: ClientCapabilities,
+        client_info: Implementation,
+    ) -> tmcp::Result<InitializeResult> {
+        let mut result = self
+            .tools
+            .initialize(context, protocol_version, capabilities, client_info)
+            .await?;
+        result.capabilities.resources = Some(ResourcesCapability {
+            subscribe: Some(true),
+            list_changed: Some(false),
+        });
+        Ok(result)
+    }
+
+    async fn list_tools(
+        &self,
+        context: &ServerCtx,
+        cursor: Option<Cursor>,
+    ) -> tmcp::Result<ListToolsResult> {
+        self.tools.list_tools(context, cursor).await
+    }
+
+    async fn call_tool(
+        &self,
+        context: &ServerCtx,
+        name: String,
+        arguments: Option<Arguments>,
+    ) -> tmcp::Result<CallToolResult> {
+        self.tools.call_tool(context, name, arguments).await
+    }
+
+    async fn list_resources(
+        &self,
+        _context: &ServerCtx,
+        _cursor: Option<Cursor>,
+    ) -> tmcp::Result<ListResourcesResult> {
+        Ok(
+            ListResourcesResult::new().with_resources(RESOURCES.iter().map(|resource| {
+                Resource::new(resource.uri, resource.name)
+                    .with_description(resource.description)
+                    .with_mime_type(resource.mime_type)
+            })),
+        )
+    }
+
+    async fn read_resource(
+        &self,
+        _context: &ServerCtx,
+        uri: String,
+    ) -> tmcp::Result<ReadResourceResult> {
+        let info = resource_info(&uri)
+            .ok_or_else(|| protocol_error(&Error::UnknownResource(uri.clone())))?;
+        let automation = self.tools.automation.clone();
+        let text = block_in_place(move || {
+            automation.request(move |canopy| {
+                read_resource(canopy, info.uri)
+                    .map_err(|error| CanopyError::Internal(error.to_string()))
+            })
+        })
+        .map_err(|error| protocol_error(&Error::from(error)))?;
+        Ok(ReadResourceResult::new().with_text(uri, info.mime_type, text))
+    }
+
+    async fn resources_subscribe(&self, context: &ServerCtx, uri: String) -> tmcp::Result<()> {
+        if resource_info(&uri).is_none() {
+            return Err(protocol_error(&Error::UnknownResource(uri)));
+        }
+        self.subscriptions.lock().insert(uri);
+        if self.observing.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let automation = self.tools.automation.clone();
+        let changes =
+            block_in_place(move || automation.request(|canopy| Ok(canopy.observe_changes())))
+                .map_err(|error| protocol_error(&Error::from(error)))?;
+        if self.observing.swap(true, Ordering::SeqCst) {
+            // Another subscription started forwarding first; this receiver is pruned unread.
+            return Ok(());
+        }
+        tokio::spawn(forward_changes(
+            changes,
+            Arc::clone(&self.subscriptions),
+            context.clone(),
+        ));
+        Ok(())
+    }
+
+    async fn resources_unsubscribe(&self, _context: &ServerCtx, uri: String) -> tmcp::Result<()> {
+        self.subscriptions.lock().remove(&uri);
+        Ok(())
+    }
 }
 
-/// Construct the live MCP server for a running canopy app.
-fn live_canopy_mcp_server(automation: AutomationHandle) -> LiveCanopyMcpServer {
-    LiveCanopyMcpServer { automation }
+/// Send a resource-updated notification for each subscribed resource an app change makes stale.
+///
+/// Changes that queue up while a notification is in flight are coalesced, so a burst of frames
+/// produces one screen update. Forwarding stops when the connection closes or the app exits.
+async fn forward_changes(
+    mut changes: UnboundedReceiver<AppChange>,
+    subscriptions: Arc<Mutex<BTreeSet<String>>>,
+    context: ServerCtx,
+) {
+    while let Some(change) = changes.next().await {
+        let mut stale: BTreeSet<&str> = changed_resources(&change).iter().copied().collect();
+        while let Ok(Some(change)) = changes.try_next() {
+            stale.extend(changed_resources(&change));
+        }
+        let updated: Vec<String> = {
+            let subscriptions = subscriptions.lock();
+            stale
+                .into_iter()
+                .filter(|uri| subscriptions.contains(*uri))
+                .map(str::to_string)
+                .collect()
+        };
+        for uri in updated {
+            if context
+                .notify(ServerNotification::ResourceUpdated { uri })
+                .is_err()
+            {
+                return;
+            }
+        }
+    }
 }
 
 #[mcp_server]
@@ -100,6 +371,30 @@ impl CanopyMcpServer {
             .map_err(|error| ToolError::internal(error.to_string()))?;
         Ok(json_tool_result(&value))
     }
+
+    #[tool]
+    /// List every command and binding the app registers, for coverage reports.
+    async fn coverage_catalog(&self) -> ToolResult<CallToolResult> {
+        let catalog = self
+            .evaluator
+            .coverage_catalog()
+            .map_err(|error| ToolError::internal(error.to_string()))?;
+        let value = serde_json::to_value(catalog)
+            .map_err(|error| ToolError::internal(error.to_string()))?;
+        Ok(json_tool_result(&value))
+    }
+
+    #[tool]
+    /// Drive fresh headless app instances with random input and shrink the first failure.
+    async fn fuzz(&self, params: FuzzConfig) -> ToolResult<CallToolResult> {
+        let report = self
+            .evaluator
+            .fuzz(&params)
+            .map_err(|error| ToolError::internal(error.to_string()))?;
+        let value =
+            serde_json::to_value(report).map_err(|error| ToolError::internal(error.to_string()))?;
+        Ok(json_tool_result(&value))
+    }
 }
 
 #[mcp_server]
@@ -122,11 +417,21 @@ impl LiveCanopyMcpServer {
     }
 
     #[tool]
-    /// Evaluate a Luau script against the currently running canopy app.
+    /// Evaluate a Luau script against the currently running canopy app. Observers may only
+    /// inspect the app; APIs that change it fail with a `read_only` error.
     async fn script_eval(&self, params: ScriptEvalRequest) -> ToolResult<CallToolResult> {
         let automation = self.automation.clone();
+        let seat = Arc::clone(&self.seat);
         let outcome = block_in_place(move || {
-            automation.request(move |canopy| Ok(evaluate_live(canopy, &params)))
+            automation.request(move |canopy| {
+                // The role is checked on the request's turn, so a role change while the
+                // request waited applies to it.
+                Ok(if seat.is_controller() {
+                    evaluate_live(canopy, &params)
+                } else {
+                    evaluate_live_read_only(canopy, &params)
+                })
+            })
         })
         .map_err(|error| ToolError::internal(error.to_string()))?;
         Ok(outcome.to_tool_result())
@@ -155,9 +460,26 @@ impl LiveCanopyMcpServer {
         Ok(json_tool_result(&value))
     }
 
+    #[tool]
+    /// List every command and binding the running app registers, for coverage reports.
+    async fn coverage_catalog(&self) -> ToolResult<CallToolResult> {
+        let automation = self.automation.clone();
+        let catalog =
+            block_in_place(move || automation.request(|canopy| Ok(canopy.coverage_catalog())))
+                .map_err(|error| ToolError::internal(error.to_string()))?;
+        let value = serde_json::to_value(catalog)
+            .map_err(|error| ToolError::internal(error.to_string()))?;
+        Ok(json_tool_result(&value))
+    }
+
     #[tool]
     /// Apply a named fixture to the running app and trigger a re-render.
     async fn apply_fixture(&self, params: ApplyFixtureRequest) -> ToolResult<CallToolResult> {
+        if !self.seat.is_controller() {
+            return Err(ToolError::invalid_input(
+                "only the controller can apply fixtures; call claim_control first",
+            ));
+        }
         let name = params.name;
         let applied_name = name.clone();
         let automation = self.automation.clone();
@@ -171,6 +493,37 @@ impl LiveCanopyMcpServer {
         let value = json!({ "applied": applied_name });
         Ok(json_tool_result(&value))
     }
+
+    #[tool]
+    /// Report whether this connection is the controller or a read-only observer.
+    async fn session_role(&self) -> ToolResult<CallToolResult> {
+        role_result(self.seat.role())
+    }
+
+    #[tool]
+    /// Become the controller, if no other connection is.
+    async fn claim_control(&self) -> ToolResult<CallToolResult> {
+        let role = self.seat.claim();
+        if role.role != ClientRole::Controller {
+            return Err(ToolError::invalid_input(
+                "another connection is the controller",
+            ));
+        }
+        role_result(role)
+    }
+
+    #[tool]
+    /// Give up the controller role and become a read-only observer.
+    async fn release_control(&self) -> ToolResult<CallToolResult> {
+        role_result(self.seat.release())
+    }
+}
+
+/// Encode a connection's role as a tool result.
+fn role_result(role: SessionRole) -> ToolResult<CallToolResult> {
+    let value =
+        serde_json::to_value(role).map_err(|error| ToolError::internal(error.to_string()))?;
+    Ok(json_tool_result(&value))
 }
 
 /// Serve `script_eval` and `script_api` over stdio for an app factory.
@@ -220,6 +573,15 @@ impl Drop for UdsServerHandle {
 }
 
 /// Serve live MCP automation for a running canopy app over a Unix-domain socket.
+///
+/// Besides the live tools, each connection serves the resources in
+/// [`RESOURCES`](crate::resources::RESOURCES) and sends a resource-updated notification when a
+/// subscribed resource changes.
+///
+/// Any number of clients may connect. The first becomes the [controller](ClientRole); the rest
+/// are read-only observers until the controller disconnects or releases the role and one of them
+/// claims it. Each connection gets its own [lane](AutomationHandle::lane), so requests from
+/// all clients run one at a time and take turns.
 pub fn serve_uds(
     socket_path: impl AsRef<Path>,
     automation: AutomationHandle,
@@ -247,18 +609,22 @@ pub fn serve_uds(
                 }
             };
 
+            let controller = Arc::new(ControllerSeat::default());
             let mut shutdown_rx = shutdown_rx;
             loop {
                 tokio::select! {
                     _ = &mut shutdown_rx => break,
                     accept_result = listener.accept() => {
                         let (stream, _) = accept_result?;
-                        let automation = automation.clone();
+                        let automation = automation.lane();
+                        let seat = Arc::new(Seat::join(&controller));
                         tokio::spawn(async move {
                             let (reader, writer) = stream.into_split();
-                            let _ignored = Server::new(move || live_canopy_mcp_server(automation.clone()))
-                                .serve_stream(reader, writer)
-                                .await;
+                            let _ignored = Server::new(move || {
+                                live_connection(automation.clone(), Arc::clone(&seat))
+                            })
+                            .serve_stream(reader, writer)
+                            .await;
                         });
                     }
                 }
@@ -380,6 +746,9 @@ mod tests {
                 script: "return echo_node.ping()".to_string(),
                 fixture: None,
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             })
             .await
             .expect("script_eval");
@@ -406,6 +775,9 @@ mod tests {
                 script: "return echo_node.get()".to_string(),
                 fixture: Some("seeded".to_string()),
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             })
             .await
             .expect("script_eval");
@@ -435,6 +807,9 @@ mod tests {
                 script: "echo_node.ping(1)".to_string(),
                 fixture: None,
                 timeout_ms: None,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             })
             .await
             .expect("script_eval");
@@ -454,4 +829,28 @@ mod tests {
                 .is_some_and(|items| !items.is_empty())
         );
     }
+
+    #[test]
+    fn seats_hand_over_the_controller_role() {
+        let shared = Arc::new(ControllerSeat::default());
+        let first = Seat::join(&shared);
+        let second = Seat::join(&shared);
+        assert!(first.is_controller());
+        assert_eq!(
+            second.claim(),
+            SessionRole {
+                role: ClientRole::Observer,
+                controller_connected: true,
+            }
+        );
+
+        drop(first);
+        assert!(!second.role().controller_connected);
+        assert!(second.claim().role == ClientRole::Controller);
+
+        let third = Seat::join(&shared);
+        assert!(!third.is_controller());
+        assert!(!second.release().controller_connected);
+        assert!(third.claim().role == ClientRole::Controller);
+    }
 }
@@ -1,6 +1,8 @@
 use std::{
-    fs,
+    fs, panic,
     path::{Component, Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
 };
 
 use canopy::Canopy;
@@ -18,10 +20,19 @@ pub struct SuiteConfig {
     pub suite_dir: PathBuf,
     /// Optional subset of scripts to run. Relative paths are resolved against `suite_dir`.
     pub scripts: Vec<PathBuf>,
+    /// Keep only scripts matching at least one filter; see [`script_matches_filter`].
+    pub filters: Vec<String>,
+    /// Number of scripts evaluated at once. Zero is treated as one.
+    pub jobs: usize,
     /// Optional timeout per script in milliseconds.
     pub timeout_ms: Option<u64>,
     /// Stop after the first failing script when true.
     pub fail_fast: bool,
+    /// Record missing snapshots and rewrite changed ones instead of failing. When false, the
+    /// `CANOPY_UPDATE_SNAPSHOTS` environment variable decides.
+    pub update_snapshots: bool,
+    /// Record the commands and bindings each script exercises into its outcome.
+    pub coverage: bool,
 }
 
 impl SuiteConfig {
@@ -30,8 +41,12 @@ impl SuiteConfig {
         Self {
             suite_dir: suite_dir.into(),
             scripts: Vec::new(),
+            filters: Vec::new(),
+            jobs: 1,
             timeout_ms: None,
             fail_fast: false,
+            update_snapshots: false,
+            coverage: false,
         }
     }
 }
@@ -62,38 +77,14 @@ pub struct ScriptResult {
     pub outcome: ScriptEvalOutcome,
 }
 
-/// Aggregated result for a smoke suite.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct SuiteResult {
-    /// Per-script results in execution order.
-    pub scripts: Vec<ScriptResult>,
-}
-
-impl SuiteResult {
-    /// Return true when all smoke scripts passed.
-    pub fn success(&self) -> bool {
-        self.scripts
-            .iter()
-            .all(|script| script.status == ScriptStatus::Passed)
-    }
-}
-
-/// Run a smoke suite against fresh headless app instances.
-pub fn run_suite(
-    factory: impl Fn() -> Result<Canopy> + Send + Sync + 'static,
-    config: &SuiteConfig,
-) -> Result<SuiteResult> {
-    let evaluator = AppEvaluator::new(app_factory(factory));
-    let scripts = discover_scripts(config)?;
-    let mut results = Vec::with_capacity(scripts.len());
-    for path in scripts {
-        let fixture = fixture_for_script(&config.suite_dir, &path);
-        let source = fs::read_to_string(&path)?;
-        let outcome = evaluator.evaluate(&ScriptEvalRequest {
-            script: source,
-            fixture: fixture.clone(),
-            timeout_ms: config.timeout_ms,
-        });
+impl ScriptResult {
+    /// Summarize an evaluation outcome, taking the message from the error or first failed
+    /// assertion.
+    pub fn from_outcome(
+        path: PathBuf,
+        fixture: Option<String>,
+        outcome: ScriptEvalOutcome,
+    ) -> Self {
         let message = outcome
             .error
             .as_ref()
@@ -110,19 +101,155 @@ pub fn run_suite(
         } else {
             ScriptStatus::Failed
         };
-        results.push(ScriptResult {
+        Self {
             path,
             fixture,
             status,
             elapsed_ms: outcome.timing.total_ms,
             message,
             outcome,
-        });
-        if config.fail_fast && results.last().is_some_and(|result| !result.outcome.success) {
-            break;
         }
     }
-    Ok(SuiteResult { scripts: results })
+}
+
+/// Aggregated result for a smoke suite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuiteResult {
+    /// Per-script results in execution order.
+    pub scripts: Vec<ScriptResult>,
+}
+
+impl SuiteResult {
+    /// Return true when all smoke scripts passed.
+    pub fn success(&self) -> bool {
+        self.scripts
+            .iter()
+            .all(|script| script.status == ScriptStatus::Passed)
+    }
+}
+
+/// Scripts shared between the workers of one smoke run.
+///
+/// Workers take scripts in discovery order until the queue is drained or stopped. A fail-fast
+/// queue stops itself once a failing result is recorded.
+#[derive(Debug)]
+pub struct ScriptQueue {
+    /// Scripts in discovery order.
+    scripts: Vec<PathBuf>,
+    /// Whether the queue stops after the first failing script.
+    fail_fast: bool,
+    /// Index of the next script to hand out.
+    next: AtomicUsize,
+    /// Set once workers should stop taking scripts.
+    stop: AtomicBool,
+}
+
+impl ScriptQueue {
+    /// Construct a queue over scripts in discovery order.
+    pub fn new(scripts: Vec<PathBuf>, fail_fast: bool) -> Self {
+        Self {
+            scripts,
+            fail_fast,
+            next: AtomicUsize::new(0),
+            stop: AtomicBool::new(false),
+        }
+    }
+
+    /// Return the number of scripts in the queue, including those already handed out.
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    /// Return true when the queue holds no scripts.
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Take the next script and its discovery index, or `None` once drained or stopped.
+    pub fn next_script(&self) -> Option<(usize, &Path)> {
+        if self.stop.load(Ordering::SeqCst) {
+            return None;
+        }
+        let index = self.next.fetch_add(1, Ordering::SeqCst);
+        self.scripts.get(index).map(|path| (index, path.as_path()))
+    }
+
+    /// Record a finished script, stopping a fail-fast queue when it failed.
+    pub fn finish(&self, result: &ScriptResult) {
+        if self.fail_fast && result.status == ScriptStatus::Failed {
+            self.stop();
+        }
+    }
+
+    /// Stop handing out scripts.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Run a smoke suite against fresh headless app instances.
+///
+/// With more than one job, scripts run on worker threads and results keep discovery order. A
+/// fail-fast run stops handing out scripts after the first failure, and reports every script
+/// that had already finished. A panic on a worker thread resumes on the caller.
+pub fn run_suite(
+    factory: impl Fn() -> Result<Canopy> + Send + Sync + 'static,
+    config: &SuiteConfig,
+) -> Result<SuiteResult> {
+    let evaluator = AppEvaluator::new(app_factory(factory));
+    let queue = ScriptQueue::new(discover_scripts(config)?, config.fail_fast);
+    let worker = || -> Result<Vec<(usize, ScriptResult)>> {
+        let mut results = Vec::new();
+        while let Some((index, path)) = queue.next_script() {
+            let result = run_script(&evaluator, config, path)?;
+            queue.finish(&result);
+            results.push((index, result));
+        }
+        Ok(results)
+    };
+    let jobs = config.jobs.min(queue.len());
+    let finished = if jobs <= 1 {
+        worker()?
+    } else {
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs).map(|_| scope.spawn(&worker)).collect();
+            workers
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| panic::resume_unwind(panic))
+                })
+                .collect::<Result<Vec<_>>>()
+        })?
+        .concat()
+    };
+    let mut slots = vec![None; queue.len()];
+    for (index, result) in finished {
+        slots[index] = Some(result);
+    }
+    Ok(SuiteResult {
+        scripts: slots.into_iter().flatten().collect(),
+    })
+}
+
+/// Evaluate one smoke script with its derived fixture and snapshot directory.
+fn run_script(evaluator: &AppEvaluator, config: &SuiteConfig, path: &Path) -> Result<ScriptResult> {
+    let fixture = fixture_for_script(&config.suite_dir, path);
+    let source = fs::read_to_string(path)?;
+    let outcome = evaluator.evaluate(&ScriptEvalRequest {
+        script: source,
+        fixture: fixture.clone(),
+        timeout_ms: config.timeout_ms,
+        snapshot_dir: Some(snapshot_dir_for_script(path).to_string_lossy().to_string()),
+        update_snapshots: config.update_snapshots.then_some(true),
+        coverage: config.coverage.then_some(true),
+    });
+    Ok(ScriptResult::from_outcome(
+        path.to_path_buf(),
+        fixture,
+        outcome,
+    ))
 }
 
 /// Derive a fixture name from the first path component under the suite root.
@@ -139,6 +266,14 @@ pub fn fixture_for_script(suite_dir: &Path, script: &Path) -> Option<String> {
     }
 }
 
+/// Return the directory holding the snapshot files of a script: `snapshots` beside the script.
+pub fn snapshot_dir_for_script(script: &Path) -> PathBuf {
+    script
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("snapshots")
+}
+
 /// Resolve the ordered list of smoke scripts for a suite run.
 ///
 /// An explicit script list keeps its given order, because that order decides which script a
@@ -165,7 +300,80 @@ pub fn discover_scripts(config: &SuiteConfig) -> Result<Vec<PathBuf>> {
     if scripts.is_empty() {
         return Err(Error::NoScripts(config.suite_dir.clone()));
     }
-    Ok(scripts)
+    if config.filters.is_empty() {
+        return Ok(scripts);
+    }
+    let filtered: Vec<_> = scripts
+        .into_iter()
+        .filter(|path| {
+            config
+                .filters
+                .iter()
+                .any(|filter| script_matches_filter(&config.suite_dir, path, filter))
+        })
+        .collect();
+    if filtered.is_empty() {
+        return Err(Error::NoMatchingScripts {
+            suite: config.suite_dir.clone(),
+            filters: config.filters.join(", "),
+        });
+    }
+    Ok(filtered)
+}
+
+/// Return the path of a script relative to the suite root, with `/` separators.
+pub fn script_relative_name(suite_dir: &Path, script: &Path) -> String {
+    let relative = script.strip_prefix(suite_dir).unwrap_or(script);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Does a smoke script match a `--filter` pattern?
+///
+/// A filter names either a fixture or a glob. The glob is matched against the script path
+/// relative to the suite root, with `/` separators; `*` and `?` stay within one path segment
+/// and `**` spans segments. A glob without a `/` also matches the file name alone.
+pub fn script_matches_filter(suite_dir: &Path, script: &Path, filter: &str) -> bool {
+    if fixture_for_script(suite_dir, script).as_deref() == Some(filter) {
+        return true;
+    }
+    let relative = script_relative_name(suite_dir, script);
+    if glob_match(filter.as_bytes(), relative.as_bytes()) {
+        return true;
+    }
+    !filter.contains('/')
+        && script
+            .file_name()
+            .is_some_and(|name| glob_match(filter.as_bytes(), name.to_string_lossy().as_bytes()))
+}
+
+/// Match a `/`-separated path against a glob with `*`, `**` and `?` wildcards.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', b'/', rest @ ..] => {
+            glob_match(rest, text)
+                || text
+                    .iter()
+                    .enumerate()
+                    .any(|(index, byte)| *byte == b'/' && glob_match(rest, &text[index + 1..]))
+        }
+        [b'*', b'*', rest @ ..] => (0..=text.len()).any(|skip| glob_match(rest, &text[skip..])),
+        [b'*', rest @ ..] => {
+            let segment = text
+                .iter()
+                .position(|byte| *byte == b'/')
+                .unwrap_or(text.len());
+            (0..=segment).any(|skip| glob_match(rest, &text[skip..]))
+        }
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(byte) if *byte != b'/') && glob_match(rest, &text[1..])
+        }
+        [byte, rest @ ..] => text.first() == Some(byte) && glob_match(rest, &text[1..]),
+    }
 }
 
 /// Recursively collect `.luau` scripts under a directory.
@@ -239,6 +447,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn script_queue_hands_out_scripts_until_stopped() {
+        let queue = ScriptQueue::new(vec![PathBuf::from("a.luau"), PathBuf::from("b.luau")], true);
+        assert_eq!(queue.next_script(), Some((0, Path::new("a.luau"))));
+        queue.stop();
+        assert_eq!(queue.next_script(), None);
+        assert_eq!(queue.len(), 2);
+    }
+
     #[test]
     fn fixture_is_the_first_normal_component() {
         let suite = Path::new("/tmp/smoke");
@@ -260,4 +477,82 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn filters_match_fixtures_and_globs() {
+        let suite = Path::new("/tmp/smoke");
+        let script = Path::new("/tmp/smoke/with_items/nav/scroll.luau");
+        assert!(script_matches_filter(suite, script, "with_items"));
+        assert!(script_matches_filter(suite, script, "with_items/**"));
+        assert!(script_matches_filter(suite, script, "**/scroll.luau"));
+        assert!(script_matches_filter(suite, script, "scroll*"));
+        assert!(script_matches_filter(
+            suite,
+            script,
+            "with_items/*/s?roll.luau"
+        ));
+        assert!(!script_matches_filter(suite, script, "with_items/*.luau"));
+        assert!(!script_matches_filter(suite, script, "nav"));
+        assert!(!script_matches_filter(suite, script, "empty"));
+    }
+
+    #[test]
+    fn filters_that_match_nothing_are_an_error() -> Result<()> {
+        let dir = unique_dir();
+        let root = dir.path();
+        fs::write(root.join("a.luau"), "return true")?;
+        let mut config = SuiteConfig::new(root);
+        config.filters = vec!["b*".to_string()];
+        assert!(matches!(
+            discover_scripts(&config),
+            Err(Error::NoMatchingScripts { .. })
+        ));
+        config.filters.push("a*".to_string());
+        assert_eq!(file_names(&discover_scripts(&config)?), vec!["a.luau"]);
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_runs_report_in_discovery_order() -> Result<()> {
+        let dir = unique_dir();
+        let root = dir.path();
+        for (name, source) in [
+            ("a.luau", "return 1"),
+            ("b.luau", "canopy.assert(false, \"b fails\")"),
+            ("c.luau", "return 3"),
+            ("d.luau", "return 4"),
+        ] {
+            fs::write(root.join(name), source)?;
+        }
+        let mut config = SuiteConfig::new(root);
+        config.jobs = 3;
+        let result = run_suite(|| Ok(Canopy::new()), &config)?;
+        assert_eq!(
+            file_names(
+                &result
+                    .scripts
+                    .iter()
+                    .map(|script| script.path.clone())
+                    .collect::<Vec<_>>()
+            ),
+            vec!["a.luau", "b.luau", "c.luau", "d.luau"]
+        );
+        assert_eq!(result.scripts[1].status, ScriptStatus::Failed);
+        assert!(
+            result.scripts[1]
+                .message
+                .as_deref()
+                .is_some_and(|message| message.contains("b fails"))
+        );
+        assert!(!result.success());
+        Ok(())
+    }
+
+    #[test]
+    fn snapshots_live_beside_their_script() {
+        assert_eq!(
+            snapshot_dir_for_script(Path::new("/tmp/smoke/with_items/navigation.luau")),
+            Path::new("/tmp/smoke/with_items/snapshots")
+        );
+    }
 }
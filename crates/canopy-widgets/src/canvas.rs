@@ -0,0 +1,493 @@
+//! Drawing surface that rasterizes shapes at sub-cell resolution.
+
+use std::f64::consts::TAU;
+
+use canopy::{
+    Canopy, Context, Loader, ViewContext, Widget, command, derive_commands,
+    error::Result,
+    geom::{Line, Point, Rect},
+    layout::Layout,
+    render::{Render, SubCell},
+    style::Color,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// A shape in the canvas coordinate space.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Individual points.
+    Points(Vec<(f64, f64)>),
+    /// A straight line between two points.
+    Line {
+        /// Start point.
+        from: (f64, f64),
+        /// End point.
+        to: (f64, f64),
+    },
+    /// An axis-aligned rectangle between two opposite corners.
+    Rect {
+        /// First corner.
+        from: (f64, f64),
+        /// Opposite corner.
+        to: (f64, f64),
+        /// Whether to fill the interior as well as the outline.
+        filled: bool,
+    },
+    /// A circle outline. The radius is in canvas units along each axis, so the circle becomes
+    /// an ellipse when the axes have different scales.
+    Circle {
+        /// Center point.
+        center: (f64, f64),
+        /// Radius in canvas units.
+        radius: f64,
+    },
+    /// A closed polygon outline through a list of vertices.
+    Polygon(Vec<(f64, f64)>),
+    /// Text anchored with its first character in the cell that contains a point.
+    Label {
+        /// Anchor point.
+        at: (f64, f64),
+        /// Label text.
+        text: String,
+    },
+}
+
+/// A shape with its layer and style.
+#[derive(Debug, Clone)]
+struct Item {
+    /// Layer the shape is drawn on.
+    layer: i32,
+    /// Style path whose foreground colors the shape.
+    style: String,
+    /// The shape itself.
+    shape: Shape,
+}
+
+/// Widget that draws lines, shapes, points and labels on a logical coordinate space.
+///
+/// The x axis runs left to right from the first to the second x bound, and the y axis runs
+/// bottom to top. Shapes are drawn on a grid of sub-cell pixels, braille by default, in layer
+/// order; shapes on the same layer are drawn in the order they were added. Labels are drawn as
+/// text over the pixels once every layer is rasterized.
+pub struct Canvas {
+    /// Logical x coordinates at the left and right edges.
+    x_bounds: (f64, f64),
+    /// Logical y coordinates at the bottom and top edges.
+    y_bounds: (f64, f64),
+    /// Glyph grid used to draw pixels.
+    grid: SubCell,
+    /// Style path whose background fills the canvas.
+    style: String,
+    /// Shapes sorted by layer.
+    items: Vec<Item>,
+}
+
+#[derive_commands]
+impl Canvas {
+    /// Create an empty canvas spanning the given x and y bounds.
+    pub fn new(x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Self {
+        Self {
+            x_bounds,
+            y_bounds,
+            grid: SubCell::Braille,
+            style: String::from("canvas"),
+            items: Vec::new(),
+        }
+    }
+
+    /// Configure the glyph grid used to draw pixels.
+    pub fn with_grid(mut self, grid: SubCell) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Configure the style path whose background fills the canvas.
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Return the glyph grid used to draw pixels.
+    pub fn grid(&self) -> SubCell {
+        self.grid
+    }
+
+    /// Return the logical x and y bounds.
+    pub fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        (self.x_bounds, self.y_bounds)
+    }
+
+    /// Change the logical x and y bounds.
+    pub fn set_bounds(&mut self, x_bounds: (f64, f64), y_bounds: (f64, f64)) {
+        self.x_bounds = x_bounds;
+        self.y_bounds = y_bounds;
+    }
+
+    /// Add a shape to a layer, colored with the foreground of a style path.
+    pub fn draw(&mut self, layer: i32, style: impl Into<String>, shape: Shape) {
+        let index = self.items.partition_point(|item| item.layer <= layer);
+        self.items.insert(
+            index,
+            Item {
+                layer,
+                style: style.into(),
+                shape,
+            },
+        );
+    }
+
+    /// Add a shape to a layer, returning the canvas for chaining.
+    pub fn with_shape(mut self, layer: i32, style: impl Into<String>, shape: Shape) -> Self {
+        self.draw(layer, style, shape);
+        self
+    }
+
+    /// Return the shapes on the canvas in drawing order.
+    pub fn shapes(&self) -> impl Iterator<Item = &Shape> {
+        self.items.iter().map(|item| &item.shape)
+    }
+
+    /// Remove every shape.
+    #[command]
+    pub fn clear(&mut self, _ctx: &mut dyn Context) -> Result<()> {
+        self.items.clear();
+        Ok(())
+    }
+
+    /// Remove the shapes on one layer.
+    /// @param layer The layer to clear.
+    #[command]
+    pub fn clear_layer(&mut self, _ctx: &mut dyn Context, layer: i32) -> Result<()> {
+        self.items.retain(|item| item.layer != layer);
+        Ok(())
+    }
+
+    /// Draw pixels with a glyph grid.
+    /// @param grid The glyph grid.
+    #[command]
+    pub fn set_grid(&mut self, _ctx: &mut dyn Context, grid: SubCell) -> Result<()> {
+        self.grid = grid;
+        Ok(())
+    }
+
+    /// Return the pixel mapping for a rectangle of cells.
+    fn projection(&self, rect: Rect) -> Projection {
+        let size = self.grid.size();
+        Projection {
+            width: rect.w * size.w,
+            height: rect.h * size.h,
+            x_bounds: self.x_bounds,
+            y_bounds: self.y_bounds,
+        }
+    }
+
+    /// Return the cell that contains a canvas point when the canvas is painted into a
+    /// rectangle, or `None` if the point is outside it.
+    pub fn cell(&self, rect: Rect, point: (f64, f64)) -> Option<Point> {
+        let size = self.grid.size();
+        let (x, y) = self.projection(rect).pixel(point)?;
+        Some(Point {
+            x: rect.tl.x + x / size.w,
+            y: rect.tl.y + y / size.h,
+        })
+    }
+
+    /// Paint the canvas into a rectangle of cells.
+    ///
+    /// Widgets that build on a canvas, such as charts, call this to draw into part of their
+    /// own area.
+    pub fn paint(&self, r: &mut Render, rect: Rect) -> Result<()> {
+        if rect.w == 0 || rect.h == 0 {
+            return Ok(());
+        }
+        let background = r.resolve_style_name_at(&self.style, rect, rect.tl).bg;
+        let mut raster = Raster::new(self.projection(rect));
+        for item in &self.items {
+            let color = r.resolve_style_name_at(&item.style, rect, rect.tl).fg;
+            raster.shape(&item.shape, color);
+        }
+        r.subcells(self.grid, rect, background, |x, y| raster.get(x, y))?;
+
+        for item in &self.items {
+            let Shape::Label { at, text } = &item.shape else {
+                continue;
+            };
+            let Some(cell) = self.cell(rect, *at) else {
+                continue;
+            };
+            let width = (text.width() as u32).min(rect.tl.x + rect.w - cell.x);
+            r.text(&item.style, Line::new(cell.x, cell.y, width), text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Canvas {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        self.paint(r, ctx.view().view_rect_local())
+    }
+}
+
+impl Loader for Canvas {
+    /// Register commands for the canvas widget.
+    fn load(cnpy: &mut Canopy) -> Result<()> {
+        cnpy.add_commands::<Self>()?;
+        Ok(())
+    }
+}
+
+/// The mapping from canvas coordinates to a grid of pixels.
+#[derive(Debug, Clone, Copy)]
+struct Projection {
+    /// Width in pixels.
+    width: u32,
+    /// Height in pixels.
+    height: u32,
+    /// Logical x coordinates at the left and right edges.
+    x_bounds: (f64, f64),
+    /// Logical y coordinates at the bottom and top edges.
+    y_bounds: (f64, f64),
+}
+
+impl Projection {
+    /// Map a canvas point to fractional pixel coordinates, with y flipped to grow downwards.
+    fn project(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let scale = |value: f64, (low, high): (f64, f64), pixels: u32| {
+            if high == low {
+                0.0
+            } else {
+                (value - low) / (high - low) * f64::from(pixels.saturating_sub(1))
+            }
+        };
+        (
+            scale(x, self.x_bounds, self.width),
+            f64::from(self.height.saturating_sub(1)) - scale(y, self.y_bounds, self.height),
+        )
+    }
+
+    /// Return the pixel that contains a canvas point, if it is inside the raster.
+    fn pixel(&self, point: (f64, f64)) -> Option<(u32, u32)> {
+        let (x, y) = self.project(point);
+        let (x, y) = (x.round(), y.round());
+        (x >= 0.0 && y >= 0.0 && x < f64::from(self.width) && y < f64::from(self.height))
+            .then_some((x as u32, y as u32))
+    }
+}
+
+/// A grid of optional pixel colors.
+struct Raster {
+    /// Mapping from canvas coordinates to pixels.
+    view: Projection,
+    /// Pixel colors in row-major order.
+    pixels: Vec<Option<Color>>,
+}
+
+impl Raster {
+    /// Create an empty raster.
+    fn new(view: Projection) -> Self {
+        Self {
+            view,
+            pixels: vec![None; (view.width * view.height) as usize],
+        }
+    }
+
+    /// Return the color of a pixel.
+    fn get(&self, x: u32, y: u32) -> Option<Color> {
+        if x >= self.view.width || y >= self.view.height {
+            return None;
+        }
+        self.pixels[(y * self.view.width + x) as usize]
+    }
+
+    /// Color a pixel, ignoring pixels outside the raster.
+    fn set(&mut self, x: f64, y: f64, color: Color) {
+        if x < 0.0 || y < 0.0 || x >= f64::from(self.view.width) || y >= f64::from(self.view.height)
+        {
+            return;
+        }
+        let index = y as usize * self.view.width as usize + x as usize;
+        self.pixels[index] = Some(color);
+    }
+
+    /// Rasterize a shape. Labels are drawn separately as text.
+    fn shape(&mut self, shape: &Shape, color: Color) {
+        match shape {
+            Shape::Points(points) => {
+                for point in points {
+                    let (x, y) = self.view.project(*point);
+                    self.set(x.round(), y.round(), color);
+                }
+            }
+            Shape::Line { from, to } => {
+                let (from, to) = (self.view.project(*from), self.view.project(*to));
+                self.line(from, to, color);
+            }
+            Shape::Rect { from, to, filled } => self.rect(*from, *to, *filled, color),
+            Shape::Circle { center, radius } => self.circle(*center, *radius, color),
+            Shape::Polygon(vertices) => {
+                let projected: Vec<_> = vertices.iter().map(|v| self.view.project(*v)).collect();
+                for (i, from) in projected.iter().enumerate() {
+                    let to = projected[(i + 1) % projected.len()];
+                    self.line(*from, to, color);
+                }
+            }
+            Shape::Label { .. } => {}
+        }
+    }
+
+    /// Draw a rectangle outline, or fill it.
+    fn rect(&mut self, from: (f64, f64), to: (f64, f64), filled: bool, color: Color) {
+        let (left, top) = self.view.project(from);
+        let (right, bottom) = self.view.project(to);
+        let (left, right) = (left.min(right).round(), left.max(right).round());
+        let (top, bottom) = (top.min(bottom).round(), top.max(bottom).round());
+        if filled {
+            let mut y = top.max(0.0);
+            while y <= bottom.min(f64::from(self.view.height) - 1.0) {
+                self.line((left, y), (right, y), color);
+                y += 1.0;
+            }
+            return;
+        }
+        self.line((left, top), (right, top), color);
+        self.line((right, top), (right, bottom), color);
+        self.line((right, bottom), (left, bottom), color);
+        self.line((left, bottom), (left, top), color);
+    }
+
+    /// Draw a circle outline as a closed path of short lines.
+    fn circle(&mut self, center: (f64, f64), radius: f64, color: Color) {
+        let (cx, cy) = self.view.project(center);
+        let (edge_x, _) = self.view.project((center.0 + radius, center.1));
+        let (_, edge_y) = self.view.project((center.0, center.1 + radius));
+        let (rx, ry) = ((edge_x - cx).abs(), (edge_y - cy).abs());
+        let steps = ((rx.max(ry) * TAU).ceil() as usize).clamp(8, 4096);
+        let point = |step: usize| {
+            let angle = TAU * step as f64 / steps as f64;
+            (cx + rx * angle.cos(), cy + ry * angle.sin())
+        };
+        for step in 0..steps {
+            self.line(point(step), point(step + 1), color);
+        }
+    }
+
+    /// Draw a line between fractional pixel coordinates with Bresenham's algorithm.
+    ///
+    /// The line is first clipped to the raster so that far-away endpoints cost nothing.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), color: Color) {
+        let Some((from, to)) = self.clip(from, to) else {
+            return;
+        };
+        let (mut x, mut y) = (from.0.round() as i64, from.1.round() as i64);
+        let (end_x, end_y) = (to.0.round() as i64, to.1.round() as i64);
+        let dx = (end_x - x).abs();
+        let dy = -(end_y - y).abs();
+        let step_x = if x < end_x { 1 } else { -1 };
+        let step_y = if y < end_y { 1 } else { -1 };
+        let mut error = dx + dy;
+        loop {
+            self.set(x as f64, y as f64, color);
+            if x == end_x && y == end_y {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    /// Clip a line to the raster with the Liang-Barsky algorithm.
+    fn clip(&self, from: (f64, f64), to: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+        if !(from.0.is_finite() && from.1.is_finite() && to.0.is_finite() && to.1.is_finite()) {
+            return None;
+        }
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let max_x = f64::from(self.view.width) - 0.5;
+        let max_y = f64::from(self.view.height) - 0.5;
+        let edges = [
+            (-dx, from.0 + 0.5),
+            (dx, max_x - from.0),
+            (-dy, from.1 + 0.5),
+            (dy, max_y - from.1),
+        ];
+        let (mut enter, mut leave) = (0.0f64, 1.0f64);
+        for (direction, distance) in edges {
+            if direction == 0.0 {
+                if distance < 0.0 {
+                    return None;
+                }
+                continue;
+            }
+            let t = distance / direction;
+            if direction < 0.0 {
+                enter = enter.max(t);
+            } else {
+                leave = leave.min(t);
+            }
+        }
+        (enter <= leave).then(|| {
+            (
+                (from.0 + enter * dx, from.1 + enter * dy),
+                (from.0 + leave * dx, from.1 + leave * dy),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_are_kept_in_layer_order() {
+        let point = |x| Shape::Points(vec![(x, 0.0)]);
+        let canvas = Canvas::new((0.0, 1.0), (0.0, 1.0))
+            .with_shape(1, "a", point(1.0))
+            .with_shape(0, "b", point(2.0))
+            .with_shape(1, "c", point(3.0))
+            .with_shape(-1, "d", point(4.0));
+        let order: Vec<_> = canvas.shapes().cloned().collect();
+        assert_eq!(order, vec![point(4.0), point(2.0), point(1.0), point(3.0)]);
+    }
+
+    #[test]
+    fn lines_are_clipped_to_the_raster() {
+        let mut raster = Raster::new(Projection {
+            width: 4,
+            height: 4,
+            x_bounds: (0.0, 3.0),
+            y_bounds: (0.0, 3.0),
+        });
+        raster.line((-1e12, 1.0), (1e12, 1.0), Color::White);
+        let row: Vec<_> = (0..4).map(|x| raster.get(x, 1)).collect();
+        assert_eq!(row, vec![Some(Color::White); 4]);
+        assert_eq!(raster.pixels.iter().flatten().count(), 4);
+
+        raster.line((10.0, 10.0), (20.0, 20.0), Color::White);
+        raster.line((f64::NAN, 0.0), (1.0, 1.0), Color::White);
+        assert_eq!(raster.pixels.iter().flatten().count(), 4);
+    }
+
+    #[test]
+    fn points_map_bounds_to_the_raster_edges() {
+        let view = Projection {
+            width: 8,
+            height: 8,
+            x_bounds: (-1.0, 1.0),
+            y_bounds: (10.0, 20.0),
+        };
+        assert_eq!(view.pixel((-1.0, 20.0)), Some((0, 0)));
+        assert_eq!(view.pixel((1.0, 10.0)), Some((7, 7)));
+        assert_eq!(view.pixel((2.0, 10.0)), None);
+    }
+}
@@ -0,0 +1,306 @@
+use canopy::{
+    Canopy, CommandEnum, Context, Loader, ViewContext, Widget, command, derive_commands,
+    error::Result,
+    geom::{Line, Point, Rect},
+    layout::Layout,
+    render::Render,
+};
+use unicode_width::UnicodeWidthStr;
+
+use super::{HORIZONTAL_EIGHTHS, VERTICAL_EIGHTHS, eighths, format_value, put, series_style};
+
+/// Direction that bars grow in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CommandEnum)]
+pub enum Orientation {
+    /// Bars grow upwards from labels along the bottom.
+    #[default]
+    Vertical,
+    /// Bars grow rightwards from labels along the left.
+    Horizontal,
+}
+
+/// One labelled bar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    /// Label drawn beside the bar.
+    pub label: String,
+    /// Bar value.
+    pub value: f64,
+    /// Style path for the bar, or `None` for the chart's bar style.
+    pub style: Option<String>,
+}
+
+impl Bar {
+    /// Create a bar with a label and a value.
+    pub fn new(label: impl Into<String>, value: f64) -> Self {
+        Self {
+            label: label.into(),
+            value,
+            style: None,
+        }
+    }
+
+    /// Configure the style path for this bar.
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+}
+
+/// Chart of labelled bars, filled in eighths of a cell.
+///
+/// Vertical bars are `bar_width` columns wide with their value above them and their label
+/// below. Horizontal bars take one row each, with their label on the left and their value
+/// after the bar. Bars grow from zero up to the maximum, which is the largest value unless
+/// fixed.
+pub struct BarChart {
+    /// Bars in display order.
+    bars: Vec<Bar>,
+    /// Direction the bars grow in.
+    orientation: Orientation,
+    /// Width of each vertical bar in columns.
+    bar_width: u32,
+    /// Gap between vertical bars in columns.
+    gap: u32,
+    /// Fixed value that fills a whole bar, or `None` to scale to the data.
+    max: Option<f64>,
+    /// Style path for bars without their own style.
+    style: String,
+    /// Style path for bar labels.
+    label_style: String,
+    /// Style path for value labels.
+    value_style: String,
+}
+
+#[derive_commands]
+impl BarChart {
+    /// Create an empty vertical bar chart.
+    pub fn new() -> Self {
+        Self {
+            bars: Vec::new(),
+            orientation: Orientation::default(),
+            bar_width: 3,
+            gap: 1,
+            max: None,
+            style: series_style(0),
+            label_style: String::from("chart/label"),
+            value_style: String::from("chart/value"),
+        }
+    }
+
+    /// Configure the direction the bars grow in.
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Configure the width of vertical bars and the gap between them, in columns.
+    pub fn with_bar_width(mut self, width: u32, gap: u32) -> Self {
+        self.bar_width = width.max(1);
+        self.gap = gap;
+        self
+    }
+
+    /// Fix the value that fills a whole bar.
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Configure the style path for bars without their own style.
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Configure the initial bars.
+    pub fn with_bars(mut self, bars: impl IntoIterator<Item = Bar>) -> Self {
+        self.bars = bars.into_iter().collect();
+        self
+    }
+
+    /// Return the bars in display order.
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    /// Set the value of the bar with a label, adding the bar at the end if it is new.
+    /// @param label The bar label.
+    /// @param value The new value.
+    #[command]
+    pub fn set_value(&mut self, _ctx: &mut dyn Context, label: String, value: f64) -> Result<()> {
+        match self.bars.iter_mut().find(|bar| bar.label == label) {
+            Some(bar) => bar.value = value,
+            None => self.bars.push(Bar::new(label, value)),
+        }
+        Ok(())
+    }
+
+    /// Remove the bar with a label.
+    /// @param label The bar label.
+    #[command]
+    pub fn remove(&mut self, _ctx: &mut dyn Context, label: String) -> Result<()> {
+        self.bars.retain(|bar| bar.label != label);
+        Ok(())
+    }
+
+    /// Remove every bar.
+    #[command]
+    pub fn clear(&mut self, _ctx: &mut dyn Context) -> Result<()> {
+        self.bars.clear();
+        Ok(())
+    }
+
+    /// Grow the bars in a direction.
+    /// @param orientation The direction bars grow in.
+    #[command]
+    pub fn set_orientation(
+        &mut self,
+        _ctx: &mut dyn Context,
+        orientation: Orientation,
+    ) -> Result<()> {
+        self.orientation = orientation;
+        Ok(())
+    }
+
+    /// Return the value that fills a whole bar.
+    fn scale_max(&self) -> f64 {
+        self.max.unwrap_or_else(|| {
+            self.bars
+                .iter()
+                .map(|bar| bar.value)
+                .filter(|value| value.is_finite())
+                .fold(0.0, f64::max)
+        })
+    }
+
+    /// Return the style path for a bar.
+    fn bar_style<'a>(&'a self, bar: &'a Bar) -> &'a str {
+        bar.style.as_deref().unwrap_or(&self.style)
+    }
+
+    /// Draw bars growing upwards.
+    fn render_vertical(&self, r: &mut Render, area: Rect) -> Result<()> {
+        let rows = area.h.saturating_sub(1);
+        let max = self.scale_max();
+        for (i, bar) in self.bars.iter().enumerate() {
+            let left = area.tl.x + i as u32 * (self.bar_width + self.gap);
+            if left >= area.tl.x + area.w {
+                break;
+            }
+            let height = fraction(bar.value, max) * f64::from(rows) * 8.0;
+            for row in 0..rows {
+                let glyph = eighths(&VERTICAL_EIGHTHS, height - f64::from(row) * 8.0);
+                for x in left..left + self.bar_width {
+                    let point = Point {
+                        x,
+                        y: area.tl.y + rows - 1 - row,
+                    };
+                    put(r, self.bar_style(bar), area, point, glyph)?;
+                }
+            }
+
+            let filled_rows = (height / 8.0).ceil() as u32;
+            if filled_rows < rows {
+                let y = area.tl.y + rows - 1 - filled_rows;
+                let value = format_value(bar.value);
+                self.centered(r, &self.value_style, left, y, &value)?;
+            }
+            self.centered(r, &self.label_style, left, area.tl.y + rows, &bar.label)?;
+        }
+        Ok(())
+    }
+
+    /// Draw text centered over a vertical bar, truncated to the bar width.
+    fn centered(&self, r: &mut Render, style: &str, left: u32, y: u32, text: &str) -> Result<()> {
+        let width = (text.width() as u32).min(self.bar_width);
+        let x = left + (self.bar_width - width) / 2;
+        r.text(style, Line::new(x, y, width), text)
+    }
+
+    /// Draw bars growing rightwards.
+    fn render_horizontal(&self, r: &mut Render, area: Rect) -> Result<()> {
+        let label_width = self
+            .bars
+            .iter()
+            .map(|bar| bar.label.width() as u32)
+            .max()
+            .unwrap_or(0)
+            .min(area.w / 2);
+        let values: Vec<String> = self
+            .bars
+            .iter()
+            .map(|bar| format_value(bar.value))
+            .collect();
+        let value_width = values.iter().map(|v| v.width() as u32).max().unwrap_or(0);
+        let left = area.tl.x + label_width + u32::from(label_width > 0);
+        let columns = (area.tl.x + area.w)
+            .saturating_sub(left)
+            .saturating_sub(value_width + 1);
+        let max = self.scale_max();
+        for ((i, bar), value) in self.bars.iter().enumerate().zip(&values) {
+            let y = area.tl.y + i as u32;
+            if y >= area.tl.y + area.h {
+                break;
+            }
+            r.text(
+                &self.label_style,
+                Line::new(area.tl.x, y, label_width),
+                &bar.label,
+            )?;
+            let length = fraction(bar.value, max) * f64::from(columns) * 8.0;
+            for column in 0..columns {
+                let glyph = eighths(&HORIZONTAL_EIGHTHS, length - f64::from(column) * 8.0);
+                let point = Point {
+                    x: left + column,
+                    y,
+                };
+                put(r, self.bar_style(bar), area, point, glyph)?;
+            }
+            let end = left + (length / 8.0).ceil() as u32 + 1;
+            r.text(
+                &self.value_style,
+                Line::new(end, y, value.width() as u32),
+                value,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for BarChart {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        let area = ctx.view().view_rect_local();
+        match self.orientation {
+            Orientation::Vertical => self.render_vertical(r, area),
+            Orientation::Horizontal => self.render_horizontal(r, area),
+        }
+    }
+}
+
+impl Loader for BarChart {
+    /// Register commands for the bar chart widget.
+    fn load(cnpy: &mut Canopy) -> Result<()> {
+        cnpy.add_commands::<Self>()?;
+        Ok(())
+    }
+}
+
+/// Return how much of a bar a value fills, from 0 to 1.
+fn fraction(value: f64, max: f64) -> f64 {
+    if max > 0.0 && value.is_finite() {
+        (value / max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,152 @@
+use canopy::{
+    Canopy, Context, Loader, ViewContext, Widget, command, derive_commands, error::Result,
+    geom::Point, layout::Layout, render::Render, style::ResolvedStyle,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::{HORIZONTAL_EIGHTHS, eighths, format_value, put};
+
+/// Horizontal progress gauge with a centered label.
+///
+/// The bar fills from the left in eighths of a cell. The label defaults to the percentage and
+/// is drawn in reverse video where it overlaps the filled part.
+pub struct Gauge {
+    /// Filled fraction, from 0 to 1.
+    ratio: f64,
+    /// Label text, or `None` to show the percentage.
+    label: Option<String>,
+    /// Style path for the bar.
+    style: String,
+    /// Style path for the label.
+    label_style: String,
+}
+
+#[derive_commands]
+impl Gauge {
+    /// Create an empty gauge.
+    pub fn new() -> Self {
+        Self {
+            ratio: 0.0,
+            label: None,
+            style: String::from("chart/gauge"),
+            label_style: String::from("chart/gauge/label"),
+        }
+    }
+
+    /// Configure the filled fraction, clamped to the range 0 to 1.
+    pub fn with_ratio(mut self, ratio: f64) -> Self {
+        self.ratio = clamp_ratio(ratio);
+        self
+    }
+
+    /// Configure a fixed label in place of the percentage.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Configure the style paths for the bar and the label.
+    pub fn with_style(mut self, style: impl Into<String>, label_style: impl Into<String>) -> Self {
+        self.style = style.into();
+        self.label_style = label_style.into();
+        self
+    }
+
+    /// Return the filled fraction.
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// Set the filled fraction, clamped to the range 0 to 1.
+    /// @param ratio The filled fraction.
+    #[command]
+    pub fn set_ratio(&mut self, _ctx: &mut dyn Context, ratio: f64) -> Result<()> {
+        self.ratio = clamp_ratio(ratio);
+        Ok(())
+    }
+
+    /// Set the filled fraction as a percentage.
+    /// @param percent The filled percentage, from 0 to 100.
+    #[command]
+    pub fn set_percent(&mut self, _ctx: &mut dyn Context, percent: f64) -> Result<()> {
+        self.ratio = clamp_ratio(percent / 100.0);
+        Ok(())
+    }
+
+    /// Set a fixed label, or show the percentage again when the label is empty.
+    /// @param label The label text.
+    #[command]
+    pub fn set_label(&mut self, _ctx: &mut dyn Context, label: String) -> Result<()> {
+        self.label = (!label.is_empty()).then_some(label);
+        Ok(())
+    }
+
+    /// Return the label text.
+    fn label_text(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| format!("{}%", format_value((self.ratio * 100.0).round())))
+    }
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for Gauge {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        let area = ctx.view().view_rect_local();
+        let filled = self.ratio * f64::from(area.w) * 8.0;
+        for column in 0..area.w {
+            let glyph = eighths(&HORIZONTAL_EIGHTHS, filled - f64::from(column) * 8.0);
+            for row in 0..area.h {
+                let point = Point {
+                    x: area.tl.x + column,
+                    y: area.tl.y + row,
+                };
+                put(r, &self.style, area, point, glyph)?;
+            }
+        }
+
+        let label = self.label_text();
+        let width = label.width() as u32;
+        let mut x = area.tl.x + area.w.saturating_sub(width) / 2;
+        let y = area.tl.y + area.h / 2;
+        let bar = r.resolve_style_name_at(&self.style, area, area.tl);
+        for grapheme in label.graphemes(true) {
+            let point = Point { x, y };
+            let style = if f64::from(x - area.tl.x + 1) * 8.0 <= filled {
+                ResolvedStyle::new(bar.bg, bar.fg, bar.attrs)
+            } else {
+                r.resolve_style_name_at(&self.label_style, area, point)
+            };
+            r.put_grapheme(style, point, grapheme)?;
+            x += grapheme.width() as u32;
+        }
+        Ok(())
+    }
+}
+
+impl Loader for Gauge {
+    /// Register commands for the gauge widget.
+    fn load(cnpy: &mut Canopy) -> Result<()> {
+        cnpy.add_commands::<Self>()?;
+        Ok(())
+    }
+}
+
+/// Clamp a fraction to the range 0 to 1, treating NaN as empty.
+fn clamp_ratio(ratio: f64) -> f64 {
+    if ratio.is_nan() {
+        0.0
+    } else {
+        ratio.clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,356 @@
+use canopy::{
+    Canopy, Context, Loader, ViewContext, Widget, command, derive_commands,
+    error::{Error, Result},
+    geom::{Line, Point, Rect},
+    layout::Layout,
+    render::{Render, SubCell},
+};
+use unicode_width::UnicodeWidthStr;
+
+use super::{Samples, extent, format_value, put, series_style, ticks};
+use crate::canvas::{Canvas, Shape};
+
+/// Default number of points a series keeps.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// A named sequence of points in a line chart.
+#[derive(Debug, Clone)]
+pub struct Series {
+    /// Name shown in the legend.
+    name: String,
+    /// Points, oldest first.
+    points: Samples<(f64, f64)>,
+    /// Style path for the line, or `None` for `chart/series/<index>`.
+    style: Option<String>,
+}
+
+impl Series {
+    /// Create an empty series.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            points: Samples::new(DEFAULT_CAPACITY),
+            style: None,
+        }
+    }
+
+    /// Configure the number of points kept.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.points.set_capacity(capacity);
+        self
+    }
+
+    /// Configure the initial points.
+    pub fn with_points(mut self, points: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        self.points.set(points);
+        self
+    }
+
+    /// Configure the style path for the line.
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// Return the series name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the points, oldest first.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.points.values.iter().copied()
+    }
+}
+
+/// Line chart of one or more series, with axes, tick labels and a legend.
+///
+/// The y axis and its tick labels run down the left edge and the x axis and its tick labels
+/// along the bottom. Lines are drawn on a braille canvas by default. Each axis spans the data
+/// unless its bounds are fixed, so streamed points scroll the x axis along.
+pub struct LineChart {
+    /// Series in legend order.
+    series: Vec<Series>,
+    /// Fixed x bounds, or `None` to span the data.
+    x_bounds: Option<(f64, f64)>,
+    /// Fixed y bounds, or `None` to span the data.
+    y_bounds: Option<(f64, f64)>,
+    /// Glyph grid used to draw lines.
+    grid: SubCell,
+    /// Whether to draw the legend.
+    legend: bool,
+    /// Style path for the axes and tick labels.
+    axis_style: String,
+    /// Style path for legend names.
+    legend_style: String,
+}
+
+#[derive_commands]
+impl LineChart {
+    /// Create a line chart with no series.
+    pub fn new() -> Self {
+        Self {
+            series: Vec::new(),
+            x_bounds: None,
+            y_bounds: None,
+            grid: SubCell::Braille,
+            legend: true,
+            axis_style: String::from("chart/axis"),
+            legend_style: String::from("chart/legend"),
+        }
+    }
+
+    /// Add a series, returning the chart for chaining.
+    pub fn with_series(mut self, series: Series) -> Self {
+        self.series.push(series);
+        self
+    }
+
+    /// Fix the x bounds.
+    pub fn with_x_bounds(mut self, low: f64, high: f64) -> Self {
+        self.x_bounds = Some((low, high));
+        self
+    }
+
+    /// Fix the y bounds.
+    pub fn with_y_bounds(mut self, low: f64, high: f64) -> Self {
+        self.y_bounds = Some((low, high));
+        self
+    }
+
+    /// Configure the glyph grid used to draw lines.
+    pub fn with_grid(mut self, grid: SubCell) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Configure whether to draw the legend.
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    /// Add a series and return its index.
+    pub fn add_series(&mut self, series: Series) -> usize {
+        self.series.push(series);
+        self.series.len() - 1
+    }
+
+    /// Return the series in legend order.
+    pub fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    /// Return a series by index, or an error naming the index.
+    fn series_mut(&mut self, index: usize) -> Result<&mut Series> {
+        self.series
+            .get_mut(index)
+            .ok_or_else(|| Error::Invalid(format!("line chart has no series {index}")))
+    }
+
+    /// Append a point to a series, dropping its oldest point past the capacity.
+    /// @param series The series index.
+    /// @param x The x coordinate.
+    /// @param y The y coordinate.
+    #[command]
+    pub fn push(&mut self, _ctx: &mut dyn Context, series: usize, x: f64, y: f64) -> Result<()> {
+        self.series_mut(series)?.points.push((x, y));
+        Ok(())
+    }
+
+    /// Append a value to a series at the x coordinate after its last point.
+    /// @param series The series index.
+    /// @param y The y coordinate.
+    #[command]
+    pub fn push_value(&mut self, _ctx: &mut dyn Context, series: usize, y: f64) -> Result<()> {
+        let points = &mut self.series_mut(series)?.points;
+        let x = points.values.back().map_or(0.0, |(x, _)| x + 1.0);
+        points.push((x, y));
+        Ok(())
+    }
+
+    /// Remove every point from every series.
+    #[command]
+    pub fn clear(&mut self, _ctx: &mut dyn Context) -> Result<()> {
+        for series in &mut self.series {
+            series.points.values.clear();
+        }
+        Ok(())
+    }
+
+    /// Return the x and y bounds, spanning the data where they are not fixed.
+    fn bounds(&self) -> ((f64, f64), (f64, f64)) {
+        let points = || self.series.iter().flat_map(Series::points);
+        (
+            self.x_bounds
+                .unwrap_or_else(|| extent(points().map(|(x, _)| x))),
+            self.y_bounds
+                .unwrap_or_else(|| extent(points().map(|(_, y)| y))),
+        )
+    }
+
+    /// Build a canvas with one line per series.
+    fn canvas(&self, x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Canvas {
+        let mut canvas = Canvas::new(x_bounds, y_bounds)
+            .with_grid(self.grid)
+            .with_style("chart");
+        for (i, series) in self.series.iter().enumerate() {
+            let style = series.style.clone().unwrap_or_else(|| series_style(i));
+            let layer = i as i32;
+            let mut points = series.points();
+            let Some(mut from) = points.next() else {
+                continue;
+            };
+            if series.points.values.len() == 1 {
+                canvas.draw(layer, style, Shape::Points(vec![from]));
+                continue;
+            }
+            for to in points {
+                canvas.draw(layer, style.clone(), Shape::Line { from, to });
+                from = to;
+            }
+        }
+        canvas
+    }
+
+    /// Draw the y axis with tick marks and labels to the left of the plot.
+    fn render_y_axis(
+        &self,
+        r: &mut Render,
+        canvas: &Canvas,
+        plot: Rect,
+        labels: &[(f64, String)],
+    ) -> Result<()> {
+        let x = plot.tl.x - 1;
+        for y in plot.tl.y..plot.tl.y + plot.h {
+            put(r, &self.axis_style, plot, Point { x, y }, '│')?;
+        }
+        let x_low = canvas.bounds().0.0;
+        for (value, label) in labels {
+            let Some(cell) = canvas.cell(plot, (x_low, *value)) else {
+                continue;
+            };
+            put(r, &self.axis_style, plot, Point { x, y: cell.y }, '┤')?;
+            let width = label.width() as u32;
+            r.text(
+                &self.axis_style,
+                Line::new(x.saturating_sub(width), cell.y, width),
+                label,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Draw the x axis with tick marks and labels below the plot.
+    fn render_x_axis(&self, r: &mut Render, canvas: &Canvas, plot: Rect, right: u32) -> Result<()> {
+        let ((x_low, x_high), (y_low, _)) = canvas.bounds();
+        let y = plot.tl.y + plot.h;
+        put(
+            r,
+            &self.axis_style,
+            plot,
+            Point {
+                x: plot.tl.x - 1,
+                y,
+            },
+            '└',
+        )?;
+        for x in plot.tl.x..plot.tl.x + plot.w {
+            put(r, &self.axis_style, plot, Point { x, y }, '─')?;
+        }
+        let count = (plot.w / 8).max(2) as usize;
+        let mut free = plot.tl.x;
+        for value in ticks(x_low, x_high, count) {
+            let Some(cell) = canvas.cell(plot, (value, y_low)) else {
+                continue;
+            };
+            put(r, &self.axis_style, plot, Point { x: cell.x, y }, '┬')?;
+            let label = format_value(value);
+            let width = label.width() as u32;
+            let left = cell.x.saturating_sub(width / 2).max(free);
+            if left + width > right {
+                continue;
+            }
+            r.text(&self.axis_style, Line::new(left, y + 1, width), &label)?;
+            free = left + width + 1;
+        }
+        Ok(())
+    }
+
+    /// Draw the legend in the top right corner of the plot.
+    fn render_legend(&self, r: &mut Render, plot: Rect) -> Result<()> {
+        let width = self
+            .series
+            .iter()
+            .map(|series| series.name.width() as u32 + 2)
+            .max()
+            .unwrap_or(0);
+        if width > plot.w {
+            return Ok(());
+        }
+        let x = plot.tl.x + plot.w - width;
+        for (i, series) in self.series.iter().enumerate() {
+            let y = plot.tl.y + i as u32;
+            if y >= plot.tl.y + plot.h {
+                break;
+            }
+            let style = series.style.clone().unwrap_or_else(|| series_style(i));
+            put(r, &style, plot, Point { x, y }, '━')?;
+            r.text(
+                &self.legend_style,
+                Line::new(x + 1, y, width - 1),
+                &format!(" {}", series.name),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for LineChart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for LineChart {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        let area = ctx.view().view_rect_local();
+        let (x_bounds, y_bounds) = self.bounds();
+        let rows = area.h.saturating_sub(2);
+        let labels: Vec<(f64, String)> = ticks(y_bounds.0, y_bounds.1, (rows / 2).max(2) as usize)
+            .into_iter()
+            .map(|value| (value, format_value(value)))
+            .collect();
+        let gutter = labels
+            .iter()
+            .map(|(_, label)| label.width() as u32)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        if rows == 0 || area.w <= gutter {
+            return Ok(());
+        }
+        let plot = Rect::new(area.tl.x + gutter, area.tl.y, area.w - gutter, rows);
+
+        let canvas = self.canvas(x_bounds, y_bounds);
+        canvas.paint(r, plot)?;
+        self.render_y_axis(r, &canvas, plot, &labels)?;
+        self.render_x_axis(r, &canvas, plot, area.tl.x + area.w)?;
+        if self.legend && self.series.iter().any(|series| !series.name.is_empty()) {
+            self.render_legend(r, plot)?;
+        }
+        Ok(())
+    }
+}
+
+impl Loader for LineChart {
+    /// Register commands for the line chart widget.
+    fn load(cnpy: &mut Canopy) -> Result<()> {
+        cnpy.add_commands::<Self>()?;
+        Ok(())
+    }
+}
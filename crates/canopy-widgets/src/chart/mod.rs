@@ -0,0 +1,175 @@
+//! Chart widgets for dashboards: sparklines, bar charts, line charts and gauges.
+//!
+//! Charts take their colors from style paths. Data series use `chart/series/<index>`, axes
+//! use `chart/axis` and gauges use `chart/gauge`; the built-in themes give the first six
+//! series distinct colors.
+
+/// Horizontal and vertical bar charts.
+mod bar;
+/// Progress gauge.
+mod gauge;
+/// Multi-series line chart with axes.
+mod line;
+/// Single-row block sparkline.
+mod sparkline;
+
+use std::collections::VecDeque;
+
+pub use bar::{Bar, BarChart, Orientation};
+use canopy::{
+    error::Result,
+    geom::{Point, Rect},
+    render::Render,
+};
+pub use gauge::Gauge;
+pub use line::{LineChart, Series};
+pub use sparkline::Sparkline;
+
+/// Blocks filled from the bottom in eighths of a cell.
+const VERTICAL_EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Blocks filled from the left in eighths of a cell.
+const HORIZONTAL_EIGHTHS: [char; 9] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// Return the style path for a data series.
+pub fn series_style(index: usize) -> String {
+    format!("chart/series/{index}")
+}
+
+/// A bounded queue of recent samples. Pushing past the capacity drops the oldest sample.
+#[derive(Debug, Clone)]
+struct Samples<T> {
+    /// Samples, oldest first.
+    values: VecDeque<T>,
+    /// Maximum number of samples kept.
+    capacity: usize,
+}
+
+impl<T> Samples<T> {
+    /// Create an empty queue.
+    fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Change the capacity, dropping the oldest samples if needed.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    /// Append a sample.
+    fn push(&mut self, value: T) {
+        self.values.push_back(value);
+        self.trim();
+    }
+
+    /// Replace every sample.
+    fn set(&mut self, values: impl IntoIterator<Item = T>) {
+        self.values.clear();
+        self.values.extend(values);
+        self.trim();
+    }
+
+    /// Drop the oldest samples beyond the capacity.
+    fn trim(&mut self) {
+        while self.values.len() > self.capacity {
+            self.values.pop_front();
+        }
+    }
+}
+
+/// Return the glyph for a cell filled to `fill` eighths, clamped to a whole cell.
+fn eighths(glyphs: &[char; 9], fill: f64) -> char {
+    glyphs[fill.round().clamp(0.0, 8.0) as usize]
+}
+
+/// Draw one glyph with a named style.
+fn put(r: &mut Render, style: &str, area: Rect, point: Point, ch: char) -> Result<()> {
+    let resolved = r.resolve_style_name_at(style, area, point);
+    r.put_cell(resolved, point, ch)
+}
+
+/// Format a value for a label, dropping trailing zeros.
+fn format_value(value: f64) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        String::from("0")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Return evenly spaced tick values within a range, stepping by 1, 2 or 5 times a power of
+/// ten so that at most `count` ticks fit.
+fn ticks(low: f64, high: f64, count: usize) -> Vec<f64> {
+    if !(low.is_finite() && high.is_finite()) || high <= low || count == 0 {
+        return vec![low];
+    }
+    let rough = (high - low) / count.max(2).saturating_sub(1) as f64;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let first = (low / step).ceil() as i64;
+    let last = (high / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Return the low and high bounds of some values, widened when they are all equal.
+fn extent(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (low, high) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| {
+            (low.min(value), high.max(value))
+        });
+    if low > high {
+        (0.0, 1.0)
+    } else if low == high {
+        (low - 1.0, high + 1.0)
+    } else {
+        (low, high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn samples_drop_the_oldest_values_past_capacity() {
+        let mut samples = Samples::new(3);
+        for value in 0..5 {
+            samples.push(value);
+        }
+        assert_eq!(samples.values, [2, 3, 4]);
+        samples.set_capacity(2);
+        assert_eq!(samples.values, [3, 4]);
+        samples.set(0..10);
+        assert_eq!(samples.values, [8, 9]);
+    }
+
+    #[test]
+    fn ticks_step_by_round_numbers() {
+        assert_eq!(ticks(0.0, 10.0, 3), vec![0.0, 5.0, 10.0]);
+        assert_eq!(
+            ticks(0.0, 100.0, 6),
+            vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]
+        );
+        assert_eq!(ticks(-1.0, 1.0, 5), vec![-1.0, -0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(ticks(3.0, 3.0, 5), vec![3.0]);
+    }
+
+    #[test]
+    fn values_format_without_trailing_zeros() {
+        assert_eq!(format_value(10.0), "10");
+        assert_eq!(format_value(2.5), "2.5");
+        assert_eq!(format_value(0.126), "0.13");
+        assert_eq!(format_value(-0.001), "0");
+    }
+}
@@ -0,0 +1,137 @@
+use canopy::{
+    Canopy, Context, Loader, ViewContext, Widget, command, derive_commands, error::Result,
+    geom::Point, layout::Layout, render::Render,
+};
+
+use super::{Samples, VERTICAL_EIGHTHS, eighths, put, series_style};
+
+/// Default number of samples a sparkline keeps.
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Block sparkline of recent values, one column per sample and filled in eighths of a cell.
+///
+/// The newest samples are drawn at the right when there are more samples than columns. Bars
+/// grow from zero up to the maximum, which is the largest visible sample unless fixed.
+pub struct Sparkline {
+    /// Recent samples.
+    samples: Samples<f64>,
+    /// Fixed value that fills the full height, or `None` to scale to the data.
+    max: Option<f64>,
+    /// Style path for the bars.
+    style: String,
+}
+
+#[derive_commands]
+impl Sparkline {
+    /// Create an empty sparkline.
+    pub fn new() -> Self {
+        Self {
+            samples: Samples::new(DEFAULT_CAPACITY),
+            max: None,
+            style: series_style(0),
+        }
+    }
+
+    /// Configure the number of samples kept.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.samples.set_capacity(capacity);
+        self
+    }
+
+    /// Fix the value that fills the full height.
+    pub fn with_max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Configure the style path for the bars.
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    /// Configure the initial samples.
+    pub fn with_data(mut self, values: impl IntoIterator<Item = f64>) -> Self {
+        self.samples.set(values);
+        self
+    }
+
+    /// Return the samples, oldest first.
+    pub fn data(&self) -> impl Iterator<Item = f64> + '_ {
+        self.samples.values.iter().copied()
+    }
+
+    /// Append a sample, dropping the oldest one past the capacity.
+    /// @param value The new sample.
+    #[command]
+    pub fn push(&mut self, _ctx: &mut dyn Context, value: f64) -> Result<()> {
+        self.samples.push(value);
+        Ok(())
+    }
+
+    /// Replace every sample.
+    /// @param values The new samples, oldest first.
+    #[command]
+    pub fn set_data(&mut self, _ctx: &mut dyn Context, values: Vec<f64>) -> Result<()> {
+        self.samples.set(values);
+        Ok(())
+    }
+
+    /// Remove every sample.
+    #[command]
+    pub fn clear(&mut self, _ctx: &mut dyn Context) -> Result<()> {
+        self.samples.values.clear();
+        Ok(())
+    }
+}
+
+impl Default for Sparkline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for Sparkline {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        let area = ctx.view().view_rect_local();
+        let values = &self.samples.values;
+        let visible = values.range(values.len().saturating_sub(area.w as usize)..);
+        let max = self
+            .max
+            .unwrap_or_else(|| visible.clone().copied().fold(0.0, f64::max));
+        for (column, value) in visible.enumerate() {
+            let height = if max > 0.0 && value.is_finite() {
+                value.max(0.0) / max * f64::from(area.h) * 8.0
+            } else {
+                0.0
+            };
+            for row in 0..area.h {
+                let fill = height - f64::from(row) * 8.0;
+                let point = Point {
+                    x: area.tl.x + column as u32,
+                    y: area.tl.y + area.h - 1 - row,
+                };
+                put(
+                    r,
+                    &self.style,
+                    area,
+                    point,
+                    eighths(&VERTICAL_EIGHTHS, fill),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Loader for Sparkline {
+    /// Register commands for the sparkline widget.
+    fn load(cnpy: &mut Canopy) -> Result<()> {
+        cnpy.add_commands::<Self>()?;
+        Ok(())
+    }
+}
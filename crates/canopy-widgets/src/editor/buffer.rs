@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    time::SystemTime,
+};
 
 use ropey::Rope;
 use unicode_segmentation::UnicodeSegmentation;
@@ -6,8 +9,12 @@ use unicode_segmentation::UnicodeSegmentation;
 use super::{
     Selection, TextPosition, TextRange, display_width,
     edit::{Edit, Transaction},
+    history::{HistoryStep, UndoTree},
 };
 
+/// Maximum number of characters scanned when matching a bracket.
+const MAX_BRACKET_SCAN: usize = 100_000;
+
 /// Information about how an edit changed logical line counts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LineChange {
@@ -30,10 +37,8 @@ pub struct TextBuffer {
     revision: u64,
     /// Latest line change since the last sync.
     pending_change: Option<LineChange>,
-    /// Undo history.
-    undo: Vec<Transaction>,
-    /// Redo history.
-    redo: Vec<Transaction>,
+    /// Branching undo history.
+    history: UndoTree,
     /// Active transaction for grouped edits.
     transaction: Option<Transaction>,
 }
@@ -55,8 +60,7 @@ impl TextBuffer {
             selection,
             revision: 0,
             pending_change: None,
-            undo: Vec::new(),
-            redo: Vec::new(),
+            history: UndoTree::new(),
             transaction: None,
         }
     }
@@ -168,8 +172,7 @@ impl TextBuffer {
             return;
         }
         transaction.finish(self.selection);
-        self.undo.push(transaction);
-        self.redo.clear();
+        self.history.push(transaction, SystemTime::now());
     }
 
     /// Begin a grouped transaction that commits when the guard is dropped.
@@ -183,28 +186,48 @@ impl TextBuffer {
 
     /// Undo the most recent transaction.
     pub fn undo(&mut self) -> bool {
-        let Some(transaction) = self.undo.pop() else {
-            return false;
-        };
-        for edit in transaction.edits.iter().rev() {
-            self.apply_edit(edit, EditDirection::Undo);
+        self.commit_transaction();
+        match self.history.undo_target() {
+            Some(target) => self.travel_to(target),
+            None => false,
         }
-        self.selection = transaction.before;
-        self.redo.push(transaction);
-        true
     }
 
-    /// Redo the most recently undone transaction.
+    /// Redo along the most recently visited branch.
     pub fn redo(&mut self) -> bool {
-        let Some(transaction) = self.redo.pop() else {
-            return false;
-        };
-        for edit in &transaction.edits {
-            self.apply_edit(edit, EditDirection::Redo);
+        self.commit_transaction();
+        match self.history.redo_target() {
+            Some(target) => self.travel_to(target),
+            None => false,
         }
-        self.selection = transaction.after;
-        self.undo.push(transaction);
-        true
+    }
+
+    /// Move back through history states in creation order, across branches.
+    pub fn earlier(&mut self, step: HistoryStep) -> bool {
+        self.commit_transaction();
+        let target = self.history.earlier_target(step);
+        self.travel_to(target)
+    }
+
+    /// Move forward through history states in creation order, across branches.
+    pub fn later(&mut self, step: HistoryStep) -> bool {
+        self.commit_transaction();
+        let target = self.history.later_target(step);
+        self.travel_to(target)
+    }
+
+    /// Return the undo history.
+    pub fn history(&self) -> &UndoTree {
+        &self.history
+    }
+
+    /// Replace the undo history.
+    ///
+    /// The current state of the history must describe the buffer's text, as
+    /// checked by [`UndoTree::decode`] when given the same text.
+    pub fn set_history(&mut self, history: UndoTree) {
+        self.transaction = None;
+        self.history = history;
     }
 
     /// Insert text at the cursor, replacing any selection.
@@ -404,6 +427,58 @@ impl TextBuffer {
         Some(self.rope.slice(start_char..end_char).to_string())
     }
 
+    /// Find the bracket matching the one at a position.
+    ///
+    /// `pairs` lists opening and closing characters. The scan is bounded so it
+    /// stays cheap on large buffers.
+    pub fn matching_bracket(
+        &self,
+        pos: TextPosition,
+        pairs: &[(char, char)],
+    ) -> Option<TextPosition> {
+        let start = self.try_position_to_char(pos)?;
+        let c = self.rope.get_char(start)?;
+        let (open, close, forward) = pairs.iter().find_map(|&(open, close)| {
+            if c == open {
+                Some((open, close, true))
+            } else if c == close {
+                Some((open, close, false))
+            } else {
+                None
+            }
+        })?;
+        let (inner, outer) = if forward {
+            (open, close)
+        } else {
+            (close, open)
+        };
+        let mut chars = self.rope.chars_at(if forward { start } else { start + 1 });
+        let mut at = if forward { start } else { start + 1 };
+        let mut depth = 0usize;
+        for _ in 0..MAX_BRACKET_SCAN {
+            let ch = if forward {
+                let ch = chars.next()?;
+                at += 1;
+                ch
+            } else {
+                let ch = chars.prev()?;
+                at -= 1;
+                ch
+            };
+            if ch == inner {
+                depth += 1;
+            } else if ch == outer {
+                depth -= 1;
+                if depth == 0 {
+                    let idx = if forward { at - 1 } else { at };
+                    let line = self.rope.char_to_line(idx);
+                    return Some(TextPosition::new(line, idx - self.rope.line_to_char(line)));
+                }
+            }
+        }
+        None
+    }
+
     /// Record an edit into the active transaction or history.
     fn record_edit(&mut self, edit: Edit) {
         if let Some(transaction) = self.transaction.as_mut() {
@@ -412,8 +487,7 @@ impl TextBuffer {
             let mut transaction = Transaction::new(self.selection);
             transaction.edits.push(edit);
             transaction.finish(self.selection);
-            self.undo.push(transaction);
-            self.redo.clear();
+            self.history.push(transaction, SystemTime::now());
         }
     }
 
@@ -468,6 +542,34 @@ impl TextBuffer {
         }
     }
 
+    /// Undo and redo transactions to reach a history state.
+    fn travel_to(&mut self, target: usize) -> bool {
+        if target == self.history.current() {
+            return false;
+        }
+        let (undo, redo) = self.history.route(target);
+        for seq in undo {
+            let Some(transaction) = self.history.transaction(seq).cloned() else {
+                continue;
+            };
+            for edit in transaction.edits.iter().rev() {
+                self.apply_edit(edit, EditDirection::Undo);
+            }
+            self.selection = transaction.before;
+        }
+        for seq in redo {
+            let Some(transaction) = self.history.transaction(seq).cloned() else {
+                continue;
+            };
+            for edit in &transaction.edits {
+                self.apply_edit(edit, EditDirection::Redo);
+            }
+            self.selection = transaction.after;
+        }
+        self.history.set_current(target);
+        true
+    }
+
     /// Apply an edit in the specified direction.
     fn apply_edit(&mut self, edit: &Edit, direction: EditDirection) {
         let (remove_text, insert_text) = match direction {
@@ -536,7 +638,7 @@ enum EditDirection {
 }
 
 /// Advance a position by the text contents.
-fn advance_position(start: TextPosition, text: &str) -> TextPosition {
+pub(super) fn advance_position(start: TextPosition, text: &str) -> TextPosition {
     let mut line = start.line;
     let mut column = start.column;
     for (idx, part) in text.split('\n').enumerate() {
@@ -676,6 +778,52 @@ mod tests {
         assert_eq!(buf.text(), "abcd");
     }
 
+    #[test]
+    fn edits_after_undo_branch_history() {
+        let mut buf = TextBuffer::new("a");
+        buf.insert_text("b");
+        assert!(buf.undo());
+        buf.insert_text("c");
+        assert_eq!(buf.text(), "ac");
+        assert!(!buf.redo());
+
+        assert!(buf.earlier(HistoryStep::Count(1)));
+        assert_eq!(buf.text(), "ab");
+        assert!(buf.earlier(HistoryStep::Count(1)));
+        assert_eq!(buf.text(), "a");
+        assert!(buf.redo());
+        assert_eq!(buf.text(), "ab");
+        assert!(buf.later(HistoryStep::Count(5)));
+        assert_eq!(buf.text(), "ac");
+        assert_eq!(buf.history().len(), 3);
+    }
+
+    #[test]
+    fn matching_bracket_scans_nested_pairs() {
+        let buf = TextBuffer::new("f(a, (b)\n)[");
+        let pairs = [('(', ')'), ('[', ']')];
+        let open = TextPosition::new(0, 1);
+        let close = TextPosition::new(1, 0);
+        assert_eq!(buf.matching_bracket(open, &pairs), Some(close));
+        assert_eq!(buf.matching_bracket(close, &pairs), Some(open));
+        assert_eq!(
+            buf.matching_bracket(TextPosition::new(0, 7), &pairs),
+            Some(TextPosition::new(0, 5))
+        );
+        assert_eq!(buf.matching_bracket(TextPosition::new(1, 1), &pairs), None);
+        assert_eq!(buf.matching_bracket(TextPosition::new(0, 0), &pairs), None);
+
+        let unmatched = TextBuffer::new(")x\n[)");
+        assert_eq!(
+            unmatched.matching_bracket(TextPosition::new(0, 0), &pairs),
+            None
+        );
+        assert_eq!(
+            unmatched.matching_bracket(TextPosition::new(1, 1), &pairs),
+            None
+        );
+    }
+
     #[test]
     fn strict_accessors_reject_out_of_bounds_positions() {
         let buf = TextBuffer::new("ab\ncd");
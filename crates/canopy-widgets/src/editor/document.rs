@@ -0,0 +1,416 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    result::Result as StdResult,
+    str,
+    time::SystemTime,
+};
+
+use super::{TextBuffer, UndoTree};
+use crate::error::{Error, Result};
+
+/// Byte order mark prefix for UTF-8 files.
+const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
+/// Suffix appended to the temporary file used for atomic saves.
+const SAVE_SUFFIX: &str = ".canopy-save";
+/// Suffix appended to the hidden file holding persisted undo history.
+const UNDO_SUFFIX: &str = ".canopy-undo";
+
+/// Line terminator used when writing a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// Unix line endings (`\n`).
+    #[default]
+    Lf,
+    /// Windows line endings (`\r\n`).
+    CrLf,
+}
+
+impl LineEnding {
+    /// Return the terminator as a string.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+}
+
+/// On-disk representation details preserved across a load and save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentFormat {
+    /// Line terminator used when writing.
+    pub line_ending: LineEnding,
+    /// Whether the file starts with a UTF-8 byte order mark.
+    pub bom: bool,
+    /// Whether the file ends with a line terminator.
+    pub trailing_newline: bool,
+}
+
+impl Default for DocumentFormat {
+    fn default() -> Self {
+        Self {
+            line_ending: LineEnding::Lf,
+            bom: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+impl DocumentFormat {
+    /// Decode file bytes into buffer text, detecting the file format.
+    ///
+    /// Buffer text always uses `\n` and omits the final line terminator; the
+    /// returned format records what is needed to write the bytes back unchanged.
+    pub fn decode(bytes: &[u8]) -> StdResult<(Self, String), str::Utf8Error> {
+        let (bom, body) = match bytes.strip_prefix(UTF8_BOM) {
+            Some(body) => (true, body),
+            None => (false, bytes),
+        };
+        let raw = str::from_utf8(body)?;
+        let crlf = raw.matches("\r\n").count();
+        let lf = raw.matches('\n').count().saturating_sub(crlf);
+        let line_ending = if crlf > lf {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        };
+        let mut text = if crlf > 0 {
+            raw.replace("\r\n", "\n")
+        } else {
+            raw.to_string()
+        };
+        let trailing_newline = text.ends_with('\n');
+        if trailing_newline {
+            text.pop();
+        }
+        Ok((
+            Self {
+                line_ending,
+                bom,
+                trailing_newline,
+            },
+            text,
+        ))
+    }
+
+    /// Encode buffer text into file bytes using this format.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let mut out = Vec::with_capacity(text.len() + UTF8_BOM.len() + 2);
+        if self.bom {
+            out.extend_from_slice(UTF8_BOM);
+        }
+        let terminator = self.line_ending.as_str().as_bytes();
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                out.extend_from_slice(terminator);
+            }
+            out.extend_from_slice(line.as_bytes());
+        }
+        if self.trailing_newline {
+            out.extend_from_slice(terminator);
+        }
+        out
+    }
+}
+
+/// File metadata used to detect modifications made outside the editor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct DiskStamp {
+    /// Last modification time, when the platform reports one.
+    modified: Option<SystemTime>,
+    /// File length in bytes.
+    len: u64,
+}
+
+impl DiskStamp {
+    /// Read the stamp for a path, returning `None` when the file does not exist.
+    fn read(path: &Path) -> io::Result<Option<Self>> {
+        match fs::metadata(path) {
+            Ok(meta) => Ok(Some(Self {
+                modified: meta.modified().ok(),
+                len: meta.len(),
+            })),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// A file-backed document for an editor buffer.
+///
+/// The document owns the file path and format and tracks which buffer revision
+/// was last written, so the editor can report unsaved changes.
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// File path backing the document.
+    path: PathBuf,
+    /// On-disk format to use when saving.
+    format: DocumentFormat,
+    /// Buffer revision that matches the file contents, if any.
+    saved_revision: Option<u64>,
+    /// File metadata observed at the last load or save.
+    stamp: Option<DiskStamp>,
+}
+
+impl Document {
+    /// Construct a document for a file that has not been written yet.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            format: DocumentFormat::default(),
+            saved_revision: None,
+            stamp: None,
+        }
+    }
+
+    /// Load a document from disk, returning it with its decoded text.
+    ///
+    /// A missing file yields an empty new document.
+    pub fn load(path: impl Into<PathBuf>) -> Result<(Self, String)> {
+        let mut doc = Self::new(path);
+        let text = doc.read()?;
+        Ok((doc, text))
+    }
+
+    /// Return the file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the on-disk format.
+    pub fn format(&self) -> DocumentFormat {
+        self.format
+    }
+
+    /// Replace the on-disk format used by the next save.
+    pub fn set_format(&mut self, format: DocumentFormat) {
+        self.format = format;
+        self.saved_revision = None;
+    }
+
+    /// Return true when the buffer has changed since the last load or save.
+    pub fn is_dirty(&self, buffer: &TextBuffer) -> bool {
+        self.saved_revision != Some(buffer.revision())
+    }
+
+    /// Return true when the file changed on disk since the last load or save.
+    pub fn modified_on_disk(&self) -> Result<bool> {
+        let current = DiskStamp::read(&self.path).map_err(|err| self.io_error(err))?;
+        Ok(current != self.stamp)
+    }
+
+    /// Write the buffer to disk atomically.
+    ///
+    /// Unless `force` is set, the save is refused when the file changed on disk
+    /// since it was loaded.
+    pub fn save(&mut self, buffer: &TextBuffer, force: bool) -> Result<()> {
+        if !force && self.modified_on_disk()? {
+            return Err(Error::ModifiedOnDisk(self.path.clone()));
+        }
+        let bytes = self.format.encode(&buffer.text());
+        write_atomic(&self.path, &bytes).map_err(|err| self.io_error(err))?;
+        self.stamp = DiskStamp::read(&self.path).map_err(|err| self.io_error(err))?;
+        self.saved_revision = Some(buffer.revision());
+        Ok(())
+    }
+
+    /// Re-read the file, returning its decoded text.
+    ///
+    /// The caller installs the text and then calls [`Document::mark_saved`].
+    pub fn reload(&mut self) -> Result<String> {
+        self.read()
+    }
+
+    /// Record the buffer revision that matches the file contents.
+    pub fn mark_saved(&mut self, buffer: &TextBuffer) {
+        self.saved_revision = Some(buffer.revision());
+    }
+
+    /// Return the path of the hidden undo file stored next to the document.
+    pub fn undo_path(&self) -> PathBuf {
+        sibling_path(&self.path, UNDO_SUFFIX)
+    }
+
+    /// Write the buffer's undo history next to the document.
+    pub fn save_history(&self, buffer: &TextBuffer) -> Result<()> {
+        let bytes = buffer.history().encode(&buffer.text())?;
+        write_atomic(&self.undo_path(), &bytes).map_err(|err| self.io_error(err))
+    }
+
+    /// Read undo history saved for the buffer's current text.
+    ///
+    /// Returns `None` when there is no undo file or it does not match the text.
+    pub fn load_history(&self, buffer: &TextBuffer) -> Result<Option<UndoTree>> {
+        match fs::read(self.undo_path()) {
+            Ok(bytes) => Ok(UndoTree::decode(&bytes, &buffer.text())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(self.io_error(err)),
+        }
+    }
+
+    /// Forget the saved revision so the document reports as dirty.
+    pub(super) fn mark_dirty(&mut self) {
+        self.saved_revision = None;
+    }
+
+    /// Read and decode the file, updating format and disk metadata.
+    fn read(&mut self) -> Result<String> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                self.format = DocumentFormat::default();
+                self.stamp = None;
+                return Ok(String::new());
+            }
+            Err(err) => return Err(self.io_error(err)),
+        };
+        let (format, text) = DocumentFormat::decode(&bytes)
+            .map_err(|_| Error::InvalidEncoding(self.path.clone()))?;
+        self.format = format;
+        self.stamp = DiskStamp::read(&self.path).map_err(|err| self.io_error(err))?;
+        Ok(text)
+    }
+
+    /// Wrap an I/O error with the document path.
+    fn io_error(&self, source: io::Error) -> Error {
+        Error::DocumentIo {
+            path: self.path.clone(),
+            source,
+        }
+    }
+}
+
+/// Return a hidden sibling path: `dir/name` becomes `dir/.name{suffix}`.
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Write bytes to a sibling temporary file and rename it over the target.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if path.file_name().is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "path has no file name",
+        ));
+    }
+    let tmp = sibling_path(path, SAVE_SUFFIX);
+    let result = write_and_rename(&tmp, path, bytes);
+    if result.is_err() {
+        fs::remove_file(&tmp).ok();
+    }
+    result
+}
+
+/// Write the temporary file, carry over permissions and move it into place.
+fn write_and_rename(tmp: &Path, path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut file = File::create(tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+    if let Ok(meta) = fs::metadata(path) {
+        fs::set_permissions(tmp, meta.permissions())?;
+    }
+    fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_detects_crlf_bom_and_trailing_newline() {
+        let (format, text) = DocumentFormat::decode(b"\xef\xbb\xbfone\r\ntwo\r\n").unwrap();
+        assert_eq!(text, "one\ntwo");
+        assert_eq!(
+            format,
+            DocumentFormat {
+                line_ending: LineEnding::CrLf,
+                bom: true,
+                trailing_newline: true,
+            }
+        );
+        assert_eq!(format.encode(&text), b"\xef\xbb\xbfone\r\ntwo\r\n");
+    }
+
+    #[test]
+    fn decode_roundtrips_without_trailing_newline() {
+        let (format, text) = DocumentFormat::decode(b"a\nb").unwrap();
+        assert_eq!(text, "a\nb");
+        assert!(!format.trailing_newline);
+        assert_eq!(format.line_ending, LineEnding::Lf);
+        assert_eq!(format.encode(&text), b"a\nb");
+    }
+
+    #[test]
+    fn decode_rejects_invalid_utf8() {
+        assert!(DocumentFormat::decode(b"\xff\xfe").is_err());
+    }
+
+    #[test]
+    fn save_tracks_dirty_state_and_external_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.txt");
+        fs::write(&path, "hello\r\n").unwrap();
+
+        let (mut doc, text) = Document::load(&path).unwrap();
+        let mut buffer = TextBuffer::new(text);
+        doc.mark_saved(&buffer);
+        assert!(!doc.is_dirty(&buffer));
+
+        buffer.insert_text("!");
+        assert!(doc.is_dirty(&buffer));
+        doc.save(&buffer, false).unwrap();
+        assert!(!doc.is_dirty(&buffer));
+        assert_eq!(fs::read(&path).unwrap(), b"hello!\r\n");
+
+        fs::write(&path, "changed elsewhere\n").unwrap();
+        assert!(doc.modified_on_disk().unwrap());
+        assert!(matches!(
+            doc.save(&buffer, false),
+            Err(Error::ModifiedOnDisk(_))
+        ));
+        doc.save(&buffer, true).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"hello!\r\n");
+    }
+
+    #[test]
+    fn history_roundtrips_next_to_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("doc.txt");
+        let mut doc = Document::new(&path);
+        assert_eq!(doc.undo_path(), dir.path().join(".doc.txt.canopy-undo"));
+
+        let mut buffer = TextBuffer::new("a");
+        buffer.insert_text("b");
+        doc.save(&buffer, false).unwrap();
+        doc.save_history(&buffer).unwrap();
+
+        let (doc, text) = Document::load(&path).unwrap();
+        let mut reopened = TextBuffer::new(text);
+        let history = doc.load_history(&reopened).unwrap().unwrap();
+        reopened.set_history(history);
+        assert!(reopened.undo());
+        assert_eq!(reopened.text(), "a");
+
+        let other = TextBuffer::new("changed");
+        assert!(doc.load_history(&other).unwrap().is_none());
+    }
+
+    #[test]
+    fn load_missing_file_creates_new_document() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new.txt");
+        let (mut doc, text) = Document::load(&path).unwrap();
+        assert_eq!(text, "");
+        let buffer = TextBuffer::new("fresh");
+        assert!(doc.is_dirty(&buffer));
+        doc.save(&buffer, false).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fresh\n");
+    }
+}
@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use super::{Selection, TextRange};
 
 /// A single text edit applied to the buffer.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Edit {
     /// Range replaced by the edit (before applying it).
     pub range: TextRange,
@@ -23,7 +25,7 @@ impl Edit {
 }
 
 /// A group of edits that form a single undo/redo step.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// Edits in the order they were applied.
     pub edits: Vec<Edit>,
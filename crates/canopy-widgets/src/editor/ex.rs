@@ -0,0 +1,117 @@
+use canopy::Context;
+
+use super::{HistoryStep, search::PromptState, widget::Editor};
+
+/// Commands accepted on the vi `:` command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ExCommand {
+    /// `:w` writes the document; `:w!` overwrites external changes.
+    Write {
+        /// Whether to ignore changes made on disk.
+        force: bool,
+    },
+    /// `:e` reloads the document; `:e!` discards unsaved edits.
+    Edit {
+        /// Whether to discard unsaved edits.
+        force: bool,
+    },
+    /// `:earlier {N}|{N}s|{N}m|{N}h|{N}d` moves back through undo history.
+    Earlier(HistoryStep),
+    /// `:later {N}|{N}s|{N}m|{N}h|{N}d` moves forward through undo history.
+    Later(HistoryStep),
+}
+
+impl ExCommand {
+    /// Parse a command line, without its leading colon.
+    pub(super) fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (name, arg) = input.split_once(' ').unwrap_or((input, ""));
+        match name {
+            "ea" | "earlier" => return HistoryStep::parse(arg).map(Self::Earlier),
+            "lat" | "later" => return HistoryStep::parse(arg).map(Self::Later),
+            _ if !arg.is_empty() => return None,
+            _ => {}
+        }
+        let (name, force) = match input.strip_suffix('!') {
+            Some(name) => (name, true),
+            None => (input, false),
+        };
+        match name {
+            "w" | "write" => Some(Self::Write { force }),
+            "e" | "edit" => Some(Self::Edit { force }),
+            _ => None,
+        }
+    }
+}
+
+impl Editor {
+    /// Start the ex command-line prompt.
+    pub(super) fn start_ex_prompt(&mut self) {
+        self.prompt = Some(PromptState::Ex {
+            input: String::new(),
+        });
+    }
+
+    /// Run an ex command line, leaving any failure as a prompt message.
+    pub(super) fn run_ex_command(&mut self, input: &str, ctx: &mut dyn Context) {
+        let result = match ExCommand::parse(input) {
+            Some(ExCommand::Write { force }) => {
+                self.save_document(force).map_err(|e| e.to_string())
+            }
+            Some(ExCommand::Edit { force }) => {
+                self.reload_document(force).map_err(|e| e.to_string())
+            }
+            Some(ExCommand::Earlier(step)) => {
+                self.buffer.earlier(step);
+                self.update_preferred_column();
+                Ok(())
+            }
+            Some(ExCommand::Later(step)) => {
+                self.buffer.later(step);
+                self.update_preferred_column();
+                Ok(())
+            }
+            None => Err(format!("not an editor command: {}", input.trim())),
+        };
+        self.ensure_cursor_visible(ctx);
+        self.prompt = result.err().map(|text| PromptState::Message { text });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn parse_recognizes_force_suffix() {
+        assert_eq!(
+            ExCommand::parse("w"),
+            Some(ExCommand::Write { force: false })
+        );
+        assert_eq!(
+            ExCommand::parse(" write! "),
+            Some(ExCommand::Write { force: true })
+        );
+        assert_eq!(
+            ExCommand::parse("e!"),
+            Some(ExCommand::Edit { force: true })
+        );
+        assert_eq!(ExCommand::parse("q"), None);
+    }
+
+    #[test]
+    fn parse_history_steps() {
+        assert_eq!(
+            ExCommand::parse("earlier"),
+            Some(ExCommand::Earlier(HistoryStep::Count(1)))
+        );
+        assert_eq!(
+            ExCommand::parse("lat 10s"),
+            Some(ExCommand::Later(HistoryStep::Time(Duration::from_secs(10))))
+        );
+        assert_eq!(ExCommand::parse("earlier 3q"), None);
+        assert_eq!(ExCommand::parse("w now"), None);
+    }
+}
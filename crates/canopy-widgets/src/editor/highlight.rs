@@ -132,6 +132,7 @@ fn map_style(style: SyntectStyle) -> Style {
         fg: Paint::solid(map_color(style.foreground)),
         bg: Paint::solid(map_color(style.background)),
         attrs,
+        underline: None,
     }
 }
 
@@ -0,0 +1,364 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use super::edit::Transaction;
+use crate::error::{Error, Result};
+
+/// Version tag written to persisted undo files.
+const UNDO_FILE_VERSION: u32 = 1;
+
+/// Amount of history to move through with `earlier` and `later`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryStep {
+    /// A number of states, in the order they were created.
+    Count(usize),
+    /// A span of wall-clock time.
+    Time(Duration),
+}
+
+impl HistoryStep {
+    /// Parse a vim-style amount such as `3`, `10s`, `5m`, `2h` or `1d`.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Some(Self::Count(1));
+        }
+        let (digits, unit) = match input.find(|c: char| !c.is_ascii_digit()) {
+            Some(idx) => input.split_at(idx),
+            None => (input, ""),
+        };
+        let amount: u64 = digits.parse().ok()?;
+        let seconds = match unit {
+            "" => return usize::try_from(amount).ok().map(Self::Count),
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        Some(Self::Time(Duration::from_secs(
+            amount.checked_mul(seconds)?,
+        )))
+    }
+}
+
+/// One state in the undo tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryNode {
+    /// Parent state, or `None` for the original text.
+    parent: Option<usize>,
+    /// Transaction that leads from the parent to this state.
+    transaction: Option<Transaction>,
+    /// Wall-clock time the state was created.
+    time: SystemTime,
+    /// Child followed by redo: the branch most recently visited.
+    redo_child: Option<usize>,
+}
+
+/// A read-only summary of one undo state, used for inspection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// State number in creation order; `0` is the original text.
+    pub seq: usize,
+    /// Parent state number.
+    pub parent: Option<usize>,
+    /// Wall-clock time the state was created.
+    pub time: SystemTime,
+    /// Number of edits in the transaction leading to the state.
+    pub edits: usize,
+    /// Whether the buffer is currently at this state.
+    pub current: bool,
+}
+
+/// Branching undo history.
+///
+/// Every committed transaction becomes a new state whose parent is the state
+/// it was applied to, so undoing and then editing starts a new branch instead
+/// of discarding the redo history. States are numbered in creation order,
+/// which is the order `earlier` and `later` walk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoTree {
+    /// States in creation order; index `0` is the original text.
+    nodes: Vec<HistoryNode>,
+    /// State the buffer currently matches.
+    current: usize,
+}
+
+/// On-disk format for persisted undo history.
+#[derive(Serialize, Deserialize)]
+struct UndoFile {
+    /// Format version.
+    version: u32,
+    /// Hash of the text the history ends at.
+    text_hash: u64,
+    /// Persisted history.
+    tree: UndoTree,
+}
+
+impl Default for UndoTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UndoTree {
+    /// Construct a history holding only the original state.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![HistoryNode {
+                parent: None,
+                transaction: None,
+                time: SystemTime::now(),
+                redo_child: None,
+            }],
+            current: 0,
+        }
+    }
+
+    /// Return the current state number.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Return the number of states, including the original text.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return true when no transaction has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.len() == 1
+    }
+
+    /// Summarize every state in creation order.
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(seq, node)| HistoryEntry {
+                seq,
+                parent: node.parent,
+                time: node.time,
+                edits: node.transaction.as_ref().map_or(0, |t| t.edits.len()),
+                current: seq == self.current,
+            })
+            .collect()
+    }
+
+    /// Record a transaction as a new child of the current state.
+    pub(super) fn push(&mut self, transaction: Transaction, time: SystemTime) {
+        let seq = self.nodes.len();
+        self.nodes.push(HistoryNode {
+            parent: Some(self.current),
+            transaction: Some(transaction),
+            time,
+            redo_child: None,
+        });
+        self.nodes[self.current].redo_child = Some(seq);
+        self.current = seq;
+    }
+
+    /// Return the transaction that leads to a state.
+    pub(super) fn transaction(&self, seq: usize) -> Option<&Transaction> {
+        self.nodes.get(seq)?.transaction.as_ref()
+    }
+
+    /// Return the state one undo step away.
+    pub(super) fn undo_target(&self) -> Option<usize> {
+        self.nodes[self.current].parent
+    }
+
+    /// Return the state one redo step away, following the latest branch.
+    pub(super) fn redo_target(&self) -> Option<usize> {
+        self.nodes[self.current].redo_child
+    }
+
+    /// Return the state reached by moving back through creation order.
+    pub(super) fn earlier_target(&self, step: HistoryStep) -> usize {
+        match step {
+            HistoryStep::Count(count) => self.current.saturating_sub(count),
+            HistoryStep::Time(span) => self.nodes[self.current]
+                .time
+                .checked_sub(span)
+                .map_or(0, |time| self.latest_at(time))
+                .min(self.current),
+        }
+    }
+
+    /// Return the state reached by moving forward through creation order.
+    pub(super) fn later_target(&self, step: HistoryStep) -> usize {
+        let last = self.nodes.len().saturating_sub(1);
+        match step {
+            HistoryStep::Count(count) => self.current.saturating_add(count).min(last),
+            HistoryStep::Time(span) => self.nodes[self.current]
+                .time
+                .checked_add(span)
+                .map_or(last, |time| self.latest_at(time))
+                .max(self.current),
+        }
+    }
+
+    /// Return the newest state created at or before a time.
+    fn latest_at(&self, time: SystemTime) -> usize {
+        self.nodes
+            .iter()
+            .rposition(|node| node.time <= time)
+            .unwrap_or(0)
+    }
+
+    /// Compute the states to undo and then redo to move to a target state.
+    ///
+    /// The undo list runs from the current state up to the common ancestor; the
+    /// redo list runs from below the common ancestor down to the target.
+    pub(super) fn route(&self, target: usize) -> (Vec<usize>, Vec<usize>) {
+        let mut ancestors = Vec::new();
+        let mut node = Some(target.min(self.nodes.len().saturating_sub(1)));
+        while let Some(seq) = node {
+            ancestors.push(seq);
+            node = self.nodes[seq].parent;
+        }
+        let mut undo = Vec::new();
+        let mut node = self.current;
+        while !ancestors.contains(&node) {
+            undo.push(node);
+            node = self.nodes[node].parent.unwrap_or(0);
+        }
+        let redo = ancestors
+            .into_iter()
+            .take_while(|seq| *seq != node)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        (undo, redo)
+    }
+
+    /// Mark a state as current, pointing redo along the path to it.
+    pub(super) fn set_current(&mut self, target: usize) {
+        let mut node = target;
+        while let Some(parent) = self.nodes[node].parent {
+            self.nodes[parent].redo_child = Some(node);
+            node = parent;
+        }
+        self.current = target;
+    }
+
+    /// Encode the history as undo file bytes, tagged with the text it ends at.
+    ///
+    /// Fails when the history cannot be serialized, rather than writing an
+    /// empty undo file.
+    pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+        let file = UndoFile {
+            version: UNDO_FILE_VERSION,
+            text_hash: text_hash(text),
+            tree: self.clone(),
+        };
+        serde_json::to_vec(&file).map_err(Error::UndoEncode)
+    }
+
+    /// Decode undo file bytes.
+    ///
+    /// Returns `None` when the bytes are not valid history or were written for
+    /// different text.
+    pub fn decode(bytes: &[u8], text: &str) -> Option<Self> {
+        let file = serde_json::from_slice::<UndoFile>(bytes).ok()?;
+        let valid = file.version == UNDO_FILE_VERSION
+            && file.text_hash == text_hash(text)
+            && file.tree.is_consistent();
+        valid.then_some(file.tree)
+    }
+
+    /// Check structural invariants of deserialized history.
+    fn is_consistent(&self) -> bool {
+        let len = self.nodes.len();
+        len > 0
+            && self.current < len
+            && self.nodes[0].parent.is_none()
+            && self.nodes.iter().enumerate().skip(1).all(|(seq, node)| {
+                node.parent.is_some_and(|parent| parent < seq) && node.transaction.is_some()
+            })
+            && self
+                .nodes
+                .iter()
+                .all(|node| node.redo_child.is_none_or(|child| child < len))
+    }
+}
+
+/// Hash text with 64-bit FNV-1a, which is stable across builds.
+fn text_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::{Selection, TextPosition};
+
+    fn transaction() -> Transaction {
+        Transaction::new(Selection::caret(TextPosition::new(0, 0)))
+    }
+
+    #[test]
+    fn parse_steps() {
+        assert_eq!(HistoryStep::parse(""), Some(HistoryStep::Count(1)));
+        assert_eq!(HistoryStep::parse("3"), Some(HistoryStep::Count(3)));
+        assert_eq!(
+            HistoryStep::parse("2m"),
+            Some(HistoryStep::Time(Duration::from_secs(120)))
+        );
+        assert_eq!(HistoryStep::parse("5x"), None);
+    }
+
+    #[test]
+    fn route_crosses_branches() {
+        let start = SystemTime::UNIX_EPOCH;
+        let mut tree = UndoTree::new();
+        tree.push(transaction(), start);
+        tree.push(transaction(), start);
+        tree.set_current(1);
+        tree.push(transaction(), start);
+        assert_eq!(tree.current(), 3);
+        assert_eq!(tree.route(2), (vec![3], vec![2]));
+        assert_eq!(tree.route(0), (vec![3, 1], vec![]));
+        tree.set_current(2);
+        assert_eq!(tree.redo_target(), None);
+        tree.set_current(1);
+        assert_eq!(tree.redo_target(), Some(2));
+    }
+
+    #[test]
+    fn time_targets_follow_creation_times() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut tree = UndoTree::new();
+        for offset in [0, 10, 20, 30] {
+            tree.push(transaction(), start + Duration::from_secs(offset));
+        }
+        assert_eq!(
+            tree.earlier_target(HistoryStep::Time(Duration::from_secs(15))),
+            2
+        );
+        assert_eq!(tree.earlier_target(HistoryStep::Count(10)), 0);
+        tree.set_current(1);
+        assert_eq!(
+            tree.later_target(HistoryStep::Time(Duration::from_secs(20))),
+            3
+        );
+        assert_eq!(tree.later_target(HistoryStep::Count(10)), 4);
+    }
+
+    #[test]
+    fn undo_file_requires_matching_text() {
+        let mut tree = UndoTree::new();
+        tree.push(transaction(), SystemTime::now());
+        let bytes = tree.encode("text").unwrap();
+
+        let restored = UndoTree::decode(&bytes, "text").unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.current(), 1);
+        assert!(UndoTree::decode(&bytes, "other").is_none());
+        assert!(UndoTree::decode(b"{}", "text").is_none());
+    }
+}
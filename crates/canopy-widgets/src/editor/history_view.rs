@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use canopy::{
+    Canopy, Context, Loader, TypedId, ViewContext, Widget, command, derive_commands,
+    error::Result,
+    geom::Line,
+    layout::{CanvasContext, Layout, Size},
+    render::Render,
+    state::NodeName,
+};
+
+use super::{Editor, HistoryEntry, UndoTree};
+
+/// Interval between checks of a followed editor's history.
+const REFRESH: Duration = Duration::from_millis(100);
+
+/// One prepared outline row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HistoryRow {
+    /// Branch depth of the state.
+    depth: usize,
+    /// State number.
+    seq: usize,
+    /// Number of edits leading to the state.
+    edits: usize,
+    /// Whether the buffer is at this state.
+    current: bool,
+}
+
+/// Scrollable outline of an editor's branching undo history.
+///
+/// Each state is listed once. The newest child of a state continues at the
+/// same depth, and older branches are indented beneath the state they fork
+/// from. A view built with [`HistoryView::following`] tracks an editor and
+/// refreshes after edits, undos and time travel; otherwise it shows the
+/// snapshot installed with [`HistoryView::set_history`].
+#[derive(Default)]
+pub struct HistoryView {
+    /// Outline rows from the last snapshot.
+    rows: Vec<HistoryRow>,
+    /// Editor whose history the view follows.
+    source: Option<TypedId<Editor>>,
+    /// Size and current state of the history the rows were built from.
+    seen: Option<(usize, usize)>,
+}
+
+#[derive_commands]
+impl HistoryView {
+    /// Construct an empty view.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a view that follows an editor's history.
+    pub fn following(editor: TypedId<Editor>) -> Self {
+        Self {
+            source: Some(editor),
+            ..Self::default()
+        }
+    }
+
+    /// Install a snapshot of an undo history.
+    pub fn set_history(&mut self, history: &UndoTree) {
+        self.rows = outline(&history.entries());
+        self.seen = Some((history.len(), history.current()));
+    }
+
+    /// Rebuild the outline from the followed editor if its history moved.
+    ///
+    /// Returns false once the editor is gone.
+    fn refresh(&mut self, context: &mut dyn Context) -> bool {
+        let Some(source) = self.source else {
+            return false;
+        };
+        let seen = self.seen;
+        let entries = context.with_widget(source, |editor, _| {
+            let history = editor.history();
+            let state = (history.len(), history.current());
+            Ok((seen != Some(state)).then(|| (state, history.entries())))
+        });
+        match entries {
+            Ok(Some((state, entries))) => {
+                self.rows = outline(&entries);
+                self.seen = Some(state);
+                true
+            }
+            Ok(None) => true,
+            Err(_) => false,
+        }
+    }
+
+    #[command]
+    /// Scroll up by one line.
+    pub fn scroll_up(&self, context: &mut dyn Context) {
+        context.scroll_up();
+    }
+
+    #[command]
+    /// Scroll down by one line.
+    pub fn scroll_down(&self, context: &mut dyn Context) {
+        context.scroll_down();
+    }
+
+    #[command]
+    /// Scroll up by one viewport.
+    pub fn page_up(&self, context: &mut dyn Context) {
+        context.page_up();
+    }
+
+    #[command]
+    /// Scroll down by one viewport.
+    pub fn page_down(&self, context: &mut dyn Context) {
+        context.page_down();
+    }
+}
+
+impl Loader for HistoryView {
+    fn load(canopy: &mut Canopy) -> Result<()> {
+        canopy.add_commands::<Self>()
+    }
+}
+
+impl Widget for HistoryView {
+    fn poll(&mut self, context: &mut dyn Context) -> Option<Duration> {
+        self.refresh(context).then_some(REFRESH)
+    }
+
+    fn accept_focus(&self, _context: &dyn ViewContext) -> bool {
+        true
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::fill().overflow_y()
+    }
+
+    fn canvas(&self, view: Size<u32>, _context: &CanvasContext) -> Size<u32> {
+        Size::new(view.w, u32::try_from(self.rows.len()).unwrap_or(u32::MAX))
+    }
+
+    fn render(&mut self, render: &mut Render, context: &dyn ViewContext) -> Result<()> {
+        let view = context.view();
+        render.fill("editor/text", view.outer_rect_local(), ' ')?;
+        let viewport = view.view_rect();
+        let width = view.content.w;
+        for (index, row) in self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(viewport.tl.y as usize)
+            .take(viewport.h as usize)
+        {
+            let y = u32::try_from(index).unwrap_or(u32::MAX) - viewport.tl.y;
+            let indent = u32::try_from(row.depth * 2).unwrap_or(u32::MAX).min(width);
+            let (marker, style) = if row.current {
+                ('@', "editor/line-number/current")
+            } else {
+                ('o', "editor/line-number")
+            };
+            let label = match row.edits {
+                0 => format!("{marker} {} original", row.seq),
+                1 => format!("{marker} {} 1 edit", row.seq),
+                edits => format!("{marker} {} {edits} edits", row.seq),
+            };
+            render.text(
+                style,
+                Line::new(indent, y, width.saturating_sub(indent)),
+                &label,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> NodeName {
+        NodeName::convert("history_view")
+    }
+}
+
+/// Lay out history entries as an indented depth-first outline.
+fn outline(entries: &[HistoryEntry]) -> Vec<HistoryRow> {
+    let mut children = vec![Vec::new(); entries.len()];
+    for entry in entries {
+        if let Some(parent) = entry.parent {
+            children[parent].push(entry.seq);
+        }
+    }
+    let mut rows = Vec::with_capacity(entries.len());
+    let mut stack = if entries.is_empty() {
+        Vec::new()
+    } else {
+        vec![(0, 0)]
+    };
+    while let Some((seq, depth)) = stack.pop() {
+        let entry = &entries[seq];
+        rows.push(HistoryRow {
+            depth,
+            seq,
+            edits: entry.edits,
+            current: entry.current,
+        });
+        let Some((newest, older)) = children[seq].split_last() else {
+            continue;
+        };
+        stack.push((*newest, depth));
+        stack.extend(older.iter().rev().map(|child| (*child, depth + 1)));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor::{HistoryStep, TextBuffer};
+
+    #[test]
+    fn outline_indents_older_branches() {
+        let mut buffer = TextBuffer::new("");
+        buffer.insert_text("a");
+        buffer.insert_text("b");
+        buffer.undo();
+        buffer.insert_text("c");
+        buffer.earlier(HistoryStep::Count(1));
+
+        let rows = outline(&buffer.history().entries())
+            .into_iter()
+            .map(|row| (row.depth, row.seq, row.current))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![(0, 0, false), (0, 1, false), (1, 2, true), (0, 3, false)]
+        );
+    }
+}
@@ -0,0 +1,136 @@
+/// Language-specific editing conventions for the editor.
+///
+/// When an editor has a language configured, Enter carries indentation
+/// forward and indents after openers, typed closers dedent to their opener,
+/// bracket and quote pairs auto-close or wrap the selection, and comments can
+/// be toggled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageConfig {
+    /// Text inserted for one indentation level.
+    pub indent_unit: String,
+    /// Token that starts a line comment.
+    pub line_comment: Option<String>,
+    /// Tokens that open and close a block comment.
+    pub block_comment: Option<(String, String)>,
+    /// Auto-closing pairs; pairs with distinct characters are also brackets.
+    pub pairs: Vec<(char, char)>,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self {
+            indent_unit: "    ".to_string(),
+            line_comment: None,
+            block_comment: None,
+            pairs: vec![('(', ')'), ('[', ']'), ('{', '}'), ('"', '"')],
+        }
+    }
+}
+
+impl LanguageConfig {
+    /// Construct a configuration with generic brackets and no comment syntax.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a configuration for a file extension such as `rs` or `py`.
+    ///
+    /// Unknown extensions yield the generic configuration.
+    pub fn for_extension(extension: &str) -> Self {
+        let slash = Some(("/*", "*/"));
+        let (indent, line, block, quote): (&str, _, _, bool) =
+            match extension.to_ascii_lowercase().as_str() {
+                "rs" => ("    ", Some("//"), slash, false),
+                "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "cs" | "swift" | "kt" => {
+                    ("    ", Some("//"), slash, true)
+                }
+                "go" => ("\t", Some("//"), slash, true),
+                "js" | "jsx" | "ts" | "tsx" | "css" | "scss" => ("  ", Some("//"), slash, true),
+                "py" | "sh" | "bash" | "zsh" | "rb" | "toml" | "yaml" | "yml" => {
+                    ("    ", Some("#"), None, true)
+                }
+                "lua" | "luau" => ("    ", Some("--"), Some(("--[[", "]]")), true),
+                "html" | "xml" | "md" => ("  ", None, Some(("<!--", "-->")), false),
+                "sql" => ("    ", Some("--"), slash, true),
+                _ => return Self::default(),
+            };
+        let mut config = Self::default()
+            .with_indent_unit(indent)
+            .with_line_comment(line)
+            .with_block_comment(block);
+        if quote {
+            config.pairs.push(('\'', '\''));
+        }
+        config
+    }
+
+    /// Configure the indentation unit.
+    pub fn with_indent_unit(mut self, unit: impl Into<String>) -> Self {
+        self.indent_unit = unit.into();
+        self
+    }
+
+    /// Configure the line comment token.
+    pub fn with_line_comment(mut self, token: Option<&str>) -> Self {
+        self.line_comment = token.map(str::to_string);
+        self
+    }
+
+    /// Configure the block comment tokens.
+    pub fn with_block_comment(mut self, tokens: Option<(&str, &str)>) -> Self {
+        self.block_comment = tokens.map(|(open, close)| (open.to_string(), close.to_string()));
+        self
+    }
+
+    /// Configure the auto-closing pairs.
+    pub fn with_pairs(mut self, pairs: Vec<(char, char)>) -> Self {
+        self.pairs = pairs;
+        self
+    }
+
+    /// Return the closing character for an opening character.
+    pub fn closing(&self, open: char) -> Option<char> {
+        self.pairs
+            .iter()
+            .find(|(o, _)| *o == open)
+            .map(|(_, close)| *close)
+    }
+
+    /// Return true when a character closes a bracket pair.
+    pub fn is_closer(&self, c: char) -> bool {
+        self.pairs
+            .iter()
+            .any(|(open, close)| *close == c && open != close)
+    }
+
+    /// Return the bracket pairs, excluding symmetric quote pairs.
+    pub(super) fn brackets(&self) -> Vec<(char, char)> {
+        self.pairs
+            .iter()
+            .copied()
+            .filter(|(open, close)| open != close)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_selects_comment_tokens() {
+        let rust = LanguageConfig::for_extension("rs");
+        assert_eq!(rust.line_comment.as_deref(), Some("//"));
+        assert_eq!(rust.closing('\''), None);
+        assert_eq!(rust.closing('{'), Some('}'));
+
+        let python = LanguageConfig::for_extension("PY");
+        assert_eq!(python.line_comment.as_deref(), Some("#"));
+        assert_eq!(python.block_comment, None);
+        assert_eq!(python.closing('\''), Some('\''));
+
+        assert_eq!(LanguageConfig::for_extension("xyz"), LanguageConfig::new());
+        assert!(rust.is_closer(')'));
+        assert!(!rust.is_closer('"'));
+    }
+}
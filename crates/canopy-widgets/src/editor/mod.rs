@@ -6,10 +6,20 @@
 
 /// Text buffer implementation backed by a rope.
 pub(crate) mod buffer;
+/// File-backed documents with format and dirty tracking.
+mod document;
 /// Undo/redo edit definitions.
 mod edit;
+/// Ex command parsing for the vi command line.
+mod ex;
 /// Syntax highlighting helpers.
 pub mod highlight;
+/// Branching undo history.
+mod history;
+/// Inspectable view of an editor's undo tree.
+mod history_view;
+/// Language-specific editing conventions.
+mod language;
 /// Layout and wrapping cache.
 mod layout;
 /// Text position and range types.
@@ -18,6 +28,8 @@ pub(crate) mod position;
 mod search;
 /// Selection types and helpers.
 pub(crate) mod selection;
+/// Language-aware indentation, pairing and comment helpers.
+mod smart;
 /// Shared editor helpers.
 mod util;
 /// Vi mode state helpers.
@@ -26,6 +38,10 @@ mod vi;
 pub(crate) mod widget;
 
 pub use buffer::{LineChange, TextBuffer};
+pub use document::{Document, DocumentFormat, LineEnding};
+pub use history::{HistoryEntry, HistoryStep, UndoTree};
+pub use history_view::HistoryView;
+pub use language::LanguageConfig;
 pub use position::{TextPosition, TextRange};
 pub use selection::Selection;
 pub use util::{display_width, tab_width};
@@ -84,6 +100,10 @@ pub struct EditorConfig {
     pub line_numbers: LineNumbers,
     /// Tab stop width in columns.
     pub tab_stop: usize,
+    /// Persist undo history in a file next to the document.
+    pub undo_file: bool,
+    /// Language conventions for indentation, pairing and comments.
+    pub language: Option<LanguageConfig>,
 }
 
 impl Default for EditorConfig {
@@ -98,6 +118,8 @@ impl Default for EditorConfig {
             read_only: false,
             line_numbers: LineNumbers::None,
             tab_stop: 4,
+            undo_file: false,
+            language: None,
         }
     }
 }
@@ -161,4 +183,16 @@ impl EditorConfig {
         self.tab_stop = tab_stop.max(1);
         self
     }
+
+    /// Configure undo history persistence for file-backed editors.
+    pub fn with_undo_file(mut self, undo_file: bool) -> Self {
+        self.undo_file = undo_file;
+        self
+    }
+
+    /// Configure language-aware editing.
+    pub fn with_language(mut self, language: Option<LanguageConfig>) -> Self {
+        self.language = language;
+        self
+    }
 }
@@ -1,7 +1,9 @@
 use std::cmp::Ordering;
 
+use serde::{Deserialize, Serialize};
+
 /// A position in the text buffer expressed as a logical line and a char index.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TextPosition {
     /// Logical line index (0-based).
     pub line: usize,
@@ -32,7 +34,7 @@ impl PartialOrd for TextPosition {
 }
 
 /// A half-open text range expressed in buffer coordinates.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TextRange {
     /// Range start position (inclusive).
     pub start: TextPosition,
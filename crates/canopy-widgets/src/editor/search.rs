@@ -233,6 +233,16 @@ pub(super) enum PromptState {
         /// Whether to replace all remaining matches.
         replace_all: bool,
     },
+    /// Ex command-line input.
+    Ex {
+        /// Current command text, without the leading colon.
+        input: String,
+    },
+    /// Message shown until the next key press.
+    Message {
+        /// Message text.
+        text: String,
+    },
 }
 
 impl Editor {
@@ -299,6 +309,7 @@ impl Editor {
                 Event::Key(key::Key {
                     key: key::KeyCode::Char(c),
                     mods,
+                    ..
                 }),
             ) if !mods.ctrl && !mods.alt => {
                 query.push(*c);
@@ -344,6 +355,7 @@ impl Editor {
                 Event::Key(key::Key {
                     key: key::KeyCode::Char(c),
                     mods,
+                    ..
                 }),
             ) if !mods.ctrl && !mods.alt => {
                 query.push(*c);
@@ -399,6 +411,7 @@ impl Editor {
                 Event::Key(key::Key {
                     key: key::KeyCode::Char(c),
                     mods,
+                    ..
                 }),
             ) if !mods.ctrl && !mods.alt => {
                 replacement.push(*c);
@@ -480,6 +493,50 @@ impl Editor {
                 self.prompt = None;
                 EventOutcome::Handle
             }
+            (
+                PromptState::Ex { input },
+                Event::Key(key::Key {
+                    key: key::KeyCode::Enter,
+                    ..
+                }),
+            ) => {
+                self.prompt = None;
+                self.run_ex_command(&input, ctx);
+                EventOutcome::Handle
+            }
+            (
+                PromptState::Ex { mut input },
+                Event::Key(key::Key {
+                    key: key::KeyCode::Backspace,
+                    ..
+                }),
+            ) => {
+                self.prompt = input.pop().map(|_| PromptState::Ex { input });
+                EventOutcome::Handle
+            }
+            (
+                PromptState::Ex { mut input },
+                Event::Key(key::Key {
+                    key: key::KeyCode::Char(c),
+                    mods,
+                    ..
+                }),
+            ) if !mods.ctrl && !mods.alt => {
+                input.push(*c);
+                self.prompt = Some(PromptState::Ex { input });
+                EventOutcome::Handle
+            }
+            (
+                PromptState::Ex { .. },
+                Event::Key(key::Key {
+                    key: key::KeyCode::Esc,
+                    ..
+                }),
+            )
+            | (PromptState::Message { .. }, Event::Key(_)) => {
+                self.prompt = None;
+                EventOutcome::Handle
+            }
             _ => EventOutcome::Ignore,
         }
     }
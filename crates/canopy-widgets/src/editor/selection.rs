@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use super::position::{TextPosition, TextRange};
 
 /// A text selection expressed as an anchor and head position.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Selection {
     /// Anchor position for the selection.
     anchor: TextPosition,
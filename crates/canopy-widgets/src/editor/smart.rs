@@ -0,0 +1,369 @@
+use super::{
+    Selection, TextBuffer, TextPosition, TextRange, buffer::advance_position, widget::Editor,
+};
+
+impl Editor {
+    /// Apply buffer edits as one undo step.
+    ///
+    /// Inside an open text-entry transaction the edits join it, so an insert
+    /// session still undoes as a whole.
+    fn undo_step<R>(&mut self, f: impl FnOnce(&mut TextBuffer) -> R) -> R {
+        let result = if self.text_entry_transaction {
+            f(&mut self.buffer)
+        } else {
+            let mut transaction = self.buffer.transaction();
+            f(&mut transaction)
+        };
+        self.update_preferred_column();
+        result
+    }
+
+    /// Return the character at the cursor, if any.
+    fn char_at_cursor(&self) -> Option<char> {
+        let cursor = self.buffer.cursor();
+        self.buffer
+            .line_text(cursor.line)
+            .chars()
+            .nth(cursor.column)
+    }
+
+    /// Return the character before the cursor on its line, if any.
+    fn char_before_cursor(&self) -> Option<char> {
+        let cursor = self.buffer.cursor();
+        let column = cursor.column.checked_sub(1)?;
+        self.buffer.line_text(cursor.line).chars().nth(column)
+    }
+
+    /// Insert a typed character, applying the language's pairing rules.
+    ///
+    /// Returns the text actually inserted, for insert-repeat bookkeeping.
+    pub(super) fn type_char(&mut self, c: char) -> String {
+        let Some(language) = self.config.language.clone() else {
+            self.handle_insert_text(&c.to_string());
+            return c.to_string();
+        };
+        if self.config.read_only {
+            return String::new();
+        }
+        let next = self.char_at_cursor();
+        let selection = self.buffer.selection();
+        if let Some(close) = language.closing(c) {
+            if !selection.is_empty() {
+                self.wrap_selection(c, close);
+                return String::new();
+            }
+            if close == c && next == Some(c) {
+                self.buffer.move_right(false);
+                self.update_preferred_column();
+                return String::new();
+            }
+            let after_word = self
+                .char_before_cursor()
+                .is_some_and(|prev| prev.is_alphanumeric());
+            let free = next.is_none_or(|next| next.is_whitespace() || language.is_closer(next));
+            if free && !(close == c && after_word) {
+                let pair = format!("{c}{close}");
+                self.undo_step(|buffer| {
+                    buffer.insert_text(&pair);
+                    buffer.move_left(false);
+                });
+                return pair;
+            }
+        } else if language.is_closer(c) {
+            if next == Some(c) {
+                self.buffer.move_right(false);
+                self.update_preferred_column();
+                return String::new();
+            }
+            self.dedent_for_closer(c);
+        }
+        self.handle_insert_text(&c.to_string());
+        c.to_string()
+    }
+
+    /// Surround the selection with a pair and keep the inner text selected.
+    fn wrap_selection(&mut self, open: char, close: char) {
+        let range = self.buffer.selection().range();
+        let inner = self.buffer.range_text(range);
+        let start = advance_position(range.start, &open.to_string());
+        let end = advance_position(start, &inner);
+        self.undo_step(|buffer| {
+            buffer.replace_range(range, &format!("{open}{inner}{close}"));
+            buffer.set_selection(Selection::new(start, end));
+        });
+    }
+
+    /// Reindent a whitespace-only line to its opener before typing a closer.
+    fn dedent_for_closer(&mut self, close: char) {
+        let cursor = self.buffer.cursor();
+        let line_text = self.buffer.line_text(cursor.line);
+        let prefix: String = line_text.chars().take(cursor.column).collect();
+        if prefix.is_empty() || !prefix.chars().all(char::is_whitespace) {
+            return;
+        }
+        let Some(language) = &self.config.language else {
+            return;
+        };
+        let Some(open) = language
+            .pairs
+            .iter()
+            .find(|(open, c)| *c == close && open != c)
+            .map(|(open, _)| *open)
+        else {
+            return;
+        };
+        let Some(opener) = self.find_unmatched_opener(cursor, open, close) else {
+            return;
+        };
+        let indent = leading_whitespace(&self.buffer.line_text(opener.line));
+        if indent == prefix {
+            return;
+        }
+        let range = TextRange::new(TextPosition::new(cursor.line, 0), cursor);
+        self.undo_step(|buffer| buffer.replace_range(range, &indent));
+    }
+
+    /// Scan backwards from a position for an opener with no closer before it.
+    fn find_unmatched_opener(
+        &self,
+        from: TextPosition,
+        open: char,
+        close: char,
+    ) -> Option<TextPosition> {
+        let mut depth = 0usize;
+        for line in (0..=from.line).rev() {
+            let chars: Vec<char> = self.buffer.line_text(line).chars().collect();
+            let end = if line == from.line {
+                from.column.min(chars.len())
+            } else {
+                chars.len()
+            };
+            for column in (0..end).rev() {
+                if chars[column] == close {
+                    depth += 1;
+                } else if chars[column] == open {
+                    if depth == 0 {
+                        return Some(TextPosition::new(line, column));
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+        None
+    }
+
+    /// Insert a line break, carrying indentation and indenting after openers.
+    ///
+    /// Returns the text actually inserted, for insert-repeat bookkeeping.
+    pub(super) fn insert_newline(&mut self) -> String {
+        let Some(language) = self.config.language.clone() else {
+            self.handle_insert_text("\n");
+            return "\n".to_string();
+        };
+        if self.config.read_only {
+            return String::new();
+        }
+        let cursor = self.buffer.selection().range().start;
+        let line_text = self.buffer.line_text(cursor.line);
+        let before: String = line_text.chars().take(cursor.column).collect();
+        let indent = leading_whitespace(&before);
+        let opener = before
+            .trim_end()
+            .chars()
+            .next_back()
+            .and_then(|c| language.closing(c).filter(|close| *close != c));
+        let mut text = format!("\n{indent}");
+        if opener.is_some() {
+            text.push_str(&language.indent_unit);
+        }
+        let split = opener.is_some() && self.char_at_cursor() == opener;
+        self.undo_step(|buffer| {
+            buffer.insert_text(&text);
+            if split {
+                let inner = buffer.cursor();
+                buffer.insert_text(&format!("\n{indent}"));
+                buffer.set_cursor(inner);
+            }
+        });
+        text
+    }
+
+    /// Delete an empty auto-closed pair around the cursor.
+    pub(super) fn delete_empty_pair(&mut self) -> bool {
+        let Some(language) = &self.config.language else {
+            return false;
+        };
+        if self.config.read_only || !self.buffer.selection().is_empty() {
+            return false;
+        }
+        let (Some(prev), Some(next)) = (self.char_before_cursor(), self.char_at_cursor()) else {
+            return false;
+        };
+        if language.closing(prev) != Some(next) {
+            return false;
+        }
+        let cursor = self.buffer.cursor();
+        let range = TextRange::new(
+            TextPosition::new(cursor.line, cursor.column - 1),
+            TextPosition::new(cursor.line, cursor.column + 1),
+        );
+        self.undo_step(|buffer| buffer.replace_range(range, ""));
+        true
+    }
+
+    /// Return the bracket under or just before the cursor and its match.
+    pub(super) fn bracket_match(&self) -> Option<(TextPosition, TextPosition)> {
+        let language = self.config.language.as_ref()?;
+        let brackets = language.brackets();
+        let cursor = self.buffer.cursor();
+        let before = cursor
+            .column
+            .checked_sub(1)
+            .map(|column| TextPosition::new(cursor.line, column));
+        [Some(cursor), before]
+            .into_iter()
+            .flatten()
+            .find_map(|pos| {
+                self.buffer
+                    .matching_bracket(pos, &brackets)
+                    .map(|other| (pos, other))
+            })
+    }
+
+    /// Return the lines covered by the selection, or the cursor line.
+    fn selected_lines(&self) -> (usize, usize) {
+        let range = self.buffer.selection().range();
+        let end = if range.end.line > range.start.line && range.end.column == 0 {
+            range.end.line - 1
+        } else {
+            range.end.line
+        };
+        (range.start.line, end)
+    }
+
+    /// Toggle line comments over the selected lines as one undo step.
+    ///
+    /// Falls back to a block comment when the language has no line comment.
+    pub(super) fn toggle_line_comment(&mut self) {
+        let Some(language) = self.config.language.clone() else {
+            return;
+        };
+        let Some(token) = language.line_comment else {
+            self.toggle_block_comment();
+            return;
+        };
+        if self.config.read_only {
+            return;
+        }
+        let (first, last) = self.selected_lines();
+        let lines: Vec<(usize, String)> = (first..=last)
+            .map(|line| (line, self.buffer.line_text(line)))
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect();
+        if lines.is_empty() {
+            return;
+        }
+        let commented = lines
+            .iter()
+            .all(|(_, text)| text.trim_start().starts_with(&token));
+        let column = lines
+            .iter()
+            .map(|(_, text)| leading_whitespace(text).chars().count())
+            .min()
+            .unwrap_or(0);
+        let had_selection = !self.buffer.selection().is_empty();
+        let cursor = self.buffer.cursor();
+        let prefix = format!("{token} ");
+        self.undo_step(|buffer| {
+            // Column where the cursor line's prefix changed, and by how much.
+            let mut shift = None;
+            for (line, text) in &lines {
+                let (at, delta) = if commented {
+                    let indent = leading_whitespace(text).chars().count();
+                    let rest = &text.trim_start()[token.len()..];
+                    let len = token.chars().count() + usize::from(rest.starts_with(' '));
+                    let start = TextPosition::new(*line, indent);
+                    buffer.replace_range(
+                        TextRange::new(start, TextPosition::new(*line, indent + len)),
+                        "",
+                    );
+                    (indent, -(len as isize))
+                } else {
+                    let at = TextPosition::new(*line, column);
+                    buffer.replace_range(TextRange::new(at, at), &prefix);
+                    (column, prefix.chars().count() as isize)
+                };
+                if *line == cursor.line {
+                    shift = Some((at, delta));
+                }
+            }
+            if had_selection {
+                let end = buffer.line_end_position(last, false);
+                buffer.set_selection(Selection::new(TextPosition::new(first, 0), end));
+            } else {
+                let column = match shift {
+                    Some((at, delta)) if cursor.column >= at => {
+                        cursor.column.saturating_add_signed(delta).max(at)
+                    }
+                    _ => cursor.column,
+                };
+                buffer.set_cursor(TextPosition::new(cursor.line, column));
+            }
+        });
+    }
+
+    /// Toggle a block comment around the selection or the cursor line.
+    ///
+    /// Falls back to line comments when the language has no block comment.
+    pub(super) fn toggle_block_comment(&mut self) {
+        let Some(language) = self.config.language.clone() else {
+            return;
+        };
+        let Some((open, close)) = language.block_comment else {
+            if language.line_comment.is_some() {
+                self.toggle_line_comment();
+            }
+            return;
+        };
+        if self.config.read_only {
+            return;
+        }
+        let selection = self.buffer.selection();
+        let range = if selection.is_empty() {
+            let line = selection.head().line;
+            let text = self.buffer.line_text(line);
+            let indent = leading_whitespace(&text).chars().count();
+            TextRange::new(
+                TextPosition::new(line, indent),
+                self.buffer.line_end_position(line, false),
+            )
+        } else {
+            selection.range()
+        };
+        let text = self.buffer.range_text(range);
+        let replacement = match text
+            .strip_prefix(open.as_str())
+            .and_then(|rest| rest.strip_suffix(close.as_str()))
+        {
+            Some(inner) => {
+                let inner = inner.strip_prefix(' ').unwrap_or(inner);
+                inner.strip_suffix(' ').unwrap_or(inner).to_string()
+            }
+            None => format!("{open} {text} {close}"),
+        };
+        let end = advance_position(range.start, &replacement);
+        self.undo_step(|buffer| {
+            buffer.replace_range(range, &replacement);
+            if selection.is_empty() {
+                buffer.set_cursor(range.start);
+            } else {
+                buffer.set_selection(Selection::new(range.start, end));
+            }
+        });
+    }
+}
+
+/// Return the leading whitespace of a line.
+fn leading_whitespace(text: &str) -> String {
+    text.chars().take_while(|c| c.is_whitespace()).collect()
+}
@@ -1,6 +1,10 @@
-use std::sync::{
-    Arc,
-    atomic::{AtomicUsize, Ordering},
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
 };
 
 use canopy::{
@@ -17,7 +21,7 @@ use canopy::{
 
 use super::{Selection, TextPosition, TextRange};
 use crate::editor::{
-    EditMode, Editor, EditorConfig, LineNumbers, WrapMode,
+    EditMode, Editor, EditorConfig, LanguageConfig, LineNumbers, WrapMode,
     highlight::{HighlightSpan, Highlighter},
 };
 
@@ -29,6 +33,8 @@ struct EditorHost {
     text: String,
     /// Editor configuration.
     config: EditorConfig,
+    /// File to open instead of the initial text.
+    path: Option<PathBuf>,
     /// Number of times the host command was triggered.
     binding_hits: usize,
 }
@@ -40,6 +46,7 @@ impl EditorHost {
         Self {
             text: text.to_string(),
             config,
+            path: None,
             binding_hits: 0,
         }
     }
@@ -62,7 +69,10 @@ impl Widget for EditorHost {
     }
 
     fn on_mount(&mut self, c: &mut dyn Context) -> Result<()> {
-        let editor = Editor::with_config(self.text.clone(), self.config.clone());
+        let editor = match &self.path {
+            Some(path) => Editor::open(path.clone(), self.config.clone())?,
+            None => Editor::with_config(self.text.clone(), self.config.clone()),
+        };
         let editor_id = c.add_keyed::<EditorSlot>(editor)?;
         c.set_layout(Layout::fill())?;
         c.set_layout_of(editor_id, Layout::fill())?;
@@ -99,6 +109,24 @@ fn build_harness(text: &str, config: EditorConfig, width: u32, height: u32) -> H
     harness
 }
 
+fn build_document_harness(path: PathBuf, config: EditorConfig, width: u32, height: u32) -> Harness {
+    let mut host = EditorHost::new("", config);
+    host.path = Some(path);
+    let mut harness = Harness::builder(host)
+        .size(width, height)
+        .build()
+        .expect("Failed to build harness");
+    harness.render().expect("Failed to render");
+    harness
+        .with_root_context(|_root: &mut EditorHost, ctx| {
+            ctx.focus_first(FocusScope::Current)?;
+            Ok(())
+        })
+        .expect("Failed to focus editor");
+    harness.render().expect("Failed to render");
+    harness
+}
+
 fn with_editor<R>(harness: &mut Harness, f: impl FnOnce(&mut Editor) -> R) -> R {
     let mut f = Some(f);
     harness
@@ -449,6 +477,7 @@ fn highlight_spans_apply_styles() {
         fg: Paint::solid(Color::Red),
         bg: Paint::solid(Color::Black),
         attrs: AttrSet::default(),
+        underline: None,
     };
     with_editor(&mut harness, |editor| {
         editor.set_highlighter(Some(Box::new(TestHighlighter {
@@ -468,6 +497,7 @@ fn highlight_spans_inherit_editor_background() {
         fg: Paint::solid(Color::Green),
         bg: Paint::solid(Color::Red),
         attrs: AttrSet::default(),
+        underline: None,
     };
     with_editor(&mut harness, |editor| {
         editor.set_highlighter(Some(Box::new(TestHighlighter {
@@ -528,6 +558,212 @@ end)
     assert_eq!(host_binding_hits(&mut harness), 0);
 }
 
+#[test]
+fn vi_write_and_reload_document() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    fs::write(&path, "one\r\ntwo\r\n").unwrap();
+    let config = EditorConfig::new().with_mode(EditMode::Vi);
+    let mut harness = build_document_harness(path.clone(), config, 20, 3);
+    assert_eq!(editor_text(&mut harness), "one\ntwo");
+    assert!(!with_editor(&mut harness, |editor| editor.is_dirty()));
+
+    harness.keys(['A', '!']).unwrap();
+    harness.key(key::KeyCode::Esc).unwrap();
+    assert!(with_editor(&mut harness, |editor| editor.is_dirty()));
+    harness.type_text(":w").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert!(!with_editor(&mut harness, |editor| editor.is_dirty()));
+    assert_eq!(fs::read(&path).unwrap(), b"one!\r\ntwo\r\n");
+
+    fs::write(&path, "changed\n").unwrap();
+    harness.type_text(":e").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert_eq!(editor_text(&mut harness), "changed");
+    harness.key('u').unwrap();
+    assert_eq!(editor_text(&mut harness), "one!\ntwo");
+}
+
+#[test]
+fn vi_write_reports_external_modification() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    fs::write(&path, "a\n").unwrap();
+    let config = EditorConfig::new().with_mode(EditMode::Vi);
+    let mut harness = build_document_harness(path.clone(), config, 40, 2);
+    fs::write(&path, "external edit\n").unwrap();
+
+    harness.type_text(":w").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert!(harness.tbuf().contains_text("changed on disk"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "external edit\n");
+
+    harness.key(key::KeyCode::Esc).unwrap();
+    harness.type_text(":w!").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a\n");
+}
+
+#[test]
+fn vi_time_travel_crosses_undo_branches() {
+    let config = EditorConfig::new().with_mode(EditMode::Vi);
+    let mut harness = build_harness("a", config, 20, 2);
+    harness.keys(['A', 'b']).unwrap();
+    harness.key(key::KeyCode::Esc).unwrap();
+    harness.key('u').unwrap();
+    harness.keys(['A', 'c']).unwrap();
+    harness.key(key::KeyCode::Esc).unwrap();
+    assert_eq!(editor_text(&mut harness), "ac");
+
+    harness.keys(['g', '-']).unwrap();
+    assert_eq!(editor_text(&mut harness), "ab");
+    harness.keys(['g', '-']).unwrap();
+    assert_eq!(editor_text(&mut harness), "a");
+    harness.keys(['g', '+', 'g', '+']).unwrap();
+    assert_eq!(editor_text(&mut harness), "ac");
+
+    harness.type_text(":earlier 10m").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert_eq!(editor_text(&mut harness), "a");
+    harness.type_text(":later 1h").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert_eq!(editor_text(&mut harness), "ac");
+}
+
+#[test]
+fn undo_file_restores_history_after_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("notes.txt");
+    fs::write(&path, "a\n").unwrap();
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Vi)
+        .with_undo_file(true);
+    let mut harness = build_document_harness(path.clone(), config.clone(), 20, 2);
+    harness.keys(['A', 'b']).unwrap();
+    harness.key(key::KeyCode::Esc).unwrap();
+    harness.type_text(":w").unwrap();
+    harness.key(key::KeyCode::Enter).unwrap();
+    assert!(dir.path().join(".notes.txt.canopy-undo").exists());
+
+    let mut reopened = build_document_harness(path, config, 20, 2);
+    assert_eq!(editor_text(&mut reopened), "ab");
+    reopened.key('u').unwrap();
+    assert_eq!(editor_text(&mut reopened), "a");
+}
+
+#[test]
+fn language_enter_indents_between_brackets() {
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Text)
+        .with_language(Some(LanguageConfig::for_extension("rs")));
+    let mut harness = build_harness("", config, 20, 4);
+    harness.type_text("fn f() {").unwrap();
+    assert_eq!(editor_text(&mut harness), "fn f() {}");
+    harness.key(key::KeyCode::Enter).unwrap();
+    harness.type_text("x").unwrap();
+    assert_eq!(editor_text(&mut harness), "fn f() {\n    x\n}");
+    assert_eq!(editor_cursor(&mut harness), TextPosition::new(1, 5));
+
+    with_editor(&mut harness, |editor| editor.buffer.undo());
+    assert_eq!(editor_text(&mut harness), "fn f() {\n    \n}");
+    with_editor(&mut harness, |editor| editor.buffer.undo());
+    assert_eq!(editor_text(&mut harness), "fn f() {}");
+}
+
+#[test]
+fn language_closer_dedents_and_pairs_delete_together() {
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Text)
+        .with_language(Some(LanguageConfig::for_extension("rs")));
+    let mut harness = build_harness("if a {\n    b;\n        ", config, 20, 4);
+    with_editor(&mut harness, |editor| {
+        editor.buffer.set_cursor(TextPosition::new(2, 8));
+    });
+    harness.type_text("}").unwrap();
+    assert_eq!(editor_text(&mut harness), "if a {\n    b;\n}");
+
+    harness.type_text(" [").unwrap();
+    assert_eq!(editor_text(&mut harness), "if a {\n    b;\n} []");
+    harness.key(key::KeyCode::Backspace).unwrap();
+    assert_eq!(editor_text(&mut harness), "if a {\n    b;\n} ");
+}
+
+#[test]
+fn language_wraps_selection_in_pair() {
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Text)
+        .with_language(Some(LanguageConfig::new()));
+    let mut harness = build_harness("a b", config, 20, 2);
+    with_editor(&mut harness, |editor| {
+        editor.buffer.set_selection(Selection::new(
+            TextPosition::new(0, 2),
+            TextPosition::new(0, 3),
+        ));
+    });
+    harness.type_text("(").unwrap();
+    assert_eq!(editor_text(&mut harness), "a (b)");
+    assert_eq!(
+        editor_selection(&mut harness).range(),
+        TextRange::new(TextPosition::new(0, 3), TextPosition::new(0, 4))
+    );
+}
+
+#[test]
+fn toggle_comment_is_one_undo_step() {
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Vi)
+        .with_language(Some(LanguageConfig::for_extension("rs")));
+    let mut harness = build_harness("a\n  b", config, 20, 3);
+    harness.keys(['g', 'g', 'V', 'j', 'g', 'c']).unwrap();
+    assert_eq!(editor_text(&mut harness), "// a\n//   b");
+    harness.keys(['g', 'g', 'g', 'c', 'c']).unwrap();
+    assert_eq!(editor_text(&mut harness), "a\n//   b");
+    harness.key('u').unwrap();
+    assert_eq!(editor_text(&mut harness), "// a\n//   b");
+    harness.key('u').unwrap();
+    assert_eq!(editor_text(&mut harness), "a\n  b");
+
+    harness.keys(['g', 'g', 'g', 'b', 'c']).unwrap();
+    assert_eq!(editor_text(&mut harness), "/* a */\n  b");
+}
+
+#[test]
+fn toggle_comment_keeps_cursor_in_indentation() {
+    let config = EditorConfig::new()
+        .with_mode(EditMode::Vi)
+        .with_language(Some(LanguageConfig::for_extension("rs")));
+    let mut harness = build_harness("    a", config, 20, 2);
+    with_editor(&mut harness, |editor| {
+        editor.buffer.set_cursor(TextPosition::new(0, 1));
+    });
+    harness.keys(['g', 'c', 'c']).unwrap();
+    assert_eq!(editor_text(&mut harness), "    // a");
+    assert_eq!(editor_cursor(&mut harness), TextPosition::new(0, 1));
+    harness.keys(['g', 'c', 'c']).unwrap();
+    assert_eq!(editor_text(&mut harness), "    a");
+    assert_eq!(editor_cursor(&mut harness), TextPosition::new(0, 1));
+
+    with_editor(&mut harness, |editor| {
+        editor.buffer.set_cursor(TextPosition::new(0, 4));
+    });
+    harness.keys(['g', 'c', 'c']).unwrap();
+    assert_eq!(editor_cursor(&mut harness), TextPosition::new(0, 7));
+}
+
+#[test]
+fn bracket_under_cursor_matches() {
+    let config = EditorConfig::new().with_language(Some(LanguageConfig::new()));
+    let mut harness = build_harness("f(x)", config, 20, 2);
+    let matched = with_editor(&mut harness, |editor| {
+        editor.buffer.set_cursor(TextPosition::new(0, 4));
+        editor.bracket_match()
+    });
+    assert_eq!(
+        matched,
+        Some((TextPosition::new(0, 3), TextPosition::new(0, 1)))
+    );
+}
+
 #[derive(Clone)]
 struct TestHighlighter {
     style: Style,
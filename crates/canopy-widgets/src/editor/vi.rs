@@ -5,7 +5,7 @@ use canopy::{
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{
-    Selection, TextPosition, TextRange,
+    HistoryStep, Selection, TextPosition, TextRange,
     search::SearchDirection,
     widget::{Editor, is_word_char},
 };
@@ -41,6 +41,11 @@ pub enum PendingKey {
     Yank,
     /// Waiting for a `g` sequence.
     G,
+    /// Waiting for the final key of `gcc` or `gbc`.
+    Comment {
+        /// Whether to toggle a block comment instead of line comments.
+        block: bool,
+    },
 }
 
 /// Repeatable edit actions.
@@ -359,6 +364,13 @@ impl Editor {
                 self.start_replace_prompt();
                 EventOutcome::Handle
             }
+            Event::Key(key::Key {
+                key: key::KeyCode::Char(':'),
+                ..
+            }) => {
+                self.start_ex_prompt();
+                EventOutcome::Handle
+            }
             Event::Key(key::Key {
                 key: key::KeyCode::Char('u'),
                 ..
@@ -371,6 +383,7 @@ impl Editor {
             Event::Key(key::Key {
                 key: key::KeyCode::Char('r'),
                 mods,
+                ..
             }) if mods.ctrl => {
                 self.buffer.redo();
                 self.update_preferred_column();
@@ -649,6 +662,59 @@ impl Editor {
                 self.vi.set_pending(None);
                 EventOutcome::Handle
             }
+            (
+                PendingKey::G,
+                Event::Key(key::Key {
+                    key: key::KeyCode::Char(c @ ('c' | 'b')),
+                    ..
+                }),
+            ) => {
+                self.vi
+                    .set_pending(Some(PendingKey::Comment { block: *c == 'b' }));
+                EventOutcome::Handle
+            }
+            (
+                PendingKey::Comment { block },
+                Event::Key(key::Key {
+                    key: key::KeyCode::Char('c'),
+                    ..
+                }),
+            ) => {
+                if block {
+                    self.toggle_block_comment();
+                } else {
+                    self.toggle_line_comment();
+                }
+                self.ensure_cursor_visible(ctx);
+                self.vi.set_pending(None);
+                EventOutcome::Handle
+            }
+            (
+                PendingKey::G,
+                Event::Key(key::Key {
+                    key: key::KeyCode::Char('-'),
+                    ..
+                }),
+            ) => {
+                self.buffer.earlier(HistoryStep::Count(1));
+                self.update_preferred_column();
+                self.ensure_cursor_visible(ctx);
+                self.vi.set_pending(None);
+                EventOutcome::Handle
+            }
+            (
+                PendingKey::G,
+                Event::Key(key::Key {
+                    key: key::KeyCode::Char('+'),
+                    ..
+                }),
+            ) => {
+                self.buffer.later(HistoryStep::Count(1));
+                self.update_preferred_column();
+                self.ensure_cursor_visible(ctx);
+                self.vi.set_pending(None);
+                EventOutcome::Handle
+            }
             (
                 PendingKey::Delete,
                 Event::Key(key::Key {
@@ -714,9 +780,10 @@ impl Editor {
             Event::Key(key::Key {
                 key: key::KeyCode::Char(c),
                 mods,
+                ..
             }) if !mods.ctrl && !mods.alt => {
-                self.handle_insert_text(&c.to_string());
-                self.vi.push_inserted(&c.to_string());
+                let inserted = self.type_char(*c);
+                self.vi.push_inserted(&inserted);
                 self.ensure_cursor_visible(ctx);
                 EventOutcome::Handle
             }
@@ -724,7 +791,11 @@ impl Editor {
                 key: key::KeyCode::Backspace,
                 ..
             }) => {
-                if self.handle_delete_backward() {
+                if self.delete_empty_pair() {
+                    self.vi.pop_inserted_grapheme();
+                    self.vi.pop_inserted_grapheme();
+                    self.ensure_cursor_visible(ctx);
+                } else if self.handle_delete_backward() {
                     self.vi.pop_inserted_grapheme();
                     self.ensure_cursor_visible(ctx);
                 }
@@ -744,8 +815,8 @@ impl Editor {
                 ..
             }) => {
                 if self.config.multiline {
-                    self.handle_insert_text("\n");
-                    self.vi.push_inserted("\n");
+                    let inserted = self.insert_newline();
+                    self.vi.push_inserted(&inserted);
                     self.ensure_cursor_visible(ctx);
                     EventOutcome::Handle
                 } else {
@@ -807,6 +878,23 @@ impl Editor {
         ctx: &mut dyn Context,
         mode: VisualMode,
     ) -> EventOutcome {
+        if self.vi.pending() == Some(PendingKey::G) {
+            self.vi.set_pending(None);
+            if let Event::Key(key::Key {
+                key: key::KeyCode::Char(c @ ('c' | 'b')),
+                ..
+            }) = event
+            {
+                if *c == 'b' {
+                    self.toggle_block_comment();
+                } else {
+                    self.toggle_line_comment();
+                }
+                self.exit_visual();
+                self.ensure_cursor_visible(ctx);
+                return EventOutcome::Handle;
+            }
+        }
         match event {
             Event::Key(key::Key {
                 key: key::KeyCode::Esc,
@@ -815,6 +903,13 @@ impl Editor {
                 self.exit_visual();
                 EventOutcome::Handle
             }
+            Event::Key(key::Key {
+                key: key::KeyCode::Char('g'),
+                ..
+            }) => {
+                self.vi.set_pending(Some(PendingKey::G));
+                EventOutcome::Handle
+            }
             Event::Key(key::Key {
                 key: key::KeyCode::Char('d'),
                 ..
@@ -1,11 +1,12 @@
 use std::{
     collections::HashMap,
+    path::PathBuf,
     time::{Duration, Instant},
 };
 
 use canopy::{
     Context, EventOutcome, ViewContext, Widget, command, cursor, derive_commands,
-    error::Result,
+    error::{Error, Result},
     event::{Event, key, mouse},
     geom::{Direction, Line, Point, Rect},
     layout::{CanvasContext, Constraint, MeasureConstraints, Measurement, Size},
@@ -15,13 +16,14 @@ use canopy::{
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{
-    EditMode, EditorConfig, LineNumbers, Selection, TextBuffer, TextPosition, TextRange, WrapMode,
-    display_width,
+    Document, EditMode, EditorConfig, LineNumbers, Selection, TextBuffer, TextPosition, TextRange,
+    UndoTree, WrapMode, display_width,
     highlight::{HighlightSpan, Highlighter},
     layout::{LayoutCache, WrapSegment, layout_line},
     search::{PromptState, SearchDirection, SearchState},
     vi::{ViMode, ViState},
 };
+use crate::Error as WidgetError;
 
 /// Maximum delay between clicks to count as multi-click selection.
 const DOUBLE_CLICK_MS: u64 = 500;
@@ -34,6 +36,8 @@ pub struct Editor {
     pub(super) config: EditorConfig,
     /// Text buffer backing the editor.
     pub(super) buffer: TextBuffer,
+    /// File-backed document, when the editor edits a file.
+    pub(super) document: Option<Document>,
     /// Layout cache for wrapping and mapping.
     pub(super) layout: LayoutCache,
     /// Cached cursor position in content coordinates.
@@ -94,6 +98,8 @@ struct RenderLineContext<'a, 'b> {
     origin: Point,
     /// Width of the line-number gutter.
     gutter_width: u32,
+    /// Bracket under the cursor and its match, highlighted together.
+    bracket_match: Option<(TextPosition, TextPosition)>,
 }
 
 impl<'a, 'b> RenderLineContext<'a, 'b> {
@@ -104,6 +110,7 @@ impl<'a, 'b> RenderLineContext<'a, 'b> {
             view_rect,
             origin,
             gutter_width,
+            bracket_match: None,
         }
     }
 }
@@ -169,6 +176,7 @@ impl Editor {
         Self {
             config,
             buffer,
+            document: None,
             layout: LayoutCache::new(),
             cursor_point: None,
             cursor_view_point: None,
@@ -185,6 +193,32 @@ impl Editor {
         }
     }
 
+    /// Construct an editor backed by a file. A missing file opens as a new, empty document.
+    pub fn open(path: impl Into<PathBuf>, config: EditorConfig) -> Result<Self> {
+        let (mut document, text) = Document::load(path).map_err(document_error)?;
+        let mut editor = Self::with_config(text, config);
+        if editor.config.undo_file
+            && let Some(history) = document
+                .load_history(&editor.buffer)
+                .map_err(document_error)?
+        {
+            editor.buffer.set_history(history);
+        }
+        document.mark_saved(&editor.buffer);
+        editor.document = Some(document);
+        Ok(editor)
+    }
+
+    /// Return the backing document, if any.
+    pub fn document(&self) -> Option<&Document> {
+        self.document.as_ref()
+    }
+
+    /// Return the undo history.
+    pub fn history(&self) -> &UndoTree {
+        self.buffer.history()
+    }
+
     /// Return the current editor configuration.
     pub fn config(&self) -> &EditorConfig {
         &self.config
@@ -207,6 +241,9 @@ impl Editor {
         self.buffer.set_cursor(TextPosition::new(0, 0));
         self.update_preferred_column();
         self.highlight_cache.clear();
+        if let Some(document) = self.document.as_mut() {
+            document.mark_dirty();
+        }
     }
 
     /// Return the current selection.
@@ -457,9 +494,10 @@ impl Editor {
             Event::Key(key::Key {
                 key: key::KeyCode::Char(c),
                 mods,
+                ..
             }) if !mods.ctrl && !mods.alt => {
                 self.begin_text_entry_transaction();
-                self.handle_insert_text(&c.to_string());
+                self.type_char(*c);
                 self.ensure_cursor_visible(ctx);
                 EventOutcome::Handle
             }
@@ -468,7 +506,7 @@ impl Editor {
                 ..
             }) => {
                 self.commit_text_entry_transaction();
-                if self.handle_delete_backward() {
+                if self.delete_empty_pair() || self.handle_delete_backward() {
                     self.ensure_cursor_visible(ctx);
                 }
                 EventOutcome::Handle
@@ -551,7 +589,7 @@ impl Editor {
             }) => {
                 self.commit_text_entry_transaction();
                 if self.config.multiline {
-                    self.handle_insert_text("\n");
+                    self.insert_newline();
                     self.ensure_cursor_visible(ctx);
                     EventOutcome::Handle
                 } else {
@@ -655,6 +693,38 @@ impl Editor {
         })
     }
 
+    /// Write the buffer to the backing document.
+    pub(super) fn save_document(&mut self, force: bool) -> crate::Result<()> {
+        self.commit_text_entry_transaction();
+        let document = self.document.as_mut().ok_or(WidgetError::NoDocument)?;
+        document.save(&self.buffer, force)?;
+        if self.config.undo_file {
+            document.save_history(&self.buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the buffer with the document contents as one undoable edit.
+    pub(super) fn reload_document(&mut self, force: bool) -> crate::Result<()> {
+        self.commit_text_entry_transaction();
+        let document = self.document.as_mut().ok_or(WidgetError::NoDocument)?;
+        if !force && document.is_dirty(&self.buffer) {
+            return Err(WidgetError::UnsavedChanges(document.path().to_path_buf()));
+        }
+        let text = document.reload()?;
+        if self.buffer.text() != text {
+            let cursor = self.buffer.cursor();
+            let last_line = self.buffer.line_count().saturating_sub(1);
+            let end = self.buffer.line_end_position(last_line, false);
+            self.buffer
+                .replace_range(TextRange::new(TextPosition::new(0, 0), end), &text);
+            self.buffer.set_cursor(cursor);
+        }
+        document.mark_saved(&self.buffer);
+        self.update_preferred_column();
+        Ok(())
+    }
+
     /// Render a single display line of text and gutter content.
     fn render_line(
         &mut self,
@@ -754,6 +824,12 @@ impl Editor {
 
             if selection_on_line && g_start < line_end_sel && g_end > line_start_sel {
                 style_name = "editor/selection";
+            } else if ctx.bracket_match.is_some_and(|(a, b)| {
+                [a, b]
+                    .iter()
+                    .any(|pos| pos.line == line_idx && pos.column == g_start)
+            }) {
+                style_name = "editor/bracket-match";
             } else if let Some((start, end)) = current_search_range {
                 if g_start < end && g_end > start {
                     style_name = "editor/search/current";
@@ -849,6 +925,43 @@ impl Editor {
         self.buffer.redo();
         self.update_preferred_column();
     }
+
+    /// Save the buffer to its document.
+    /// @param force Overwrite the file even if it changed on disk since it was read.
+    #[command]
+    pub fn save(&mut self, _ctx: &mut dyn Context, #[arg(default)] force: bool) -> Result<()> {
+        self.save_document(force).map_err(document_error)
+    }
+
+    /// Reload the buffer from its document.
+    /// @param force Discard unsaved edits instead of failing.
+    #[command]
+    pub fn reload(&mut self, ctx: &mut dyn Context, #[arg(default)] force: bool) -> Result<()> {
+        self.reload_document(force).map_err(document_error)?;
+        self.ensure_cursor_visible(ctx);
+        Ok(())
+    }
+
+    /// Toggle comments over the selected lines, or the cursor line.
+    /// @param block Toggle a block comment instead of line comments.
+    #[command]
+    pub fn toggle_comment(&mut self, ctx: &mut dyn Context, #[arg(default)] block: bool) {
+        self.commit_text_entry_transaction();
+        if block {
+            self.toggle_block_comment();
+        } else {
+            self.toggle_line_comment();
+        }
+        self.ensure_cursor_visible(ctx);
+    }
+
+    /// Return whether the buffer has edits that are not saved to its document.
+    #[command]
+    pub fn is_dirty(&self) -> bool {
+        self.document
+            .as_ref()
+            .is_some_and(|document| document.is_dirty(&self.buffer))
+    }
 }
 
 impl Widget for Editor {
@@ -865,7 +978,7 @@ impl Widget for Editor {
                 _ => cursor::CursorShape::Block,
             },
         };
-        Some(cursor::Cursor { location, shape })
+        Some(cursor::Cursor::new(location, shape).with_blink(true))
     }
 
     fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
@@ -880,6 +993,7 @@ impl Widget for Editor {
 
         {
             let mut line_ctx = RenderLineContext::new(r, view_rect, origin, gutter_width);
+            line_ctx.bracket_match = self.bracket_match();
             for row in 0..view_rect.h {
                 let display_line = view_rect.tl.y.saturating_add(row) as usize;
                 if display_line >= self.layout.total_lines() {
@@ -1049,9 +1163,16 @@ pub(super) fn prompt_text(prompt: &PromptState) -> String {
             format!("Replace {query} with: {replacement}")
         }
         PromptState::ReplaceConfirm { .. } => "Replace? (y/n/a/q)".to_string(),
+        PromptState::Ex { input } => format!(":{input}"),
+        PromptState::Message { text } => text.clone(),
     }
 }
 
+/// Convert a document failure into a command error.
+fn document_error(err: WidgetError) -> Error {
+    Error::Invalid(err.to_string())
+}
+
 /// Format a line number gutter entry.
 fn line_number_text(mode: LineNumbers, line: usize, cursor_line: usize, width: u32) -> String {
     let number = match mode {
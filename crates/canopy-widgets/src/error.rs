@@ -1,4 +1,4 @@
-use std::{io::Error as IoError, result::Result as StdResult};
+use std::{io::Error as IoError, path::PathBuf, result::Result as StdResult};
 
 use thiserror::Error;
 
@@ -14,6 +14,30 @@ pub enum Error {
     /// I/O error while reading font bytes.
     #[error("font I/O failed: {0}")]
     Io(#[from] IoError),
+    /// I/O error while reading or writing an editor document.
+    #[error("document I/O failed for {}: {source}", .path.display())]
+    DocumentIo {
+        /// Document path.
+        path: PathBuf,
+        /// Underlying I/O failure.
+        #[source]
+        source: IoError,
+    },
+    /// Document bytes are not valid UTF-8.
+    #[error("document is not valid UTF-8: {}", .0.display())]
+    InvalidEncoding(PathBuf),
+    /// The document file changed on disk since it was loaded or saved.
+    #[error("document changed on disk: {}", .0.display())]
+    ModifiedOnDisk(PathBuf),
+    /// The document has edits that would be discarded.
+    #[error("document has unsaved changes: {}", .0.display())]
+    UnsavedChanges(PathBuf),
+    /// The editor is not backed by a document.
+    #[error("editor has no document")]
+    NoDocument,
+    /// Undo history could not be encoded for its undo file.
+    #[error("undo history encoding failed: {0}")]
+    UndoEncode(#[source] serde_json::Error),
 }
 
 /// Result type for canopy-widgets helpers.
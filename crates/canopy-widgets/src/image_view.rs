@@ -1,20 +1,19 @@
 //! Image viewer widget with zoom and pan controls.
 
-use std::path::Path;
+use std::{path::Path, sync::Arc};
 
 use canopy::{
     Canopy, Context, Loader, ViewContext, Widget, command,
     commands::ZoomDirection,
     derive_commands, error as canopy_error,
     geom::{Direction, Point, Rect, Size},
+    graphics::Pixmap,
     layout::{CanvasContext, Layout},
-    render::Render,
-    style::{AttrSet, Color, ResolvedStyle},
+    render::{Render, SubCell},
+    style::Color,
 };
 use image::RgbaImage;
 
-/// Character used to render two vertical pixels per terminal cell.
-const HALF_BLOCK: char = '\u{2580}';
 /// Minimum zoom factor.
 const MIN_ZOOM: f32 = 0.1;
 /// Maximum zoom factor.
@@ -108,10 +107,14 @@ pub struct ImageView {
     image_height: u32,
     /// Integral image for fast sampling.
     integral: IntegralImage,
+    /// Full-resolution pixels for terminals with a graphics protocol.
+    pixmap: Option<Arc<Pixmap>>,
     /// Zoom factor in display subpixels per image pixel.
     zoom: f32,
     /// Whether the view should auto-fit the image to the terminal.
     auto_fit: bool,
+    /// Glyph grid used to draw the image as text.
+    mode: SubCell,
 }
 
 #[derive_commands]
@@ -201,24 +204,18 @@ impl ImageView {
         }
     }
 
-    /// Compute the image-space bounds of a display subpixel.
-    fn subpixel_bounds(
-        &self,
-        zoom: f32,
-        subpixel_column: f32,
-        subpixel_row: f32,
-    ) -> (f32, f32, f32, f32) {
-        let inverse_zoom = 1.0 / zoom;
-        let left = subpixel_column * inverse_zoom;
-        let right = (subpixel_column + 1.0) * inverse_zoom;
-        let top = subpixel_row * inverse_zoom;
-        let bottom = (subpixel_row + 1.0) * inverse_zoom;
-        (left, top, right, bottom)
-    }
-
     /// Sample a color from the image for a display subpixel.
     fn sample_color(&self, zoom: f32, subpixel_column: f32, subpixel_row: f32) -> Color {
-        let (left, top, right, bottom) = self.subpixel_bounds(zoom, subpixel_column, subpixel_row);
+        self.sample_span(zoom, subpixel_column, subpixel_row, 1.0, 1.0)
+    }
+
+    /// Sample a color from the image for a span of display subpixels.
+    fn sample_span(&self, zoom: f32, column: f32, row: f32, width: f32, height: f32) -> Color {
+        let inverse_zoom = 1.0 / zoom;
+        let left = column * inverse_zoom;
+        let right = (column + width) * inverse_zoom;
+        let top = row * inverse_zoom;
+        let bottom = (row + height) * inverse_zoom;
         let center_column = (left + right) * 0.5;
         let center_row = (top + bottom) * 0.5;
         if center_column < 0.0
@@ -288,7 +285,7 @@ impl ImageView {
         Some((red, green, blue))
     }
 
-    /// Render the image into the provided view rectangle.
+    /// Render the image into the provided view rectangle with the current glyph grid.
     fn render_cells(
         &self,
         render: &mut Render,
@@ -298,27 +295,69 @@ impl ImageView {
         zoom: f32,
     ) -> canopy_error::Result<()> {
         let (offset_x, offset_y) = offset;
+        let grid = self.mode.size();
+        let pixel_width = 1.0 / grid.w as f32;
+        // Display subpixels are half a cell tall.
+        let pixel_height = 2.0 / grid.h as f32;
+        let dest = Rect::new(origin.x, origin.y, view.w, view.h);
+        render.subcells(self.mode, dest, Color::Black, |x, y| {
+            let column = view.tl.x as f32 + x as f32 * pixel_width - offset_x;
+            let row = view.tl.y as f32 * 2.0 + y as f32 * pixel_height - offset_y;
+            Some(self.sample_span(zoom, column, row, pixel_width, pixel_height))
+        })
+    }
 
-        for row_index in 0..view.h {
-            let top_subpixel_row = view.tl.y.saturating_add(row_index).saturating_mul(2);
-            let bottom_subpixel_row = top_subpixel_row.saturating_add(1);
-            let top_row = top_subpixel_row as f32 - offset_y;
-            let bottom_row = bottom_subpixel_row as f32 - offset_y;
-
-            for column_index in 0..view.w {
-                let column = (view.tl.x + column_index) as f32 - offset_x;
-                let top_color = self.sample_color(zoom, column, top_row);
-                let bottom_color = self.sample_color(zoom, column, bottom_row);
-                let style = ResolvedStyle::new(top_color, bottom_color, AttrSet::default());
-                let point = Point {
-                    x: origin.x + column_index,
-                    y: origin.y + row_index,
-                };
-                render.put_cell(style, point, HALF_BLOCK)?;
-            }
+    /// Place the full-resolution image over the cells it covers in the view.
+    ///
+    /// Terminals with a graphics protocol show it instead of the half-block cells beneath.
+    fn place_image(
+        &self,
+        render: &mut Render,
+        view: Rect,
+        origin: Point,
+        offset: (f32, f32),
+        zoom: f32,
+    ) -> canopy_error::Result<()> {
+        let Some(pixmap) = &self.pixmap else {
+            return Ok(());
+        };
+        if zoom <= 0.0 {
+            return Ok(());
         }
+        let (offset_x, offset_y) = offset;
 
-        Ok(())
+        // Cells covered by the image, in canvas coordinates.
+        let left = offset_x.floor().max(view.tl.x as f32);
+        let right = (offset_x + self.image_width_f32() * zoom)
+            .ceil()
+            .min((view.tl.x + view.w) as f32);
+        let top = (offset_y / 2.0).floor().max(view.tl.y as f32);
+        let bottom = ((offset_y + self.image_height_f32() * zoom) / 2.0)
+            .ceil()
+            .min((view.tl.y + view.h) as f32);
+        if right <= left || bottom <= top {
+            return Ok(());
+        }
+
+        // Image pixels shown in those cells.
+        let image_x = |column: f32| ((column - offset_x) / zoom).clamp(0.0, self.image_width_f32());
+        let image_y =
+            |row: f32| ((row * 2.0 - offset_y) / zoom).clamp(0.0, self.image_height_f32());
+        let source_left = image_x(left).floor();
+        let source_top = image_y(top).floor();
+        let source = Rect::new(
+            source_left as u32,
+            source_top as u32,
+            (image_x(right).ceil() - source_left) as u32,
+            (image_y(bottom).ceil() - source_top) as u32,
+        );
+        let dest = Rect::new(
+            origin.x + (left as u32 - view.tl.x),
+            origin.y + (top as u32 - view.tl.y),
+            (right - left) as u32,
+            (bottom - top) as u32,
+        );
+        render.image(pixmap, source, dest)
     }
 
     /// Create a new image view widget.
@@ -326,12 +365,17 @@ impl ImageView {
         let image_width = image.width();
         let image_height = image.height();
         let integral = IntegralImage::new(image);
+        let pixmap = Pixmap::new(image_width, image_height, image.as_raw().clone())
+            .ok()
+            .map(Arc::new);
         Self {
             image_width,
             image_height,
             integral,
+            pixmap,
             zoom: 1.0,
             auto_fit: true,
+            mode: SubCell::default(),
         }
     }
 
@@ -349,6 +393,33 @@ impl ImageView {
         self
     }
 
+    /// Configure the glyph grid used to draw the image as text.
+    pub fn with_mode(mut self, mode: SubCell) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Return the glyph grid used to draw the image as text.
+    pub fn mode(&self) -> SubCell {
+        self.mode
+    }
+
+    /// Draw the image with a glyph grid.
+    /// @param mode The glyph grid.
+    #[command]
+    pub fn set_mode(&mut self, _ctx: &mut dyn Context, mode: SubCell) -> canopy_error::Result<()> {
+        self.mode = mode;
+        Ok(())
+    }
+
+    /// Switch to the next glyph grid, wrapping from braille back to half blocks.
+    #[command]
+    pub fn cycle_mode(&mut self, _ctx: &mut dyn Context) -> canopy_error::Result<()> {
+        let index = SubCell::ALL.iter().position(|mode| *mode == self.mode);
+        self.mode = SubCell::ALL[index.map_or(0, |i| (i + 1) % SubCell::ALL.len())];
+        Ok(())
+    }
+
     /// Zoom around the view center.
     /// @param dir The zoom direction.
     #[command]
@@ -415,7 +486,9 @@ impl Widget for ImageView {
         self.apply_auto_fit(view_size);
 
         let offset = self.center_offset(view_size, self.zoom);
-        self.render_cells(render, view_rect, view.content_origin(), offset, self.zoom)
+        let origin = view.content_origin();
+        self.render_cells(render, view_rect, origin, offset, self.zoom)?;
+        self.place_image(render, view_rect, origin, offset, self.zoom)
     }
 
     /// Accept focus so key bindings apply to this widget.
@@ -215,13 +215,16 @@ impl Widget for Input {
     }
 
     fn cursor(&self) -> Option<cursor::Cursor> {
-        Some(cursor::Cursor {
-            location: Point {
-                x: self.buffer.cursor_display(),
-                y: 0,
-            },
-            shape: cursor::CursorShape::Block,
-        })
+        Some(
+            cursor::Cursor::new(
+                Point {
+                    x: self.buffer.cursor_display(),
+                    y: 0,
+                },
+                cursor::CursorShape::Block,
+            )
+            .with_blink(true),
+        )
     }
 
     fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
@@ -1,5 +1,7 @@
 /// Log panel widget.
 pub mod logs;
+/// Frame statistics panel widget.
+pub mod stats;
 /// Inspector view layout.
 mod view;
 
@@ -14,7 +16,7 @@ use crate::{frame, tabs};
 /// Default inspector bindings exposed through `inspector.default_bindings()`.
 const DEFAULT_BINDINGS: &str = r#"
 canopy.bind("Tab", { path = "inspector/", description = "Next tab" }, function()
-    tabs.select_by(1)
+    view.select_tab(1)
 end)
 
 canopy.bind("C", { path = "logs", description = "Clear log entry" }, function()
@@ -98,6 +100,7 @@ impl Loader for Inspector {
     fn load(c: &mut Canopy) -> Result<()> {
         c.add_commands::<Self>()?;
         c.add_commands::<tabs::Tabs>()?;
+        c.add_commands::<view::View>()?;
         c.register_default_bindings("inspector", DEFAULT_BINDINGS)?;
         Logs::load(c)?;
         Ok(())
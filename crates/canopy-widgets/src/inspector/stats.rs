@@ -0,0 +1,101 @@
+//! Frame statistics panel for the inspector widget.
+
+use std::time::Duration;
+
+use canopy::{
+    Context, FrameStats, ViewContext, Widget, derive_commands, error::Result, render::Render,
+    state::NodeName,
+};
+
+/// Interval between refreshes of the statistics display.
+const REFRESH: Duration = Duration::from_millis(500);
+
+/// Widget showing render timing for recent frames.
+pub struct Stats;
+
+#[derive_commands]
+impl Stats {
+    /// Construct a statistics panel.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format a duration as fractional milliseconds.
+fn millis(d: Duration) -> String {
+    format!("{:.2}ms", d.as_secs_f64() * 1000.0)
+}
+
+/// Format frame statistics as display lines.
+fn lines(stats: &FrameStats) -> Vec<String> {
+    vec![
+        format!("frames   {}", stats.frames),
+        format!("fps      {:.1}", stats.fps),
+        format!("last     {}", millis(stats.last.total())),
+        format!("  layout {}", millis(stats.last.layout)),
+        format!("  draw   {}", millis(stats.last.draw)),
+        format!("  emit   {}", millis(stats.last.emit)),
+        format!("  events {}", stats.last.events),
+        format!("average  {}", millis(stats.average)),
+        format!("worst    {}", millis(stats.worst)),
+    ]
+}
+
+impl Widget for Stats {
+    fn render(&mut self, r: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
+        let rect = ctx.view().view_rect_local();
+        r.fill("", rect, ' ')?;
+        for (i, line) in lines(&ctx.frame_stats()).iter().enumerate() {
+            let Ok(row) = u32::try_from(i) else {
+                break;
+            };
+            if row >= rect.h {
+                break;
+            }
+            r.text("text", rect.line(row)?, line)?;
+        }
+        Ok(())
+    }
+
+    fn poll(&mut self, _ctx: &mut dyn Context) -> Option<Duration> {
+        // Polling schedules a render, which picks up the latest statistics.
+        Some(REFRESH)
+    }
+
+    fn name(&self) -> NodeName {
+        NodeName::convert("stats")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use canopy::FrameTiming;
+
+    use super::*;
+
+    #[test]
+    fn lines_break_down_the_last_frame() {
+        let stats = FrameStats {
+            frames: 12,
+            last: FrameTiming {
+                layout: Duration::from_micros(250),
+                draw: Duration::from_millis(1),
+                emit: Duration::from_micros(500),
+                events: 3,
+            },
+            fps: 59.94,
+            ..FrameStats::default()
+        };
+        let lines = lines(&stats);
+        assert_eq!(lines[0], "frames   12");
+        assert_eq!(lines[1], "fps      59.9");
+        assert_eq!(lines[2], "last     1.75ms");
+        assert_eq!(lines[6], "  events 3");
+    }
+}
@@ -1,12 +1,19 @@
 use canopy::{
-    Context, NodeId, ViewContext, Widget, derive_commands, error::Result, layout::Layout,
-    state::NodeName,
+    Context, NodeId, TypedId, ViewContext, Widget, command, derive_commands, error::Result,
+    layout::Layout, state::NodeName,
 };
 
+use super::{logs::Logs, stats::Stats};
 use crate::tabs::Tabs;
 
 /// View contains the body of the inspector.
-pub struct View;
+#[derive(Default)]
+pub struct View {
+    /// Tab bar selecting the visible panel.
+    tabs: Option<TypedId<Tabs>>,
+    /// Panels in tab order. Only the active panel is shown.
+    panels: Vec<NodeId>,
+}
 
 impl Widget for View {
     fn layout(&self) -> Layout {
@@ -26,17 +33,41 @@ impl Widget for View {
 impl View {
     /// Construct a new inspector view with child node IDs.
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
     /// Construct a new inspector view.
     pub fn install(context: &mut dyn Context) -> Result<(NodeId, NodeId, NodeId)> {
-        let tabs = context.create_detached(Tabs::new(vec!["Stats", "Logs"]))?;
-        let logs = context.create_detached(super::logs::Logs::new())?;
-        let view_id = context.create_detached(Self::new())?;
-        context.set_children_of(view_id.into(), vec![tabs.into(), logs.into()])?;
+        // Logs start capturing when first shown, so show them by default.
+        let tabs = context.create_detached(Tabs::new(vec!["Stats", "Logs"]).with_active(1))?;
+        let stats = context.create_detached(Stats::new())?;
+        let logs = context.create_detached(Logs::new())?;
+        let view_id = context.create_detached(Self {
+            tabs: Some(tabs),
+            panels: vec![stats.into(), logs.into()],
+        })?;
+        context.set_children_of(view_id.into(), vec![tabs.into(), stats.into(), logs.into()])?;
         context.set_layout_of(tabs, Layout::column().flex_horizontal(1).fixed_height(1))?;
+        context.set_layout_of(stats, Layout::fill())?;
         context.set_layout_of(logs, Layout::fill())?;
+        context.set_hidden_of(stats.into(), true)?;
         Ok((view_id.into(), tabs.into(), logs.into()))
     }
+
+    /// Switch to another tab by signed offset, showing only its panel.
+    /// @param delta Signed tab delta. Positive moves forward and negative moves backward.
+    #[command]
+    pub fn select_tab(&mut self, ctx: &mut dyn Context, delta: i32) -> Result<()> {
+        let Some(tabs) = self.tabs else {
+            return Ok(());
+        };
+        let active = ctx.with_widget(tabs, |tabs, ctx| {
+            tabs.select_by(ctx, delta);
+            Ok(tabs.active())
+        })?;
+        for (i, panel) in self.panels.iter().enumerate() {
+            ctx.set_hidden_of(*panel, i != active)?;
+        }
+        Ok(())
+    }
 }
@@ -9,8 +9,12 @@
 mod boxed;
 /// Button widget with command dispatch.
 mod button;
+/// Drawing surface for lines, shapes and labels.
+mod canvas;
 /// Content centering container.
 mod center;
+/// Chart widgets for dashboards.
+pub mod chart;
 /// Dropdown selection widget.
 mod dropdown;
 /// Experimental editor API with syntax highlighting and vi mode.
@@ -57,6 +61,7 @@ mod wrap;
 
 pub use boxed::{Border, BoxGlyphs, DOUBLE, ROUND, SINGLE, SINGLE_THICK};
 pub use button::Button;
+pub use canvas::{Canvas, Shape};
 pub use center::Center;
 pub use dropdown::Dropdown;
 pub use error::{Error, Result};
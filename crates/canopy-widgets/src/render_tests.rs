@@ -6,12 +6,17 @@ mod tests {
         Canopy, Context, Loader, ViewContext, Widget, buf,
         commands::{CommandNode, CommandSpec},
         error::Result,
+        geom::Rect,
         layout::Layout,
+        render::SubCell,
         state::NodeName,
         testing::harness::Harness,
     };
 
-    use crate::{BoxGlyphs, Button, Frame, List, ScrollGlyphs, Text};
+    use crate::{
+        BoxGlyphs, Button, Canvas, Frame, ImageView, List, ScrollGlyphs, Shape, Text,
+        chart::{Bar, BarChart, Gauge, LineChart, Orientation, Series, Sparkline},
+    };
 
     const ASCII_BOX: BoxGlyphs = BoxGlyphs {
         topleft: '+',
@@ -124,4 +129,192 @@ mod tests {
             .assert_matches(buf![">One" " Two" " Three" ""]);
         Ok(())
     }
+
+    #[test]
+    fn image_view_places_its_image_over_the_covered_cells() -> Result<()> {
+        let image = image::RgbaImage::from_pixel(20, 10, image::Rgba([0, 128, 255, 255]));
+        let root = SnapshotRoot::new(ImageView::new(&image));
+        let mut harness = Harness::builder(root).size(10, 5).build()?;
+        harness.render()?;
+
+        // Fitting halves the image, which is then centred vertically.
+        let images = harness.buf().images();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].source, Rect::new(0, 0, 20, 10));
+        assert_eq!(images[0].dest, Rect::new(0, 1, 10, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn image_view_quadrants_resolve_detail_within_a_cell() -> Result<()> {
+        let image = image::RgbaImage::from_fn(4, 4, |x, _| {
+            if x % 2 == 0 {
+                image::Rgba([255, 0, 0, 255])
+            } else {
+                image::Rgba([0, 0, 255, 255])
+            }
+        });
+        let root = SnapshotRoot::new(ImageView::new(&image).with_mode(SubCell::Quadrant));
+        let mut harness = Harness::builder(root).size(2, 1).build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["▌▌"]);
+        Ok(())
+    }
+
+    #[test]
+    fn canvas_draws_lines_in_braille() -> Result<()> {
+        let canvas = Canvas::new((0.0, 7.0), (0.0, 7.0)).with_shape(
+            0,
+            "canvas/line",
+            Shape::Line {
+                from: (0.0, 0.0),
+                to: (7.0, 7.0),
+            },
+        );
+        let root = SnapshotRoot::new(canvas);
+        let mut harness = Harness::builder(root).size(4, 2).build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["  ⡠⠊" "⡠⠊  "]);
+        Ok(())
+    }
+
+    #[test]
+    fn canvas_outlines_and_fills_rectangles() -> Result<()> {
+        let outline = Canvas::new((0.0, 7.0), (0.0, 7.0)).with_shape(
+            0,
+            "canvas/shape",
+            Shape::Rect {
+                from: (0.0, 0.0),
+                to: (7.0, 7.0),
+                filled: false,
+            },
+        );
+        let mut harness = Harness::builder(SnapshotRoot::new(outline))
+            .size(4, 2)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["⡏⠉⠉⢹" "⣇⣀⣀⣸"]);
+
+        let filled = Canvas::new((0.0, 3.0), (0.0, 3.0))
+            .with_grid(SubCell::HalfBlock)
+            .with_shape(
+                0,
+                "canvas/shape",
+                Shape::Rect {
+                    from: (1.0, 1.0),
+                    to: (2.0, 2.0),
+                    filled: true,
+                },
+            );
+        let mut harness = Harness::builder(SnapshotRoot::new(filled))
+            .size(4, 2)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf![" ▄▄" " ▀▀"]);
+        Ok(())
+    }
+
+    #[test]
+    fn canvas_labels_draw_over_pixels() -> Result<()> {
+        let canvas = Canvas::new((0.0, 5.0), (0.0, 1.0))
+            .with_grid(SubCell::HalfBlock)
+            .with_shape(
+                0,
+                "canvas/label",
+                Shape::Label {
+                    at: (1.0, 1.0),
+                    text: "ok".into(),
+                },
+            )
+            .with_shape(
+                1,
+                "canvas/line",
+                Shape::Line {
+                    from: (0.0, 0.0),
+                    to: (5.0, 0.0),
+                },
+            );
+        let root = SnapshotRoot::new(canvas);
+        let mut harness = Harness::builder(root).size(6, 1).build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["▄ok▄▄▄"]);
+        Ok(())
+    }
+
+    #[test]
+    fn sparkline_fills_columns_in_eighths() -> Result<()> {
+        let sparkline = Sparkline::new().with_data((0..=8).map(f64::from));
+        let mut harness = Harness::builder(SnapshotRoot::new(sparkline))
+            .size(9, 1)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf![" ▁▂▃▄▅▆▇█"]);
+
+        // Only the newest samples that fit are drawn, scaled to the visible maximum.
+        let sparkline = Sparkline::new().with_data([8.0, 1.0, 2.0, 3.0, 4.0]);
+        let mut harness = Harness::builder(SnapshotRoot::new(sparkline))
+            .size(4, 2)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["  ▄█" "▄███"]);
+        Ok(())
+    }
+
+    #[test]
+    fn bar_charts_label_vertical_and_horizontal_bars() -> Result<()> {
+        let bars = [Bar::new("A", 2.0), Bar::new("B", 4.0)];
+        let vertical = BarChart::new().with_bar_width(1, 1).with_bars(bars);
+        let mut harness = Harness::builder(SnapshotRoot::new(vertical))
+            .size(3, 3)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["2 █" "█ █" "A B"]);
+
+        let horizontal = BarChart::new()
+            .with_orientation(Orientation::Horizontal)
+            .with_bars([Bar::new("cpu", 50.0), Bar::new("io", 100.0)]);
+        let mut harness = Harness::builder(SnapshotRoot::new(horizontal))
+            .size(12, 2)
+            .build()?;
+        harness.render()?;
+        harness
+            .tbuf()
+            .assert_matches(buf!["cpu ██ 50" "io  ████ 100"]);
+        Ok(())
+    }
+
+    #[test]
+    fn line_chart_draws_axes_ticks_and_legend() -> Result<()> {
+        let chart = LineChart::new().with_series(Series::new("up").with_points([
+            (0.0, 0.0),
+            (5.0, 2.0),
+            (10.0, 4.0),
+        ]));
+        let mut harness = Harness::builder(SnapshotRoot::new(chart))
+            .size(12, 8)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf![
+            "4┤      ━ up"
+            " │       ⡰⠁"
+            " │     ⢀⠎"
+            "2┤   ⢀⠔⠁"
+            " │  ⡰⠁"
+            "0┤⡠⠊"
+            " └┬────────┬"
+            "  0       10"
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn gauge_centres_its_percentage_over_the_bar() -> Result<()> {
+        let gauge = Gauge::new().with_ratio(0.5);
+        let mut harness = Harness::builder(SnapshotRoot::new(gauge))
+            .size(10, 1)
+            .build()?;
+        harness.render()?;
+        harness.tbuf().assert_matches(buf!["███50%"]);
+        Ok(())
+    }
 }
@@ -28,6 +28,9 @@ end)
 canopy.bind("q", { path = "root", description = "Quit" }, function()
     root.quit()
 end)
+canopy.bind("ctrl-z", { path = "root", description = "Suspend" }, function()
+    root.suspend()
+end)
 canopy.bind("a", { path = "inspector", description = "Focus app" }, function()
     root.focus_app()
 end)
@@ -168,6 +171,12 @@ impl Root {
         Ok(())
     }
 
+    #[command]
+    /// Suspend the program as a shell job. It resumes with a full repaint when continued.
+    pub fn suspend(&mut self, c: &mut dyn Context) -> Result<()> {
+        c.suspend()
+    }
+
     #[command]
     /// Dump diagnostic information about the tree, focus, and bindings.
     pub fn dump_diagnostics(&mut self, c: &mut dyn Context) -> Result<()> {
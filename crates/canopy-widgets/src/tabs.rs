@@ -25,6 +25,17 @@ impl Tabs {
         }
     }
 
+    /// Start with a different tab active. Out-of-range indices are clamped to the last tab.
+    pub fn with_active(mut self, active: usize) -> Self {
+        self.active = active.min(self.tabs.len().saturating_sub(1));
+        self
+    }
+
+    /// Return the index of the active tab.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
     /// Select a tab by signed offset.
     /// @param delta Signed tab delta. Positive moves forward and negative moves backward.
     #[command]
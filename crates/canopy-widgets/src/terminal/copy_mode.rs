@@ -0,0 +1,881 @@
+use canopy::event::key::{Key, KeyCode};
+use itty_core::inspect::StyledRunPublic;
+use regex::Regex;
+use unicode_width::UnicodeWidthChar;
+
+/// Style used for search matches.
+const MATCH_STYLE: &str = "terminal/search/match";
+/// Style used for the match under the cursor.
+const CURRENT_MATCH_STYLE: &str = "terminal/search/current";
+/// Style used for the copy-mode selection.
+const SELECTION_STYLE: &str = "terminal/selection";
+
+/// A cell position in a copy-mode snapshot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct CopyPoint {
+    /// Snapshot line, counting from the oldest scrollback line.
+    pub(super) line: usize,
+    /// Character column within the line.
+    pub(super) column: usize,
+}
+
+impl CopyPoint {
+    /// Construct a point.
+    pub(super) fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Return the cell column where character `column` of a line starts.
+///
+/// Wide glyphs take two cells; columns past the end of the text take one each.
+pub(super) fn cell_column(text: &str, column: usize) -> usize {
+    let mut cells = 0;
+    let mut chars = 0;
+    for c in text.chars().take(column) {
+        cells += c.width().unwrap_or(0);
+        chars += 1;
+    }
+    cells + (column - chars)
+}
+
+/// Return the character column that covers cell `cell` of a line.
+pub(super) fn char_column(text: &str, cell: usize) -> usize {
+    let mut cells = 0;
+    for (column, c) in text.chars().enumerate() {
+        cells += c.width().unwrap_or(0);
+        if cells > cell {
+            return column;
+        }
+    }
+    text.chars().count() + (cell - cells)
+}
+
+/// One search match, covering columns `start..end` of a line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CopyMatch {
+    /// Snapshot line of the match.
+    line: usize,
+    /// First matched column.
+    start: usize,
+    /// Column after the last matched column.
+    end: usize,
+}
+
+/// Frozen terminal contents: the scrollback followed by the visible grid.
+pub(super) struct Snapshot {
+    /// Plain text of each line.
+    pub(super) text: Vec<String>,
+    /// Styled runs of each line, parallel to `text`.
+    pub(super) runs: Vec<Vec<StyledRunPublic>>,
+}
+
+/// Search prompt being edited.
+struct Prompt {
+    /// Query typed so far.
+    query: String,
+    /// Whether the search runs towards older output.
+    backward: bool,
+    /// Cursor position when the prompt opened.
+    origin: CopyPoint,
+}
+
+/// Outcome of a key press in copy mode.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum CopyAction {
+    /// The key was consumed.
+    Handled,
+    /// Copy the text to the clipboard and leave copy mode.
+    Yank(String),
+    /// Leave copy mode.
+    Exit,
+}
+
+/// Keyboard navigation, search and selection over a terminal snapshot.
+///
+/// Copy mode works on a frozen snapshot so output arriving from the child
+/// process does not shift lines under the cursor. Columns count characters.
+pub(super) struct CopyMode {
+    /// Frozen terminal contents.
+    snapshot: Snapshot,
+    /// Viewport height in lines.
+    rows: usize,
+    /// First snapshot line shown in the viewport.
+    top: usize,
+    /// Cursor position.
+    cursor: CopyPoint,
+    /// Column vertical motions aim for.
+    want_column: usize,
+    /// Selection anchor, when a selection is active.
+    anchor: Option<CopyPoint>,
+    /// Whether the selection covers whole lines.
+    linewise: bool,
+    /// Last submitted search pattern.
+    query: String,
+    /// Compiled search pattern.
+    search: Option<Regex>,
+    /// Whether the search runs towards older output.
+    backward: bool,
+    /// Matches of the search pattern, in document order.
+    matches: Vec<CopyMatch>,
+    /// Search prompt being edited.
+    prompt: Option<Prompt>,
+    /// Pending numeric count.
+    count: Option<usize>,
+    /// Whether a `g` prefix is pending.
+    pending_g: bool,
+}
+
+impl CopyMode {
+    /// Start copy mode over a snapshot with the cursor at `cursor`.
+    pub(super) fn new(snapshot: Snapshot, rows: usize, cursor: CopyPoint) -> Self {
+        let mut mode = Self {
+            snapshot,
+            rows: rows.max(1),
+            top: 0,
+            cursor,
+            want_column: cursor.column,
+            anchor: None,
+            linewise: false,
+            query: String::new(),
+            search: None,
+            backward: false,
+            matches: Vec::new(),
+            prompt: None,
+            count: None,
+            pending_g: false,
+        };
+        mode.top = mode.max_top();
+        mode.move_to(cursor);
+        mode
+    }
+
+    /// Return the snapshot lines, oldest first.
+    pub(super) fn lines(&self) -> &[String] {
+        &self.snapshot.text
+    }
+
+    /// Return the styled runs of a snapshot line.
+    pub(super) fn runs(&self, line: usize) -> &[StyledRunPublic] {
+        self.snapshot.runs.get(line).map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the first snapshot line shown in the viewport.
+    pub(super) fn top(&self) -> usize {
+        self.top
+    }
+
+    /// Return the cursor position.
+    pub(super) fn cursor(&self) -> CopyPoint {
+        self.cursor
+    }
+
+    /// Adapt to a new viewport height.
+    pub(super) fn resize(&mut self, rows: usize) {
+        self.rows = rows.max(1);
+        self.top = self.top.min(self.max_top());
+        self.scroll_to_cursor();
+    }
+
+    /// Scroll the viewport by a number of lines, keeping the cursor inside it.
+    pub(super) fn scroll(&mut self, delta: isize) {
+        self.top = self.top.saturating_add_signed(delta).min(self.max_top());
+        let last = self.top + self.rows - 1;
+        let line = self.cursor.line.clamp(self.top, last);
+        self.set_cursor_line(line);
+    }
+
+    /// Return the search prompt text, while a prompt is open.
+    pub(super) fn prompt(&self) -> Option<String> {
+        self.prompt.as_ref().map(|prompt| {
+            let marker = if prompt.backward { '?' } else { '/' };
+            format!("{marker}{}", prompt.query)
+        })
+    }
+
+    /// Return a position indicator such as `[12/340]`, prefixed by the
+    /// pattern and match count while a search is active.
+    pub(super) fn status(&self) -> String {
+        let position = format!("[{}/{}]", self.cursor.line + 1, self.line_count());
+        if self.search.is_none() {
+            return position;
+        }
+        let marker = if self.backward { '?' } else { '/' };
+        let query = &self.query;
+        match self.current_match() {
+            Some(index) => format!(
+                "{marker}{query} ({}/{}) {position}",
+                index + 1,
+                self.matches.len()
+            ),
+            None => format!("{marker}{query} ({}) {position}", self.matches.len()),
+        }
+    }
+
+    /// Return styled column spans to draw over a line, in drawing order.
+    pub(super) fn highlights(&self, line: usize) -> Vec<(usize, usize, &'static str)> {
+        let current = self.current_match().map(|index| self.matches[index]);
+        let mut spans: Vec<_> = self
+            .matches
+            .iter()
+            .filter(|found| found.line == line)
+            .map(|found| {
+                let style = if Some(*found) == current {
+                    CURRENT_MATCH_STYLE
+                } else {
+                    MATCH_STYLE
+                };
+                (found.start, found.end, style)
+            })
+            .collect();
+        if let Some((start, end)) = self.selection_span(line) {
+            spans.push((start, end, SELECTION_STYLE));
+        }
+        spans
+    }
+
+    /// Compile and run a search, moving to the first match in its direction.
+    ///
+    /// Returns the number of matches.
+    pub(super) fn set_search(
+        &mut self,
+        query: String,
+        backward: bool,
+    ) -> Result<usize, regex::Error> {
+        let regex = Regex::new(&query)?;
+        self.matches = find_matches(&regex, &self.snapshot.text);
+        self.search = Some(regex);
+        self.query = query;
+        self.backward = backward;
+        self.prompt = None;
+        if let Some(target) = self.next_match_from(self.cursor, backward) {
+            self.move_to(target);
+        }
+        Ok(self.matches.len())
+    }
+
+    /// Move to the next match in the search direction, or against it when
+    /// `reverse` is set. Returns the match position.
+    pub(super) fn search_next(&mut self, reverse: bool) -> Option<CopyPoint> {
+        let target = self.next_match_from(self.cursor, self.backward != reverse)?;
+        self.move_to(target);
+        Some(target)
+    }
+
+    /// Return the selected text, or the cursor line when nothing is selected.
+    pub(super) fn yank_text(&self) -> String {
+        let Some(anchor) = self.anchor else {
+            return self.line_text(self.cursor.line).trim_end().to_string();
+        };
+        let (start, end) = ordered(anchor, self.cursor);
+        (start.line..=end.line)
+            .map(|line| {
+                let text = self.line_text(line);
+                let (from, to) = self
+                    .selection_span(line)
+                    .unwrap_or((0, text.chars().count()));
+                let segment: String = text.chars().skip(from).take(to - from).collect();
+                if to >= text.chars().count() {
+                    segment.trim_end().to_string()
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Handle a key press.
+    pub(super) fn handle_key(&mut self, key: Key) -> CopyAction {
+        let key = key.normalize();
+        if self.prompt.is_some() {
+            self.handle_prompt_key(key);
+            return CopyAction::Handled;
+        }
+        if self.pending_g {
+            self.pending_g = false;
+            if key.key == KeyCode::Char('g') && !key.mods.ctrl {
+                let line = self.count.take().map_or(0, |count| count - 1);
+                self.set_cursor_line(line.min(self.last_line()));
+            }
+            self.count = None;
+            return CopyAction::Handled;
+        }
+        if let KeyCode::Char(c) = key.key
+            && !key.mods.ctrl
+            && let Some(digit) = c.to_digit(10)
+            && (digit > 0 || self.count.is_some())
+        {
+            let count = self.count.unwrap_or(0);
+            self.count = Some(count.saturating_mul(10).saturating_add(digit as usize));
+            return CopyAction::Handled;
+        }
+        let count = self.count.take();
+        self.handle_command_key(key, count)
+    }
+
+    /// Handle a key that is not a count or `g` suffix.
+    fn handle_command_key(&mut self, key: Key, count: Option<usize>) -> CopyAction {
+        match key.key {
+            KeyCode::Esc if self.anchor.is_some() => self.anchor = None,
+            KeyCode::Esc | KeyCode::Char('q') if !key.mods.ctrl => return CopyAction::Exit,
+            KeyCode::Enter => return CopyAction::Yank(self.yank_text()),
+            KeyCode::Char('y') if !key.mods.ctrl => return CopyAction::Yank(self.yank_text()),
+            KeyCode::Char('v') if !key.mods.ctrl => self.toggle_selection(false),
+            KeyCode::Char('V') => self.toggle_selection(true),
+            KeyCode::Char('g') if !key.mods.ctrl => {
+                self.pending_g = true;
+                self.count = count;
+            }
+            KeyCode::Char('G') => {
+                let line = count.map_or(self.last_line(), |count| count - 1);
+                self.set_cursor_line(line.min(self.last_line()));
+            }
+            KeyCode::Char(c @ ('/' | '?')) => {
+                self.prompt = Some(Prompt {
+                    query: String::new(),
+                    backward: c == '?',
+                    origin: self.cursor,
+                });
+            }
+            KeyCode::Char(c @ ('n' | 'N')) if !key.mods.ctrl => {
+                for _ in 0..count.unwrap_or(1) {
+                    self.search_next(c == 'N');
+                }
+            }
+            _ => self.handle_motion_key(key, count.unwrap_or(1)),
+        }
+        CopyAction::Handled
+    }
+
+    /// Apply a cursor motion key `count` times.
+    fn handle_motion_key(&mut self, key: Key, count: usize) {
+        let half = (self.rows / 2).max(1) as isize;
+        let page = self.rows as isize;
+        let lines = match (key.mods.ctrl, key.key) {
+            (true, KeyCode::Char('u')) => Some(-half),
+            (true, KeyCode::Char('d')) => Some(half),
+            (true, KeyCode::Char('b')) | (false, KeyCode::PageUp) => Some(-page),
+            (true, KeyCode::Char('f')) | (false, KeyCode::PageDown) => Some(page),
+            (true, KeyCode::Char('y')) => {
+                self.scroll(-(count as isize));
+                return;
+            }
+            (true, KeyCode::Char('e')) => {
+                self.scroll(count as isize);
+                return;
+            }
+            _ => None,
+        };
+        if let Some(lines) = lines {
+            let delta = lines.saturating_mul(count as isize);
+            self.top = self.top.saturating_add_signed(delta).min(self.max_top());
+            let line = self.cursor.line.saturating_add_signed(delta);
+            self.set_cursor_line(line.min(self.last_line()));
+            return;
+        }
+        if key.mods.ctrl {
+            return;
+        }
+        for _ in 0..count {
+            if !self.apply_motion(key.key) {
+                break;
+            }
+        }
+    }
+
+    /// Apply one motion. Returns false when the key is not a repeatable motion.
+    fn apply_motion(&mut self, key: KeyCode) -> bool {
+        let cursor = self.cursor;
+        match key {
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.move_to(CopyPoint::new(cursor.line, cursor.column.saturating_sub(1)));
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.move_to(CopyPoint::new(cursor.line, cursor.column + 1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.set_cursor_line(cursor.line.saturating_sub(1));
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.set_cursor_line((cursor.line + 1).min(self.last_line()));
+            }
+            KeyCode::Char('w') => self.move_to(self.word_forward(cursor)),
+            KeyCode::Char('b') => self.move_to(self.word_backward(cursor)),
+            KeyCode::Char('e') => self.move_to(self.word_end(cursor)),
+            _ => {
+                self.apply_jump(key);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Apply a motion that does not repeat, such as `0` or `H`.
+    fn apply_jump(&mut self, key: KeyCode) {
+        let line = self.cursor.line;
+        let bottom = (self.top + self.rows - 1).min(self.last_line());
+        match key {
+            KeyCode::Char('0') | KeyCode::Home => self.move_to(CopyPoint::new(line, 0)),
+            KeyCode::Char('^') => {
+                let column = self
+                    .line_text(line)
+                    .chars()
+                    .position(|c| !c.is_whitespace())
+                    .unwrap_or(0);
+                self.move_to(CopyPoint::new(line, column));
+            }
+            KeyCode::Char('$') | KeyCode::End => {
+                let end = self.line_text(line).trim_end().chars().count();
+                self.move_to(CopyPoint::new(line, end.saturating_sub(1)));
+                self.want_column = usize::MAX;
+            }
+            KeyCode::Char('H') => self.set_cursor_line(self.top),
+            KeyCode::Char('M') => self.set_cursor_line((self.top + bottom) / 2),
+            KeyCode::Char('L') => self.set_cursor_line(bottom),
+            _ => {}
+        }
+    }
+
+    /// Edit the search prompt, searching incrementally as the query changes.
+    fn handle_prompt_key(&mut self, key: Key) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        match key.key {
+            KeyCode::Enter => {
+                self.prompt = None;
+                return;
+            }
+            KeyCode::Esc => {
+                let origin = prompt.origin;
+                self.prompt = None;
+                self.clear_search();
+                self.move_to(origin);
+                return;
+            }
+            KeyCode::Backspace if prompt.query.is_empty() => {
+                self.prompt = None;
+                return;
+            }
+            KeyCode::Backspace => {
+                prompt.query.pop();
+            }
+            KeyCode::Char(c) if !key.mods.ctrl => prompt.query.push(c),
+            _ => return,
+        }
+        self.update_incremental();
+    }
+
+    /// Re-run the search for the prompt query from the prompt's origin.
+    ///
+    /// A query that is not yet a valid pattern keeps the previous matches.
+    fn update_incremental(&mut self) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        let (query, backward, origin) = (prompt.query.clone(), prompt.backward, prompt.origin);
+        if query.is_empty() {
+            self.clear_search();
+            self.move_to(origin);
+            return;
+        }
+        let Ok(regex) = Regex::new(&query) else {
+            return;
+        };
+        self.matches = find_matches(&regex, &self.snapshot.text);
+        self.search = Some(regex);
+        self.query = query;
+        self.backward = backward;
+        let target = self.next_match_from(origin, backward).unwrap_or(origin);
+        self.move_to(target);
+    }
+
+    /// Drop the search pattern and its matches.
+    fn clear_search(&mut self) {
+        self.search = None;
+        self.query.clear();
+        self.matches.clear();
+    }
+
+    /// Return the index of the match starting at the cursor.
+    fn current_match(&self) -> Option<usize> {
+        self.matches
+            .iter()
+            .position(|found| CopyPoint::new(found.line, found.start) == self.cursor)
+    }
+
+    /// Return the first match strictly after (or before) a point, wrapping.
+    fn next_match_from(&self, from: CopyPoint, backward: bool) -> Option<CopyPoint> {
+        let mut starts = self
+            .matches
+            .iter()
+            .map(|found| CopyPoint::new(found.line, found.start));
+        if backward {
+            starts
+                .clone()
+                .rev()
+                .find(|start| *start < from)
+                .or_else(|| starts.next_back())
+        } else {
+            starts
+                .clone()
+                .find(|start| *start > from)
+                .or_else(|| starts.next())
+        }
+    }
+
+    /// Start, switch or end a selection.
+    fn toggle_selection(&mut self, linewise: bool) {
+        if self.anchor.is_some() && self.linewise == linewise {
+            self.anchor = None;
+        } else {
+            self.anchor.get_or_insert(self.cursor);
+            self.linewise = linewise;
+        }
+    }
+
+    /// Return the selected columns of a line.
+    fn selection_span(&self, line: usize) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        let (start, end) = ordered(anchor, self.cursor);
+        if line < start.line || line > end.line {
+            return None;
+        }
+        let width = self.line_text(line).chars().count();
+        if self.linewise {
+            return Some((0, width.max(1)));
+        }
+        let from = if line == start.line { start.column } else { 0 };
+        let to = if line == end.line {
+            end.column + 1
+        } else {
+            width.max(from + 1)
+        };
+        Some((from, to))
+    }
+
+    /// Return the text of a snapshot line.
+    fn line_text(&self, line: usize) -> &str {
+        self.snapshot.text.get(line).map_or("", String::as_str)
+    }
+
+    /// Return the number of snapshot lines.
+    fn line_count(&self) -> usize {
+        self.snapshot.text.len().max(1)
+    }
+
+    /// Return the last snapshot line.
+    fn last_line(&self) -> usize {
+        self.line_count() - 1
+    }
+
+    /// Return the largest viewport top line.
+    fn max_top(&self) -> usize {
+        self.line_count().saturating_sub(self.rows)
+    }
+
+    /// Return the last column the cursor may occupy on a line.
+    fn last_column(&self, line: usize) -> usize {
+        self.line_text(line).chars().count().saturating_sub(1)
+    }
+
+    /// Move to a line, aiming for the remembered column.
+    fn set_cursor_line(&mut self, line: usize) {
+        let column = self.want_column.min(self.last_column(line));
+        self.cursor = CopyPoint::new(line, column);
+        self.scroll_to_cursor();
+    }
+
+    /// Move to a point, clamped to the snapshot.
+    fn move_to(&mut self, point: CopyPoint) {
+        let line = point.line.min(self.last_line());
+        let column = point.column.min(self.last_column(line));
+        self.cursor = CopyPoint::new(line, column);
+        self.want_column = column;
+        self.scroll_to_cursor();
+    }
+
+    /// Scroll the viewport so the cursor is visible.
+    fn scroll_to_cursor(&mut self) {
+        if self.cursor.line < self.top {
+            self.top = self.cursor.line;
+        } else if self.cursor.line >= self.top + self.rows {
+            self.top = self.cursor.line + 1 - self.rows;
+        }
+    }
+
+    /// Return the character class at a point; line ends count as whitespace.
+    fn class_at(&self, point: CopyPoint) -> CharClass {
+        self.line_text(point.line)
+            .chars()
+            .nth(point.column)
+            .map_or(CharClass::Space, CharClass::of)
+    }
+
+    /// Step one cell forward, wrapping to the next line.
+    fn step_forward(&self, point: CopyPoint) -> Option<CopyPoint> {
+        if point.column < self.last_column(point.line) {
+            Some(CopyPoint::new(point.line, point.column + 1))
+        } else if point.line < self.last_line() {
+            Some(CopyPoint::new(point.line + 1, 0))
+        } else {
+            None
+        }
+    }
+
+    /// Step one cell backward, wrapping to the end of the previous line.
+    fn step_backward(&self, point: CopyPoint) -> Option<CopyPoint> {
+        if point.column > 0 {
+            Some(CopyPoint::new(point.line, point.column - 1))
+        } else if point.line > 0 {
+            Some(CopyPoint::new(
+                point.line - 1,
+                self.last_column(point.line - 1),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Return the start of the next word, as vi `w` does.
+    fn word_forward(&self, from: CopyPoint) -> CopyPoint {
+        let class = self.class_at(from);
+        let mut point = from;
+        while let Some(next) = self.step_forward(point) {
+            let crossed = next.line != point.line;
+            point = next;
+            if crossed || self.class_at(point) != class {
+                break;
+            }
+        }
+        while self.class_at(point) == CharClass::Space {
+            let Some(next) = self.step_forward(point) else {
+                break;
+            };
+            point = next;
+        }
+        point
+    }
+
+    /// Return the start of the previous word, as vi `b` does.
+    fn word_backward(&self, from: CopyPoint) -> CopyPoint {
+        let Some(mut point) = self.step_backward(from) else {
+            return from;
+        };
+        while self.class_at(point) == CharClass::Space {
+            let Some(previous) = self.step_backward(point) else {
+                return point;
+            };
+            point = previous;
+        }
+        let class = self.class_at(point);
+        while let Some(previous) = self.step_backward(point)
+            && previous.line == point.line
+            && self.class_at(previous) == class
+        {
+            point = previous;
+        }
+        point
+    }
+
+    /// Return the end of the current or next word, as vi `e` does.
+    fn word_end(&self, from: CopyPoint) -> CopyPoint {
+        let Some(mut point) = self.step_forward(from) else {
+            return from;
+        };
+        while self.class_at(point) == CharClass::Space {
+            let Some(next) = self.step_forward(point) else {
+                return point;
+            };
+            point = next;
+        }
+        let class = self.class_at(point);
+        while let Some(next) = self.step_forward(point)
+            && next.line == point.line
+            && self.class_at(next) == class
+        {
+            point = next;
+        }
+        point
+    }
+}
+
+/// Character classes that delimit vi words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    /// Whitespace and line ends.
+    Space,
+    /// Alphanumerics and underscores.
+    Word,
+    /// Everything else.
+    Punctuation,
+}
+
+impl CharClass {
+    /// Classify a character.
+    fn of(c: char) -> Self {
+        if c.is_whitespace() {
+            Self::Space
+        } else if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else {
+            Self::Punctuation
+        }
+    }
+}
+
+/// Order two points.
+fn ordered(a: CopyPoint, b: CopyPoint) -> (CopyPoint, CopyPoint) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+/// Find the non-empty matches of a pattern, converting byte offsets to columns.
+fn find_matches(regex: &Regex, lines: &[String]) -> Vec<CopyMatch> {
+    let mut matches = Vec::new();
+    for (line, text) in lines.iter().enumerate() {
+        for found in regex.find_iter(text).filter(|found| !found.is_empty()) {
+            let start = text[..found.start()].chars().count();
+            matches.push(CopyMatch {
+                line,
+                start,
+                end: start + found.as_str().chars().count(),
+            });
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn copy_mode(lines: &[&str], rows: usize, cursor: CopyPoint) -> CopyMode {
+        let snapshot = Snapshot {
+            text: lines.iter().map(ToString::to_string).collect(),
+            runs: Vec::new(),
+        };
+        CopyMode::new(snapshot, rows, cursor)
+    }
+
+    fn keys(mode: &mut CopyMode, keys: &str) -> CopyAction {
+        let mut action = CopyAction::Handled;
+        for c in keys.chars() {
+            action = mode.handle_key(c.into());
+        }
+        action
+    }
+
+    #[test]
+    fn vi_motions_cross_lines_and_scroll() {
+        let mut mode = copy_mode(
+            &["one two", "three", "", "four.five", "six"],
+            2,
+            CopyPoint::new(4, 0),
+        );
+        assert_eq!(mode.top(), 3);
+
+        keys(&mut mode, "gg");
+        assert_eq!(mode.cursor(), CopyPoint::new(0, 0));
+        assert_eq!(mode.top(), 0);
+
+        keys(&mut mode, "ww");
+        assert_eq!(mode.cursor(), CopyPoint::new(1, 0));
+        keys(&mut mode, "w");
+        assert_eq!(mode.cursor(), CopyPoint::new(3, 0));
+        keys(&mut mode, "e");
+        assert_eq!(mode.cursor(), CopyPoint::new(3, 3));
+        keys(&mut mode, "w");
+        assert_eq!(mode.cursor(), CopyPoint::new(3, 4));
+        keys(&mut mode, "$");
+        assert_eq!(mode.cursor(), CopyPoint::new(3, 8));
+        keys(&mut mode, "j");
+        assert_eq!(mode.cursor(), CopyPoint::new(4, 2));
+        keys(&mut mode, "2k0");
+        assert_eq!(mode.cursor(), CopyPoint::new(2, 0));
+        keys(&mut mode, "b");
+        assert_eq!(mode.cursor(), CopyPoint::new(1, 0));
+        keys(&mut mode, "2G");
+        assert_eq!(mode.cursor(), CopyPoint::new(1, 0));
+        assert_eq!(mode.status(), "[2/5]");
+    }
+
+    #[test]
+    fn incremental_search_moves_and_wraps() {
+        let mut mode = copy_mode(
+            &["error: one", "ok", "error: two", "done"],
+            4,
+            CopyPoint::new(3, 0),
+        );
+        keys(&mut mode, "?err");
+        assert_eq!(mode.prompt().as_deref(), Some("?err"));
+        assert_eq!(mode.cursor(), CopyPoint::new(2, 0));
+        keys(&mut mode, "or: t");
+        mode.handle_key(KeyCode::Backspace.into());
+        mode.handle_key(KeyCode::Enter.into());
+        assert_eq!(mode.prompt(), None);
+        assert_eq!(mode.status(), "?error:  (2/2) [3/4]");
+
+        keys(&mut mode, "n");
+        assert_eq!(mode.cursor(), CopyPoint::new(0, 0));
+        keys(&mut mode, "n");
+        assert_eq!(mode.cursor(), CopyPoint::new(2, 0));
+        keys(&mut mode, "N");
+        assert_eq!(mode.cursor(), CopyPoint::new(0, 0));
+        assert_eq!(
+            mode.highlights(0),
+            vec![(0, 7, CURRENT_MATCH_STYLE)],
+            "the match under the cursor is highlighted as current"
+        );
+
+        assert_eq!(mode.set_search(r"\d+".into(), false), Ok(0));
+        assert!(mode.set_search("(".into(), false).is_err());
+    }
+
+    #[test]
+    fn prompt_escape_restores_cursor() {
+        let mut mode = copy_mode(&["alpha", "beta"], 2, CopyPoint::new(1, 2));
+        keys(&mut mode, "/alp");
+        assert_eq!(mode.cursor(), CopyPoint::new(0, 0));
+        mode.handle_key(KeyCode::Esc.into());
+        assert_eq!(mode.cursor(), CopyPoint::new(1, 2));
+        assert!(mode.highlights(0).is_empty());
+        assert_eq!(mode.handle_key(KeyCode::Esc.into()), CopyAction::Exit);
+    }
+
+    #[test]
+    fn yank_copies_selection_or_line() {
+        let mut mode = copy_mode(
+            &["first line   ", "second line", "third"],
+            3,
+            CopyPoint::new(0, 6),
+        );
+        assert_eq!(
+            keys(&mut mode, "vjhy"),
+            CopyAction::Yank("line\nsecond".to_string())
+        );
+
+        let mut mode = copy_mode(&["first line   ", "second"], 2, CopyPoint::new(0, 2));
+        assert_eq!(
+            keys(&mut mode, "Vjy"),
+            CopyAction::Yank("first line\nsecond".to_string())
+        );
+
+        let mut mode = copy_mode(&["first line   ", "second"], 2, CopyPoint::new(0, 2));
+        assert_eq!(
+            keys(&mut mode, "y"),
+            CopyAction::Yank("first line".to_string())
+        );
+    }
+
+    #[test]
+    fn columns_map_through_wide_glyphs() {
+        let line = "a漢b";
+        assert_eq!(cell_column(line, 1), 1);
+        assert_eq!(cell_column(line, 2), 3);
+        assert_eq!(cell_column(line, 5), 6);
+        assert_eq!(char_column(line, 2), 1);
+        assert_eq!(char_column(line, 3), 2);
+        assert_eq!(char_column(line, 6), 5);
+    }
+}
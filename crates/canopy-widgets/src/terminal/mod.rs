@@ -1,3 +1,6 @@
+/// Keyboard copy mode over the scrollback.
+mod copy_mode;
+
 use std::{
     mem,
     path::PathBuf,
@@ -7,7 +10,7 @@ use std::{
 };
 
 use canopy::{
-    Context, EventOutcome, ViewContext, Widget, cursor, derive_commands,
+    Context, EventOutcome, ViewContext, Widget, command, cursor, derive_commands,
     error::{Error, Result},
     event::{self, key, mouse},
     geom,
@@ -31,6 +34,8 @@ use portable_pty::ExitStatus;
 use tokio::runtime::{Builder, Runtime};
 use unicode_segmentation::UnicodeSegmentation;
 
+use copy_mode::{CopyAction, CopyMode, CopyPoint, Snapshot, cell_column, char_column};
+
 /// Fallback terminal column count before sizing is known.
 const DEFAULT_COLUMNS: usize = 80;
 /// Fallback terminal line count before sizing is known.
@@ -41,6 +46,8 @@ const DEFAULT_SCROLLBACK: usize = 10_000;
 const POLL_INTERVAL_MS: u64 = 16;
 /// Maximum delay between clicks to count as a multi-click selection.
 const DOUBLE_CLICK_MS: u64 = 400;
+/// Lines scrolled per mouse wheel step in copy mode.
+const COPY_MODE_WHEEL_LINES: isize = 3;
 
 /// Track click timing for selection behavior.
 struct ClickState {
@@ -423,6 +430,10 @@ impl TerminalConfig {
 }
 
 /// Terminal widget backed by `itty`.
+///
+/// Ctrl+Shift+Space, or the `enter_copy_mode` command, enters copy mode: a
+/// tmux-like view of the scrollback and grid with vi motions, `/` and `?`
+/// regex search, `v`/`V` selection and `y` to yank to the clipboard callback.
 pub struct Terminal {
     /// User-provided configuration.
     config: TerminalConfig,
@@ -446,6 +457,36 @@ pub struct Terminal {
     exit_notified: bool,
     /// Cached child exit status.
     exit_status: Option<ExitStatus>,
+    /// Copy mode state, while copy mode is active.
+    copy_mode: Option<CopyMode>,
+    /// Scrollback text from the last capture, with the live page it was read against.
+    scrollback_cache: Option<(LivePage, Vec<String>)>,
+}
+
+/// Live page contents that identify a scrollback capture.
+///
+/// New output changes the visible text, the cursor, or the scroll offset of a
+/// scrolled-back view, so an unchanged page means the history is unchanged too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LivePage {
+    /// Visible grid text.
+    text: Vec<String>,
+    /// Lines scrolled back from the bottom.
+    display_offset: usize,
+    /// Cursor position in the grid.
+    cursor: Option<(usize, usize)>,
+}
+
+impl LivePage {
+    /// Read the live page of a session.
+    fn read(session: &Session) -> Self {
+        let state = session.state();
+        Self {
+            text: session.visible_text(),
+            display_offset: state.display_offset,
+            cursor: state.cursor.grid_pos,
+        }
+    }
 }
 
 #[derive_commands]
@@ -467,6 +508,8 @@ impl Terminal {
             title: Arc::new(Mutex::new(None)),
             exit_notified: false,
             exit_status: None,
+            copy_mode: None,
+            scrollback_cache: None,
         }
     }
 
@@ -492,6 +535,183 @@ impl Terminal {
         self.driver.as_ref().map(DriverRuntime::handle)
     }
 
+    /// Enter copy mode: a keyboard cursor over a frozen snapshot of the
+    /// scrollback and grid, with vi motions, regex search and yanking.
+    #[command]
+    pub fn enter_copy_mode(&mut self) {
+        self.start_copy_mode();
+    }
+
+    /// Leave copy mode and return to the live terminal.
+    #[command]
+    pub fn exit_copy_mode(&mut self) {
+        self.copy_mode = None;
+    }
+
+    /// Return whether copy mode is active.
+    #[command]
+    pub fn in_copy_mode(&self) -> bool {
+        self.copy_mode.is_some()
+    }
+
+    /// Return the scrollback and visible grid text, oldest line first.
+    ///
+    /// In copy mode this is the snapshot being navigated.
+    /// @return Lines of terminal text.
+    #[command]
+    pub fn scrollback(&mut self) -> Vec<String> {
+        if let Some(copy) = &self.copy_mode {
+            return copy.lines().to_vec();
+        }
+        let Some(session) = self.session.as_mut() else {
+            return Vec::new();
+        };
+        let page = LivePage::read(session);
+        match &self.scrollback_cache {
+            Some((cached, text)) if *cached == page => text.clone(),
+            _ => {
+                let text = capture_snapshot(session).text;
+                self.scrollback_cache = Some((page, text.clone()));
+                text
+            }
+        }
+    }
+
+    /// Search the scrollback for a regular expression, entering copy mode and
+    /// moving to the nearest match.
+    /// @param pattern Regular expression to search for.
+    /// @param backward Search towards older output.
+    /// @return Number of matches.
+    #[command]
+    pub fn search(&mut self, pattern: String, #[arg(default)] backward: bool) -> Result<usize> {
+        let copy = self
+            .start_copy_mode()
+            .ok_or_else(|| Error::Invalid("terminal session is not running".to_string()))?;
+        copy.set_search(pattern, backward)
+            .map_err(|error| Error::Invalid(error.to_string()))
+    }
+
+    /// Move to the next search match in copy mode.
+    /// @param reverse Move against the search direction.
+    /// @return Scrollback line of the match, counting from zero.
+    #[command]
+    pub fn search_next(&mut self, #[arg(default)] reverse: bool) -> Option<usize> {
+        self.copy_mode
+            .as_mut()?
+            .search_next(reverse)
+            .map(|point| point.line)
+    }
+
+    /// Copy the copy-mode selection, or the cursor line, to the clipboard and
+    /// leave copy mode.
+    /// @return The copied text.
+    #[command]
+    pub fn yank(&mut self) -> Option<String> {
+        let text = self.copy_mode.take()?.yank_text();
+        self.store_clipboard(text.clone());
+        Some(text)
+    }
+
+    /// Enter copy mode if it is not active, returning its state.
+    fn start_copy_mode(&mut self) -> Option<&mut CopyMode> {
+        if self.copy_mode.is_none() {
+            self.clear_selection();
+            let session = self.session.as_mut()?;
+            let state = session.state();
+            let snapshot = capture_snapshot(session);
+            let top = snapshot
+                .text
+                .len()
+                .saturating_sub(state.lines + state.display_offset);
+            let cursor = match state.cursor.grid_pos {
+                Some((row, col)) if state.cursor.visible_in_viewport => {
+                    let line = top + row;
+                    let text = snapshot.text.get(line).map_or("", String::as_str);
+                    CopyPoint::new(line, char_column(text, col))
+                }
+                _ => CopyPoint::new(top, 0),
+            };
+            self.copy_mode = Some(CopyMode::new(snapshot, state.lines, cursor));
+        }
+        self.copy_mode.as_mut()
+    }
+
+    /// Handle a key press while copy mode is active.
+    fn handle_copy_mode_key(&mut self, key: key::Key) {
+        let Some(copy) = self.copy_mode.as_mut() else {
+            return;
+        };
+        match copy.handle_key(key) {
+            CopyAction::Handled => {}
+            CopyAction::Yank(text) => {
+                self.copy_mode = None;
+                self.store_clipboard(text);
+            }
+            CopyAction::Exit => self.copy_mode = None,
+        }
+    }
+
+    /// Render the copy-mode snapshot with search, selection and status overlays.
+    fn render_copy_mode(
+        &mut self,
+        rndr: &mut Render,
+        origin: geom::Point,
+        size: geom::Size,
+    ) -> Result<()> {
+        let Some(copy) = &self.copy_mode else {
+            return Ok(());
+        };
+        let default_bg = self.config.colors.background;
+        let rows = (size.h as usize).min(copy.lines().len().saturating_sub(copy.top()));
+        for row in 0..rows {
+            let line = copy.top() + row;
+            for run in copy.runs(line) {
+                render_run(rndr, origin, row, run, None, 0, default_bg)?;
+            }
+            let text = &copy.lines()[line];
+            for (start, end, style) in copy.highlights(line) {
+                let segment: String = text.chars().skip(start).take(end - start).collect();
+                let x = cell_column(text, start);
+                let span = geom::Line::new(
+                    origin.x.saturating_add(x as u32),
+                    origin.y.saturating_add(row as u32),
+                    (cell_column(text, end) - x) as u32,
+                );
+                rndr.text(style, span, &segment)?;
+            }
+        }
+
+        let status = copy.status();
+        let width = (status.chars().count() as u32).min(size.w);
+        let status_line = geom::Line::new(origin.x.saturating_add(size.w - width), origin.y, width);
+        rndr.text("terminal/prompt", status_line, &status)?;
+        if let Some(prompt) = copy.prompt() {
+            let prompt_line = geom::Line::new(
+                origin.x,
+                origin.y.saturating_add(size.h.saturating_sub(1)),
+                size.w,
+            );
+            rndr.text("terminal/prompt", prompt_line, &prompt)?;
+        }
+
+        let cursor = copy.cursor();
+        let x = cell_column(&copy.lines()[cursor.line], cursor.column);
+        self.cursor = cursor
+            .line
+            .checked_sub(copy.top())
+            .filter(|row| *row < size.h as usize)
+            .map(|row| {
+                cursor::Cursor::new(
+                    geom::Point {
+                        x: x as u32,
+                        y: row as u32,
+                    },
+                    cursor::CursorShape::Block,
+                )
+            });
+        Ok(())
+    }
+
     /// Lazily create the backend session and driver bridge.
     fn mount_session(&mut self) -> Result<()> {
         if self.session.is_some() {
@@ -540,6 +760,9 @@ impl Terminal {
         }
 
         self.last_size = size;
+        if let Some(copy) = self.copy_mode.as_mut() {
+            copy.resize(size.rows);
+        }
         if let Some(session) = self.session.as_mut()
             && let Err(_error) = session.resize_grid_and_pty(size.columns, size.rows, 1.0)
         {}
@@ -738,6 +961,11 @@ impl Terminal {
         let Some(text) = self.session().and_then(Session::copy_selection) else {
             return;
         };
+        self.store_clipboard(text);
+    }
+
+    /// Pass text to the configured clipboard callback.
+    fn store_clipboard(&self, text: String) {
         if let Some(store) = &self.config.clipboard_store {
             store(text);
         }
@@ -759,6 +987,11 @@ impl Terminal {
 
     /// Encode and send a keyboard event to the backend session.
     fn handle_key(&mut self, key: key::Key) -> bool {
+        if self.copy_mode.is_some() {
+            self.handle_copy_mode_key(key);
+            return true;
+        }
+
         if key.mods.shift {
             match key.key {
                 key::KeyCode::PageUp => {
@@ -782,6 +1015,10 @@ impl Terminal {
             return true;
         }
 
+        if key.mods.ctrl && key.mods.shift && key.key == key::KeyCode::Char(' ') {
+            return self.start_copy_mode().is_some();
+        }
+
         let Some(mapped) = map_key(key) else {
             return false;
         };
@@ -847,6 +1084,10 @@ impl Widget for Terminal {
             return Ok(());
         };
 
+        if self.copy_mode.is_some() {
+            return self.render_copy_mode(rndr, view.content_origin(), content_size);
+        }
+
         let state = session.state();
         let runs = session.visible_runs();
         let selection = session.selection();
@@ -902,12 +1143,21 @@ impl Widget for Terminal {
                 }
             }
             event::Event::Paste(content) => {
+                self.copy_mode = None;
                 self.clear_selection();
                 self.handle_paste(content);
                 Ok(EventOutcome::Handle)
             }
             event::Event::Mouse(mouse_event) => {
                 ctx.set_focus(ctx.node_id())?;
+                if let Some(copy) = self.copy_mode.as_mut() {
+                    match mouse_event.action {
+                        mouse::Action::ScrollUp => copy.scroll(-COPY_MODE_WHEEL_LINES),
+                        mouse::Action::ScrollDown => copy.scroll(COPY_MODE_WHEEL_LINES),
+                        _ => {}
+                    }
+                    return Ok(EventOutcome::Handle);
+                }
                 let Some(state) = self.state() else {
                     return Ok(EventOutcome::Ignore);
                 };
@@ -1032,6 +1282,56 @@ fn terminal_config(config: &TerminalConfig, size: TerminalSize) -> EguiTTYConfig
     builder.build()
 }
 
+/// Capture the scrollback and visible grid as a frozen snapshot.
+///
+/// The session only exposes the viewport, so this pages from the top of the
+/// history down to the live grid, then restores the original scroll position.
+fn capture_snapshot(session: &mut Session) -> Snapshot {
+    let restore = session.state().display_offset;
+    let mut offset = restore;
+    loop {
+        session.scroll_page_up();
+        let next = session.state().display_offset;
+        if next == offset {
+            break;
+        }
+        offset = next;
+    }
+
+    let history = offset;
+    let total = history + session.state().lines;
+    let mut text = vec![String::new(); total];
+    let mut runs = Vec::new();
+    runs.resize_with(total, Vec::new);
+    loop {
+        let first = history - offset;
+        let page = session
+            .visible_text()
+            .into_iter()
+            .zip(session.visible_runs());
+        for (row, (line, line_runs)) in page.enumerate() {
+            if let Some(slot) = text.get_mut(first + row) {
+                *slot = line;
+                runs[first + row] = line_runs;
+            }
+        }
+        if offset == 0 {
+            break;
+        }
+        session.scroll_page_down();
+        let next = session.state().display_offset;
+        if next == offset {
+            break;
+        }
+        offset = next;
+    }
+
+    if restore > 0 {
+        session.scroll_delta(restore.try_into().unwrap_or_default());
+    }
+    Snapshot { text, runs }
+}
+
 /// Convert a Canopy key into an `itty` key.
 fn map_key(key: key::Key) -> Option<IttyKey> {
     let mut modifiers = IttyModifiers::empty();
@@ -1091,13 +1391,13 @@ fn cursor_from_state(state: &TerminalState) -> Option<cursor::Cursor> {
         "Beam" => cursor::CursorShape::Line,
         _ => cursor::CursorShape::Block,
     };
-    Some(cursor::Cursor {
-        location: geom::Point {
+    Some(cursor::Cursor::new(
+        geom::Point {
             x: col as u32,
             y: row as u32,
         },
         shape,
-    })
+    ))
 }
 
 /// Render one styled run from the backend snapshot into Canopy cells.
@@ -1146,6 +1446,16 @@ fn render_run(
         )?;
         col += width;
     }
+    if let Some(target) = run.hyperlink.as_deref() {
+        rndr.set_link(
+            geom::Line::new(
+                origin.x.saturating_add(run.start_col as u32),
+                origin.y.saturating_add(row_idx as u32),
+                (col - run.start_col) as u32,
+            ),
+            Some(target),
+        );
+    }
     Ok(())
 }
 
@@ -1327,6 +1637,60 @@ mod tests {
         assert_eq!(buf.rows()[0], ["A", "⚡", "", "e\u{301}", "B", " "]);
     }
 
+    #[test]
+    fn renders_terminal_hyperlinks_onto_cells() {
+        let base_style = ResolvedStyle::new(Color::White, Color::Black, AttrSet::default());
+        let mut buf = TermBuf::new(geom::Size::new(8, 1), '\0', base_style).expect("buffer");
+        let stylemap = StyleMap::new();
+        let mut styles = StyleManager::new();
+        let run = StyledRunPublic {
+            text: "docs".to_string(),
+            fg: Rgba8 {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            },
+            bg: None,
+            italic: false,
+            underline: true,
+            strikethrough: false,
+            bold: false,
+            hyperlink: Some("https://example.com/docs".into()),
+            start_col: 2,
+            end_col: 6,
+        };
+
+        {
+            let mut rndr = Render::new(
+                &stylemap,
+                &mut styles,
+                &mut buf,
+                geom::Rect::new(0, 0, 8, 1),
+                geom::Point::default(),
+            );
+            render_run(
+                &mut rndr,
+                geom::Point::default(),
+                0,
+                &run,
+                None,
+                0,
+                Color::Black,
+            )
+            .expect("render terminal run");
+        }
+
+        let links: Vec<Option<&str>> = (0..8)
+            .map(|x| {
+                buf.get(geom::Point { x, y: 0 })
+                    .and_then(|cell| cell.link.as_deref())
+            })
+            .collect();
+        let link = Some("https://example.com/docs");
+        assert_eq!(links, [None, None, link, link, link, link, None, None]);
+    }
+
     #[test]
     fn maps_shift_backtab_to_shift_tab() {
         let key = key::Shift + key::KeyCode::BackTab;
@@ -1356,6 +1720,40 @@ mod tests {
         assert_eq!(selected, "hello");
     }
 
+    #[test]
+    fn copy_mode_searches_and_yanks_terminal_text() {
+        let copied = Arc::new(Mutex::new(None));
+        let store = Arc::clone(&copied);
+        let mut terminal = Terminal::new(TerminalConfig::new().with_clipboard_store(move |text| {
+            *store.lock().expect("clipboard lock") = Some(text);
+        }));
+        terminal
+            .mount_session()
+            .expect("mount itty-backed terminal");
+        terminal
+            .session_mut()
+            .expect("session")
+            .set_visible_lines(&["build ok".to_string(), "test failed: 3".to_string()])
+            .expect("seed lines");
+
+        assert!(terminal.scrollback().iter().any(|line| line == "build ok"));
+        let matches = terminal
+            .search(r"failed: \d".to_string(), false)
+            .expect("valid pattern");
+        assert_eq!(matches, 1);
+        assert!(terminal.in_copy_mode());
+        assert!(terminal.search("(".to_string(), false).is_err());
+
+        assert!(terminal.handle_key(key::KeyCode::Char('v').into()));
+        assert!(terminal.handle_key(key::KeyCode::Char('e').into()));
+        assert!(terminal.handle_key(key::KeyCode::Char('y').into()));
+        assert!(!terminal.in_copy_mode());
+        assert_eq!(
+            copied.lock().expect("clipboard lock").as_deref(),
+            Some("failed")
+        );
+    }
+
     #[test]
     fn focus_reports_follow_the_terminal_mode() {
         let terminal = mounted_terminal();
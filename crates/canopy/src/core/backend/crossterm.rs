@@ -1,6 +1,12 @@
 use std::{
+    collections::HashSet,
+    fmt,
     io::{self, Stderr, Write},
     mem,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
 };
 
 use futures::{
@@ -11,18 +17,93 @@ use futures::{
     pin_mut,
     stream::{Stream, StreamExt},
 };
+use parking_lot::Mutex;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
     Canopy,
-    backend::{BackendControl, TerminalSession},
-    core::{Core, dump::dump, text},
+    backend::{BackendControl, TerminalOptions, TerminalSession, probe},
+    core::{
+        Core,
+        dump::dump,
+        frame::{FramePolicy, FrameScheduler},
+        text,
+    },
+    cursor::{Cursor, CursorShape},
     error::{self, Result},
     event::{Event, key, mouse},
     geom::{Point, Size},
-    render::RenderBackend,
-    style::{Color, ResolvedStyle},
+    graphics::{self, DEFAULT_CELL_PIXELS, ImagePlacement, ImageProtocol},
+    recording,
+    render::{RenderBackend, hyperlink_sequence},
+    style::{AttrSet, Color, ResolvedStyle, UnderlineStyle},
 };
+/// Terminal input reader shared between the run loop and the controller.
+///
+/// The controller drops the reader while another program owns the terminal, so the reader's
+/// thread cannot steal that program's input.
+#[derive(Clone, Default)]
+struct SharedInput(Arc<Mutex<InputState>>);
+
+/// Reader state behind a [`SharedInput`].
+#[derive(Default)]
+struct InputState {
+    /// Open terminal reader.
+    stream: Option<cevent::EventStream>,
+    /// Whether the reader was closed to lend the terminal out.
+    released: bool,
+    /// Terminal size read on reopening, reported before any further input.
+    resize: Option<(u16, u16)>,
+}
+
+impl SharedInput {
+    /// Start reading terminal input.
+    ///
+    /// Resizes that happen while another program owns the terminal are never read, so
+    /// reopening after a release reports the current size as a resize event.
+    fn open(&self) {
+        let mut state = self.0.lock();
+        state.stream = Some(cevent::EventStream::new());
+        if mem::take(&mut state.released) {
+            state.resize = terminal::size().ok();
+        }
+    }
+
+    /// Stop reading terminal input.
+    fn close(&self) {
+        let mut state = self.0.lock();
+        drop(state.stream.take());
+        state.released = true;
+    }
+}
+
+impl fmt::Debug for SharedInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.0.lock();
+        f.debug_struct("SharedInput")
+            .field("open", &state.stream.is_some())
+            .field("resize", &state.resize)
+            .finish()
+    }
+}
+
+impl Stream for SharedInput {
+    type Item = io::Result<cevent::Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.0.lock();
+        if let Some((width, height)) = state.resize.take() {
+            return Poll::Ready(Some(Ok(cevent::Event::Resize(width, height))));
+        }
+        // The reader is only closed while the UI thread is lending out the terminal, and it is
+        // reopened before the run loop polls again.
+        match state.stream.as_mut() {
+            Some(stream) => stream.poll_next_unpin(cx),
+            None => Poll::Pending,
+        }
+    }
+}
+
 /// Simple event source wrapper for receiving events.
 ///
 /// This coalesces consecutive mouse-move events so clicks are not delayed by move bursts.
@@ -75,6 +156,14 @@ where
         Ok(None)
     }
 
+    /// Take the next event only if one is ready, including a buffered one.
+    fn try_next(&mut self) -> Result<Option<Event>> {
+        if let Some(event) = self.pending.take() {
+            return Ok(Some(event));
+        }
+        self.next_ready()
+    }
+
     /// Await the next event, coalescing consecutive ready mouse moves.
     async fn next(&mut self) -> Result<Event> {
         if let Some(event) = self.pending.take() {
@@ -146,6 +235,56 @@ fn translate_color(c: Color) -> style::Color {
     }
 }
 
+/// Build the SGR sequences for an underline with an optional style and color.
+///
+/// A plain SGR 4 comes first, so terminals that ignore the colon-separated
+/// extended forms still draw a single underline in the text color.
+fn underline_sequence(attrs: AttrSet, color: Option<Color>) -> String {
+    let mut seq = String::from("\x1b[4m");
+    let variant = match attrs.underline_style {
+        UnderlineStyle::Single => None,
+        UnderlineStyle::Double => Some(2),
+        UnderlineStyle::Curly => Some(3),
+        UnderlineStyle::Dotted => Some(4),
+        UnderlineStyle::Dashed => Some(5),
+    };
+    if let Some(variant) = variant {
+        seq.push_str(&format!("\x1b[4:{variant}m"));
+    }
+    match color {
+        Some(Color::Rgb { r, g, b }) => seq.push_str(&format!("\x1b[58:2::{r}:{g}:{b}m")),
+        Some(color) => {
+            let index = ansi_index(color);
+            seq.push_str(&format!("\x1b[58:5:{index}m"));
+        }
+        None => {}
+    }
+    seq
+}
+
+/// Return the 256-color palette index for a non-RGB color.
+fn ansi_index(c: Color) -> u8 {
+    match c {
+        Color::Black => 0,
+        Color::DarkRed => 1,
+        Color::DarkGreen => 2,
+        Color::DarkYellow => 3,
+        Color::DarkBlue => 4,
+        Color::DarkMagenta => 5,
+        Color::DarkCyan => 6,
+        Color::Grey => 7,
+        Color::DarkGrey => 8,
+        Color::Red => 9,
+        Color::Green => 10,
+        Color::Yellow => 11,
+        Color::Blue => 12,
+        Color::Magenta => 13,
+        Color::Cyan => 14,
+        Color::White | Color::Rgb { .. } => 15,
+        Color::AnsiValue(a) => a,
+    }
+}
+
 /// Map IO results into canopy errors.
 fn translate_result<T>(e: io::Result<T>) -> Result<T> {
     match e {
@@ -170,10 +309,13 @@ trait TerminalOperations: io::Write {
     fn disable_mouse_capture(&mut self) -> io::Result<()>;
     /// Hide the cursor.
     fn hide_cursor(&mut self) -> io::Result<()>;
-    /// Show the cursor.
+    /// Restore the user's cursor shape and show the cursor.
     fn show_cursor(&mut self) -> io::Result<()>;
     /// Push keyboard enhancement flags.
-    fn push_keyboard_enhancements(&mut self) -> io::Result<()>;
+    fn push_keyboard_enhancements(
+        &mut self,
+        flags: cevent::KeyboardEnhancementFlags,
+    ) -> io::Result<()>;
     /// Pop keyboard enhancement flags.
     fn pop_keyboard_enhancements(&mut self) -> io::Result<()>;
 }
@@ -208,14 +350,16 @@ impl TerminalOperations for Stderr {
     }
 
     fn show_cursor(&mut self) -> io::Result<()> {
+        self.execute(ccursor::SetCursorStyle::DefaultUserShape)?;
         self.execute(ccursor::Show).map(|_| ())
     }
 
-    fn push_keyboard_enhancements(&mut self) -> io::Result<()> {
-        self.execute(cevent::PushKeyboardEnhancementFlags(
-            cevent::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES,
-        ))
-        .map(|_| ())
+    fn push_keyboard_enhancements(
+        &mut self,
+        flags: cevent::KeyboardEnhancementFlags,
+    ) -> io::Result<()> {
+        self.execute(cevent::PushKeyboardEnhancementFlags(flags))
+            .map(|_| ())
     }
 
     fn pop_keyboard_enhancements(&mut self) -> io::Result<()> {
@@ -250,11 +394,27 @@ impl TerminalCapabilities {
     }
 }
 
+/// Return the kitty keyboard protocol flags to push.
+///
+/// Key repeat and release reporting needs every key sent as an escape code. Alternate keys are
+/// requested with it so shifted keys still arrive as the characters they type.
+fn keyboard_flags(report_key_events: bool) -> cevent::KeyboardEnhancementFlags {
+    let flags = cevent::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+    if report_key_events {
+        flags
+            | cevent::KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            | cevent::KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+            | cevent::KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+    } else {
+        flags
+    }
+}
+
 /// Acquire terminal capabilities in dependency order.
 fn acquire_terminal(
     terminal: &mut impl TerminalOperations,
     capabilities: &mut TerminalCapabilities,
-    keyboard_enhancements: bool,
+    keyboard_enhancements: Option<cevent::KeyboardEnhancementFlags>,
 ) -> io::Result<()> {
     terminal.enable_raw_mode()?;
     capabilities.raw_mode_enabled = true;
@@ -264,8 +424,8 @@ fn acquire_terminal(
     capabilities.mouse_capture_enabled = true;
     terminal.hide_cursor()?;
     capabilities.cursor_hidden = true;
-    if keyboard_enhancements {
-        terminal.push_keyboard_enhancements()?;
+    if let Some(flags) = keyboard_enhancements {
+        terminal.push_keyboard_enhancements(flags)?;
         capabilities.keyboard_enhancements_pushed = true;
     }
     Ok(())
@@ -333,6 +493,12 @@ pub struct CrosstermControl {
     capabilities: TerminalCapabilities,
     /// Whether to enable keyboard enhancement flags on startup.
     enable_keyboard_enhancements: bool,
+    /// Whether to request key repeat and release events.
+    report_key_events: bool,
+    /// Whether kitty image data is freed before the terminal is released.
+    image_cleanup: bool,
+    /// Terminal input reader, open while the controller owns the terminal.
+    input: SharedInput,
 }
 
 impl CrosstermControl {
@@ -342,9 +508,33 @@ impl CrosstermControl {
             terminal: io::stderr(),
             capabilities: TerminalCapabilities::default(),
             enable_keyboard_enhancements,
+            report_key_events: false,
+            image_cleanup: false,
+            input: SharedInput::default(),
         }
     }
 
+    /// Return the input reader that follows this controller's ownership of the terminal.
+    fn input(&self) -> SharedInput {
+        self.input.clone()
+    }
+
+    /// Configure whether terminals are asked for key repeat and release events.
+    ///
+    /// This has no effect unless keyboard enhancements are enabled.
+    pub fn with_key_events(mut self, enabled: bool) -> Self {
+        self.report_key_events = enabled;
+        self
+    }
+
+    /// Configure whether kitty image data is freed before the terminal is released.
+    ///
+    /// Terminals keep uploaded images for the whole session otherwise.
+    pub fn with_image_cleanup(mut self, enabled: bool) -> Self {
+        self.image_cleanup = enabled;
+        self
+    }
+
     /// Enter alternate screen and raw mode, rolling back a partial start.
     fn enter(&mut self) -> io::Result<()> {
         if self.capabilities.is_active() {
@@ -356,7 +546,8 @@ impl CrosstermControl {
         if let Err(error) = acquire_terminal(
             &mut self.terminal,
             &mut self.capabilities,
-            self.enable_keyboard_enhancements,
+            self.enable_keyboard_enhancements
+                .then(|| keyboard_flags(self.report_key_events)),
         ) {
             drop(release_terminal(&mut self.terminal, &mut self.capabilities));
             return Err(error);
@@ -366,6 +557,14 @@ impl CrosstermControl {
 
     /// Leave alternate screen and restore terminal state.
     fn exit(&mut self) -> io::Result<()> {
+        if self.image_cleanup && self.capabilities.alternate_screen_entered {
+            // Free images while the screen showing them is current, so images on the main
+            // screen are left alone.
+            drop(
+                self.terminal
+                    .execute(style::Print(graphics::kitty_delete_all())),
+            );
+        }
         release_terminal(&mut self.terminal, &mut self.capabilities)
     }
 }
@@ -378,9 +577,12 @@ impl Default for CrosstermControl {
 
 impl BackendControl for CrosstermControl {
     fn start(&mut self) -> Result<()> {
-        translate_result(self.enter())
+        translate_result(self.enter())?;
+        self.input.open();
+        Ok(())
     }
     fn stop(&mut self) -> Result<()> {
+        self.input.close();
         translate_result(self.exit())
     }
 }
@@ -391,21 +593,152 @@ impl Drop for CrosstermControl {
     }
 }
 
+/// DEC private mode number of synchronized updates.
+const SYNCHRONIZED_UPDATE_MODE: u16 = 2026;
+/// Private mode that leaves the cursor beside a sixel image instead of below it.
+const SIXEL_CURSOR_MODE: u16 = 8452;
+/// Sequence that starts a DEC mode 2026 synchronized update.
+const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+/// Sequence that ends a DEC mode 2026 synchronized update.
+const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
+
 /// Crossterm-backed render backend.
 pub struct CrosstermRender {
     /// Stderr handle used for rendering output.
     fp: Stderr,
+    /// Hyperlink target currently open on the terminal.
+    link: Option<String>,
+    /// Whether frames are wrapped in synchronized updates.
+    synchronized: bool,
+    /// Whether a synchronized update is open for the current frame.
+    in_frame: bool,
+    /// Whether the terminal cursor is driven instead of painted into frames.
+    native_cursor: bool,
+    /// Location where the terminal cursor is shown, or `None` while it is hidden.
+    cursor: Option<Point>,
+    /// Shape and blink state most recently sent with DECSCUSR.
+    cursor_style: Option<(CursorShape, bool)>,
+    /// Whether output since the last cursor placement has moved the terminal cursor.
+    cursor_moved: bool,
+    /// Graphics protocol used to draw images, or `None` to show their text fallback.
+    images: Option<ImageProtocol>,
+    /// Whether the terminal supports [`SIXEL_CURSOR_MODE`].
+    sixel_cursor_mode: bool,
+    /// Terminal size read for drawing images, refreshed on every full repaint.
+    geometry: Option<ImageGeometry>,
+    /// Kitty image ids already uploaded to the terminal.
+    transmitted: HashSet<u32>,
+    /// Kitty image ids with a placement on screen.
+    placed: HashSet<u32>,
+}
+
+/// Terminal size used to scale and clip images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ImageGeometry {
+    /// Cell size in pixels.
+    cell: Size,
+    /// Screen height in rows.
+    rows: u32,
 }
 
 impl CrosstermRender {
-    /// Flush pending output.
+    /// Configure whether frames are wrapped in DEC mode 2026 synchronized updates.
+    pub fn with_synchronized_output(mut self, enabled: bool) -> Self {
+        self.synchronized = enabled;
+        self
+    }
+
+    /// Configure whether the terminal's own cursor shows the focused widget's cursor.
+    pub fn with_native_cursor(mut self, enabled: bool) -> Self {
+        self.native_cursor = enabled;
+        self
+    }
+
+    /// Configure the graphics protocol used to draw images, or `None` to show their text
+    /// fallback.
+    pub fn with_images(mut self, protocol: Option<ImageProtocol>) -> Self {
+        self.images = protocol;
+        self
+    }
+
+    /// Configure whether the terminal keeps the cursor beside sixel images (mode 8452).
+    ///
+    /// Without it, sixel images are clipped above the bottom row so drawing them cannot
+    /// scroll the screen.
+    pub fn with_sixel_cursor_mode(mut self, enabled: bool) -> Self {
+        self.sixel_cursor_mode = enabled;
+        self
+    }
+
+    /// Return the terminal size for drawing images, reading it once per full repaint.
+    fn geometry(&mut self) -> ImageGeometry {
+        *self.geometry.get_or_insert_with(image_geometry)
+    }
+
+    /// Prepare for the first output of a frame.
+    ///
+    /// This opens a synchronized update, or hides a visible cursor so it does not
+    /// chase output across the screen when updates are not synchronized.
+    fn begin_frame(&mut self) -> io::Result<()> {
+        self.cursor_moved = true;
+        if self.synchronized && !self.in_frame {
+            self.fp.queue(style::Print(BEGIN_SYNCHRONIZED_UPDATE))?;
+            self.in_frame = true;
+        }
+        if !self.synchronized && self.cursor.take().is_some() {
+            self.fp.queue(ccursor::Hide)?;
+        }
+        Ok(())
+    }
+
+    /// Move the terminal cursor to a location and set its shape, or hide it.
+    fn place_cursor(&mut self, cursor: Option<&Cursor>) -> io::Result<()> {
+        let target = cursor.map(|c| c.location);
+        let style = cursor.map(|c| (c.shape, c.blink));
+        if !self.cursor_moved
+            && self.cursor == target
+            && (style.is_none() || self.cursor_style == style)
+        {
+            return Ok(());
+        }
+        self.begin_frame()?;
+        match cursor {
+            None => {
+                if self.cursor.take().is_some() {
+                    self.fp.queue(ccursor::Hide)?;
+                }
+            }
+            Some(c) => {
+                let x = u16::try_from(c.location.x).unwrap_or(u16::MAX);
+                let y = u16::try_from(c.location.y).unwrap_or(u16::MAX);
+                self.fp.queue(ccursor::MoveTo(x, y))?;
+                if self.cursor_style != style {
+                    self.fp.queue(cursor_style(c.shape, c.blink))?;
+                    self.cursor_style = style;
+                }
+                if self.cursor.replace(c.location).is_none() {
+                    self.fp.queue(ccursor::Show)?;
+                }
+            }
+        }
+        self.cursor_moved = false;
+        Ok(())
+    }
+
+    /// Flush pending output, closing any open hyperlink and synchronized update first.
     fn flush(&mut self) -> io::Result<()> {
+        self.free_unplaced()?;
+        self.set_link(None)?;
+        if mem::take(&mut self.in_frame) {
+            self.fp.queue(style::Print(END_SYNCHRONIZED_UPDATE))?;
+        }
         self.fp.flush()?;
         Ok(())
     }
 
     /// Apply a style to subsequent output.
     fn apply_style(&mut self, s: &ResolvedStyle) -> io::Result<()> {
+        self.begin_frame()?;
         // Always reset first to clear any previous attributes, then set colors and attrs.
         // Order is important: reset clears everything, so we must set colors after.
         self.fp
@@ -436,13 +769,103 @@ impl CrosstermRender {
         }
         if s.attrs.underline {
             self.fp
-                .queue(style::SetAttribute(style::Attribute::Underlined))?;
+                .queue(style::Print(underline_sequence(s.attrs, s.underline)))?;
+        }
+        Ok(())
+    }
+
+    /// Open or close an OSC 8 hyperlink when the target changes.
+    fn set_link(&mut self, target: Option<&str>) -> io::Result<()> {
+        if self.link.as_deref() == target {
+            return Ok(());
+        }
+        self.fp.queue(style::Print(hyperlink_sequence(target)))?;
+        self.link = target.map(str::to_string);
+        Ok(())
+    }
+
+    /// Draw an image over its cells with the configured graphics protocol.
+    fn draw_image(&mut self, image: &ImagePlacement) -> io::Result<()> {
+        let Some(protocol) = self.images else {
+            return Ok(());
+        };
+        let geometry = self.geometry();
+        let sequence = match protocol {
+            ImageProtocol::Kitty => {
+                self.placed.insert(image.image.id());
+                kitty_sequence(image, &mut self.transmitted)
+            }
+            ImageProtocol::Sixel if self.sixel_cursor_mode => format!(
+                "\x1b[?{SIXEL_CURSOR_MODE}h{}\x1b[?{SIXEL_CURSOR_MODE}l",
+                graphics::sixel(image, geometry.cell)
+            ),
+            ImageProtocol::Sixel => {
+                // The cursor moves below a sixel image, which scrolls the screen if the image
+                // reaches the bottom row.
+                let Some(clipped) = graphics::clip_rows(image, geometry.rows.saturating_sub(1))
+                else {
+                    return Ok(());
+                };
+                graphics::sixel(&clipped, geometry.cell)
+            }
+        };
+        self.begin_frame()?;
+        self.set_link(None)?;
+        let x = u16::try_from(image.dest.tl.x).unwrap_or(u16::MAX);
+        let y = u16::try_from(image.dest.tl.y).unwrap_or(u16::MAX);
+        self.fp.queue(ccursor::MoveTo(x, y))?;
+        self.fp.queue(style::Print(sequence))?;
+        Ok(())
+    }
+
+    /// Remove a kitty image placement. Sixel images are painted over by the cells beneath.
+    ///
+    /// The image data stays uploaded until the end of the frame, so a moved image is not
+    /// sent again.
+    fn erase_image(&mut self, image: &ImagePlacement) -> io::Result<()> {
+        if self.images == Some(ImageProtocol::Kitty) {
+            self.begin_frame()?;
+            self.fp
+                .queue(style::Print(graphics::kitty_delete(&image.image)))?;
+            self.placed.remove(&image.image.id());
+        }
+        Ok(())
+    }
+
+    /// Remove every kitty image and free its data before a full repaint.
+    ///
+    /// A full repaint follows every resize, so the image geometry is read again too.
+    fn erase_images(&mut self) -> io::Result<()> {
+        self.geometry = None;
+        if self.images == Some(ImageProtocol::Kitty) {
+            self.begin_frame()?;
+            self.fp.queue(style::Print(graphics::kitty_delete_all()))?;
+            self.transmitted.clear();
+            self.placed.clear();
+        }
+        Ok(())
+    }
+
+    /// Free the data of uploaded kitty images that no longer have a placement.
+    ///
+    /// This covers images that were replaced or dropped, which the terminal would otherwise
+    /// keep for the rest of the session.
+    fn free_unplaced(&mut self) -> io::Result<()> {
+        let unplaced: Vec<u32> = self.transmitted.difference(&self.placed).copied().collect();
+        if unplaced.is_empty() {
+            return Ok(());
+        }
+        self.begin_frame()?;
+        for id in unplaced {
+            self.fp.queue(style::Print(graphics::kitty_free(id)))?;
+            self.transmitted.remove(&id);
         }
         Ok(())
     }
 
     /// Write text at a position.
     fn text(&mut self, loc: Point, txt: &str) -> io::Result<()> {
+        self.begin_frame()?;
         for run in positioned_text_runs(loc, txt) {
             let x = u16::try_from(run.location.x).map_err(|_| {
                 io::Error::new(
@@ -463,6 +886,54 @@ impl CrosstermRender {
     }
 }
 
+/// Build the kitty escape sequences that draw an image at the cursor.
+///
+/// Images are uploaded the first time they are drawn and placed by id afterwards.
+fn kitty_sequence(image: &ImagePlacement, transmitted: &mut HashSet<u32>) -> String {
+    let mut out = String::new();
+    if transmitted.insert(image.image.id()) {
+        out.push_str(&graphics::kitty_transmit(&image.image));
+    }
+    out.push_str(&graphics::kitty_place(image));
+    out
+}
+
+/// Read the terminal's cell size in pixels and its height in rows.
+///
+/// A common default cell size is used when the terminal does not report one.
+fn image_geometry() -> ImageGeometry {
+    let window = terminal::window_size().ok();
+    let cell = window
+        .filter(|size| size.columns > 0 && size.rows > 0 && size.width > 0 && size.height > 0)
+        .map_or(DEFAULT_CELL_PIXELS, |size| {
+            Size::new(
+                u32::from(size.width / size.columns),
+                u32::from(size.height / size.rows),
+            )
+        });
+    let rows = window
+        .map(|size| size.rows)
+        .filter(|rows| *rows > 0)
+        .or_else(|| terminal::size().ok().map(|(_, rows)| rows))
+        .unwrap_or(u16::MAX);
+    ImageGeometry {
+        cell,
+        rows: u32::from(rows),
+    }
+}
+
+/// Map a cursor shape and blink state to its DECSCUSR command.
+fn cursor_style(shape: CursorShape, blink: bool) -> ccursor::SetCursorStyle {
+    match (shape, blink) {
+        (CursorShape::Block, true) => ccursor::SetCursorStyle::BlinkingBlock,
+        (CursorShape::Block, false) => ccursor::SetCursorStyle::SteadyBlock,
+        (CursorShape::Underscore, true) => ccursor::SetCursorStyle::BlinkingUnderScore,
+        (CursorShape::Underscore, false) => ccursor::SetCursorStyle::SteadyUnderScore,
+        (CursorShape::Line, true) => ccursor::SetCursorStyle::BlinkingBar,
+        (CursorShape::Line, false) => ccursor::SetCursorStyle::SteadyBar,
+    }
+}
+
 /// A string fragment with an absolute terminal-cell location.
 #[derive(Debug, PartialEq, Eq)]
 struct PositionedTextRun {
@@ -520,7 +991,21 @@ fn push_positioned_text_run(runs: &mut Vec<PositionedTextRun>, location: Point,
 
 impl Default for CrosstermRender {
     fn default() -> Self {
-        Self { fp: io::stderr() }
+        Self {
+            fp: io::stderr(),
+            link: None,
+            synchronized: false,
+            in_frame: false,
+            native_cursor: false,
+            cursor: None,
+            cursor_style: None,
+            cursor_moved: false,
+            images: None,
+            sixel_cursor_mode: false,
+            geometry: None,
+            transmitted: HashSet::new(),
+            placed: HashSet::new(),
+        }
     }
 }
 
@@ -529,6 +1014,19 @@ impl RenderBackend for CrosstermRender {
         translate_result(self.flush())
     }
 
+    fn invalidate(&mut self) -> Result<()> {
+        // Restarting the backend hid the cursor and reset its shape.
+        self.link = None;
+        self.cursor = None;
+        self.cursor_style = None;
+        self.cursor_moved = true;
+        // Releasing the terminal freed uploaded images, and the window may have changed.
+        self.transmitted.clear();
+        self.placed.clear();
+        self.geometry = None;
+        Ok(())
+    }
+
     fn style(&mut self, s: &ResolvedStyle) -> Result<()> {
         translate_result(self.apply_style(s))
     }
@@ -537,6 +1035,34 @@ impl RenderBackend for CrosstermRender {
         translate_result(self.text(loc, txt))
     }
 
+    fn link(&mut self, target: Option<&str>) -> Result<()> {
+        translate_result(self.set_link(target))
+    }
+
+    fn supports_cursor(&self) -> bool {
+        self.native_cursor
+    }
+
+    fn cursor(&mut self, cursor: Option<&Cursor>) -> Result<()> {
+        translate_result(self.place_cursor(cursor))
+    }
+
+    fn supports_images(&self) -> bool {
+        self.images.is_some()
+    }
+
+    fn draw_image(&mut self, image: &ImagePlacement) -> Result<()> {
+        translate_result(self.draw_image(image))
+    }
+
+    fn erase_image(&mut self, image: &ImagePlacement) -> Result<()> {
+        translate_result(self.erase_image(image))
+    }
+
+    fn erase_images(&mut self) -> Result<()> {
+        translate_result(self.erase_images())
+    }
+
     fn supports_char_shift(&self) -> bool {
         true
     }
@@ -551,6 +1077,7 @@ impl RenderBackend for CrosstermRender {
         }
 
         let count_abs = count.unsigned_abs().min(u16::MAX as u32) as u16;
+        translate_result(self.begin_frame())?;
         translate_result(self.fp.queue(ccursor::MoveTo(loc.x as u16, loc.y as u16)))?;
         let seq = if count > 0 {
             format!("\x1b[{count_abs}@")
@@ -571,6 +1098,7 @@ impl RenderBackend for CrosstermRender {
             return Ok(());
         }
         let count_abs = count.unsigned_abs().min(u16::MAX as u32) as u16;
+        translate_result(self.begin_frame())?;
         let region = format!("\x1b[{};{}r", top + 1, bottom + 1);
         translate_result(self.fp.queue(style::Print(region)))?;
         translate_result(self.fp.queue(ccursor::MoveTo(0, top)))?;
@@ -591,6 +1119,18 @@ fn translate_key_modifiers(mods: cevent::KeyModifiers) -> key::Mods {
         shift: mods.contains(cevent::KeyModifiers::SHIFT),
         ctrl: mods.contains(cevent::KeyModifiers::CONTROL),
         alt: mods.contains(cevent::KeyModifiers::ALT),
+        super_key: mods.contains(cevent::KeyModifiers::SUPER),
+        hyper: mods.contains(cevent::KeyModifiers::HYPER),
+        meta: mods.contains(cevent::KeyModifiers::META),
+    }
+}
+
+/// Translate a crossterm key event kind into a canopy key kind.
+fn translate_key_kind(kind: cevent::KeyEventKind) -> key::KeyKind {
+    match kind {
+        cevent::KeyEventKind::Press => key::KeyKind::Press,
+        cevent::KeyEventKind::Repeat => key::KeyKind::Repeat,
+        cevent::KeyEventKind::Release => key::KeyKind::Release,
     }
 }
 
@@ -666,6 +1206,7 @@ fn translate_event(e: cevent::Event) -> Event {
                     cevent::ModifierKeyCode::IsoLevel5Shift => key::ModifierKeyCode::IsoLevel5Shift,
                 }),
             },
+            kind: translate_key_kind(k.kind),
         }),
         cevent::Event::Mouse(m) => {
             let mut button = mouse::Button::None;
@@ -725,64 +1266,144 @@ fn handle_render_error(
     error
 }
 
+/// Maximum events handled in one batch before a frame is considered.
+const EVENT_BATCH_BUDGET: usize = 256;
+
+/// Return true for the Ctrl+C interrupt that stops the run loop.
+fn is_interrupt(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Key(key::Key {
+            key: key::KeyCode::Char('c'),
+            mods: key::Mods { ctrl: true, .. },
+            kind: key::KeyKind::Press,
+            ..
+        })
+    )
+}
+
+/// Stop the terminal session and dump the node tree after Ctrl+C.
+fn interrupt(cnpy: &Canopy, session: &TerminalSession) -> i32 {
+    drop(session.stop());
+    eprintln!("\nCtrl+C pressed - Node tree dump:");
+    match dump(&cnpy.core, cnpy.core.root, cnpy.core.focus) {
+        Ok(dump_str) => eprintln!("{dump_str}"),
+        Err(dump_err) => eprintln!("Failed to dump node tree: {dump_err}"),
+    }
+    130
+}
+
+/// Build the capability queries a run loop sends before starting.
+fn terminal_queries(policy: FramePolicy, options: TerminalOptions) -> String {
+    let mut queries = String::new();
+    if policy.synchronized_output {
+        queries.push_str(&probe::mode_query(SYNCHRONIZED_UPDATE_MODE));
+    }
+    if options.images {
+        queries.push_str(probe::KITTY_GRAPHICS_QUERY);
+        queries.push_str(&probe::mode_query(SIXEL_CURSOR_MODE));
+    }
+    queries
+}
+
 /// Run the main render/event loop using the crossterm backend.
 ///
 /// Ctrl+C dumps the node tree and stops the loop with status 130. Keyboard enhancement flags
-/// are enabled so escape codes are unambiguous.
+/// are enabled so escape codes are unambiguous, and key repeats and releases are requested when
+/// the terminal options ask for them.
+///
+/// Ready events are handled in batches and rendered as one frame. Frames caused by user input
+/// render immediately; other redraws are limited by the canopy's [`FramePolicy`](crate::FramePolicy).
+/// Frames are wrapped in synchronized updates only when the terminal reports support for them.
 pub fn runloop(mut cnpy: Canopy) -> Result<i32> {
-    let mut be = CrosstermRender::default();
-    cnpy.register_backend(CrosstermControl::new(true));
+    let policy = cnpy.frame_policy();
+    let options = cnpy.terminal_options();
+    let mut frames = FrameScheduler::new(policy);
+    let replies = probe::query(&terminal_queries(policy, options));
+    let images = options
+        .images
+        .then(|| ImageProtocol::detect(replies.graphics()))
+        .flatten();
+    let mut be = CrosstermRender::default()
+        .with_synchronized_output(
+            policy.synchronized_output && replies.mode_supported(SYNCHRONIZED_UPDATE_MODE),
+        )
+        .with_native_cursor(options.native_cursor)
+        .with_images(images)
+        .with_sixel_cursor_mode(replies.mode_supported(SIXEL_CURSOR_MODE));
+    let control = CrosstermControl::new(true)
+        .with_key_events(options.key_events)
+        .with_image_cleanup(images == Some(ImageProtocol::Kitty));
+    let input = control.input();
+    cnpy.register_backend(control);
     let backend = cnpy
         .backend
         .take()
         .ok_or_else(|| error::Error::Internal("backend not set".into()))?;
     let session = TerminalSession::new(backend)?;
+    cnpy.core.terminal = Some(session.handle());
 
     let rx = cnpy
         .event_rx
         .take()
         .ok_or_else(|| error::Error::InvalidOperation("event loop already initialized".into()))?;
 
-    let mut events = EventSource::new(cevent::EventStream::new(), rx);
+    let mut events = EventSource::new(input, rx);
     let size = translate_result(terminal::size())?;
     cnpy.set_root_size(Size::new(size.0.into(), size.1.into()))?;
+    if let Some(path) = recording::path_from_env() {
+        cnpy.start_recording(&path)?;
+    }
 
     if let Err(e) = cnpy.render(&mut be) {
         return Err(handle_render_error(e, &cnpy.core, &session));
     }
     translate_result(be.flush())?;
+    cnpy.record_frame();
+    frames.rendered(Instant::now());
     if let Some(code) = cnpy.core.take_exit_request() {
         return Ok(code);
     }
 
     loop {
-        let event = block_on(events.next())?;
-
-        if matches!(
-            &event,
-            Event::Key(key::Key {
-                key: key::KeyCode::Char('c'),
-                mods: key::Mods { ctrl: true, .. },
-            })
-        ) {
-            drop(session.stop());
-            eprintln!("\nCtrl+C pressed - Node tree dump:");
-            match dump(&cnpy.core, cnpy.core.root, cnpy.core.focus) {
-                Ok(dump_str) => eprintln!("{dump_str}"),
-                Err(dump_err) => eprintln!("Failed to dump node tree: {dump_err}"),
+        let mut next = Some(block_on(events.next())?);
+        let mut handled = 0;
+        while let Some(event) = next {
+            if is_interrupt(&event) {
+                return Ok(interrupt(&cnpy, &session));
             }
-            return Ok(130);
+            frames.note_event(&event);
+            cnpy.record_event(&event);
+            cnpy.event(event)?;
+            handled += 1;
+            next = if handled < EVENT_BATCH_BUDGET && cnpy.core.exit_requested.is_none() {
+                events.try_next()?
+            } else {
+                None
+            };
         }
-
-        cnpy.event(event)?;
         cnpy.service_automation();
         if let Some(code) = cnpy.core.take_exit_request() {
             return Ok(code);
         }
+        if !cnpy.render_pending() {
+            continue;
+        }
+        let now = Instant::now();
+        if let Some(delay) = frames.delay(now) {
+            if frames.arm() {
+                cnpy.wake_after(delay)?;
+            }
+            continue;
+        }
+        frames.rendered(now);
         match cnpy.render_if_pending(&mut be) {
             Ok(rendered) => {
-                if rendered && let Err(e) = translate_result(be.flush()) {
-                    return Err(handle_render_error(e, &cnpy.core, &session));
+                if rendered {
+                    if let Err(e) = translate_result(be.flush()) {
+                        return Err(handle_render_error(e, &cnpy.core, &session));
+                    }
+                    cnpy.record_frame();
                 }
             }
             Err(e) => {
@@ -807,6 +1428,7 @@ mod tests {
     use futures::{channel::mpsc::unbounded, stream};
 
     use super::*;
+    use crate::{geom::Rect, style::Attr};
 
     /// Pending stream that records when cancellation drops it.
     struct DropReader {
@@ -882,7 +1504,10 @@ mod tests {
             self.release("cursor+")
         }
 
-        fn push_keyboard_enhancements(&mut self) -> io::Result<()> {
+        fn push_keyboard_enhancements(
+            &mut self,
+            _flags: cevent::KeyboardEnhancementFlags,
+        ) -> io::Result<()> {
             self.acquire("keyboard+")
         }
 
@@ -926,12 +1551,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reopened_input_reports_the_size_first() {
+        let input = SharedInput::default();
+        input.0.lock().resize = Some((80, 24));
+        let mut reader = input.clone();
+        assert!(matches!(
+            reader.next().now_or_never(),
+            Some(Some(Ok(cevent::Event::Resize(80, 24))))
+        ));
+        assert!(reader.next().now_or_never().is_none());
+    }
+
     #[test]
     fn terminal_capabilities_balance_in_reverse_order() -> io::Result<()> {
         let mut terminal = FakeTerminal::default();
         let mut capabilities = TerminalCapabilities::default();
 
-        acquire_terminal(&mut terminal, &mut capabilities, true)?;
+        acquire_terminal(
+            &mut terminal,
+            &mut capabilities,
+            Some(keyboard_flags(false)),
+        )?;
         release_terminal(&mut terminal, &mut capabilities)?;
 
         assert_eq!(
@@ -962,8 +1603,12 @@ mod tests {
             };
             let mut capabilities = TerminalCapabilities::default();
 
-            acquire_terminal(&mut terminal, &mut capabilities, true)
-                .expect_err("configured acquisition should fail");
+            acquire_terminal(
+                &mut terminal,
+                &mut capabilities,
+                Some(keyboard_flags(false)),
+            )
+            .expect_err("configured acquisition should fail");
             release_terminal(&mut terminal, &mut capabilities)
                 .expect("rollback should release every acquired capability");
 
@@ -971,6 +1616,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn key_events_carry_kind_and_extended_modifiers() {
+        let release = cevent::KeyEvent::new_with_kind(
+            cevent::KeyCode::Char('k'),
+            cevent::KeyModifiers::CONTROL | cevent::KeyModifiers::SUPER,
+            cevent::KeyEventKind::Release,
+        );
+        let Event::Key(k) = translate_event(cevent::Event::Key(release)) else {
+            panic!("expected a key event");
+        };
+        assert_eq!(k.kind, key::KeyKind::Release);
+        assert_eq!(k.mods, key::Ctrl + key::Super);
+        assert!(!is_interrupt(&Event::Key(
+            (key::Ctrl + 'c').with_kind(key::KeyKind::Release)
+        )));
+        assert!(is_interrupt(&Event::Key(key::Ctrl + 'c')));
+        assert!(
+            !keyboard_flags(false).contains(cevent::KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        );
+        assert!(
+            keyboard_flags(true).contains(cevent::KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+        );
+    }
+
     #[test]
     fn event_source_surfaces_terminal_reader_failure() {
         let (_internal_tx, internal_rx) = unbounded();
@@ -1020,4 +1689,65 @@ mod tests {
 
         assert_eq!(runs, vec![text_run(1, 3, "e\u{0301}x")]);
     }
+
+    #[test]
+    fn underline_sequence_degrades_to_plain_underline() {
+        let plain = AttrSet::new(Attr::Underline);
+        assert_eq!(underline_sequence(plain, None), "\x1b[4m");
+
+        let curly = plain.with_underline(UnderlineStyle::Curly);
+        assert_eq!(
+            underline_sequence(curly, Some(Color::Rgb { r: 255, g: 0, b: 8 })),
+            "\x1b[4m\x1b[4:3m\x1b[58:2::255:0:8m"
+        );
+        assert_eq!(
+            underline_sequence(
+                AttrSet::default().with_underline(UnderlineStyle::Dashed),
+                Some(Color::Red)
+            ),
+            "\x1b[4m\x1b[4:5m\x1b[58:5:9m"
+        );
+    }
+
+    #[test]
+    fn hyperlink_sequence_opens_closes_and_sanitizes() {
+        assert_eq!(
+            hyperlink_sequence(Some("https://example.com")),
+            "\x1b]8;;https://example.com\x1b\\"
+        );
+        assert_eq!(hyperlink_sequence(None), "\x1b]8;;\x1b\\");
+        assert_eq!(
+            hyperlink_sequence(Some("https://a\x1b\\b\x07")),
+            "\x1b]8;;https://a\\b\x1b\\"
+        );
+    }
+
+    #[test]
+    fn image_sequences_upload_kitty_images_once() {
+        let image = Arc::new(
+            graphics::Pixmap::new(1, 1, vec![255, 0, 0, 255]).expect("pixmap should build"),
+        );
+        let id = image.id();
+        let placement = ImagePlacement {
+            source: image.rect(),
+            image,
+            dest: Rect::new(2, 1, 1, 1),
+        };
+        let mut transmitted = HashSet::new();
+        assert_eq!(
+            kitty_sequence(&placement, &mut transmitted),
+            format!(
+                "\x1b_Ga=t,f=32,s=1,v=1,i={id},q=2,m=0;/wAA/w==\x1b\\\
+                 \x1b_Ga=p,i={id},p=1,x=0,y=0,w=1,h=1,c=1,r=1,C=1,q=2\x1b\\"
+            )
+        );
+        assert_eq!(
+            kitty_sequence(&placement, &mut transmitted),
+            format!("\x1b_Ga=p,i={id},p=1,x=0,y=0,w=1,h=1,c=1,r=1,C=1,q=2\x1b\\")
+        );
+        assert_eq!(
+            graphics::sixel(&placement, Size::new(1, 2)),
+            "\x1bP0;1;0q\"1;1;1;2#180;2;100;0;0#180B\x1b\\"
+        );
+    }
 }
@@ -1,5 +1,7 @@
 /// Crossterm backend implementation.
 pub mod crossterm;
+/// Terminal capability queries answered before a session starts.
+mod probe;
 use std::{fmt::Debug, sync::Arc};
 
 use parking_lot::Mutex;
@@ -18,6 +20,57 @@ pub trait BackendControl: Debug + Send {
     fn stop(&mut self) -> Result<()>;
 }
 
+/// Terminal features a run loop asks the backend to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalOptions {
+    /// Drive the terminal's own cursor instead of painting the cursor into the frame.
+    ///
+    /// A native cursor follows the user's cursor preferences and lets input method
+    /// popups and screen magnifiers find the caret. Off by default, so the cursor is
+    /// painted with the app's own style unless the app opts in.
+    pub native_cursor: bool,
+    /// Ask the terminal for key repeat and release events.
+    ///
+    /// This uses the kitty keyboard protocol and is ignored by terminals without it. Releases
+    /// reach only bindings made for them, such as `release-space`.
+    pub key_events: bool,
+    /// Draw images with the terminal's graphics protocol when one is detected.
+    ///
+    /// Terminals are checked for the kitty graphics protocol and sixel. Images fall back to
+    /// their text rendition when neither is found.
+    pub images: bool,
+}
+
+impl Default for TerminalOptions {
+    fn default() -> Self {
+        Self {
+            native_cursor: false,
+            key_events: false,
+            images: true,
+        }
+    }
+}
+
+impl TerminalOptions {
+    /// Configure whether the terminal's own cursor is used.
+    pub fn with_native_cursor(mut self, enabled: bool) -> Self {
+        self.native_cursor = enabled;
+        self
+    }
+
+    /// Configure whether key repeat and release events are requested.
+    pub fn with_key_events(mut self, enabled: bool) -> Self {
+        self.key_events = enabled;
+        self
+    }
+
+    /// Configure whether images are drawn with a terminal graphics protocol.
+    pub fn with_images(mut self, enabled: bool) -> Self {
+        self.images = enabled;
+        self
+    }
+}
+
 /// Guard that ensures backend start/stop are paired for a terminal session.
 pub struct TerminalSession {
     /// Shared session state used by normal exit, panic cleanup, and drop.
@@ -51,6 +104,39 @@ impl TerminalCleanup {
     }
 }
 
+/// Cloneable capability to lend the terminal to another program.
+#[derive(Clone)]
+pub(crate) struct TerminalHandle {
+    /// Shared terminal state.
+    state: Arc<Mutex<TerminalState>>,
+}
+
+impl TerminalHandle {
+    /// Stop the backend, run a closure, and start the backend again.
+    ///
+    /// A session that was already stopped stays stopped. The backend restarts even if the
+    /// closure's own work failed, so the caller can report the failure on a working terminal.
+    pub(crate) fn release_while<R>(&self, f: impl FnOnce() -> R) -> Result<R> {
+        let was_active = {
+            let mut state = self.state.lock();
+            if state.active {
+                state.backend.stop()?;
+                state.active = false;
+                true
+            } else {
+                false
+            }
+        };
+        let out = f();
+        if was_active {
+            let mut state = self.state.lock();
+            state.backend.start()?;
+            state.active = true;
+        }
+        Ok(out)
+    }
+}
+
 impl TerminalSession {
     /// Start the backend and create a new session guard.
     pub(crate) fn new(mut backend: Box<dyn BackendControl>) -> Result<Self> {
@@ -70,6 +156,13 @@ impl TerminalSession {
         }
     }
 
+    /// Return a handle that can lend the terminal to another program.
+    pub(crate) fn handle(&self) -> TerminalHandle {
+        TerminalHandle {
+            state: Arc::clone(&self.state),
+        }
+    }
+
     /// Stop the backend if the session is active.
     pub(crate) fn stop(&self) -> Result<()> {
         self.cleanup().stop()
@@ -168,6 +261,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn released_terminal_restarts_only_an_active_session() -> Result<()> {
+        let lifecycle = Arc::new(Mutex::new(Lifecycle::default()));
+        let backend = Box::new(RecordingControl {
+            lifecycle: Arc::clone(&lifecycle),
+            fail_start: false,
+        });
+        let session = TerminalSession::new(backend)?;
+        let handle = session.handle();
+
+        let seen = handle.release_while(|| {
+            let lifecycle = lifecycle.lock();
+            (lifecycle.starts, lifecycle.stops)
+        })?;
+        assert_eq!(seen, (1, 1));
+        assert_eq!(lifecycle.lock().starts, 2);
+
+        session.stop()?;
+        handle.release_while(|| ())?;
+        drop(session);
+
+        let lifecycle = lifecycle.lock();
+        assert_eq!(lifecycle.starts, 2);
+        assert_eq!(lifecycle.stops, 2);
+        Ok(())
+    }
+
     #[test]
     fn failed_session_start_never_calls_stop() {
         let lifecycle = Arc::new(Mutex::new(Lifecycle::default()));
@@ -0,0 +1,197 @@
+//! Terminal capability queries.
+//!
+//! Queries are written before the input reader starts and followed by a primary device
+//! attributes request, which every terminal answers. The DA1 reply marks the end of the
+//! answers, so terminals that ignore a query cost no more than one round trip.
+
+use std::time::Duration;
+
+use crate::graphics::GraphicsSupport;
+
+/// How long to wait for the terminal to answer a batch of queries.
+const TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Primary device attributes request, sent last in every batch.
+const DA1: &str = "\x1b[c";
+
+/// Kitty graphics query for a one-pixel image, answered only by terminals with the protocol.
+pub(super) const KITTY_GRAPHICS_QUERY: &str = "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\";
+
+/// Reply to [`KITTY_GRAPHICS_QUERY`] from a terminal that accepts the image.
+const KITTY_GRAPHICS_OK: &str = "\x1b_Gi=31;OK";
+
+/// Primary device attribute that advertises sixel graphics.
+const SIXEL_ATTRIBUTE: u16 = 4;
+
+/// Build a DECRQM query for a private mode.
+pub(super) fn mode_query(mode: u16) -> String {
+    format!("\x1b[?{mode}$p")
+}
+
+/// Replies read from the terminal in answer to a batch of queries.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(super) struct Replies {
+    /// Raw reply bytes, decoded lossily.
+    text: String,
+}
+
+impl Replies {
+    /// Wrap reply text read from a terminal.
+    pub(super) fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+
+    /// Return whether a DECRPM reply reports a private mode as recognised and changeable.
+    ///
+    /// Modes reported as not recognised (0) or permanently reset (4) are unsupported.
+    pub(super) fn mode_supported(&self, mode: u16) -> bool {
+        let prefix = format!("\x1b[?{mode};");
+        self.text.match_indices(&prefix).any(|(at, _)| {
+            let rest = &self.text[at + prefix.len()..];
+            matches!(rest.as_bytes(), [b'1'..=b'3', b'$', b'y', ..])
+        })
+    }
+
+    /// Return the attributes of the primary device attributes reply, if one arrived.
+    ///
+    /// The leading conformance level is dropped.
+    fn device_attributes(&self) -> Option<Vec<u16>> {
+        let start = self.text.rfind("\x1b[?")? + 3;
+        let params = self.text[start..].strip_suffix('c')?;
+        let mut attributes = params.split(';').map(|p| p.parse().ok());
+        attributes.next()?;
+        attributes.collect()
+    }
+
+    /// Return the graphics protocols the terminal reported, or `None` if it did not answer.
+    pub(super) fn graphics(&self) -> Option<GraphicsSupport> {
+        let attributes = self.device_attributes()?;
+        Some(GraphicsSupport {
+            kitty: self.text.contains(KITTY_GRAPHICS_OK),
+            sixel: attributes.contains(&SIXEL_ATTRIBUTE),
+        })
+    }
+}
+
+/// Write queries to the terminal and collect the replies.
+///
+/// Raw mode is enabled for the duration so the replies are neither echoed nor
+/// line-buffered. A terminal that does not answer in time yields no replies.
+pub(super) fn query(queries: &str) -> Replies {
+    #[cfg(unix)]
+    {
+        unix::query(queries).unwrap_or_default()
+    }
+    #[cfg(not(unix))]
+    {
+        let _unused = queries;
+        Replies::default()
+    }
+}
+
+/// Reply reading through the controlling terminal.
+#[cfg(unix)]
+mod unix {
+    use std::{
+        fs::File,
+        io::{self, Read, Write},
+        time::Instant,
+    };
+
+    use crossterm::terminal;
+    use rustix::event::{PollFd, PollFlags, Timespec, poll};
+
+    use super::{DA1, Replies, TIMEOUT};
+
+    /// Write queries and read replies until the DA1 answer arrives or the timeout passes.
+    pub(super) fn query(queries: &str) -> io::Result<Replies> {
+        let mut tty = File::options().read(true).write(true).open("/dev/tty")?;
+        let raw = terminal::is_raw_mode_enabled()?;
+        if !raw {
+            terminal::enable_raw_mode()?;
+        }
+        let replies = exchange(&mut tty, queries);
+        if !raw {
+            terminal::disable_raw_mode()?;
+        }
+        replies
+    }
+
+    /// Send the queries and collect reply bytes.
+    fn exchange(tty: &mut File, queries: &str) -> io::Result<Replies> {
+        tty.write_all(queries.as_bytes())?;
+        tty.write_all(DA1.as_bytes())?;
+        tty.flush()?;
+
+        let deadline = Instant::now() + TIMEOUT;
+        let mut bytes = Vec::new();
+        let mut chunk = [0; 256];
+        while !answered(&bytes) {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            let timeout = Timespec::try_from(left).map_err(io::Error::other)?;
+            let mut fds = [PollFd::new(&*tty, PollFlags::IN)];
+            if poll(&mut fds, Some(&timeout))? == 0 {
+                break;
+            }
+            let read = tty.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            bytes.extend_from_slice(&chunk[..read]);
+        }
+        Ok(Replies::new(String::from_utf8_lossy(&bytes)))
+    }
+
+    /// Return whether the reply bytes end with the DA1 answer `CSI ? … c`.
+    fn answered(bytes: &[u8]) -> bool {
+        let Some(start) = bytes.windows(3).rposition(|w| w == b"\x1b[?") else {
+            return false;
+        };
+        let tail = &bytes[start + 3..];
+        tail.last() == Some(&b'c')
+            && tail[..tail.len() - 1]
+                .iter()
+                .all(|b| b.is_ascii_digit() || *b == b';')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrpm_replies_report_changeable_modes() {
+        let replies = Replies::new("\x1b[?2026;2$y\x1b[?1004;4$y\x1b[?62;22c");
+        assert!(replies.mode_supported(2026));
+        assert!(!replies.mode_supported(1004));
+        assert!(!replies.mode_supported(2004));
+        assert!(!Replies::new("\x1b[?2026;0$y").mode_supported(2026));
+        assert_eq!(mode_query(2026), "\x1b[?2026$p");
+    }
+
+    #[test]
+    fn graphics_follow_kitty_and_device_attribute_replies() {
+        assert_eq!(Replies::default().graphics(), None);
+        assert_eq!(
+            Replies::new("\x1b_Gi=31;OK\x1b\\\x1b[?62;22c").graphics(),
+            Some(GraphicsSupport {
+                kitty: true,
+                sixel: false,
+            })
+        );
+        assert_eq!(
+            Replies::new("\x1b[?64;1;4;22c").graphics(),
+            Some(GraphicsSupport {
+                kitty: false,
+                sixel: true,
+            })
+        );
+        assert_eq!(
+            Replies::new("\x1b[?4;6c").graphics().map(|g| g.sixel),
+            Some(false)
+        );
+    }
+}
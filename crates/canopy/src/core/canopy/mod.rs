@@ -13,12 +13,14 @@ use std::{
 };
 
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+use parking_lot::{Condvar, Mutex, MutexGuard};
 use ruau::{filesystem::DirectoryMountsError, source::SourceProvider, vm::NativeModule};
 use serde::{Deserialize, Serialize};
 
 use super::{
+    coverage::{CoverageCatalog, CoverageHits},
     inputmap,
-    poll::Poller,
+    poll::{Poller, VirtualClock},
     termbuf::{RenderLimits, TermBuf},
 };
 
@@ -27,12 +29,16 @@ mod routing;
 #[cfg(test)]
 mod tests;
 use crate::{
-    backend::BackendControl,
+    backend::{BackendControl, TerminalOptions},
     commands::{self, CommandDispatchKind},
     core::{
         Core, NodeId, TypedId,
         dump::dump,
         fixture::{Fixture, FixtureInfo},
+        frame::{FramePolicy, FrameStats},
+        observe::{AppChange, ObservedState, Observers},
+        recording::Recorder,
+        snapshot::SnapshotConfig,
     },
     error::{self, Result},
     event::Event,
@@ -92,8 +98,20 @@ pub struct Canopy {
 
     /// Cached terminal buffer.
     termbuf: Option<TermBuf>,
+    /// Snapshot file location and update mode, or `None` to read them from the environment.
+    snapshot_config: Option<SnapshotConfig>,
     /// Whether a render is pending after the most recent event.
     render_pending: bool,
+    /// Frame pacing used by run loops.
+    frame_policy: FramePolicy,
+    /// Terminal features used by run loops.
+    terminal_options: TerminalOptions,
+    /// Events handled since the most recent frame.
+    frame_events: usize,
+    /// Active session recording, if any.
+    recorder: Option<Recorder>,
+    /// Automation observers notified after each frame.
+    observers: Observers,
 
     /// Event sender channel.
     pub(crate) event_tx: UnboundedSender<Event>,
@@ -188,6 +206,51 @@ pub struct AutomationHandle {
     wake_tx: UnboundedSender<Event>,
     /// Thread that owns the associated Canopy instance.
     ui_thread: ThreadId,
+    /// Turns shared by every lane derived from this handle.
+    turns: Arc<AutomationTurns>,
+    /// This handle's lane, when its requests take turns with other lanes.
+    lane: Option<Arc<Mutex<()>>>,
+}
+
+/// First-come, first-served turns for requests made through automation lanes.
+#[derive(Default)]
+struct AutomationTurns {
+    /// Next ticket to hand out, and the ticket currently being served.
+    tickets: Mutex<(u64, u64)>,
+    /// Signalled whenever a turn ends.
+    turn_ended: Condvar,
+}
+
+/// One lane's turn, ended when dropped.
+struct AutomationTurn<'a> {
+    /// Turns to advance when this one ends.
+    turns: &'a AutomationTurns,
+    /// Held for the whole turn, so a lane queues at most one ticket at a time.
+    _lane: MutexGuard<'a, ()>,
+}
+
+impl AutomationTurns {
+    /// Wait until a lane's next request may run.
+    fn wait<'a>(&'a self, lane: &'a Mutex<()>) -> AutomationTurn<'a> {
+        let lane = lane.lock();
+        let mut tickets = self.tickets.lock();
+        let ticket = tickets.0;
+        tickets.0 += 1;
+        while tickets.1 != ticket {
+            self.turn_ended.wait(&mut tickets);
+        }
+        AutomationTurn {
+            turns: self,
+            _lane: lane,
+        }
+    }
+}
+
+impl Drop for AutomationTurn<'_> {
+    fn drop(&mut self) {
+        self.turns.tickets.lock().1 += 1;
+        self.turns.turn_ended.notify_all();
+    }
 }
 
 impl AutomationHandle {
@@ -209,7 +272,21 @@ impl AutomationHandle {
         Ok(())
     }
 
+    /// Return a handle on a new lane.
+    ///
+    /// Requests from lanes derived from the same handle run one at a time. Lanes take turns:
+    /// each lane holds at most one place in the queue, so a client that issues many requests
+    /// cannot starve the others. Requests from the returned handle's clones share its lane.
+    pub fn lane(&self) -> Self {
+        Self {
+            lane: Some(Arc::default()),
+            ..self.clone()
+        }
+    }
+
     /// Execute a closure on the UI thread and wait for its result.
+    ///
+    /// On a [lane](Self::lane), the request first waits for the lane's turn.
     pub fn request<R, F>(&self, callback: F) -> Result<R>
     where
         R: Send + 'static,
@@ -220,6 +297,7 @@ impl AutomationHandle {
                 "synchronous automation request from the UI thread".into(),
             ));
         }
+        let _turn = self.lane.as_ref().map(|lane| self.turns.wait(lane));
         let (tx, rx) = mpsc::channel();
         self.submit(Box::new(move |canopy| {
             let _ignored = tx.send(callback(canopy));
@@ -323,7 +401,8 @@ impl Canopy {
     pub fn new() -> Self {
         let (tx, rx) = unbounded();
         let (automation_tx, automation_rx) = mpsc::sync_channel(AUTOMATION_QUEUE_CAPACITY);
-        let core = Core::new();
+        let mut core = Core::new();
+        core.tasks.set_wake(tx.clone());
         Self {
             poller: Poller::new(tx.clone()),
             event_tx: tx,
@@ -351,7 +430,13 @@ impl Canopy {
             root_size: None,
             render_limits: RenderLimits::default(),
             termbuf: None,
+            snapshot_config: None,
             render_pending: true,
+            frame_policy: FramePolicy::default(),
+            terminal_options: TerminalOptions::default(),
+            frame_events: 0,
+            recorder: None,
+            observers: Observers::default(),
             backend: None,
             core,
         }
@@ -363,14 +448,142 @@ impl Canopy {
             callback_tx: self.automation_tx.clone(),
             wake_tx: self.event_tx.clone(),
             ui_thread: self.ui_thread,
+            turns: Arc::default(),
+            lane: None,
         }
     }
 
+    /// Subscribe to frame, focus, input mode, binding set and script API changes.
+    ///
+    /// Notifications are sent from the UI thread after each rendered frame.
+    pub fn observe_changes(&mut self) -> UnboundedReceiver<AppChange> {
+        let state = observed_state(&self.core, &self.script_host);
+        self.observers.subscribe(state)
+    }
+
+    /// Notify observers that a frame rendered.
+    pub(crate) fn notify_observers(&mut self) {
+        let state = observed_state(&self.core, &self.script_host);
+        self.observers.frame_rendered(state);
+    }
+
+    /// Return the focused node, if any.
+    pub fn focus_id(&self) -> Option<NodeId> {
+        self.core.focus
+    }
+
     /// Mark the visible application state for redraw.
     pub fn request_redraw(&mut self) {
         self.render_pending = true;
     }
 
+    /// Return true when visible state has changed since the last render.
+    pub fn render_pending(&self) -> bool {
+        self.render_pending
+    }
+
+    /// Return the frame pacing used by run loops.
+    pub fn frame_policy(&self) -> FramePolicy {
+        self.frame_policy
+    }
+
+    /// Replace the frame pacing used by run loops. Takes effect when the run loop starts.
+    pub fn set_frame_policy(&mut self, policy: FramePolicy) {
+        self.frame_policy = policy;
+    }
+
+    /// Return the terminal features used by run loops.
+    pub fn terminal_options(&self) -> TerminalOptions {
+        self.terminal_options
+    }
+
+    /// Replace the terminal features used by run loops. Takes effect when the run loop starts.
+    pub fn set_terminal_options(&mut self, options: TerminalOptions) {
+        self.terminal_options = options;
+    }
+
+    /// Return a summary of recent frame render timings.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.core.frames.stats()
+    }
+
+    /// Start recording the session to an asciicast file, with an input log beside it.
+    ///
+    /// Run loops record the events they read and the frames they draw. Recording needs a
+    /// screen size, so it starts after the root size is set.
+    pub fn start_recording(&mut self, path: &FsPath) -> Result<()> {
+        let size = self.root_size.ok_or_else(|| {
+            error::Error::Invalid("recording needs a screen size; set the root size first".into())
+        })?;
+        self.recorder = Some(Recorder::create(path, size)?);
+        Ok(())
+    }
+
+    /// Stop recording the session.
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Is the session being recorded?
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Record an input event, stopping the recording if it cannot be written.
+    pub(crate) fn record_event(&mut self, event: &Event) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(err) = recorder.event(event)
+        {
+            tracing::warn!("session recording stopped: {err}");
+            self.recorder = None;
+        }
+    }
+
+    /// Record the current frame, stopping the recording if it cannot be written.
+    pub(crate) fn record_frame(&mut self) {
+        if let (Some(recorder), Some(buf)) = (&mut self.recorder, &self.termbuf)
+            && let Err(err) = recorder.frame(buf)
+        {
+            tracing::warn!("session recording stopped: {err}");
+            self.recorder = None;
+        }
+    }
+
+    /// Ask the run loop to wake after a delay.
+    pub(crate) fn wake_after(&self, delay: Duration) -> Result<()> {
+        self.poller.wake_after(delay)
+    }
+
+    /// Drive poll timing from a virtual clock instead of wall time.
+    ///
+    /// Polls then fire only from [`Self::advance_time`] and from script waits, which advance
+    /// virtual time instead of sleeping. Install the clock before the first render: polls
+    /// scheduled on the previous clock are discarded. Run loops wait on wall time, so this is
+    /// meant for tests and headless sessions.
+    pub fn use_virtual_clock(&mut self, clock: VirtualClock) {
+        self.poller = Poller::with_virtual_clock(clock);
+    }
+
+    /// Return the installed virtual clock, if any.
+    pub fn virtual_clock(&self) -> Option<VirtualClock> {
+        self.poller.virtual_clock()
+    }
+
+    /// Start recording command dispatches and binding firings, discarding earlier hits.
+    pub fn start_coverage(&mut self) {
+        self.core.coverage = Some(CoverageHits::default());
+    }
+
+    /// Stop coverage recording and return the hits, if recording was on.
+    pub fn take_coverage(&mut self) -> Option<CoverageHits> {
+        self.core.coverage.take()
+    }
+
+    /// Return every registered command and binding, for comparison against coverage hits.
+    pub fn coverage_catalog(&self) -> CoverageCatalog {
+        CoverageCatalog::new(&self.core.commands, &self.core.input_map)
+    }
+
     /// Return the root node ID.
     pub fn root_id(&self) -> NodeId {
         self.core.root_id()
@@ -822,6 +1035,15 @@ impl Canopy {
         self.script_host.check_script(source_name, source)
     }
 
+    /// Refuse or allow script APIs that mutate the app, until changed again.
+    ///
+    /// While set, inspection APIs such as `canopy.screen_text` and `canopy.tree` still work,
+    /// but input, focus, mode and binding changes and command dispatch fail with a `read_only`
+    /// script error.
+    pub fn set_script_read_only(&self, read_only: bool) {
+        self.script_host.set_read_only(read_only);
+    }
+
     /// Drain and return log lines recorded by the most recent script evaluation.
     pub fn take_script_logs(&self) -> Vec<String> {
         self.script_host.take_logs()
@@ -1463,6 +1685,16 @@ fn implementation_path_for_declaration(path: &FsPath) -> Option<PathBuf> {
     Some(path.with_file_name(format!("{stem}.luau")))
 }
 
+/// Capture the state observers are notified about.
+fn observed_state<'a>(core: &'a Core, script_host: &script::LuauHost) -> ObservedState<'a> {
+    ObservedState {
+        focus: core.focus,
+        mode: core.input_map.current_mode(),
+        bindings: core.input_map.revision(),
+        api_ready: script_host.is_finalized(),
+    }
+}
+
 /// Render script diagnostics for an error message.
 /// A trait that allows widgets to perform recursive initialization of themselves and their
 /// children.
@@ -1,11 +1,21 @@
 //! Rendering pipeline for the canopy facade.
 
+use std::{fs, mem, path::Path, time::Instant};
+
 use super::Canopy;
 use crate::{
     NodeId,
-    core::{context::CoreViewContext, termbuf::TermBuf, view::View, world::WidgetOperation},
+    core::{
+        context::CoreViewContext,
+        export::{self, ExportFormat},
+        frame::FrameTiming,
+        snapshot::{Snapshot, SnapshotCheck, SnapshotConfig},
+        termbuf::TermBuf,
+        view::View,
+        world::WidgetOperation,
+    },
     cursor,
-    error::Result,
+    error::{Error, Result},
     geom::{Point, Rect, RectI32, Size},
     layout::Display,
     render::{NopBackend, Render, RenderBackend},
@@ -39,6 +49,52 @@ impl Canopy {
         Ok(())
     }
 
+    /// Configure where snapshot checks read and write snapshot files.
+    pub fn set_snapshot_config(&mut self, config: SnapshotConfig) {
+        self.snapshot_config = Some(config);
+    }
+
+    /// Return the snapshot configuration, defaulting to [`SnapshotConfig::from_env`].
+    pub fn snapshot_config(&self) -> SnapshotConfig {
+        self.snapshot_config
+            .clone()
+            .unwrap_or_else(SnapshotConfig::from_env)
+    }
+
+    /// Capture the rendered screen, or a region of it, rendering first if a render is pending.
+    pub fn capture_snapshot(&mut self, region: Option<Rect>) -> Result<Snapshot> {
+        self.refresh_snapshot()?;
+        let buf = self
+            .termbuf
+            .as_ref()
+            .ok_or_else(|| Error::Invalid("screen unavailable before render".into()))?;
+        Ok(Snapshot::capture(buf, region))
+    }
+
+    /// Compare the rendered screen, or a region of it, against a named snapshot file.
+    pub fn check_snapshot(&mut self, name: &str, region: Option<Rect>) -> Result<SnapshotCheck> {
+        let snapshot = self.capture_snapshot(region)?;
+        self.snapshot_config().check(name, &snapshot)
+    }
+
+    /// Export the rendered screen in a format, rendering first if a render is pending.
+    pub fn export_screen(&mut self, format: ExportFormat) -> Result<String> {
+        self.refresh_snapshot()?;
+        let buf = self
+            .termbuf
+            .as_ref()
+            .ok_or_else(|| Error::Invalid("screen unavailable before render".into()))?;
+        Ok(export::export(buf, format))
+    }
+
+    /// Export the rendered screen in a format and write it to a file.
+    pub fn save_screen(&mut self, path: &Path, format: ExportFormat) -> Result<()> {
+        let output = self.export_screen(format)?;
+        fs::write(path, output).map_err(|err| {
+            Error::Invalid(format!("screen export to {} failed: {err}", path.display()))
+        })
+    }
+
     /// Pre-render sweep of the tree.
     pub(crate) fn pre_render(&mut self) -> Result<bool> {
         let root = self.core.root;
@@ -176,8 +232,8 @@ impl Canopy {
         Ok(())
     }
 
-    /// Render the tree into an offscreen buffer.
-    fn render_pass(&mut self, root_size: Size) -> Result<TermBuf> {
+    /// Render the tree into an offscreen buffer, painting the cursor into it when `overlay` is set.
+    fn render_pass(&mut self, root_size: Size, overlay: bool) -> Result<TermBuf> {
         let mut styl = StyleManager::default();
 
         let def_style = styl
@@ -194,13 +250,17 @@ impl Canopy {
             effect_stack: &mut effect_stack,
         };
         self.render_recursive(&mut traversal, self.core.root, screen_clip, 0, 0)?;
-        self.post_render(&mut next)?;
+        if overlay && let Some(c) = self.screen_cursor()? {
+            next.overlay_cursor(c.location, c.shape);
+        }
 
         Ok(next)
     }
 
-    /// Post-render sweep of the tree.
-    pub(crate) fn post_render(&self, buf: &mut TermBuf) -> Result<()> {
+    /// Return the cursor of the nearest focused node that has one, in screen coordinates.
+    ///
+    /// Cursors outside their node's view are not shown.
+    pub(crate) fn screen_cursor(&self) -> Result<Option<cursor::Cursor>> {
         let mut current = self.core.focus;
         let mut cursor_spec: Option<(NodeId, View, cursor::Cursor)> = None;
         while let Some(id) = current {
@@ -216,19 +276,22 @@ impl Canopy {
             current = self.core.nodes.get(id).and_then(|n| n.parent);
         }
 
-        if let Some((_nid, view, c)) = cursor_spec {
-            let view_rect = Rect::new(0, 0, view.content.w, view.content.h);
-            if view_rect.contains_point(c.location) {
-                let screen_x = i64::from(view.content.tl.x) + i64::from(c.location.x);
-                let screen_y = i64::from(view.content.tl.y) + i64::from(c.location.y);
-                if let (Ok(x), Ok(y)) = (u32::try_from(screen_x), u32::try_from(screen_y)) {
-                    let screen_pos = Point { x, y };
-                    buf.overlay_cursor(screen_pos, c.shape);
-                }
-            }
+        let Some((_nid, view, c)) = cursor_spec else {
+            return Ok(None);
+        };
+        let view_rect = Rect::new(0, 0, view.content.w, view.content.h);
+        if !view_rect.contains_point(c.location) {
+            return Ok(None);
         }
-
-        Ok(())
+        let screen_x = i64::from(view.content.tl.x) + i64::from(c.location.x);
+        let screen_y = i64::from(view.content.tl.y) + i64::from(c.location.y);
+        let (Ok(x), Ok(y)) = (u32::try_from(screen_x), u32::try_from(screen_y)) else {
+            return Ok(None);
+        };
+        Ok(Some(cursor::Cursor {
+            location: Point { x, y },
+            ..c
+        }))
     }
 
     /// Render the widget tree. All visible nodes are rendered.
@@ -241,23 +304,42 @@ impl Canopy {
         }
 
         if let Some(root_size) = self.root_size {
+            let started = Instant::now();
             self.core.update_layout(root_size)?;
 
             let layout_dirty = self.pre_render()?;
             if layout_dirty {
                 self.core.update_layout(root_size)?;
             }
+            let laid_out = Instant::now();
 
-            let next = self.render_pass(root_size)?;
+            let native_cursor = be.supports_cursor();
+            let next = self.render_pass(root_size, !native_cursor)?;
+            let drawn = Instant::now();
 
             be.reset()?;
 
-            if let Some(prev) = &self.termbuf {
-                next.diff(prev, be)?;
-            } else {
-                next.render(be)?;
+            let repaint = self.core.take_repaint_request();
+            if repaint {
+                be.invalidate()?;
+            }
+            match &self.termbuf {
+                Some(prev) if !repaint => next.diff(prev, be)?,
+                _ => next.render(be)?,
+            }
+            if native_cursor {
+                be.cursor(self.screen_cursor()?.as_ref())?;
             }
             self.termbuf = Some(next);
+            self.core.frames.record(
+                started,
+                FrameTiming {
+                    layout: laid_out - started,
+                    draw: drawn - laid_out,
+                    emit: drawn.elapsed(),
+                    events: mem::take(&mut self.frame_events),
+                },
+            );
 
             if let Some(target) = self.core.take_diagnostic_dump_request() {
                 eprintln!("{}", self.diagnostic_dump(target));
@@ -268,6 +350,7 @@ impl Canopy {
             }
 
             self.render_pending = false;
+            self.notify_observers();
         }
 
         Ok(())
@@ -1,12 +1,14 @@
 //! Input routing and event dispatch for the canopy facade.
 
+use std::time::{Duration, Instant};
+
 use ruau::vm::Scope;
 
 use super::{AUTOMATION_SERVICE_BUDGET, Canopy, RoutePhase, RouteTraceEntry};
 use crate::{
     NodeId, commands,
-    core::{Core, inputmap},
-    error::Result,
+    core::{Core, inputmap, poll::VirtualClock},
+    error::{Error, Result},
     event::{Event, key, mouse},
     geom::{Point, Size},
     path::Path,
@@ -14,6 +16,10 @@ use crate::{
     widget::EventOutcome,
 };
 
+/// Upper bound on deadline steps in one virtual clock advance, guarding against polls that
+/// reschedule themselves with a zero delay.
+const MAX_ADVANCE_STEPS: usize = 100_000;
+
 /// Input routed through the shared bubbling pipeline.
 #[derive(Clone, Copy)]
 enum RoutedInput {
@@ -40,6 +46,13 @@ impl RoutedInput {
         }
     }
 
+    /// Return true when widgets see this input.
+    ///
+    /// Key releases reach only bindings, so widgets written for presses don't act twice.
+    fn reaches_widgets(self) -> bool {
+        !matches!(self, Self::Key(key) if key.kind == key::KeyKind::Release)
+    }
+
     /// Return true when an anchored binding may run before widget event dispatch.
     fn allows_pre_event_binding(self) -> bool {
         matches!(self, Self::Key(_))
@@ -150,14 +163,18 @@ impl Canopy {
                 fallback_binding = Some(binding);
             }
 
-            let event = input.event_for_node(&self.core, id);
-            self.trace_route(
-                RoutePhase::WidgetEvent,
-                Some(id),
-                &path,
-                format!("{event:?}"),
-            );
-            let outcome = self.core.dispatch_event_on_node(id, &event)?;
+            let outcome = if input.reaches_widgets() {
+                let event = input.event_for_node(&self.core, id);
+                self.trace_route(
+                    RoutePhase::WidgetEvent,
+                    Some(id),
+                    &path,
+                    format!("{event:?}"),
+                );
+                self.core.dispatch_event_on_node(id, &event)?
+            } else {
+                EventOutcome::Ignore
+            };
 
             match outcome {
                 EventOutcome::Handle | EventOutcome::Consume => {
@@ -195,6 +212,11 @@ impl Canopy {
         binding: inputmap::ResolvedBinding,
         scope: Option<&Scope<'_>>,
     ) -> Result<bool> {
+        if let Some(coverage) = &mut self.core.coverage
+            && let Some(record) = self.core.input_map.binding(binding.id)
+        {
+            coverage.record_binding(record);
+        }
         self.trace_route(
             RoutePhase::BindingExecution,
             Some(node_id),
@@ -287,6 +309,103 @@ impl Canopy {
         Ok(())
     }
 
+    /// Advance the virtual clock, firing each poll and wake as its deadline passes.
+    ///
+    /// Deadlines are visited in order, so a poll that reschedules itself inside the window fires
+    /// again. Fails unless a virtual clock is installed with [`Self::use_virtual_clock`].
+    pub fn advance_time(&mut self, duration: Duration) -> Result<()> {
+        let clock = self.poller.virtual_clock().ok_or_else(|| {
+            Error::Invalid("advancing time needs a virtual clock; install one first".into())
+        })?;
+        let target = clock
+            .now()
+            .checked_add(duration)
+            .ok_or_else(|| Error::Invalid("virtual clock overflow".into()))?;
+        self.advance_clock_to(&clock, target)
+    }
+
+    /// Advance the virtual clock to the next deadline, stopping at `limit`.
+    ///
+    /// Script waits use this in place of sleeping. Returns false without advancing when there is
+    /// no virtual clock, when background tasks are still running on wall time, or when nothing is
+    /// scheduled and there is no limit to advance to.
+    pub(crate) fn advance_time_toward(&mut self, limit: Option<Instant>) -> Result<bool> {
+        let Some(clock) = self.poller.virtual_clock() else {
+            return Ok(false);
+        };
+        if self.core.tasks.has_pending() {
+            return Ok(false);
+        }
+        let target = match (self.poller.next_deadline(), limit) {
+            (Some(next), Some(limit)) => next.min(limit),
+            (next, limit) => match next.or(limit) {
+                Some(target) => target,
+                None => return Ok(false),
+            },
+        };
+        self.advance_clock_to(&clock, target)?;
+        Ok(true)
+    }
+
+    /// Step the virtual clock through each deadline up to `target`, firing due work.
+    fn advance_clock_to(&mut self, clock: &VirtualClock, target: Instant) -> Result<()> {
+        for _ in 0..MAX_ADVANCE_STEPS {
+            let step = match self.poller.next_deadline() {
+                Some(deadline) if deadline <= target => deadline,
+                _ => target,
+            };
+            clock.advance_to(step);
+            let due = self.poller.take_due();
+            if due.wake {
+                self.event(Event::Wake)?;
+            }
+            if !due.nodes.is_empty() {
+                self.event(Event::Poll(due.nodes))?;
+            } else if !due.wake && clock.now() >= target {
+                return Ok(());
+            }
+        }
+        Err(Error::RunLoop(format!(
+            "polls kept firing after {MAX_ADVANCE_STEPS} steps without reaching the target time"
+        )))
+    }
+
+    /// Deliver completed background task results to their widgets on the UI thread.
+    ///
+    /// This runs whenever the canopy handles [`Event::Wake`]. The return value is the number of
+    /// results delivered; results for nodes removed since the task completed are dropped. Every
+    /// completed result is delivered even when a callback fails, and the first failure is
+    /// returned afterwards.
+    pub fn service_tasks(&mut self) -> Result<usize> {
+        let mut delivered = 0;
+        let mut first_error = None;
+        for (node, deliver, output) in self.core.tasks.take_completed() {
+            if !self.core.nodes.contains_key(node) {
+                continue;
+            }
+            let outcome = self
+                .core
+                .with_widget_mut(node, |w, core| {
+                    let mut ctx = crate::core::context::CoreContext::new(core, node);
+                    deliver(w, &mut ctx, output)
+                })
+                .and_then(|result| result);
+            delivered += 1;
+            if let Err(error) = outcome
+                && first_error.is_none()
+            {
+                first_error = Some(error);
+            }
+        }
+        if delivered > 0 {
+            self.render_pending = true;
+        }
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(delivered),
+        }
+    }
+
     /// Service a bounded batch of callbacks marshalled onto the UI thread.
     ///
     /// Custom run loops should call this after receiving [`Event::Wake`]. The return value is the
@@ -309,6 +428,7 @@ impl Canopy {
 
     /// Propagate an event through the tree.
     pub(crate) fn event(&mut self, e: Event) -> Result<()> {
+        self.frame_events += 1;
         match e {
             Event::Key(k) => self.key(None, k),
             Event::Mouse(m) => self.mouse(None, m),
@@ -325,7 +445,10 @@ impl Canopy {
                 let event = Event::Paste(content);
                 self.dispatch_focus_event(&event)
             }
-            Event::Wake => Ok(()),
+            Event::Wake => {
+                self.service_tasks()?;
+                Ok(())
+            }
             Event::FocusGained => {
                 self.render_pending = true;
                 self.dispatch_focus_event(&Event::FocusGained)
@@ -1,5 +1,6 @@
 use std::{
     any::Any,
+    fs, io,
     sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
@@ -9,20 +10,28 @@ use std::{
 };
 
 use futures::{StreamExt, executor::block_on};
+use parking_lot::Mutex;
 
 use super::*;
 use crate::{
-    Context, ViewContext,
+    Context, External, ViewContext,
+    backend::{BackendControl, TerminalSession},
     commands::{CommandId, CommandInvocation, CommandNode, CommandSpec},
-    core::world::test_support::assert_error_context,
-    derive_commands,
+    core::{
+        context::CoreContext,
+        recording::{self, InputRecord},
+        task::TaskId,
+        world::test_support::assert_error_context,
+    },
+    cursor, derive_commands,
     error::{Error, NodeOperationKind, Result},
     event::{Event, key, mouse},
     geom::{Direction, Point, RectI32},
     layout::Layout,
     path::Path,
-    render::{NopBackend, Render},
+    render::{NopBackend, Render, RenderBackend},
     state::NodeName,
+    style::ResolvedStyle,
     testing::{
         backend::TestRender,
         ttree::{Ba, BaLa, BaLb, OutcomeTarget, R, get_state, reset_state, run_ttree},
@@ -93,6 +102,146 @@ fn cross_thread_automation_request_completes_via_service_path() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn automation_lanes_take_turns() -> Result<()> {
+    let mut canopy = Canopy::new();
+    let mut events = canopy
+        .event_rx
+        .take()
+        .expect("test should own framework events");
+    let handle = canopy.automation_handle();
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let request = |lane: &AutomationHandle, name: &'static str| {
+        let lane = lane.clone();
+        let order = Arc::clone(&order);
+        thread::spawn(move || {
+            lane.request(move |_| {
+                order.lock().push(name);
+                Ok(())
+            })
+        })
+    };
+    let wait_for_tickets = |count: u64| {
+        while handle.turns.tickets.lock().0 < count {
+            thread::yield_now();
+        }
+    };
+
+    let busy = handle.lane();
+    let quiet = handle.lane();
+    let mut workers = vec![request(&busy, "busy-1")];
+    wait_for_tickets(1);
+    workers.push(request(&busy, "busy-2"));
+    workers.push(request(&busy, "busy-3"));
+    workers.push(request(&quiet, "quiet"));
+    wait_for_tickets(2);
+
+    while order.lock().len() < 4 {
+        assert!(matches!(block_on(events.next()), Some(Event::Wake)));
+        assert_eq!(canopy.service_automation(), 1);
+    }
+    for worker in workers {
+        worker.join().expect("request worker should not panic")?;
+    }
+    assert_eq!(order.lock()[..2], ["busy-1", "quiet"]);
+    Ok(())
+}
+
+#[derive(Default)]
+struct TaskWidget {
+    received: Vec<u32>,
+}
+
+impl Widget for TaskWidget {}
+
+fn spawn_value(core: &mut Core, id: NodeId, value: u32) -> Result<TaskId> {
+    core.with_widget_mut(id, |_, core| {
+        let mut ctx = CoreContext::new(core, id);
+        let ctx: &mut dyn Context = &mut ctx;
+        ctx.spawn(async move { value }, |w: &mut TaskWidget, _, v| {
+            w.received.push(v);
+            Ok(())
+        })
+    })?
+}
+
+#[test]
+fn spawned_tasks_deliver_on_wake_and_cancel_with_their_node() -> Result<()> {
+    let mut canopy = Canopy::new();
+    let mut events = canopy
+        .event_rx
+        .take()
+        .expect("test should own framework events");
+    let root = canopy.root_id();
+    let kept = canopy
+        .core
+        .add_child_to_boxed(root, Box::new(TaskWidget::default()))?;
+    let removed = canopy
+        .core
+        .add_child_to_boxed(root, Box::new(TaskWidget::default()))?;
+
+    let cancelled = spawn_value(&mut canopy.core, removed, 1)?;
+    canopy.core.remove_subtree(removed)?;
+    assert!(!canopy.core.tasks.is_pending(cancelled));
+
+    let task = spawn_value(&mut canopy.core, kept, 2)?;
+    canopy.render_pending = false;
+    while canopy.core.tasks.is_pending(task) {
+        let event = block_on(events.next()).expect("task completion should wake the loop");
+        assert!(matches!(event, Event::Wake));
+        canopy.event(event)?;
+    }
+
+    assert!(canopy.render_pending);
+    let received = canopy.core.with_widget_mut(kept, |w, _| {
+        let any = w as &mut dyn Any;
+        any.downcast_mut::<TaskWidget>()
+            .map(|widget| widget.received.clone())
+            .unwrap_or_default()
+    })?;
+    assert_eq!(received, vec![2]);
+    Ok(())
+}
+
+#[test]
+fn failed_task_delivery_still_delivers_the_rest() -> Result<()> {
+    let mut canopy = Canopy::new();
+    let mut events = canopy
+        .event_rx
+        .take()
+        .expect("test should own framework events");
+    let root = canopy.root_id();
+    let node = canopy
+        .core
+        .add_child_to_boxed(root, Box::new(TaskWidget::default()))?;
+
+    let failing = canopy.core.with_widget_mut(node, |_, core| {
+        let mut ctx = CoreContext::new(core, node);
+        let ctx: &mut dyn Context = &mut ctx;
+        ctx.spawn(async { 1 }, |_: &mut TaskWidget, _, _: u32| {
+            Err(Error::Internal("delivery failed".into()))
+        })
+    })??;
+    while !matches!(block_on(events.next()), Some(Event::Wake)) {}
+    let kept = spawn_value(&mut canopy.core, node, 2)?;
+    while !matches!(block_on(events.next()), Some(Event::Wake)) {}
+
+    let error = canopy
+        .service_tasks()
+        .expect_err("the failed delivery should be reported");
+    assert!(matches!(error, Error::Internal(_)));
+    assert!(!canopy.core.tasks.is_pending(failing));
+    assert!(!canopy.core.tasks.is_pending(kept));
+    let received = canopy.core.with_widget_mut(node, |w, _| {
+        let any = w as &mut dyn Any;
+        any.downcast_mut::<TaskWidget>()
+            .map(|widget| widget.received.clone())
+            .unwrap_or_default()
+    })?;
+    assert_eq!(received, vec![2]);
+    Ok(())
+}
+
 fn canopy_with_binding_order(inputs: [char; 2]) -> Result<Canopy> {
     let mut canopy = Canopy::new();
     for input in inputs {
@@ -233,6 +382,108 @@ impl Widget for StaticWidget {
     }
 }
 
+pub struct CursorWidget;
+
+impl Widget for CursorWidget {
+    fn layout(&self) -> Layout {
+        Layout::fill()
+    }
+
+    fn accept_focus(&self, _ctx: &dyn ViewContext) -> bool {
+        true
+    }
+
+    fn cursor(&self) -> Option<cursor::Cursor> {
+        Some(cursor::Cursor {
+            location: Point { x: 1, y: 0 },
+            shape: cursor::CursorShape::Line,
+            blink: true,
+        })
+    }
+}
+
+/// Backend controller that records terminal ownership changes.
+#[derive(Debug)]
+struct RecordingControl(Arc<Mutex<Vec<&'static str>>>);
+
+impl BackendControl for RecordingControl {
+    fn start(&mut self) -> Result<()> {
+        self.0.lock().push("start");
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.0.lock().push("stop");
+        Ok(())
+    }
+}
+
+/// Start a terminal session with a recording controller and lend it to a canopy.
+fn recording_terminal(
+    canopy: &mut Canopy,
+) -> Result<(TerminalSession, Arc<Mutex<Vec<&'static str>>>)> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let session = TerminalSession::new(Box::new(RecordingControl(Arc::clone(&log))))?;
+    canopy.core.terminal = Some(session.handle());
+    Ok((session, log))
+}
+
+/// Backend that counts text writes and invalidations.
+#[derive(Default)]
+struct RepaintBackend {
+    writes: usize,
+    invalidations: usize,
+}
+
+impl RenderBackend for RepaintBackend {
+    fn style(&mut self, _style: &ResolvedStyle) -> Result<()> {
+        Ok(())
+    }
+
+    fn text(&mut self, _loc: Point, _txt: &str) -> Result<()> {
+        self.writes += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn invalidate(&mut self) -> Result<()> {
+        self.invalidations += 1;
+        Ok(())
+    }
+}
+
+/// Backend that drives a native cursor and records each placement.
+#[derive(Default)]
+struct NativeCursorBackend {
+    placements: Vec<Option<cursor::Cursor>>,
+}
+
+impl RenderBackend for NativeCursorBackend {
+    fn style(&mut self, _style: &ResolvedStyle) -> Result<()> {
+        Ok(())
+    }
+
+    fn text(&mut self, _loc: Point, _txt: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn supports_cursor(&self) -> bool {
+        true
+    }
+
+    fn cursor(&mut self, cursor: Option<&cursor::Cursor>) -> Result<()> {
+        self.placements.push(cursor.cloned());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
 pub struct FailRenderWidget;
 
 impl Widget for FailRenderWidget {
@@ -514,6 +765,38 @@ fn tbindings() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn release_bindings_fire_only_on_release_and_skip_widgets() -> Result<()> {
+    run_ttree(|c, _, tree| {
+        c.eval_script(
+            r#"
+            canopy.bind("a", { description = "Leaf command" }, function() ba_la.c_leaf() end)
+            canopy.bind("release-x", { description = "Root on release" }, function() r.c_root() end)
+            "#,
+        )?;
+        c.core.set_focus(tree.a_a)?;
+
+        c.key(None, 'x')?;
+        assert!(!get_state().path.contains(&"r.c_root()".to_string()));
+
+        reset_state();
+        c.key(None, key::Key::from('x').with_kind(key::KeyKind::Release))?;
+        assert_eq!(get_state().path, vec!["r.c_root()"]);
+
+        reset_state();
+        c.key(None, key::Key::from('a').with_kind(key::KeyKind::Repeat))?;
+        assert_eq!(
+            get_state().path,
+            vec!["ba_la@key->ignore", "ba_la.c_leaf()"]
+        );
+
+        reset_state();
+        c.key(None, key::Key::from('a').with_kind(key::KeyKind::Release))?;
+        assert!(get_state().path.is_empty());
+        Ok(())
+    })
+}
+
 #[test]
 fn framework_command_bindings_share_route_resolution_and_command_scope() -> Result<()> {
     run_ttree(|c, _, tree| {
@@ -1095,3 +1378,163 @@ fn visible_render_limits_reject_sizes_before_publication() -> Result<()> {
     assert_eq!(canopy.render_limits, accepted);
     Ok(())
 }
+
+#[test]
+fn native_cursor_backends_place_the_cursor_instead_of_painting_it() -> Result<()> {
+    fn focused_canopy() -> Result<Canopy> {
+        let mut canopy = Canopy::new();
+        let id = canopy
+            .core
+            .add_child_to_boxed(canopy.core.root, Box::new(CursorWidget))?;
+        canopy.core.set_layout_of(id, Layout::fill())?;
+        canopy.set_root_size(Size::new(4, 2))?;
+        canopy.core.set_focus(id)?;
+        Ok(canopy)
+    }
+    let cell = |canopy: &Canopy, x| canopy.buf().and_then(|b| b.get(Point { x, y: 0 })).cloned();
+
+    let mut painted = focused_canopy()?;
+    painted.render(&mut TestRender::new())?;
+    assert_ne!(cell(&painted, 1), cell(&painted, 0));
+
+    let mut native = focused_canopy()?;
+    let mut backend = NativeCursorBackend::default();
+    native.render(&mut backend)?;
+    assert_eq!(cell(&native, 1), cell(&native, 0));
+    assert_eq!(
+        backend.placements,
+        vec![Some(cursor::Cursor {
+            location: Point { x: 1, y: 0 },
+            shape: cursor::CursorShape::Line,
+            blink: true,
+        })]
+    );
+    Ok(())
+}
+
+#[test]
+fn suspend_lends_the_terminal_out_and_repaints_on_resume() -> Result<()> {
+    static STOPS: AtomicUsize = AtomicUsize::new(0);
+    fn fake_stop() -> io::Result<()> {
+        STOPS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    let mut canopy = Canopy::new();
+    canopy.core.stop_process = fake_stop;
+    assert!(matches!(
+        canopy.with_root_context(|c| c.suspend()),
+        Err(Error::InvalidOperation(_))
+    ));
+    let (_session, log) = recording_terminal(&mut canopy)?;
+    canopy.set_root_size(Size::new(4, 2))?;
+    let mut backend = RepaintBackend::default();
+    canopy.render(&mut backend)?;
+    backend.writes = 0;
+    canopy.render(&mut backend)?;
+    assert_eq!(backend.writes, 0);
+
+    canopy.with_root_context(|c| c.suspend())?;
+    assert_eq!(STOPS.load(Ordering::SeqCst), 1);
+    assert_eq!(*log.lock(), ["start", "stop", "start"]);
+
+    canopy.render(&mut backend)?;
+    assert_eq!(backend.invalidations, 1);
+    assert_ne!(backend.writes, 0);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn run_external_returns_status_and_read_back_contents() -> Result<()> {
+    let dir = tempfile::tempdir().map_err(Error::TerminalIo)?;
+    let path = dir.path().join("note.txt");
+    let mut canopy = Canopy::new();
+    let (_session, log) = recording_terminal(&mut canopy)?;
+
+    let external = External::new("sh")
+        .arg("-c")
+        .arg("printf edited > \"$0\"; exit 3")
+        .arg(&path)
+        .read_back(&path);
+    let output = canopy.with_root_context(|c| c.run_external(external))?;
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.contents.as_deref(), Some("edited"));
+    assert_eq!(*log.lock(), ["start", "stop", "start"]);
+    assert!(canopy.core.repaint_requested);
+
+    let missing =
+        canopy.with_root_context(|c| c.run_external(External::new("canopy-no-such-program")));
+    assert!(matches!(missing, Err(Error::TerminalIo(_))));
+    assert_eq!(log.lock().len(), 5);
+    Ok(())
+}
+
+#[test]
+fn session_recording_logs_events_and_frames() -> Result<()> {
+    let dir = tempfile::tempdir().map_err(Error::TerminalIo)?;
+    let path = dir.path().join("session.cast");
+    let mut canopy = Canopy::new();
+    assert!(canopy.start_recording(&path).is_err());
+
+    canopy.set_root_size(Size::new(10, 2))?;
+    canopy.start_recording(&path)?;
+    assert!(canopy.is_recording());
+    let mut backend = NopBackend;
+    canopy.render(&mut backend)?;
+    canopy.record_frame();
+    canopy.record_event(&Event::Key(key::KeyCode::Char('q').into()));
+    canopy.stop_recording();
+    canopy.record_frame();
+    assert!(!canopy.is_recording());
+
+    let records = recording::read_input_log(&recording::input_log_path(&path))?;
+    assert_eq!(records.len(), 3);
+    assert!(matches!(&records[1], InputRecord::Frame { .. }));
+    assert!(matches!(&records[2], InputRecord::Key { key, .. } if key == "q"));
+    let cast = fs::read_to_string(&path).map_err(Error::TerminalIo)?;
+    assert_eq!(cast.lines().count(), 2);
+    Ok(())
+}
+
+#[test]
+fn observers_hear_frame_focus_mode_binding_and_api_changes() -> Result<()> {
+    let mut canopy = Canopy::new();
+    canopy.set_root_size(Size::new(10, 2))?;
+    let root = canopy.root_id();
+    canopy.core.set_focus(root)?;
+    let mut backend = NopBackend;
+    canopy.render(&mut backend)?;
+
+    let mut changes = canopy.observe_changes();
+    canopy.render(&mut backend)?;
+    assert_eq!(block_on(changes.next()), Some(AppChange::Frame));
+
+    canopy.core.clear_focus()?;
+    canopy.set_input_mode("insert")?;
+    canopy.render(&mut backend)?;
+    assert_eq!(block_on(changes.next()), Some(AppChange::Frame));
+    assert_eq!(block_on(changes.next()), Some(AppChange::Focus(None)));
+    assert_eq!(
+        block_on(changes.next()),
+        Some(AppChange::Mode("insert".to_string()))
+    );
+
+    canopy.render(&mut backend)?;
+    assert_eq!(block_on(changes.next()), Some(AppChange::Frame));
+
+    canopy.eval_script("canopy.bind(\"a\", { description = \"Noop\" }, function() end)")?;
+    canopy.render(&mut backend)?;
+    assert_eq!(block_on(changes.next()), Some(AppChange::Frame));
+    assert_eq!(block_on(changes.next()), Some(AppChange::Bindings));
+    assert_eq!(block_on(changes.next()), Some(AppChange::Api));
+
+    let binding = canopy.core.input_map.bindings()[0].id;
+    assert!(canopy.unbind(binding)?);
+    canopy.render(&mut backend)?;
+    assert_eq!(block_on(changes.next()), Some(AppChange::Frame));
+    assert_eq!(block_on(changes.next()), Some(AppChange::Bindings));
+    drop(changes);
+    canopy.render(&mut backend)?;
+    Ok(())
+}
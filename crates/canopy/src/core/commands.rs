@@ -1487,6 +1487,11 @@ pub(crate) fn dispatch(
     validate_node_args(core, &inv.args)?;
 
     let resolution = CommandResolver::new(core, current_id).resolve(spec);
+    if resolution.is_some()
+        && let Some(coverage) = &mut core.coverage
+    {
+        coverage.record_command(inv.id);
+    }
 
     match resolution {
         Some(CommandResolution::Free) => {
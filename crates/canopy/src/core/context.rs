@@ -1,5 +1,6 @@
 use std::{
     any::{Any, TypeId, type_name, type_name_of_val},
+    future::Future,
     iter,
     marker::PhantomData,
     ops::Deref,
@@ -8,10 +9,13 @@ use std::{
 
 use super::{
     commands,
+    external::{External, ExternalOutput},
+    frame::FrameStats,
     help::BindingSnapshot,
     id::{NodeId, TypedId},
     inputmap::{ExclusiveFrameToken, FrameworkBindingGroup},
     style::Effect,
+    task::{self, Task, TaskCallback, TaskId, TaskOutput},
     view::View,
     world::{Core, layout_driver::clamp_scroll},
 };
@@ -197,6 +201,9 @@ pub trait ViewContext {
     /// Layout configuration for a specific node.
     fn node_layout(&self, node: NodeId) -> Option<Layout>;
 
+    /// Summary of recent frame render timings.
+    fn frame_stats(&self) -> FrameStats;
+
     /// Widget type identifier for a specific node.
     fn node_type_id(&self, node: NodeId) -> Option<TypeId>;
 
@@ -683,6 +690,29 @@ pub trait Context: ViewContext {
 
     /// Request a diagnostic dump for a target node.
     fn request_diagnostic_dump(&mut self, target: NodeId);
+
+    /// Start a type-erased background task owned by the current node.
+    ///
+    /// Most callers should use the typed `spawn` and `spawn_blocking` helpers.
+    fn spawn_task(&mut self, task: Task, deliver: TaskCallback) -> Result<TaskId>;
+
+    /// Cancel a background task. Returns false if it was already delivered or cancelled.
+    fn cancel_task(&mut self, task: TaskId) -> bool;
+
+    /// Return true if a background task has not yet been delivered or cancelled.
+    fn task_pending(&self, task: TaskId) -> bool;
+
+    /// Suspend the application as a shell job, as Ctrl-Z does in other programs.
+    ///
+    /// This releases the terminal and stops the process with `SIGTSTP`. When the shell continues
+    /// the process, the terminal is restored and the next frame repaints every cell.
+    fn suspend(&mut self) -> Result<()>;
+
+    /// Run an external program in the foreground and wait for it to exit.
+    ///
+    /// The program owns the terminal while it runs. Afterwards the terminal is restored, the next
+    /// frame repaints every cell, and the exit status and any read-back file are returned.
+    fn run_external(&mut self, external: External) -> Result<ExternalOutput>;
 }
 
 impl dyn Context + '_ {
@@ -742,6 +772,39 @@ impl dyn Context + '_ {
         output.ok_or_else(|| Error::Internal("missing widget result".into()))
     }
 
+    /// Run a future in the background and deliver its output to the current widget.
+    ///
+    /// The callback runs on the UI thread once the run loop wakes. The task is cancelled when
+    /// the current node unmounts or is removed, so the callback never sees a stale widget.
+    pub fn spawn<W, T>(
+        &mut self,
+        future: impl Future<Output = T> + Send + 'static,
+        deliver: impl FnOnce(&mut W, &mut dyn Context, T) -> Result<()> + 'static,
+    ) -> Result<TaskId>
+    where
+        W: Widget + 'static,
+        T: Send + 'static,
+    {
+        let future = Box::pin(async move { Box::new(future.await) as TaskOutput });
+        self.spawn_task(Task::Future(future), task::typed_callback(deliver))
+    }
+
+    /// Run blocking work on a background thread and deliver its result to the current widget.
+    ///
+    /// Cancellation discards the result, but work that has already started runs to completion.
+    pub fn spawn_blocking<W, T>(
+        &mut self,
+        work: impl FnOnce() -> T + Send + 'static,
+        deliver: impl FnOnce(&mut W, &mut dyn Context, T) -> Result<()> + 'static,
+    ) -> Result<TaskId>
+    where
+        W: Widget + 'static,
+        T: Send + 'static,
+    {
+        let work = Box::new(move || Box::new(work()) as TaskOutput);
+        self.spawn_task(Task::Blocking(work), task::typed_callback(deliver))
+    }
+
     /// Create a widget node detached from the tree.
     pub fn create_detached<W: Widget + 'static>(&mut self, widget: W) -> Result<TypedId<W>> {
         let id = self.create_detached_boxed(widget.into())?;
@@ -901,6 +964,10 @@ impl<C: Deref<Target = Core>> ViewContext for NodeCtx<C> {
         self.core.nodes.get(node).map(|n| n.view)
     }
 
+    fn frame_stats(&self) -> FrameStats {
+        self.core.frames.stats()
+    }
+
     fn node_layout(&self, node: NodeId) -> Option<Layout> {
         self.core.nodes.get(node).map(|n| n.layout)
     }
@@ -1182,6 +1249,36 @@ impl Context for NodeCtx<&mut Core> {
     fn request_diagnostic_dump(&mut self, target: NodeId) {
         self.core.request_diagnostic_dump(target);
     }
+
+    fn spawn_task(&mut self, task: Task, deliver: TaskCallback) -> Result<TaskId> {
+        self.core.tasks.spawn(self.node_id, task, deliver)
+    }
+
+    fn cancel_task(&mut self, task: TaskId) -> bool {
+        self.core.tasks.cancel(task)
+    }
+
+    fn task_pending(&self, task: TaskId) -> bool {
+        self.core.tasks.is_pending(task)
+    }
+
+    fn suspend(&mut self) -> Result<()> {
+        if self.core.terminal.is_none() {
+            return Err(Error::InvalidOperation(
+                "no terminal session to suspend".into(),
+            ));
+        }
+        let stop = self.core.stop_process;
+        self.core
+            .with_terminal_released(stop)?
+            .map_err(Error::TerminalIo)
+    }
+
+    fn run_external(&mut self, external: External) -> Result<ExternalOutput> {
+        self.core
+            .with_terminal_released(|| external.run())?
+            .map_err(Error::TerminalIo)
+    }
 }
 
 #[cfg(test)]
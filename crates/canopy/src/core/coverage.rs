@@ -0,0 +1,149 @@
+//! Command and binding coverage recording.
+//!
+//! While recording is on, [`Canopy`](crate::Canopy) counts every command dispatch that reaches
+//! a handler and every binding that fires. A [`CoverageCatalog`] lists everything the app
+//! registers, so a test suite can report what it never exercised.
+
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::{CommandDispatchKind, CommandId, CommandSet},
+    core::inputmap::{BindingOwner, BindingRecord, BindingScope, InputMap},
+};
+
+/// Return a binding's stable name: its scope, input and path filter.
+///
+/// Binding ids are allocated per app instance, so coverage merged across instances names
+/// bindings this way instead.
+pub(crate) fn binding_key(record: &BindingRecord) -> String {
+    let scope = match &record.scope {
+        BindingScope::Mode(mode) => format!("mode({mode})"),
+        BindingScope::Exclusive(group) => format!("exclusive({})", group.as_str()),
+        scope => scope.label().to_string(),
+    };
+    let path = record.path_filter();
+    if path.is_empty() {
+        format!("{scope}:{}", record.input)
+    } else {
+        format!("{scope}:{} in {path}", record.input)
+    }
+}
+
+/// One registered command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CommandEntry {
+    /// Fully-qualified command id.
+    pub id: String,
+    /// Widget owner for node-routed commands, or the id prefix for free commands.
+    pub owner: String,
+}
+
+/// One registered binding.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BindingEntry {
+    /// Stable name built from the binding's scope, input and path filter, such as
+    /// `mode(edit):s in editor/**`.
+    pub key: String,
+    /// User-facing description.
+    pub description: String,
+    /// Whether the binding runs a `command` or a `script`.
+    pub target: String,
+    /// Whether the binding belongs to the framework rather than the application.
+    pub framework: bool,
+}
+
+/// Every command and binding an app registers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CoverageCatalog {
+    /// Registered commands, sorted by id.
+    pub commands: Vec<CommandEntry>,
+    /// Registered bindings, sorted by key.
+    pub bindings: Vec<BindingEntry>,
+}
+
+impl CoverageCatalog {
+    /// Build the catalog from a command registry and input map.
+    pub(crate) fn new(commands: &CommandSet, input_map: &InputMap) -> Self {
+        let commands = commands
+            .iter()
+            .map(|(id, spec)| CommandEntry {
+                id: id.to_string(),
+                owner: match spec.dispatch {
+                    CommandDispatchKind::Node { owner } => owner.to_string(),
+                    CommandDispatchKind::Free => id
+                        .rsplit_once("::")
+                        .map_or(id, |(prefix, _)| prefix)
+                        .to_string(),
+                },
+            })
+            .collect();
+        let mut bindings: Vec<_> = input_map
+            .bindings()
+            .iter()
+            .map(|record| BindingEntry {
+                key: binding_key(record),
+                description: record.description.clone(),
+                target: record.target.label().to_string(),
+                framework: matches!(record.owner, BindingOwner::Framework(_)),
+            })
+            .collect();
+        bindings.sort_by(|left, right| left.key.cmp(&right.key));
+        bindings.dedup_by(|left, right| left.key == right.key);
+        Self { commands, bindings }
+    }
+}
+
+/// How often one binding fired.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct BindingHit {
+    /// User-facing description of the binding.
+    pub description: String,
+    /// Number of times the binding fired.
+    pub count: u64,
+}
+
+/// Commands and bindings exercised while coverage recording was on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CoverageHits {
+    /// Dispatch counts by command id.
+    pub commands: BTreeMap<String, u64>,
+    /// Firing counts by binding key.
+    pub bindings: BTreeMap<String, BindingHit>,
+}
+
+impl CoverageHits {
+    /// Count a command dispatch that reached its handler.
+    pub(crate) fn record_command(&mut self, id: CommandId) {
+        *self.commands.entry(id.0.to_string()).or_default() += 1;
+    }
+
+    /// Count a binding that fired.
+    pub(crate) fn record_binding(&mut self, record: &BindingRecord) {
+        self.bindings
+            .entry(binding_key(record))
+            .or_insert_with(|| BindingHit {
+                description: record.description.clone(),
+                count: 0,
+            })
+            .count += 1;
+    }
+
+    /// Add another set of hits to this one.
+    pub fn merge(&mut self, other: &Self) {
+        for (id, count) in &other.commands {
+            *self.commands.entry(id.clone()).or_default() += count;
+        }
+        for (key, hit) in &other.bindings {
+            self.bindings
+                .entry(key.clone())
+                .or_insert_with(|| BindingHit {
+                    description: hit.description.clone(),
+                    count: 0,
+                })
+                .count += hit.count;
+        }
+    }
+}
@@ -13,9 +13,29 @@ pub enum CursorShape {
 
 /// Cursor position and shape.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Cursor {
     /// Location of the cursor, relative to (0, 0) in the node view rect.
     pub location: geom::Point,
     /// Shape of the cursor.
     pub shape: CursorShape,
+    /// Whether the cursor blinks. Only terminals driving a native cursor honour this.
+    pub blink: bool,
+}
+
+impl Cursor {
+    /// Construct a steady cursor at a location.
+    pub fn new(location: geom::Point, shape: CursorShape) -> Self {
+        Self {
+            location,
+            shape,
+            blink: false,
+        }
+    }
+
+    /// Configure whether the cursor blinks.
+    pub fn with_blink(mut self, blink: bool) -> Self {
+        self.blink = blink;
+        self
+    }
 }
@@ -121,6 +121,8 @@ pub enum ScriptErrorKind {
     CommandExecution,
     /// Another top-level script evaluation is active.
     ScriptBusy,
+    /// A read-only evaluation called an API that mutates the app.
+    ReadOnly,
 }
 
 impl ScriptErrorKind {
@@ -147,6 +149,7 @@ impl ScriptErrorKind {
             Self::TargetTypeMismatch => "target_type_mismatch",
             Self::CommandExecution => "command_exec",
             Self::ScriptBusy => "script_busy",
+            Self::ReadOnly => "read_only",
         }
     }
 }
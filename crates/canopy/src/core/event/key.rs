@@ -2,7 +2,10 @@
 use std::{fmt, ops::Add};
 
 /// Modifier key state.
+///
+/// Build values from the constants below, such as `Ctrl + Shift`.
 #[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
 pub struct Mods {
     /// Shift is active.
     pub shift: bool,
@@ -10,13 +13,19 @@ pub struct Mods {
     pub ctrl: bool,
     /// Alt is active.
     pub alt: bool,
+    /// Super (Command, Windows) is active.
+    pub super_key: bool,
+    /// Hyper is active.
+    pub hyper: bool,
+    /// Meta is active.
+    pub meta: bool,
 }
 
 impl Add<KeyCode> for Mods {
     type Output = Key;
 
     fn add(self, key: KeyCode) -> Self::Output {
-        Key { mods: self, key }
+        Key::new(self, key)
     }
 }
 
@@ -24,10 +33,7 @@ impl Add<char> for Mods {
     type Output = Key;
 
     fn add(self, other: char) -> Self::Output {
-        Key {
-            mods: self,
-            key: other.into(),
-        }
+        Key::new(self, other.into())
     }
 }
 
@@ -39,6 +45,9 @@ impl Add<Self> for Mods {
             shift: self.shift || other.shift,
             ctrl: self.ctrl || other.ctrl,
             alt: self.alt || other.alt,
+            super_key: self.super_key || other.super_key,
+            hyper: self.hyper || other.hyper,
+            meta: self.meta || other.meta,
         }
     }
 }
@@ -49,30 +58,48 @@ pub const Empty: Mods = Mods {
     shift: false,
     ctrl: false,
     alt: false,
+    super_key: false,
+    hyper: false,
+    meta: false,
 };
 
 /// Shift-only modifier state.
 #[allow(non_upper_case_globals)]
 pub const Shift: Mods = Mods {
     shift: true,
-    ctrl: false,
-    alt: false,
+    ..Empty
 };
 
 /// Control-only modifier state.
 #[allow(non_upper_case_globals)]
 pub const Ctrl: Mods = Mods {
-    shift: false,
     ctrl: true,
-    alt: false,
+    ..Empty
 };
 
 /// Alt-only modifier state.
 #[allow(non_upper_case_globals)]
-pub const Alt: Mods = Mods {
-    shift: false,
-    ctrl: false,
-    alt: true,
+pub const Alt: Mods = Mods { alt: true, ..Empty };
+
+/// Super-only modifier state.
+#[allow(non_upper_case_globals)]
+pub const Super: Mods = Mods {
+    super_key: true,
+    ..Empty
+};
+
+/// Hyper-only modifier state.
+#[allow(non_upper_case_globals)]
+pub const Hyper: Mods = Mods {
+    hyper: true,
+    ..Empty
+};
+
+/// Meta-only modifier state.
+#[allow(non_upper_case_globals)]
+pub const Meta: Mods = Mods {
+    meta: true,
+    ..Empty
 };
 
 /// Physical modifier key codes.
@@ -211,21 +238,57 @@ impl From<char> for KeyCode {
 /// Keys that should be preserved verbatim in text input.
 const LEAVE_INTACT: &[KeyCode] = &[KeyCode::Enter, KeyCode::Char(' ')];
 
+/// Whether a key event is a press, an auto-repeat, or a release.
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum KeyKind {
+    /// The key went down.
+    #[default]
+    Press,
+    /// The key is held down and the terminal repeated it.
+    Repeat,
+    /// The key came up.
+    ///
+    /// Only terminals speaking the kitty keyboard protocol report releases, and only when the
+    /// run loop asks for them.
+    Release,
+}
+
 /// A keystroke along with modifiers.
-/// A keystroke along with modifiers.
+///
+/// Build values with [`Key::new`], the `From` conversions, or by adding a key to [`Mods`].
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
 pub struct Key {
     /// Modifier state.
     pub mods: Mods,
     /// Key code.
     pub key: KeyCode,
+    /// Press, repeat, or release.
+    pub kind: KeyKind,
 }
 
 impl Key {
+    /// Construct a key press.
+    pub const fn new(mods: Mods, key: KeyCode) -> Self {
+        Self {
+            mods,
+            key,
+            kind: KeyKind::Press,
+        }
+    }
+
+    /// Return this key with a different event kind.
+    pub const fn with_kind(mut self, kind: KeyKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     /// Normalize key inputs for binding and matching.
     ///
     /// Normalization handles two common sources of divergence across terminals:
     ///
+    /// - **Repeats** become presses, so a held key repeats its binding. Releases stay
+    ///   distinct and only match bindings made for releases.
     /// - **Ctrl-modified ASCII control codes** (0x00–0x1F and 0x7F) are mapped to
     ///   canonical printable equivalents (e.g. 0x01 → `A`, 0x1B → `[`, 0x7F → `?`).
     ///   Some terminals emit control codes without setting the Ctrl modifier, so
@@ -269,6 +332,9 @@ impl Key {
     /// normalization automatically.
     pub fn normalize(&self) -> Self {
         let mut normalized = *self;
+        if normalized.kind == KeyKind::Repeat {
+            normalized.kind = KeyKind::Press;
+        }
         if let KeyCode::Char(c) = normalized.key {
             if let Some(mapped) = ctrl_control_code(c) {
                 normalized.key = KeyCode::Char(mapped);
@@ -297,6 +363,9 @@ impl Key {
     }
 
     /// Parse a key specification such as `ctrl-s`, `PageDown`, or `A`.
+    ///
+    /// A leading `release` part, as in `release-space`, matches the key's release instead of
+    /// its press.
     pub fn parse_spec(spec: &str) -> Result<Self, String> {
         let spec = spec.trim();
         if spec.is_empty() {
@@ -312,6 +381,7 @@ impl Key {
         };
 
         let mut mods = Empty;
+        let mut kind = KeyKind::Press;
         for part in modifier_parts {
             if part.eq_ignore_ascii_case("ctrl") || part.eq_ignore_ascii_case("control") {
                 mods.ctrl = true;
@@ -319,12 +389,25 @@ impl Key {
                 mods.alt = true;
             } else if part.eq_ignore_ascii_case("shift") {
                 mods.shift = true;
+            } else if part.eq_ignore_ascii_case("super")
+                || part.eq_ignore_ascii_case("cmd")
+                || part.eq_ignore_ascii_case("win")
+            {
+                mods.super_key = true;
+            } else if part.eq_ignore_ascii_case("hyper") {
+                mods.hyper = true;
+            } else if part.eq_ignore_ascii_case("meta") {
+                mods.meta = true;
+            } else if part.eq_ignore_ascii_case("release") {
+                kind = KeyKind::Release;
             } else {
                 return Err(format!("unknown key modifier: {part}"));
             }
         }
 
-        Ok((mods + parse_key_code(key_part)?).normalize())
+        Ok((mods + parse_key_code(key_part)?)
+            .with_kind(kind)
+            .normalize())
     }
 }
 
@@ -405,8 +488,8 @@ fn ctrl_alias_char(c: char) -> Option<char> {
 
 impl PartialEq<KeyCode> for Key {
     fn eq(&self, c: &KeyCode) -> bool {
-        // If there are modifiers, we never match.
-        if self.mods != Empty {
+        // If there are modifiers, or the key is being released, we never match.
+        if self.mods != Empty || self.kind == KeyKind::Release {
             return false;
         }
         *c == self.key
@@ -427,19 +510,13 @@ impl PartialEq<Key> for char {
 
 impl From<char> for Key {
     fn from(c: char) -> Self {
-        Self {
-            mods: Empty,
-            key: KeyCode::Char(c),
-        }
+        Self::new(Empty, KeyCode::Char(c))
     }
 }
 
 impl From<KeyCode> for Key {
     fn from(c: KeyCode) -> Self {
-        Self {
-            mods: Empty,
-            key: c,
-        }
+        Self::new(Empty, c)
     }
 }
 
@@ -481,6 +558,9 @@ impl fmt::Display for KeyCode {
 impl fmt::Display for Key {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut parts = Vec::new();
+        if self.kind == KeyKind::Release {
+            parts.push("Release");
+        }
         if self.mods.ctrl {
             parts.push("Ctrl");
         }
@@ -490,6 +570,15 @@ impl fmt::Display for Key {
         if self.mods.shift {
             parts.push("Shift");
         }
+        if self.mods.super_key {
+            parts.push("Super");
+        }
+        if self.mods.hyper {
+            parts.push("Hyper");
+        }
+        if self.mods.meta {
+            parts.push("Meta");
+        }
         if parts.is_empty() {
             write!(f, "{}", self.key)
         } else {
@@ -533,17 +622,21 @@ mod tests {
                 mods: Mods {
                     shift: false,
                     alt: false,
-                    ctrl: false
+                    ctrl: false,
+                    ..Empty
                 },
-                key: KeyCode::Char('c')
+                key: KeyCode::Char('c'),
+                kind: KeyKind::Press,
             },
             Key {
                 mods: Mods {
                     shift: false,
                     alt: false,
-                    ctrl: false
+                    ctrl: false,
+                    ..Empty
                 },
-                key: KeyCode::Char('c')
+                key: KeyCode::Char('c'),
+                kind: KeyKind::Press,
             }
         );
         Ok(())
@@ -557,6 +650,31 @@ mod tests {
         assert_eq!(Key::parse_spec("A"), Ok('A'.into()));
         assert_eq!(Key::parse_spec("Space"), Ok(' '.into()));
         assert!(Key::parse_spec("ctrl-what").is_err());
+        assert_eq!(Key::parse_spec("super-k"), Ok(Super + 'k'));
+        assert_eq!(
+            Key::parse_spec("cmd+hyper+meta-k"),
+            Ok(Super + Hyper + Meta + 'k')
+        );
+        assert_eq!(
+            Key::parse_spec("release-space"),
+            Ok(Key::from(' ').with_kind(KeyKind::Release))
+        );
         Ok(())
     }
+
+    #[test]
+    fn event_kinds_normalize() {
+        let repeat = (Shift + 'a').with_kind(KeyKind::Repeat);
+        assert_eq!(repeat.normalize(), Key::from('A'));
+
+        let release = Key::from(' ').with_kind(KeyKind::Release);
+        assert_eq!(release.normalize(), release);
+        assert_ne!(release.normalize(), Key::from(' '));
+        assert_eq!(release.to_string(), "Release+Space");
+        assert_eq!((Ctrl + Super + 'x').to_string(), "Ctrl+Super+x");
+        assert_eq!(
+            Key::parse_spec(&release.to_string()),
+            Ok(release.normalize())
+        );
+    }
 }
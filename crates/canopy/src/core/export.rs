@@ -0,0 +1,507 @@
+//! Screen export to SVG, HTML and ANSI text.
+//!
+//! Exporters turn a rendered [`TermBuf`] into a standalone document for bug reports and
+//! documentation. SVG output lays cells out on a fixed grid so it looks the same in any
+//! viewer, HTML output is a `<pre>` block with inline styles, and ANSI output is plain text
+//! with 24-bit SGR escapes that replays the screen in a terminal.
+//!
+//! Every format groups each row into runs of cells that share a style and hyperlink, and
+//! resolves named colors to RGB so output does not depend on the viewer's palette.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use crate::{
+    core::{termbuf::TermBuf, text::xml_escape},
+    error::{Error, Result},
+    geom::Point,
+    render::hyperlink_sequence,
+    style::{Color, ResolvedStyle, UnderlineStyle},
+};
+
+/// Font size of exported SVG and HTML, in pixels.
+const FONT_SIZE: f64 = 14.0;
+
+/// Width of one cell as a fraction of the font size.
+const CELL_WIDTH: f64 = 0.6;
+
+/// Height of one row as a fraction of the font size.
+const LINE_HEIGHT: f64 = 1.2;
+
+/// Baseline offset from the top of a row as a fraction of the font size.
+const BASELINE: f64 = 0.9;
+
+/// Monospace font stack used by SVG and HTML output.
+const FONT_FAMILY: &str = "ui-monospace, 'DejaVu Sans Mono', Menlo, Consolas, monospace";
+
+/// An export output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportFormat {
+    /// A standalone SVG image.
+    Svg,
+    /// A standalone HTML document with inline styles.
+    Html,
+    /// Text with ANSI SGR escape sequences.
+    Ansi,
+}
+
+impl ExportFormat {
+    /// Every format, in documentation order.
+    pub const ALL: [Self; 3] = [Self::Svg, Self::Html, Self::Ansi];
+
+    /// Return the stable lowercase name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Html => "html",
+            Self::Ansi => "ansi",
+        }
+    }
+
+    /// Return the conventional file extension for the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Html => "html",
+            Self::Ansi => "ans",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    /// Parse a format name, ignoring case.
+    fn from_str(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                Error::Invalid(format!(
+                    "unknown export format {name:?}, expected svg, html or ansi"
+                ))
+            })
+    }
+}
+
+/// Export a buffer in the given format.
+pub fn export(buf: &TermBuf, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Svg => svg(buf),
+        ExportFormat::Html => html(buf),
+        ExportFormat::Ansi => ansi(buf),
+    }
+}
+
+/// Export a buffer as a standalone SVG image.
+pub fn svg(buf: &TermBuf) -> String {
+    let size = buf.size();
+    let cell_width = FONT_SIZE * CELL_WIDTH;
+    let line_height = FONT_SIZE * LINE_HEIGHT;
+    let width = number(f64::from(size.w) * cell_width);
+    let height = number(f64::from(size.h) * line_height);
+    let rows: Vec<Vec<Run>> = (0..size.h).map(|y| runs(buf, y)).collect();
+
+    let mut out = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-size=\"{}\">\n",
+        number(FONT_SIZE)
+    );
+    out.push_str(&format!(
+        "<style>text {{ font-family: {FONT_FAMILY}; white-space: pre; }}</style>\n"
+    ));
+    out.push_str("<g shape-rendering=\"crispEdges\">\n");
+    for (y, row) in rows.iter().enumerate() {
+        let top = number(y as f64 * line_height);
+        let mut backgrounds: Vec<(u32, u32, Color)> = Vec::new();
+        for run in row {
+            match backgrounds.last_mut() {
+                Some((_, width, color)) if color.rgb() == run.style.bg.rgb() => *width += run.width,
+                _ => backgrounds.push((run.x, run.width, run.style.bg)),
+            }
+        }
+        for (x, cells, color) in backgrounds {
+            out.push_str(&format!(
+                "<rect x=\"{}\" y=\"{top}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                number(f64::from(x) * cell_width),
+                number(f64::from(cells) * cell_width),
+                number(line_height),
+                color.hex()
+            ));
+        }
+    }
+    out.push_str("</g>\n");
+    for (y, row) in rows.iter().enumerate() {
+        let baseline = number(y as f64 * line_height + FONT_SIZE * BASELINE);
+        for run in row.iter().filter(|run| !run.text.trim().is_empty()) {
+            let mut attrs = format!(
+                "x=\"{}\" y=\"{baseline}\" textLength=\"{}\" lengthAdjust=\"spacingAndGlyphs\" \
+                 fill=\"{}\"",
+                number(f64::from(run.x) * cell_width),
+                number(f64::from(run.width) * cell_width),
+                foreground(&run.style).hex()
+            );
+            let css = text_css(&run.style);
+            if !css.is_empty() {
+                attrs.push_str(&format!(" style=\"{}\"", xml_escape(&css)));
+            }
+            let text = format!(
+                "<text {attrs} xml:space=\"preserve\">{}</text>",
+                xml_escape(&run.text)
+            );
+            match &run.link {
+                Some(link) => {
+                    out.push_str(&format!("<a href=\"{}\">{text}</a>\n", xml_escape(link)))
+                }
+                None => {
+                    out.push_str(&text);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Export a buffer as a standalone HTML document with inline styles.
+pub fn html(buf: &TermBuf) -> String {
+    let size = buf.size();
+    let background = buf
+        .get(Point::zero())
+        .map_or_else(|| String::from("#000000"), |cell| cell.style.bg.hex());
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>canopy screen</title>\n</head>\n");
+    out.push_str(&format!(
+        "<body style=\"margin: 0; background-color: {background}\">\n"
+    ));
+    out.push_str(&format!(
+        "<pre style=\"margin: 0; font-family: {}; font-size: {}px; line-height: {}\">",
+        xml_escape(FONT_FAMILY),
+        number(FONT_SIZE),
+        number(LINE_HEIGHT)
+    ));
+    for y in 0..size.h {
+        if y > 0 {
+            out.push('\n');
+        }
+        for run in runs(buf, y) {
+            let mut css = format!(
+                "color: {}; background-color: {}",
+                foreground(&run.style).hex(),
+                run.style.bg.hex()
+            );
+            let text_css = text_css(&run.style);
+            if !text_css.is_empty() {
+                css.push_str("; ");
+                css.push_str(&text_css);
+            }
+            let span = format!(
+                "<span style=\"{}\">{}</span>",
+                xml_escape(&css),
+                xml_escape(&run.text)
+            );
+            match &run.link {
+                Some(link) => out.push_str(&format!("<a href=\"{}\">{span}</a>", xml_escape(link))),
+                None => out.push_str(&span),
+            }
+        }
+    }
+    out.push_str("</pre>\n</body>\n</html>\n");
+    out
+}
+
+/// Export a buffer as text with 24-bit ANSI SGR escapes, resetting attributes at each row end.
+pub fn ansi(buf: &TermBuf) -> String {
+    let mut out = String::new();
+    for y in 0..buf.size().h {
+        for run in runs(buf, y) {
+            out.push_str(&sgr(&run.style));
+            match &run.link {
+                Some(link) => {
+                    out.push_str(&hyperlink_sequence(Some(link)));
+                    out.push_str(&run.text);
+                    out.push_str(&hyperlink_sequence(None));
+                }
+                None => out.push_str(&run.text),
+            }
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// A horizontal run of cells that share a style and hyperlink.
+#[derive(Debug, Clone, PartialEq)]
+struct Run {
+    /// First column of the run.
+    x: u32,
+    /// Width of the run in cells, including wide glyph continuations.
+    width: u32,
+    /// Rendered text of the run.
+    text: String,
+    /// Style shared by every cell in the run.
+    style: ResolvedStyle,
+    /// Hyperlink target shared by every cell in the run.
+    link: Option<Arc<str>>,
+}
+
+/// Split one buffer row into runs of cells that share a style and hyperlink.
+fn runs(buf: &TermBuf, y: u32) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+    for x in 0..buf.size().w {
+        let cell = buf
+            .get(Point { x, y })
+            .expect("buffer coordinates should always be valid");
+        match runs.last_mut() {
+            Some(run) if cell.continuation => run.width += 1,
+            Some(run) if run.style == cell.style && run.link == cell.link => {
+                run.width += 1;
+                run.text.push_str(&cell.rendered_text());
+            }
+            _ => runs.push(Run {
+                x,
+                width: 1,
+                text: cell.rendered_text(),
+                style: cell.style,
+                link: cell.link.clone(),
+            }),
+        }
+    }
+    runs
+}
+
+/// Return the text color of a style, blended halfway to the background when dim.
+///
+/// SVG and HTML have no dim attribute, so they draw dim text in this color. ANSI output
+/// sends SGR 2 with the style's own color instead.
+fn foreground(style: &ResolvedStyle) -> Color {
+    if style.attrs.dim {
+        style.fg.blend(style.bg, 0.5)
+    } else {
+        style.fg
+    }
+}
+
+/// Return CSS declarations for the weight, slant and decorations of a style.
+fn text_css(style: &ResolvedStyle) -> String {
+    let attrs = style.attrs;
+    let mut css = Vec::new();
+    if attrs.bold {
+        css.push(String::from("font-weight: bold"));
+    }
+    if attrs.italic {
+        css.push(String::from("font-style: italic"));
+    }
+    let lines: Vec<&str> = [
+        (attrs.underline, "underline"),
+        (attrs.crossedout, "line-through"),
+        (attrs.overline, "overline"),
+    ]
+    .into_iter()
+    .filter_map(|(set, line)| set.then_some(line))
+    .collect();
+    if !lines.is_empty() {
+        let mut decoration = format!("text-decoration: {}", lines.join(" "));
+        if attrs.underline {
+            let line_style = match attrs.underline_style {
+                UnderlineStyle::Single => None,
+                UnderlineStyle::Double => Some("double"),
+                UnderlineStyle::Curly => Some("wavy"),
+                UnderlineStyle::Dotted => Some("dotted"),
+                UnderlineStyle::Dashed => Some("dashed"),
+            };
+            if let Some(line_style) = line_style {
+                decoration.push(' ');
+                decoration.push_str(line_style);
+            }
+            if let Some(color) = style.underline {
+                decoration.push(' ');
+                decoration.push_str(&color.hex());
+            }
+        }
+        css.push(decoration);
+    }
+    css.join("; ")
+}
+
+/// Build the SGR sequences that select a style from a reset state.
+///
+/// Underline styles and colors use the colon-separated extended forms after a plain SGR 4,
+/// matching what the terminal backend emits.
+fn sgr(style: &ResolvedStyle) -> String {
+    let (fr, fg, fb) = style.fg.rgb();
+    let (br, bg, bb) = style.bg.rgb();
+    let mut codes = vec![format!("0;38;2;{fr};{fg};{fb};48;2;{br};{bg};{bb}")];
+    let attrs = style.attrs;
+    for (set, code) in [
+        (attrs.bold, "1"),
+        (attrs.dim, "2"),
+        (attrs.italic, "3"),
+        (attrs.underline, "4"),
+        (attrs.crossedout, "9"),
+        (attrs.overline, "53"),
+    ] {
+        if set {
+            codes.push(String::from(code));
+        }
+    }
+    let mut seq = format!("\x1b[{}m", codes.join(";"));
+    if attrs.underline {
+        let variant = match attrs.underline_style {
+            UnderlineStyle::Single => None,
+            UnderlineStyle::Double => Some(2),
+            UnderlineStyle::Curly => Some(3),
+            UnderlineStyle::Dotted => Some(4),
+            UnderlineStyle::Dashed => Some(5),
+        };
+        if let Some(variant) = variant {
+            seq.push_str(&format!("\x1b[4:{variant}m"));
+        }
+        if let Some(color) = style.underline {
+            let (r, g, b) = color.rgb();
+            seq.push_str(&format!("\x1b[58:2::{r}:{g}:{b}m"));
+        }
+    }
+    seq
+}
+
+/// Format a coordinate with at most two decimals and no trailing zeros.
+fn number(value: f64) -> String {
+    let text = format!("{value:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{geom::Line, style::AttrSet};
+
+    /// Build a buffer with a bold red word, a wide glyph and markup characters.
+    fn buffer() -> TermBuf {
+        let plain = ResolvedStyle::new(Color::White, Color::Black, AttrSet::default());
+        let bold = ResolvedStyle::new(
+            Color::Rgb { r: 255, g: 0, b: 0 },
+            Color::Black,
+            AttrSet {
+                bold: true,
+                ..AttrSet::default()
+            },
+        );
+        let mut buf = TermBuf::new((8, 2), ' ', plain).unwrap();
+        buf.text(&bold, Line::new(0, 0, 3), "Hi!").unwrap();
+        buf.text(&plain, Line::new(3, 0, 5), " 界<&").unwrap();
+        buf.text(&plain, Line::new(0, 1, 8), "\"a\" b").unwrap();
+        buf
+    }
+
+    #[test]
+    fn formats_parse_by_name() {
+        for format in ExportFormat::ALL {
+            assert_eq!(format.name().parse::<ExportFormat>().unwrap(), format);
+        }
+        assert_eq!("SVG".parse::<ExportFormat>().unwrap(), ExportFormat::Svg);
+        assert!("png".parse::<ExportFormat>().is_err());
+    }
+
+    #[test]
+    fn rows_split_into_style_runs_with_wide_glyphs() {
+        let buf = buffer();
+        let row = runs(&buf, 0);
+        assert_eq!(row.len(), 2);
+        assert_eq!(
+            (row[0].x, row[0].width, row[0].text.as_str()),
+            (0, 3, "Hi!")
+        );
+        assert_eq!(
+            (row[1].x, row[1].width, row[1].text.as_str()),
+            (3, 5, " 界<&")
+        );
+    }
+
+    #[test]
+    fn ansi_output_selects_truecolor_styles() {
+        let out = ansi(&buffer());
+        assert!(out.starts_with("\x1b[0;38;2;255;0;0;48;2;0;0;0;1mHi!\x1b[0;38;2;"));
+        assert!(out.ends_with("\"a\" b   \x1b[0m\n"));
+        assert_eq!(out.matches("\x1b[0m\n").count(), 2);
+    }
+
+    #[test]
+    fn ansi_output_dims_once_and_sanitizes_links() {
+        let dim = ResolvedStyle::new(
+            Color::White,
+            Color::Black,
+            AttrSet {
+                dim: true,
+                ..AttrSet::default()
+            },
+        );
+        assert!(sgr(&dim).starts_with("\x1b[0;38;2;255;255;255;48;2;0;0;0;2m"));
+        assert_eq!(foreground(&dim), Color::White.blend(Color::Black, 0.5));
+
+        let plain = ResolvedStyle::new(Color::White, Color::Black, AttrSet::default());
+        let mut buf = TermBuf::new((2, 1), ' ', plain).unwrap();
+        buf.text(&plain, Line::new(0, 0, 2), "go").unwrap();
+        buf.set_link(Line::new(0, 0, 2), Some("https://a\x1b\\b"));
+        assert!(ansi(&buf).contains("\x1b]8;;https://a\\b\x1b\\go\x1b]8;;\x1b\\"));
+    }
+
+    #[test]
+    fn html_output_escapes_text_and_inlines_styles() {
+        let out = html(&buffer());
+        assert!(out.starts_with("<!DOCTYPE html>"));
+        assert!(out.contains(
+            "<span style=\"color: #ff0000; background-color: #000000; font-weight: bold\">Hi!</span>"
+        ));
+        assert!(out.contains(" 界&lt;&amp;</span>"));
+        assert!(out.contains("&quot;a&quot; b   </span></pre>"));
+    }
+
+    #[test]
+    fn svg_output_places_text_on_the_cell_grid() {
+        let out = svg(&buffer());
+        assert!(out.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"67.2\" height=\"33.6\""
+        ));
+        assert!(
+            out.contains("<rect x=\"0\" y=\"0\" width=\"67.2\" height=\"16.8\" fill=\"#000000\"/>")
+        );
+        assert!(out.contains(
+            "x=\"0\" y=\"12.6\" textLength=\"25.2\" lengthAdjust=\"spacingAndGlyphs\" \
+             fill=\"#ff0000\" style=\"font-weight: bold\" xml:space=\"preserve\">Hi!</text>"
+        ));
+        assert!(out.contains("x=\"25.2\" y=\"12.6\" textLength=\"42\""));
+        assert!(out.contains(">&quot;a&quot; b   </text>"));
+        assert!(out.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn decorations_map_to_css() {
+        let style = ResolvedStyle {
+            fg: Color::White,
+            bg: Color::Black,
+            attrs: AttrSet {
+                italic: true,
+                underline: true,
+                underline_style: UnderlineStyle::Curly,
+                crossedout: true,
+                ..AttrSet::default()
+            },
+            underline: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+        };
+        assert_eq!(
+            text_css(&style),
+            "font-style: italic; text-decoration: underline line-through wavy #010203"
+        );
+        assert_eq!(
+            sgr(&style),
+            "\x1b[0;38;2;255;255;255;48;2;0;0;0;3;4;9m\x1b[4:3m\x1b[58:2::1:2:3m"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Handing the terminal to external programs.
+//!
+//! [`Context::run_external`](crate::Context::run_external) stops the terminal backend, runs an
+//! [`External`] program in the foreground, and restores the backend with a full repaint when
+//! the program exits. [`Context::suspend`](crate::Context::suspend) does the same around a
+//! `SIGTSTP`, so the application stops like any other shell job.
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set.
+const FALLBACK_EDITOR: &str = "vi";
+
+/// A program to run while it owns the terminal.
+#[derive(Debug)]
+pub struct External {
+    /// Command to run with inherited standard streams.
+    command: Command,
+    /// File read back after the command exits.
+    read_back: Option<PathBuf>,
+}
+
+impl External {
+    /// Run a program with no arguments.
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Command::new(program).into()
+    }
+
+    /// Open a file in the user's editor and read it back afterwards.
+    ///
+    /// The editor is `$VISUAL`, then `$EDITOR`, then `vi`. The variable may include arguments,
+    /// such as `code --wait`.
+    pub fn editor(path: impl AsRef<Path>) -> Self {
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .ok()
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or_else(|| FALLBACK_EDITOR.into());
+        let mut parts = editor.split_whitespace();
+        let mut command = Command::new(parts.next().unwrap_or(FALLBACK_EDITOR));
+        command.args(parts).arg(path.as_ref());
+        Self {
+            command,
+            read_back: Some(path.as_ref().to_path_buf()),
+        }
+    }
+
+    /// Append an argument.
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.command.arg(arg);
+        self
+    }
+
+    /// Read a file back after the program exits, typically a temporary file it was asked to edit.
+    pub fn read_back(mut self, path: impl AsRef<Path>) -> Self {
+        self.read_back = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Run the program to completion and collect its output.
+    pub(crate) fn run(mut self) -> io::Result<ExternalOutput> {
+        let status = self.command.status()?;
+        let contents = match self.read_back {
+            Some(path) => Some(fs::read_to_string(path)?),
+            None => None,
+        };
+        Ok(ExternalOutput { status, contents })
+    }
+}
+
+impl From<Command> for External {
+    fn from(command: Command) -> Self {
+        Self {
+            command,
+            read_back: None,
+        }
+    }
+}
+
+/// Outcome of a program run through [`Context::run_external`](crate::Context::run_external).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalOutput {
+    /// Exit status of the program.
+    pub status: ExitStatus,
+    /// Contents of the read-back file, if one was requested.
+    pub contents: Option<String>,
+}
+
+/// Stop the process with `SIGTSTP`, returning once it is continued.
+#[cfg(unix)]
+pub(crate) fn stop_process() -> io::Result<()> {
+    signal_hook::low_level::raise(signal_hook::consts::SIGTSTP)
+}
+
+/// Job control is not available on this platform.
+#[cfg(not(unix))]
+pub(crate) fn stop_process() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "suspending requires job control",
+    ))
+}
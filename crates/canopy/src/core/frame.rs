@@ -0,0 +1,265 @@
+//! Frame pacing and per-frame render statistics.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::event::Event;
+
+/// Number of recent frames retained for rolling statistics.
+const FRAME_HISTORY: usize = 120;
+
+/// Frame pacing used by run loops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FramePolicy {
+    /// Maximum frames per second for renders not caused by user input, or `None` for no limit.
+    pub max_fps: Option<u32>,
+    /// Wrap each frame in a DEC mode 2026 synchronized update when the terminal supports it.
+    ///
+    /// The crossterm run loop asks the terminal with a DECRQM query at startup and leaves
+    /// frames unwrapped when the mode is not reported.
+    pub synchronized_output: bool,
+}
+
+impl Default for FramePolicy {
+    fn default() -> Self {
+        Self {
+            max_fps: Some(60),
+            synchronized_output: true,
+        }
+    }
+}
+
+impl FramePolicy {
+    /// Configure the maximum frame rate. `None` or zero disables the limit.
+    pub fn with_max_fps(mut self, max_fps: Option<u32>) -> Self {
+        self.max_fps = max_fps.filter(|fps| *fps > 0);
+        self
+    }
+
+    /// Configure synchronized-update wrapping.
+    pub fn with_synchronized_output(mut self, enabled: bool) -> Self {
+        self.synchronized_output = enabled;
+        self
+    }
+
+    /// Return the minimum time between rate-limited frames.
+    pub fn min_interval(&self) -> Duration {
+        self.max_fps
+            .filter(|fps| *fps > 0)
+            .map_or(Duration::ZERO, |fps| Duration::from_secs(1) / fps)
+    }
+}
+
+/// Timing breakdown for one rendered frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameTiming {
+    /// Time spent in layout and pre-render hooks.
+    pub layout: Duration,
+    /// Time spent drawing widgets into the offscreen buffer.
+    pub draw: Duration,
+    /// Time spent diffing and writing backend output.
+    pub emit: Duration,
+    /// Events handled since the previous frame.
+    pub events: usize,
+}
+
+impl FrameTiming {
+    /// Return the total render time for the frame.
+    pub fn total(&self) -> Duration {
+        self.layout + self.draw + self.emit
+    }
+}
+
+/// Summary of recent render performance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Frames rendered since startup.
+    pub frames: u64,
+    /// Timing of the most recent frame.
+    pub last: FrameTiming,
+    /// Mean total render time over recent frames.
+    pub average: Duration,
+    /// Slowest total render time over recent frames.
+    pub worst: Duration,
+    /// Frames per second over recent frames.
+    pub fps: f64,
+}
+
+/// Rolling record of recent frame timings.
+#[derive(Debug, Default)]
+pub(crate) struct FrameHistory {
+    /// Frames rendered since startup.
+    frames: u64,
+    /// Recent frames with their start times, oldest first.
+    recent: VecDeque<(Instant, FrameTiming)>,
+}
+
+impl FrameHistory {
+    /// Record one rendered frame.
+    pub(crate) fn record(&mut self, started: Instant, timing: FrameTiming) {
+        self.frames += 1;
+        if self.recent.len() == FRAME_HISTORY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back((started, timing));
+    }
+
+    /// Summarize the recorded frames.
+    pub(crate) fn stats(&self) -> FrameStats {
+        let Some((last_start, last)) = self.recent.back().copied() else {
+            return FrameStats::default();
+        };
+        let totals = self.recent.iter().map(|(_, timing)| timing.total());
+        let sum: Duration = totals.clone().sum();
+        let count = u32::try_from(self.recent.len()).unwrap_or(u32::MAX);
+        let span = self
+            .recent
+            .front()
+            .map_or(Duration::ZERO, |(first, _)| last_start - *first);
+        let fps = if span.is_zero() {
+            0.0
+        } else {
+            f64::from(count - 1) / span.as_secs_f64()
+        };
+        FrameStats {
+            frames: self.frames,
+            last,
+            average: sum / count,
+            worst: totals.max().unwrap_or_default(),
+            fps,
+        }
+    }
+}
+
+/// Decide when a run loop should render, coalescing bursts of events into frames.
+///
+/// Input events render as soon as the ready events have been drained, so typing
+/// stays responsive. Other redraws are limited to the policy's frame rate.
+#[derive(Debug)]
+pub(crate) struct FrameScheduler {
+    /// Minimum time between rate-limited frames.
+    interval: Duration,
+    /// Start of the most recent frame.
+    last_frame: Option<Instant>,
+    /// Whether user input is waiting to be shown.
+    input_pending: bool,
+    /// Deadline of the deferred frame a wake has been scheduled for.
+    armed: Option<Instant>,
+}
+
+impl FrameScheduler {
+    /// Construct a scheduler for a frame policy.
+    pub(crate) fn new(policy: FramePolicy) -> Self {
+        Self {
+            interval: policy.min_interval(),
+            last_frame: None,
+            input_pending: false,
+            armed: None,
+        }
+    }
+
+    /// Note an event handled before the next frame.
+    ///
+    /// A wake may be the scheduled one arriving, so the next deferral arms a fresh wake.
+    pub(crate) fn note_event(&mut self, event: &Event) {
+        if matches!(event, Event::Wake) {
+            self.armed = None;
+        }
+        if matches!(
+            event,
+            Event::Key(_) | Event::Mouse(_) | Event::Paste(_) | Event::Resize(_)
+        ) {
+            self.input_pending = true;
+        }
+    }
+
+    /// Return how long to wait before rendering a pending frame, or `None` to render now.
+    pub(crate) fn delay(&self, now: Instant) -> Option<Duration> {
+        if self.input_pending {
+            return None;
+        }
+        let last = self.last_frame?;
+        let due = last.checked_add(self.interval)?;
+        let wait = due.saturating_duration_since(now);
+        (!wait.is_zero()).then_some(wait)
+    }
+
+    /// Claim the wake for the deferred frame, returning false when one is already scheduled.
+    ///
+    /// Events that arrive while a frame is deferred do not move its deadline, so one wake
+    /// per deadline is enough.
+    pub(crate) fn arm(&mut self) -> bool {
+        let due = self
+            .last_frame
+            .and_then(|last| last.checked_add(self.interval));
+        if due.is_none() || self.armed == due {
+            return false;
+        }
+        self.armed = due;
+        true
+    }
+
+    /// Record that a frame started rendering.
+    pub(crate) fn rendered(&mut self, at: Instant) {
+        self.last_frame = Some(at);
+        self.input_pending = false;
+        self.armed = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::key;
+
+    #[test]
+    fn scheduler_limits_background_frames_but_not_input() {
+        let policy = FramePolicy::default().with_max_fps(Some(10));
+        let mut frames = FrameScheduler::new(policy);
+        let start = Instant::now();
+        assert_eq!(frames.delay(start), None);
+
+        frames.rendered(start);
+        frames.note_event(&Event::Wake);
+        assert_eq!(
+            frames.delay(start + Duration::from_millis(40)),
+            Some(Duration::from_millis(60))
+        );
+        assert!(frames.arm());
+        assert!(!frames.arm());
+        assert_eq!(frames.delay(start + Duration::from_millis(100)), None);
+
+        frames.note_event(&Event::Key(key::KeyCode::Enter.into()));
+        assert_eq!(frames.delay(start + Duration::from_millis(1)), None);
+
+        let unlimited = FrameScheduler {
+            last_frame: Some(start),
+            ..FrameScheduler::new(policy.with_max_fps(None))
+        };
+        assert_eq!(unlimited.delay(start), None);
+    }
+
+    #[test]
+    fn history_summarizes_recent_frames() {
+        let start = Instant::now();
+        let mut history = FrameHistory::default();
+        assert_eq!(history.stats(), FrameStats::default());
+
+        for (i, ms) in [2, 4, 6].into_iter().enumerate() {
+            let timing = FrameTiming {
+                draw: Duration::from_millis(ms),
+                events: i,
+                ..FrameTiming::default()
+            };
+            history.record(start + Duration::from_millis(50 * i as u64), timing);
+        }
+        let stats = history.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.last.events, 2);
+        assert_eq!(stats.average, Duration::from_millis(4));
+        assert_eq!(stats.worst, Duration::from_millis(6));
+        assert!((stats.fps - 20.0).abs() < 1e-9);
+    }
+}
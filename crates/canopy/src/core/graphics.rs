@@ -0,0 +1,556 @@
+//! Pixel images drawn with terminal graphics protocols.
+//!
+//! Widgets draw a text fallback into a rectangle of cells and then place a [`Pixmap`] over the
+//! same cells with [`Render::image`](crate::render::Render::image). Backends that speak the
+//! kitty graphics protocol or sixel draw the image and leave the covered cells alone; other
+//! backends show the fallback.
+
+use std::{
+    collections::BTreeSet,
+    env,
+    fmt::{self, Write as _},
+    iter, str,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+};
+
+use crate::{
+    error::{Error, Result},
+    geom::{Rect, Size},
+};
+
+/// Source of process-unique image identifiers. Kitty reserves zero.
+static NEXT_IMAGE_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Largest base64 payload the kitty protocol accepts in one escape sequence.
+const KITTY_CHUNK: usize = 4096;
+
+/// Intensity levels of the sixel color cube, per channel.
+const SIXEL_LEVELS: u32 = 6;
+
+/// Cell size in pixels assumed when the terminal does not report one.
+pub const DEFAULT_CELL_PIXELS: Size = Size { w: 8, h: 16 };
+
+/// An RGBA image that can be placed over terminal cells.
+pub struct Pixmap {
+    /// Process-unique identifier, used as the kitty image id.
+    id: u32,
+    /// Width in pixels.
+    width: u32,
+    /// Height in pixels.
+    height: u32,
+    /// Row-major RGBA bytes.
+    rgba: Vec<u8>,
+}
+
+impl Pixmap {
+    /// Construct a pixmap from row-major RGBA bytes.
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Result<Self> {
+        let expected = u64::from(width) * u64::from(height) * 4;
+        if rgba.len() as u64 != expected {
+            return Err(Error::Invalid(format!(
+                "pixmap of {width}x{height} needs {expected} bytes, got {}",
+                rgba.len()
+            )));
+        }
+        Ok(Self {
+            id: NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed),
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    /// Return the process-unique identifier of this pixmap.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Return the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Return the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Return the pixmap bounds as a rectangle.
+    pub fn rect(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
+    }
+
+    /// Return the row-major RGBA bytes.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Return one pixel, or transparent black outside the pixmap.
+    fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        if x >= self.width || y >= self.height {
+            return [0; 4];
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.rgba[i],
+            self.rgba[i + 1],
+            self.rgba[i + 2],
+            self.rgba[i + 3],
+        ]
+    }
+}
+
+impl fmt::Debug for Pixmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pixmap")
+            .field("id", &self.id)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A pixmap region shown over a rectangle of screen cells.
+#[derive(Debug, Clone)]
+pub struct ImagePlacement {
+    /// Image being shown.
+    pub image: Arc<Pixmap>,
+    /// Pixel rectangle of the image to show.
+    pub source: Rect,
+    /// Screen cells covered, scaled to fit.
+    pub dest: Rect,
+}
+
+impl PartialEq for ImagePlacement {
+    fn eq(&self, other: &Self) -> bool {
+        self.image.id == other.image.id && self.source == other.source && self.dest == other.dest
+    }
+}
+
+impl Eq for ImagePlacement {}
+
+/// A terminal graphics protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// The kitty graphics protocol, also spoken by WezTerm, Ghostty and Konsole.
+    Kitty,
+    /// DEC sixel graphics.
+    Sixel,
+}
+
+/// Graphics protocols a terminal reported in answer to capability queries.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphicsSupport {
+    /// The terminal answered a kitty graphics query.
+    pub kitty: bool,
+    /// The terminal listed sixel in its primary device attributes.
+    pub sixel: bool,
+}
+
+impl ImageProtocol {
+    /// Choose the graphics protocol of the current terminal.
+    ///
+    /// `support` holds the terminal's answers to capability queries, or `None` when it did
+    /// not answer, in which case the protocol is guessed from the environment.
+    pub fn detect(support: Option<GraphicsSupport>) -> Option<Self> {
+        Self::detect_with(|name| env::var(name).ok(), support)
+    }
+
+    /// Choose a graphics protocol from query answers and environment variables supplied by a
+    /// lookup function.
+    ///
+    /// `CANOPY_GRAPHICS` set to `kitty`, `sixel` or `none` overrides detection. Graphics are
+    /// off inside tmux and screen, which do not pass the protocols through by default.
+    pub fn detect_with(
+        var: impl Fn(&str) -> Option<String>,
+        support: Option<GraphicsSupport>,
+    ) -> Option<Self> {
+        if let Some(forced) = var("CANOPY_GRAPHICS") {
+            return match forced.to_ascii_lowercase().as_str() {
+                "kitty" => Some(Self::Kitty),
+                "sixel" => Some(Self::Sixel),
+                _ => None,
+            };
+        }
+        let term = var("TERM").unwrap_or_default().to_ascii_lowercase();
+        let program = var("TERM_PROGRAM").unwrap_or_default().to_ascii_lowercase();
+        if var("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+            return None;
+        }
+        if let Some(support) = support {
+            return if support.kitty {
+                Some(Self::Kitty)
+            } else if support.sixel {
+                Some(Self::Sixel)
+            } else {
+                None
+            };
+        }
+        if var("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "wezterm" | "ghostty")
+        {
+            return Some(Self::Kitty);
+        }
+        if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term.starts_with("contour")
+        {
+            return Some(Self::Sixel);
+        }
+        None
+    }
+}
+
+/// Encode bytes as padded standard base64.
+fn base64(bytes: &[u8]) -> String {
+    /// Standard base64 alphabet.
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Build the kitty escape sequences that upload a pixmap without displaying it.
+pub(crate) fn kitty_transmit(image: &Pixmap) -> String {
+    let payload = base64(image.rgba());
+    let mut out = String::new();
+    let chunks: Vec<&str> = if payload.is_empty() {
+        vec![""]
+    } else {
+        payload
+            .as_bytes()
+            .chunks(KITTY_CHUNK)
+            .map(|chunk| str::from_utf8(chunk).unwrap_or_default())
+            .collect()
+    };
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            let _ok = write!(
+                out,
+                "\x1b_Ga=t,f=32,s={},v={},i={},q=2,m={more};{chunk}\x1b\\",
+                image.width, image.height, image.id
+            );
+        } else {
+            let _ok = write!(out, "\x1b_Gm={more};{chunk}\x1b\\");
+        }
+    }
+    out
+}
+
+/// Build the kitty escape sequence that shows an uploaded pixmap at the cursor.
+///
+/// Each image has a single placement, so placing it again moves it.
+pub(crate) fn kitty_place(placement: &ImagePlacement) -> String {
+    let ImagePlacement {
+        image,
+        source,
+        dest,
+    } = placement;
+    format!(
+        "\x1b_Ga=p,i={},p=1,x={},y={},w={},h={},c={},r={},C=1,q=2\x1b\\",
+        image.id, source.tl.x, source.tl.y, source.w, source.h, dest.w, dest.h
+    )
+}
+
+/// Build the kitty escape sequence that removes the placement of an image but keeps its data.
+pub(crate) fn kitty_delete(image: &Pixmap) -> String {
+    format!("\x1b_Ga=d,d=i,i={},q=2\x1b\\", image.id)
+}
+
+/// Build the kitty escape sequence that frees the uploaded data of an image id.
+pub(crate) fn kitty_free(id: u32) -> String {
+    format!("\x1b_Ga=d,d=I,i={id},q=2\x1b\\")
+}
+
+/// Build the kitty escape sequence that removes every visible placement and frees its data.
+pub(crate) fn kitty_delete_all() -> &'static str {
+    "\x1b_Ga=d,d=A,q=2\x1b\\"
+}
+
+/// Clip a placement to the screen rows above `rows`, shrinking its source to match.
+///
+/// Returns `None` when no row of the placement is left.
+pub(crate) fn clip_rows(placement: &ImagePlacement, rows: u32) -> Option<ImagePlacement> {
+    let ImagePlacement {
+        image,
+        source,
+        dest,
+    } = placement;
+    let keep = rows.saturating_sub(dest.tl.y).min(dest.h);
+    if keep == 0 {
+        return None;
+    }
+    let source_h = (u64::from(source.h) * u64::from(keep) / u64::from(dest.h)).max(1) as u32;
+    Some(ImagePlacement {
+        image: Arc::clone(image),
+        source: Rect::new(source.tl.x, source.tl.y, source.w, source_h),
+        dest: Rect::new(dest.tl.x, dest.tl.y, dest.w, keep),
+    })
+}
+
+/// Map a channel value to its nearest sixel color cube level.
+fn sixel_level(value: u8) -> u32 {
+    (u32::from(value) * (SIXEL_LEVELS - 1) + 127) / 255
+}
+
+/// Return the sixel palette register of a pixel, or `None` for a transparent pixel.
+fn sixel_register(pixel: [u8; 4]) -> Option<u32> {
+    if pixel[3] < 128 {
+        return None;
+    }
+    let [r, g, b, _] = pixel.map(sixel_level);
+    Some((r * SIXEL_LEVELS + g) * SIXEL_LEVELS + b)
+}
+
+/// Append a run of identical sixel characters, compressing long runs.
+fn push_sixel_run(out: &mut String, ch: char, len: usize) {
+    match len {
+        0 => {}
+        1..=3 => out.extend(iter::repeat_n(ch, len)),
+        _ => {
+            let _ok = write!(out, "!{len}{ch}");
+        }
+    }
+}
+
+/// Build a sixel image of a placement, scaled to its cells at the given cell size.
+///
+/// Colors are quantized to a 216 color cube and transparent pixels leave the cells beneath
+/// them untouched.
+pub(crate) fn sixel(placement: &ImagePlacement, cell: Size) -> String {
+    let ImagePlacement {
+        image,
+        source,
+        dest,
+    } = placement;
+    let width = dest.w.saturating_mul(cell.w);
+    let height = dest.h.saturating_mul(cell.h);
+    let mut registers = Vec::with_capacity(width as usize * height as usize);
+    for y in 0..height {
+        let sy = source.tl.y + (u64::from(y) * u64::from(source.h) / u64::from(height)) as u32;
+        for x in 0..width {
+            let sx = source.tl.x + (u64::from(x) * u64::from(source.w) / u64::from(width)) as u32;
+            registers.push(sixel_register(image.pixel(sx, sy)));
+        }
+    }
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+    let used: BTreeSet<u32> = registers.iter().flatten().copied().collect();
+    for register in &used {
+        let b = register % SIXEL_LEVELS;
+        let g = register / SIXEL_LEVELS % SIXEL_LEVELS;
+        let r = register / (SIXEL_LEVELS * SIXEL_LEVELS);
+        let percent = |level: u32| level * 100 / (SIXEL_LEVELS - 1);
+        let _ok = write!(
+            out,
+            "#{register};2;{};{};{}",
+            percent(r),
+            percent(g),
+            percent(b)
+        );
+    }
+
+    let width = width as usize;
+    for (band_index, band) in registers.chunks(width.max(1) * 6).enumerate() {
+        if band_index > 0 {
+            out.push('-');
+        }
+        let band_colors: BTreeSet<u32> = band.iter().flatten().copied().collect();
+        for (color_index, register) in band_colors.iter().enumerate() {
+            if color_index > 0 {
+                out.push('$');
+            }
+            let _ok = write!(out, "#{register}");
+            let mut run = ('?', 0);
+            for x in 0..width {
+                let bits = (0..6).fold(0u8, |bits, row| match band.get(row * width + x) {
+                    Some(Some(r)) if r == register => bits | (1 << row),
+                    _ => bits,
+                });
+                let ch = char::from(b'?' + bits);
+                if ch == run.0 {
+                    run.1 += 1;
+                } else {
+                    push_sixel_run(&mut out, run.0, run.1);
+                    run = (ch, 1);
+                }
+            }
+            // Trailing blank columns need not be sent.
+            if run.0 != '?' {
+                push_sixel_run(&mut out, run.0, run.1);
+            }
+        }
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixmap(width: u32, height: u32, pixel: [u8; 4]) -> Arc<Pixmap> {
+        let rgba = pixel.repeat((width * height) as usize);
+        Arc::new(Pixmap::new(width, height, rgba).expect("pixmap size should match"))
+    }
+
+    #[test]
+    fn pixmap_rejects_mismatched_buffers() {
+        assert!(Pixmap::new(2, 2, vec![0; 15]).is_err());
+        let a = Pixmap::new(1, 1, vec![0; 4]).expect("pixmap size should match");
+        let b = Pixmap::new(1, 1, vec![0; 4]).expect("pixmap size should match");
+        assert_ne!(a.id(), b.id());
+        assert_ne!(a.id(), 0);
+    }
+
+    #[test]
+    fn base64_pads_partial_groups() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn detection_prefers_override_and_avoids_multiplexers() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| (*value).to_string())
+            }
+        };
+        let detect = |pairs| ImageProtocol::detect_with(env(pairs), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty")]),
+            Some(ImageProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")]),
+            Some(ImageProtocol::Kitty)
+        );
+        assert_eq!(detect(&[("TERM", "foot")]), Some(ImageProtocol::Sixel));
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), None);
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/t,1,0")]),
+            None
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("CANOPY_GRAPHICS", "sixel")]),
+            Some(ImageProtocol::Sixel)
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-kitty"), ("CANOPY_GRAPHICS", "none")]),
+            None
+        );
+
+        let answered =
+            |support| ImageProtocol::detect_with(env(&[("TERM", "xterm-kitty")]), support);
+        assert_eq!(answered(Some(GraphicsSupport::default())), None);
+        assert_eq!(
+            answered(Some(GraphicsSupport {
+                kitty: false,
+                sixel: true,
+            })),
+            Some(ImageProtocol::Sixel)
+        );
+        assert_eq!(
+            answered(Some(GraphicsSupport {
+                kitty: true,
+                sixel: true,
+            })),
+            Some(ImageProtocol::Kitty)
+        );
+    }
+
+    #[test]
+    fn kitty_uploads_in_chunks_and_places_source_regions() {
+        let image = pixmap(40, 20, [1, 2, 3, 255]);
+        let id = image.id();
+        let upload = kitty_transmit(&image);
+        let frames: Vec<&str> = upload.split("\x1b\\").filter(|s| !s.is_empty()).collect();
+        // 3200 bytes encode to 4268 base64 characters, which needs two chunks.
+        assert_eq!(frames.len(), 2);
+        assert!(
+            frames[0].starts_with(&format!("\x1b_Ga=t,f=32,s=40,v=20,i={id},q=2,m=1;AQID/wEC"))
+        );
+        assert_eq!(frames[0].len() - frames[0].find(';').unwrap_or(0) - 1, 4096);
+        assert!(frames[1].starts_with("\x1b_Gm=0;"));
+
+        let placement = ImagePlacement {
+            image: image.clone(),
+            source: Rect::new(4, 2, 30, 16),
+            dest: Rect::new(10, 5, 15, 4),
+        };
+        assert_eq!(
+            kitty_place(&placement),
+            format!("\x1b_Ga=p,i={id},p=1,x=4,y=2,w=30,h=16,c=15,r=4,C=1,q=2\x1b\\")
+        );
+        assert_eq!(
+            kitty_delete(&image),
+            format!("\x1b_Ga=d,d=i,i={id},q=2\x1b\\")
+        );
+        assert_eq!(kitty_free(id), format!("\x1b_Ga=d,d=I,i={id},q=2\x1b\\"));
+    }
+
+    #[test]
+    fn clipping_drops_rows_and_matching_source() {
+        let image = pixmap(40, 20, [1, 2, 3, 255]);
+        let placement = ImagePlacement {
+            image,
+            source: Rect::new(0, 0, 40, 20),
+            dest: Rect::new(2, 6, 10, 4),
+        };
+        let clipped = clip_rows(&placement, 9).expect("rows above the limit remain");
+        assert_eq!(clipped.dest, Rect::new(2, 6, 10, 3));
+        assert_eq!(clipped.source, Rect::new(0, 0, 40, 15));
+        assert_eq!(clip_rows(&placement, 20), Some(placement.clone()));
+        assert_eq!(clip_rows(&placement, 6), None);
+    }
+
+    #[test]
+    fn sixel_encodes_bands_with_run_lengths() {
+        let mut rgba = [255, 0, 0, 255].repeat(4 * 12);
+        // Make the right half of the top band transparent.
+        for y in 0..6 {
+            for x in 2..4 {
+                let i = (y * 4 + x) * 4;
+                rgba[i + 3] = 0;
+            }
+        }
+        let image = Arc::new(Pixmap::new(4, 12, rgba).expect("pixmap size should match"));
+        let placement = ImagePlacement {
+            source: image.rect(),
+            image,
+            dest: Rect::new(0, 0, 2, 1),
+        };
+        let red = 5 * SIXEL_LEVELS * SIXEL_LEVELS;
+        assert_eq!(
+            sixel(&placement, Size::new(2, 12)),
+            format!("\x1bP0;1;0q\"1;1;4;12#{red};2;100;0;0#{red}~~-#{red}!4~\x1b\\")
+        );
+    }
+}
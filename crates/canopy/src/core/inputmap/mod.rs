@@ -274,6 +274,8 @@ pub struct InputMap {
     next_insertion_id: u64,
     /// Next exclusive-frame token.
     next_token: u64,
+    /// Counter bumped whenever binding records are added or removed.
+    revision: u64,
 }
 
 impl Default for InputMap {
@@ -292,6 +294,7 @@ impl InputMap {
             next_id: 1,
             next_insertion_id: 1,
             next_token: 1,
+            revision: 0,
         }
     }
 
@@ -329,6 +332,7 @@ impl InputMap {
             insertion_id,
             path_matcher,
         });
+        self.revision += 1;
         Ok((id, removed))
     }
 
@@ -373,6 +377,7 @@ impl InputMap {
             insertion_id,
             path_matcher,
         });
+        self.revision += 1;
         Ok(id)
     }
 
@@ -388,6 +393,7 @@ impl InputMap {
             )));
         }
         let record = self.records.remove(index);
+        self.revision += 1;
         match record.target {
             BindingTarget::Script(target) => Ok(Some(target)),
             BindingTarget::Command(_) => Err(Error::Internal(
@@ -423,6 +429,9 @@ impl InputMap {
                 true
             }
         });
+        if !removed.is_empty() {
+            self.revision += 1;
+        }
         removed
     }
 
@@ -440,6 +449,9 @@ impl InputMap {
             }
         });
         self.mode_stack.clear();
+        if !removed.is_empty() {
+            self.revision += 1;
+        }
         removed
     }
 
@@ -453,6 +465,11 @@ impl InputMap {
         &self.records
     }
 
+    /// Return a counter that changes whenever the binding set changes.
+    pub(crate) fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Return one binding record by ID.
     pub(crate) fn binding(&self, id: BindingId) -> Option<&BindingRecord> {
         self.records.iter().find(|record| record.id == id)
@@ -687,6 +704,7 @@ impl InputMap {
         self.records.extend(snapshot.records);
         self.records.sort_by_key(|record| record.insertion_id);
         self.mode_stack = snapshot.mode_stack;
+        self.revision += 1;
     }
 
     /// Return all application binding IDs.
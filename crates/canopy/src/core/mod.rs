@@ -8,6 +8,8 @@ pub mod backend;
 pub mod children;
 /// Command definition and dispatch.
 pub mod commands;
+/// Command and binding coverage recording.
+pub mod coverage;
 /// Cursor and position helpers.
 pub mod cursor;
 /// Debug dump utilities.
@@ -16,16 +18,30 @@ pub mod dump;
 pub mod error;
 /// Input event types.
 pub mod event;
+/// Screen export to SVG, HTML and ANSI text.
+pub mod export;
+/// External program handoff and process suspension.
+pub mod external;
 /// Named, reproducible application fixtures.
 pub mod fixture;
+/// Frame pacing and render statistics.
+pub mod frame;
+/// Pixel images drawn with terminal graphics protocols.
+pub mod graphics;
 /// Node data and arena structures.
 pub mod node;
+/// Change notifications for live automation observers.
+pub mod observe;
 /// Path and traversal helpers.
 pub mod path;
+/// Session recording to asciicast and replayable input logs.
+pub mod recording;
 /// Rendering interfaces.
 pub mod render;
 /// Scripting support.
 pub mod script;
+/// Golden screen snapshots.
+pub mod snapshot;
 /// Shared node name types.
 pub mod state;
 /// Styling and color helpers.
@@ -51,6 +67,8 @@ pub mod id;
 pub mod inputmap;
 /// Polling utilities.
 pub mod poll;
+/// Background tasks owned by widget nodes.
+pub mod task;
 /// Terminal buffer types.
 pub mod termbuf;
 /// Text utilities.
@@ -61,6 +79,7 @@ mod widget_access;
 pub mod world;
 
 // Public exports from internal modules
+pub use backend::TerminalOptions;
 pub use canopy::{
     AutomationCallback, AutomationHandle, Canopy, Loader, RoutePhase, RouteTraceEntry,
     ScriptApiState, ScriptJournalEntry,
@@ -68,12 +87,16 @@ pub use canopy::{
 pub use change::ChangeOutcome;
 pub use children::{KeyedChildren, RemovePolicy};
 pub use context::{ChildKey, Context, FocusScope, Slot, ViewContext};
+pub use external::{External, ExternalOutput};
 pub use fixture::{Fixture, FixtureInfo};
+pub use frame::{FramePolicy, FrameStats, FrameTiming};
 pub use id::{NodeId, TypedId};
 pub use inputmap::{
     BindingId, BindingOwner, BindingPhase, BindingScope, BindingTarget, ExclusiveFrameToken,
     FrameworkBindingGroup, InputSpec,
 };
 pub use path::{Path, PathFilter};
+pub use poll::VirtualClock;
 pub use script::ScriptModuleRoots;
+pub use task::TaskId;
 pub use world::Core;
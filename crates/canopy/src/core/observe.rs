@@ -0,0 +1,97 @@
+//! Change notifications for live automation observers.
+//!
+//! An observer subscribes through [`Canopy::observe_changes`](crate::Canopy::observe_changes)
+//! and receives an [`AppChange`] after every rendered frame, plus one for each focus, input
+//! mode, binding set or script API change the frame reflects. Observers that drop their receiver
+//! are pruned on the next frame.
+
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender, unbounded};
+
+use crate::core::NodeId;
+
+/// A change in a running app that observers can react to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppChange {
+    /// A frame rendered.
+    Frame,
+    /// Focus moved to another node, or was cleared.
+    Focus(Option<NodeId>),
+    /// The active input mode changed.
+    Mode(String),
+    /// Bindings were added, replaced or removed.
+    Bindings,
+    /// The script API definition was finalized.
+    Api,
+}
+
+/// App state that observers are notified about when it changes.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ObservedState<'a> {
+    /// Focused node, if any.
+    pub(crate) focus: Option<NodeId>,
+    /// Active input mode.
+    pub(crate) mode: &'a str,
+    /// Binding set revision.
+    pub(crate) bindings: u64,
+    /// Whether the script API is finalized.
+    pub(crate) api_ready: bool,
+}
+
+/// Subscribed observers and the state they last saw.
+#[derive(Debug, Default)]
+pub(crate) struct Observers {
+    /// One sender per subscribed observer.
+    senders: Vec<UnboundedSender<AppChange>>,
+    /// Focus as of the most recent notification.
+    focus: Option<NodeId>,
+    /// Input mode as of the most recent notification.
+    mode: String,
+    /// Binding set revision as of the most recent notification.
+    bindings: u64,
+    /// Script API readiness as of the most recent notification.
+    api_ready: bool,
+}
+
+impl Observers {
+    /// Subscribe a new observer, given the app's current state.
+    pub(crate) fn subscribe(&mut self, state: ObservedState<'_>) -> UnboundedReceiver<AppChange> {
+        if self.senders.is_empty() {
+            self.focus = state.focus;
+            self.mode = state.mode.to_string();
+            self.bindings = state.bindings;
+            self.api_ready = state.api_ready;
+        }
+        let (tx, rx) = unbounded();
+        self.senders.push(tx);
+        rx
+    }
+
+    /// Notify observers of a rendered frame and any state change since the last one.
+    pub(crate) fn frame_rendered(&mut self, state: ObservedState<'_>) {
+        if self.senders.is_empty() {
+            return;
+        }
+        let mut changes = vec![AppChange::Frame];
+        if state.focus != self.focus {
+            self.focus = state.focus;
+            changes.push(AppChange::Focus(state.focus));
+        }
+        if state.mode != self.mode {
+            self.mode = state.mode.to_string();
+            changes.push(AppChange::Mode(self.mode.clone()));
+        }
+        if state.bindings != self.bindings {
+            self.bindings = state.bindings;
+            changes.push(AppChange::Bindings);
+        }
+        if state.api_ready != self.api_ready {
+            self.api_ready = state.api_ready;
+            changes.push(AppChange::Api);
+        }
+        self.senders.retain(|sender| {
+            changes
+                .iter()
+                .all(|change| sender.unbounded_send(change.clone()).is_ok())
+        });
+    }
+}
@@ -10,6 +10,7 @@ use std::{
 };
 
 use futures::channel::mpsc::UnboundedSender;
+use parking_lot::Mutex;
 
 use crate::{
     NodeId,
@@ -33,6 +34,50 @@ impl Clock for SystemClock {
     }
 }
 
+/// A clock that only moves when it is advanced explicitly.
+///
+/// Installing one with [`Canopy::use_virtual_clock`](crate::Canopy::use_virtual_clock) makes poll
+/// timing deterministic: polls fire only when
+/// [`Canopy::advance_time`](crate::Canopy::advance_time) moves time past their deadlines. Clones
+/// share the same time.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    /// Current virtual time.
+    now: Arc<Mutex<Instant>>,
+}
+
+impl VirtualClock {
+    /// Construct a virtual clock starting at the current wall time.
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Return the current virtual time.
+    pub fn now(&self) -> Instant {
+        *self.now.lock()
+    }
+
+    /// Move the clock forward to `instant`. Earlier instants leave it unchanged.
+    pub(crate) fn advance_to(&self, instant: Instant) {
+        let mut now = self.now.lock();
+        *now = (*now).max(instant);
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        Self::now(self)
+    }
+}
+
 /// One scheduled node callback.
 #[derive(Debug)]
 struct PendingNode {
@@ -99,12 +144,16 @@ impl PendingHeap {
         }
     }
 
+    /// Return the earliest live deadline.
+    fn next_deadline(&mut self) -> Option<Instant> {
+        self.discard_stale();
+        self.nodes.peek().map(|node| node.deadline)
+    }
+
     /// Calculate how long the worker should wait for the next deadline.
     fn current_wait(&mut self, now: Instant) -> Option<Duration> {
-        self.discard_stale();
-        self.nodes
-            .peek()
-            .map(|node| node.deadline.saturating_duration_since(now))
+        self.next_deadline()
+            .map(|deadline| deadline.saturating_duration_since(now))
     }
 
     /// Remove and return every callback due at `now`.
@@ -139,12 +188,18 @@ enum SchedulerCommand {
     },
     /// Cancel a node's pending callback.
     Cancel(NodeId),
+    /// Send a wake event at an absolute deadline, keeping the earliest request.
+    Wake(Instant),
     /// Stop the worker.
     Shutdown,
 }
 
 /// Apply one scheduler command, returning false on shutdown.
-fn apply_command(command: SchedulerCommand, pending: &mut PendingHeap) -> bool {
+fn apply_command(
+    command: SchedulerCommand,
+    pending: &mut PendingHeap,
+    wake: &mut Option<Instant>,
+) -> bool {
     match command {
         SchedulerCommand::Schedule { node_id, deadline } => {
             pending.schedule(node_id, deadline);
@@ -154,6 +209,10 @@ fn apply_command(command: SchedulerCommand, pending: &mut PendingHeap) -> bool {
             pending.cancel(node_id);
             true
         }
+        SchedulerCommand::Wake(deadline) => {
+            *wake = Some(wake.map_or(deadline, |current| current.min(deadline)));
+            true
+        }
         SchedulerCommand::Shutdown => false,
     }
 }
@@ -165,14 +224,26 @@ fn scheduler_worker(
     clock: &dyn Clock,
 ) {
     let mut pending = PendingHeap::default();
+    let mut wake: Option<Instant> = None;
     loop {
         let now = clock.now();
         let due = pending.collect(now);
         if !due.is_empty() && event_tx.unbounded_send(Event::Poll(due)).is_err() {
             return;
         }
+        if wake.is_some_and(|deadline| deadline <= now) {
+            wake = None;
+            if event_tx.unbounded_send(Event::Wake).is_err() {
+                return;
+            }
+        }
 
-        let command = match pending.current_wait(now) {
+        let wake_wait = wake.map(|deadline| deadline.saturating_duration_since(now));
+        let wait = match (pending.current_wait(now), wake_wait) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let command = match wait {
             Some(wait) => match commands.recv_timeout(wait) {
                 Ok(command) => command,
                 Err(mpsc::RecvTimeoutError::Timeout) => continue,
@@ -183,12 +254,32 @@ fn scheduler_worker(
                 Err(mpsc::RecvError) => return,
             },
         };
-        if !apply_command(command, &mut pending) {
+        if !apply_command(command, &mut pending, &mut wake) {
             return;
         }
     }
 }
 
+/// Scheduler state held in place of a worker while a virtual clock drives time.
+#[derive(Debug)]
+struct VirtualSchedule {
+    /// Clock that advances only on request.
+    clock: VirtualClock,
+    /// Pending callbacks.
+    pending: PendingHeap,
+    /// Earliest pending wake deadline.
+    wake: Option<Instant>,
+}
+
+/// Callbacks that fell due when a virtual clock advanced.
+#[derive(Debug, Default)]
+pub(crate) struct DueWork {
+    /// Nodes whose poll deadlines have passed.
+    pub(crate) nodes: Vec<NodeId>,
+    /// Whether a requested wake has fallen due.
+    pub(crate) wake: bool,
+}
+
 /// Owned scheduler for widget poll callbacks.
 #[derive(Debug)]
 pub struct Poller {
@@ -198,6 +289,8 @@ pub struct Poller {
     worker: Option<thread::JoinHandle<()>>,
     /// Clock used to calculate checked deadlines.
     clock: Arc<dyn Clock>,
+    /// In-place schedule used instead of the worker under a virtual clock.
+    virtual_schedule: Option<Mutex<VirtualSchedule>>,
 }
 
 impl Poller {
@@ -217,11 +310,66 @@ impl Poller {
             command_tx: Some(command_tx),
             worker: Some(worker),
             clock,
+            virtual_schedule: None,
+        }
+    }
+
+    /// Construct a scheduler driven by a virtual clock, without a worker thread.
+    ///
+    /// Nothing fires on its own: callers collect due work with [`Self::take_due`] after advancing
+    /// the clock.
+    pub(crate) fn with_virtual_clock(clock: VirtualClock) -> Self {
+        Self {
+            command_tx: None,
+            worker: None,
+            clock: Arc::new(clock.clone()),
+            virtual_schedule: Some(Mutex::new(VirtualSchedule {
+                clock,
+                pending: PendingHeap::default(),
+                wake: None,
+            })),
         }
     }
 
+    /// Return the virtual clock driving this scheduler, if any.
+    pub(crate) fn virtual_clock(&self) -> Option<VirtualClock> {
+        self.virtual_schedule
+            .as_ref()
+            .map(|schedule| schedule.lock().clock.clone())
+    }
+
+    /// Return the earliest pending poll or wake deadline under a virtual clock.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let mut schedule = self.virtual_schedule.as_ref()?.lock();
+        let wake = schedule.wake;
+        match (schedule.pending.next_deadline(), wake) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Remove and return the work due at the virtual clock's current time.
+    pub(crate) fn take_due(&self) -> DueWork {
+        let Some(schedule) = &self.virtual_schedule else {
+            return DueWork::default();
+        };
+        let mut schedule = schedule.lock();
+        let now = schedule.clock.now();
+        let nodes = schedule.pending.collect(now);
+        let wake = schedule.wake.is_some_and(|deadline| deadline <= now);
+        if wake {
+            schedule.wake = None;
+        }
+        DueWork { nodes, wake }
+    }
+
     /// Send a command unless the scheduler has already stopped.
     fn send(&self, command: SchedulerCommand) -> Result<()> {
+        if let Some(schedule) = &self.virtual_schedule {
+            let schedule = &mut *schedule.lock();
+            apply_command(command, &mut schedule.pending, &mut schedule.wake);
+            return Ok(());
+        }
         if self
             .worker
             .as_ref()
@@ -248,6 +396,16 @@ impl Poller {
         self.send(SchedulerCommand::Schedule { node_id, deadline })
     }
 
+    /// Send [`Event::Wake`] after a delay, merging with any earlier pending wake.
+    pub(crate) fn wake_after(&self, duration: Duration) -> Result<()> {
+        let deadline = self
+            .clock
+            .now()
+            .checked_add(duration)
+            .ok_or_else(|| Error::RunLoop("wake deadline overflow".into()))?;
+        self.send(SchedulerCommand::Wake(deadline))
+    }
+
     /// Cancel a node's pending callback.
     fn cancel(&self, node_id: impl Into<NodeId>) -> Result<()> {
         self.send(SchedulerCommand::Cancel(node_id.into()))
@@ -344,6 +502,58 @@ mod tests {
         assert!(matches!(event, Event::Poll(nodes) if nodes == vec![node]));
     }
 
+    #[test]
+    fn worker_emits_a_single_wake_at_the_earliest_deadline() {
+        let now = Instant::now();
+        let clock = Arc::new(ManualClock::new(now));
+        let (event_tx, mut event_rx) = unbounded();
+        let poller = Poller::with_clock(event_tx, clock.clone());
+
+        poller
+            .wake_after(Duration::from_secs(10))
+            .expect("scheduler should accept a wake");
+        poller
+            .wake_after(Duration::ZERO)
+            .expect("scheduler should accept a wake");
+        let event = block_on(event_rx.next()).expect("scheduler should emit a wake");
+        assert!(matches!(event, Event::Wake));
+        assert!(event_rx.try_next().is_err());
+    }
+
+    #[test]
+    fn virtual_scheduler_releases_work_only_when_the_clock_advances() {
+        let clock = VirtualClock::new();
+        let start = clock.now();
+        let poller = Poller::with_virtual_clock(clock.clone());
+        let (first, second) = node_ids();
+
+        poller
+            .schedule(first, Duration::from_millis(30))
+            .expect("scheduler should accept work");
+        poller
+            .schedule(second, Duration::from_millis(10))
+            .expect("scheduler should accept work");
+        poller
+            .wake_after(Duration::from_millis(20))
+            .expect("scheduler should accept a wake");
+        assert_eq!(
+            poller.next_deadline(),
+            Some(start + Duration::from_millis(10))
+        );
+        assert!(poller.take_due().nodes.is_empty());
+
+        clock.advance_to(start + Duration::from_millis(20));
+        let due = poller.take_due();
+        assert_eq!(due.nodes, vec![second]);
+        assert!(due.wake);
+
+        clock.advance_to(start);
+        assert_eq!(clock.now(), start + Duration::from_millis(20));
+        clock.advance_to(start + Duration::from_millis(30));
+        assert_eq!(poller.take_due().nodes, vec![first]);
+        assert_eq!(poller.next_deadline(), None);
+    }
+
     #[test]
     fn shutdown_joins_worker_and_rejects_more_work() {
         let (event_tx, _event_rx) = unbounded();
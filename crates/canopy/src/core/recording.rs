@@ -0,0 +1,629 @@
+//! Session recording to asciicast and replayable input logs.
+//!
+//! A [`Recorder`] writes two files for a live session. The asciicast v2 file holds every
+//! rendered frame as an output event, so the session plays back in any asciicast player. The
+//! input log beside it holds one JSON record per line: each input event in a stable text form
+//! with its timestamp, and the screen text after input was handled.
+//!
+//! [`luau_script`] converts an input log into a Luau smoke script of `canopy.send_key`,
+//! `canopy.send_click` and `canopy.send_scroll` calls, with `canopy.wait_for` checkpoints
+//! wherever the user paused. That turns a reproduction by hand into a repeatable script.
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    mem,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value as JsonValue, json};
+
+use crate::{
+    core::{export, termbuf::TermBuf},
+    error::{Error, Result},
+    event::{
+        Event,
+        key::{self, Key},
+        mouse::{self, Mouse, MouseEvent},
+    },
+    geom::Size,
+};
+
+/// Environment variable naming the asciicast file that run loops record to.
+pub const RECORD_ENV: &str = "CANOPY_RECORD";
+
+/// Default pause between inputs that earns a checkpoint in a converted script.
+pub const DEFAULT_PAUSE: Duration = Duration::from_millis(500);
+
+/// Maximum number of characters of screen text a checkpoint waits for.
+const CHECKPOINT_WIDTH: usize = 60;
+
+/// Milliseconds a converted checkpoint waits for its screen text.
+const CHECKPOINT_TIMEOUT_MS: u64 = 2000;
+
+/// Return the recording path from [`RECORD_ENV`], if it is set and not empty.
+pub fn path_from_env() -> Option<PathBuf> {
+    env::var_os(RECORD_ENV)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Return the input log path stored beside an asciicast file.
+pub fn input_log_path(cast: &Path) -> PathBuf {
+    cast.with_extension("input.jsonl")
+}
+
+/// One line of an input log. Times are seconds since recording started.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputRecord {
+    /// Recording started with the given screen size.
+    Start {
+        /// Seconds since recording started.
+        time: f64,
+        /// Screen width in cells.
+        width: u32,
+        /// Screen height in cells.
+        height: u32,
+    },
+    /// A key event, as a key spec accepted by `canopy.send_key`.
+    Key {
+        /// Seconds since recording started.
+        time: f64,
+        /// Normalized key spec such as `Ctrl+s`.
+        key: String,
+    },
+    /// A mouse event, as a mouse spec such as `ctrl-LeftDown`.
+    Mouse {
+        /// Seconds since recording started.
+        time: f64,
+        /// Mouse spec accepted by mouse bindings.
+        mouse: String,
+        /// Screen column.
+        x: u32,
+        /// Screen row.
+        y: u32,
+    },
+    /// Pasted text.
+    Paste {
+        /// Seconds since recording started.
+        time: f64,
+        /// The pasted text.
+        text: String,
+    },
+    /// The terminal was resized.
+    Resize {
+        /// Seconds since recording started.
+        time: f64,
+        /// New width in cells.
+        width: u32,
+        /// New height in cells.
+        height: u32,
+    },
+    /// The terminal gained or lost focus.
+    Focus {
+        /// Seconds since recording started.
+        time: f64,
+        /// True when focus was gained.
+        gained: bool,
+    },
+    /// The screen text of the first frame rendered after input.
+    Frame {
+        /// Seconds since recording started.
+        time: f64,
+        /// Newline-joined screen text.
+        text: String,
+    },
+}
+
+impl InputRecord {
+    /// Return the record time in seconds since recording started.
+    pub fn time(&self) -> f64 {
+        match self {
+            Self::Start { time, .. }
+            | Self::Key { time, .. }
+            | Self::Mouse { time, .. }
+            | Self::Paste { time, .. }
+            | Self::Resize { time, .. }
+            | Self::Focus { time, .. }
+            | Self::Frame { time, .. } => *time,
+        }
+    }
+}
+
+/// Records a session to an asciicast file and an input log.
+///
+/// Every write is flushed immediately, so a recording survives the process exiting without
+/// dropping the recorder.
+pub struct Recorder {
+    /// Time recording started.
+    start: Instant,
+    /// Asciicast output.
+    cast: BufWriter<File>,
+    /// Input log output.
+    log: BufWriter<File>,
+    /// Whether input arrived since the last frame, so the next frame is logged.
+    input_since_frame: bool,
+}
+
+impl Recorder {
+    /// Create an asciicast file at `path` and an input log beside it, for a screen of `size`.
+    pub fn create(path: &Path, size: Size) -> Result<Self> {
+        let open = |path: &Path| {
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|err| recording_error(path, &err))
+        };
+        let mut recorder = Self {
+            start: Instant::now(),
+            cast: open(path)?,
+            log: open(&input_log_path(path))?,
+            input_since_frame: true,
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        let term = env::var("TERM").unwrap_or_else(|_| String::from("xterm-256color"));
+        recorder.write_cast(&json!({
+            "version": 2,
+            "width": size.w,
+            "height": size.h,
+            "timestamp": timestamp,
+            "env": { "TERM": term },
+        }))?;
+        recorder.write_log(&InputRecord::Start {
+            time: 0.0,
+            width: size.w,
+            height: size.h,
+        })?;
+        Ok(recorder)
+    }
+
+    /// Record an input event. Internal poll and wake events are ignored.
+    pub fn event(&mut self, event: &Event) -> Result<()> {
+        let time = self.elapsed();
+        let record = match event {
+            Event::Key(key) => InputRecord::Key {
+                time,
+                key: key.normalize().to_string(),
+            },
+            Event::Mouse(event) => InputRecord::Mouse {
+                time,
+                mouse: mouse_spec(event),
+                x: event.location.x,
+                y: event.location.y,
+            },
+            Event::Paste(text) => InputRecord::Paste {
+                time,
+                text: text.clone(),
+            },
+            Event::Resize(size) => {
+                self.write_cast(&json!([time, "r", format!("{}x{}", size.w, size.h)]))?;
+                InputRecord::Resize {
+                    time,
+                    width: size.w,
+                    height: size.h,
+                }
+            }
+            Event::FocusGained => InputRecord::Focus { time, gained: true },
+            Event::FocusLost => InputRecord::Focus {
+                time,
+                gained: false,
+            },
+            Event::Poll(_) | Event::Wake => return Ok(()),
+        };
+        self.input_since_frame = true;
+        self.write_log(&record)
+    }
+
+    /// Record a rendered frame, logging its screen text when input arrived since the last one.
+    pub fn frame(&mut self, buf: &TermBuf) -> Result<()> {
+        let time = self.elapsed();
+        let screen = export::ansi(buf);
+        let data = format!(
+            "\x1b[H{}",
+            screen.trim_end_matches('\n').replace('\n', "\r\n")
+        );
+        self.write_cast(&json!([time, "o", data]))?;
+        if mem::take(&mut self.input_since_frame) {
+            self.write_log(&InputRecord::Frame {
+                time,
+                text: buf.screen_text(),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Return the seconds since recording started, rounded to microseconds.
+    fn elapsed(&self) -> f64 {
+        (self.start.elapsed().as_secs_f64() * 1e6).round() / 1e6
+    }
+
+    /// Write one asciicast line.
+    fn write_cast(&mut self, value: &JsonValue) -> Result<()> {
+        writeln!(self.cast, "{value}")
+            .and_then(|()| self.cast.flush())
+            .map_err(|err| recording_error(Path::new("asciicast"), &err))
+    }
+
+    /// Write one input log line.
+    fn write_log(&mut self, record: &InputRecord) -> Result<()> {
+        let line = serde_json::to_string(record)
+            .map_err(|err| Error::Invalid(format!("input record encoding failed: {err}")))?;
+        writeln!(self.log, "{line}")
+            .and_then(|()| self.log.flush())
+            .map_err(|err| recording_error(Path::new("input log"), &err))
+    }
+}
+
+/// Build an error for a failed recording write.
+fn recording_error(path: &Path, err: &io::Error) -> Error {
+    Error::Invalid(format!("recording to {} failed: {err}", path.display()))
+}
+
+/// Format a mouse event as a mouse spec such as `ctrl-LeftDown` or `ScrollUp`.
+fn mouse_spec(event: &MouseEvent) -> String {
+    let mut spec = String::new();
+    for (set, name) in [
+        (event.modifiers.ctrl, "ctrl-"),
+        (event.modifiers.alt, "alt-"),
+        (event.modifiers.shift, "shift-"),
+    ] {
+        if set {
+            spec.push_str(name);
+        }
+    }
+    spec.push_str(match event.button {
+        mouse::Button::Left => "Left",
+        mouse::Button::Right => "Right",
+        mouse::Button::Middle => "Middle",
+        mouse::Button::None => "",
+    });
+    spec.push_str(match event.action {
+        mouse::Action::Down => "Down",
+        mouse::Action::Up => "Up",
+        mouse::Action::Drag => "Drag",
+        mouse::Action::Moved => "Moved",
+        mouse::Action::ScrollDown => "ScrollDown",
+        mouse::Action::ScrollUp => "ScrollUp",
+        mouse::Action::ScrollLeft => "ScrollLeft",
+        mouse::Action::ScrollRight => "ScrollRight",
+    });
+    spec
+}
+
+/// Read an input log written by a [`Recorder`].
+pub fn read_input_log(path: &Path) -> Result<Vec<InputRecord>> {
+    let source = fs::read_to_string(path)
+        .map_err(|err| Error::Invalid(format!("input log read failed: {err}")))?;
+    source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).map_err(|err| {
+                Error::Invalid(format!(
+                    "input log {} line {}: {err}",
+                    path.display(),
+                    index + 1
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Convert an input log into a Luau smoke script.
+///
+/// Keys, left clicks, vertical scrolls and pastes become `canopy.send_*` calls, and resizes
+/// become `canopy.resize` calls. Whenever the user paused for at least `pause` before the next
+/// input, and after the last input, the script waits for a line of the recorded screen that
+/// changed since the previous checkpoint. Inputs the scripting API cannot inject are kept as
+/// comments.
+pub fn luau_script(records: &[InputRecord], pause: Duration) -> String {
+    let pause = pause.as_secs_f64();
+    let mut out = String::from("-- Converted from a recorded canopy session.\n");
+    let mut checkpoints = Checkpoints::default();
+    let mut last_input: Option<f64> = None;
+    let mut frame: Option<&str> = None;
+    let mut pressed: Option<(u32, u32)> = None;
+    for record in records {
+        match record {
+            InputRecord::Start { width, height, .. } => {
+                out.push_str(&format!("-- Recorded at {width}x{height}.\n"));
+            }
+            InputRecord::Frame { text, .. } => {
+                if last_input.is_some() {
+                    frame = Some(text);
+                } else {
+                    checkpoints.seen = screen_lines(text);
+                }
+            }
+            InputRecord::Focus { .. } => {}
+            input => {
+                if let Some((last, text)) = last_input.zip(frame)
+                    && input.time() - last >= pause
+                {
+                    checkpoints.push(&mut out, text);
+                }
+                frame = None;
+                last_input = Some(input.time());
+                convert_input(&mut out, input, &mut pressed);
+            }
+        }
+    }
+    if let Some(text) = frame {
+        checkpoints.push(&mut out, text);
+    }
+    out
+}
+
+/// Append the Luau call, or a comment, for one input record.
+fn convert_input(out: &mut String, record: &InputRecord, pressed: &mut Option<(u32, u32)>) {
+    match record {
+        InputRecord::Key { key, .. } => match Key::parse_spec(key) {
+            Ok(_) => {
+                out.push_str(&format!("canopy.send_key({})\n", luau_string(key)));
+            }
+            Err(err) => {
+                out.push_str(&format!("-- skipped key {key:?}: {err}\n"));
+            }
+        },
+        InputRecord::Mouse { mouse, x, y, .. } => {
+            let spec = Mouse::parse_spec(mouse).ok();
+            let plain = spec.is_some_and(|spec| spec.modifiers == key::Empty);
+            match spec.map(|spec| (spec.action, spec.button)) {
+                Some((_, _)) if !plain => {
+                    out.push_str(&format!("-- skipped mouse {mouse} at {x}, {y}\n"));
+                }
+                Some((mouse::Action::Down, mouse::Button::Left)) => {
+                    out.push_str(&format!("canopy.send_click({x}, {y})\n"));
+                    *pressed = Some((*x, *y));
+                }
+                Some((mouse::Action::Up, mouse::Button::Left)) if *pressed == Some((*x, *y)) => {
+                    *pressed = None;
+                }
+                Some((mouse::Action::Moved, _)) => {}
+                Some((mouse::Action::ScrollUp, _)) => {
+                    out.push_str(&format!("canopy.send_scroll(\"Up\", {x}, {y})\n"));
+                }
+                Some((mouse::Action::ScrollDown, _)) => {
+                    out.push_str(&format!("canopy.send_scroll(\"Down\", {x}, {y})\n"));
+                }
+                _ => {
+                    out.push_str(&format!("-- skipped mouse {mouse} at {x}, {y}\n"));
+                }
+            }
+        }
+        InputRecord::Paste { text, .. } => {
+            out.push_str(&format!("canopy.send_paste({})\n", luau_string(text)));
+        }
+        InputRecord::Resize { width, height, .. } => {
+            out.push_str(&format!("canopy.resize({width}, {height})\n"));
+        }
+        InputRecord::Start { .. } | InputRecord::Focus { .. } | InputRecord::Frame { .. } => {}
+    }
+}
+
+/// Checkpoint state while converting an input log.
+#[derive(Default)]
+struct Checkpoints {
+    /// Non-blank screen lines at the previous checkpoint.
+    seen: Vec<String>,
+    /// Number of checkpoints written.
+    count: usize,
+}
+
+impl Checkpoints {
+    /// Append a checkpoint that waits for the longest line of `text` not seen at the previous
+    /// checkpoint. Nothing is written when no line changed.
+    fn push(&mut self, out: &mut String, text: &str) {
+        let lines = screen_lines(text);
+        let target = lines
+            .iter()
+            .filter(|line| !self.seen.contains(line))
+            .max_by_key(|line| line.chars().count());
+        if let Some(line) = target {
+            self.count += 1;
+            let needle: String = line.chars().take(CHECKPOINT_WIDTH).collect();
+            out.push_str(&format!(
+                "canopy.assert(\n    canopy.wait_for(function()\n        \
+                 return canopy.screen_text():find({}, 1, true) ~= nil\n    \
+                 end, {CHECKPOINT_TIMEOUT_MS}),\n    \"checkpoint {} timed out\"\n)\n",
+                luau_string(needle.trim_end()),
+                self.count
+            ));
+        }
+        self.seen = lines;
+    }
+}
+
+/// Return the trimmed, non-blank lines of screen text.
+fn screen_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Quote text as a Luau string literal.
+pub fn luau_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if ch.is_control() => {
+                out.push_str(&format!("\\u{{{:x}}}", u32::from(ch)));
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        event::key::Ctrl,
+        geom::{Line, Point},
+        style::{AttrSet, Color, ResolvedStyle},
+    };
+
+    /// Build a key record.
+    fn key(time: f64, key: &str) -> InputRecord {
+        InputRecord::Key {
+            time,
+            key: key.into(),
+        }
+    }
+
+    /// Build a mouse record.
+    fn mouse(time: f64, spec: &str, x: u32, y: u32) -> InputRecord {
+        InputRecord::Mouse {
+            time,
+            mouse: spec.into(),
+            x,
+            y,
+        }
+    }
+
+    /// Build a frame record.
+    fn frame(time: f64, text: &str) -> InputRecord {
+        InputRecord::Frame {
+            time,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn mouse_specs_round_trip() {
+        let event = MouseEvent {
+            action: mouse::Action::Down,
+            button: mouse::Button::Right,
+            modifiers: Ctrl,
+            location: Point { x: 1, y: 2 },
+        };
+        let spec = mouse_spec(&event);
+        assert_eq!(spec, "ctrl-RightDown");
+        assert_eq!(Mouse::parse_spec(&spec), Ok(Mouse::from(event)));
+    }
+
+    #[test]
+    fn recorder_writes_asciicast_and_input_log() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let style = ResolvedStyle::new(Color::White, Color::Black, AttrSet::default());
+        let mut buf = TermBuf::new((4, 2), ' ', style)?;
+        buf.text(&style, Line::new(0, 0, 4), "hi")?;
+
+        let mut recorder = Recorder::create(&path, Size::new(4, 2))?;
+        recorder.frame(&buf)?;
+        recorder.event(&Event::Key(Ctrl + 's'))?;
+        recorder.event(&Event::Wake)?;
+        recorder.event(&Event::Resize(Size::new(5, 2)))?;
+        recorder.frame(&buf)?;
+        recorder.frame(&buf)?;
+
+        let cast = fs::read_to_string(&path).unwrap();
+        let lines: Vec<JsonValue> = cast
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 4);
+        assert_eq!(lines[0]["height"], 2);
+        assert_eq!(lines[1][1], "o");
+        assert!(
+            lines[1][2]
+                .as_str()
+                .unwrap()
+                .starts_with("\x1b[H\x1b[0;38;2;")
+        );
+        assert!(lines[1][2].as_str().unwrap().contains("\x1b[0m\r\n"));
+        assert_eq!(lines[2], json!([lines[2][0], "r", "5x2"]));
+
+        let records = read_input_log(&input_log_path(&path))?;
+        let kinds: Vec<&str> = records
+            .iter()
+            .map(|record| match record {
+                InputRecord::Start { .. } => "start",
+                InputRecord::Key { .. } => "key",
+                InputRecord::Resize { .. } => "resize",
+                InputRecord::Frame { .. } => "frame",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(kinds, ["start", "frame", "key", "resize", "frame"]);
+        assert_eq!(records[2], key(records[2].time(), "Ctrl+s"));
+        assert_eq!(records[4], frame(records[4].time(), "hi  \n    "));
+        Ok(())
+    }
+
+    #[test]
+    fn input_logs_convert_to_luau() {
+        let records = [
+            InputRecord::Start {
+                time: 0.0,
+                width: 20,
+                height: 3,
+            },
+            frame(0.0, "Todo\n"),
+            key(1.0, "a"),
+            key(1.1, "Ctrl+s"),
+            frame(1.2, "Todo\n- buy milk"),
+            mouse(2.0, "LeftDown", 3, 1),
+            mouse(2.05, "LeftUp", 3, 1),
+            mouse(2.06, "Moved", 4, 1),
+            mouse(2.1, "ScrollDown", 3, 1),
+            mouse(2.2, "RightDown", 3, 1),
+            InputRecord::Paste {
+                time: 2.3,
+                text: "abc".into(),
+            },
+            key(2.4, "+"),
+            frame(2.5, "Todo\n- buy milk\n- \"eggs\""),
+        ];
+        assert_eq!(
+            luau_script(&records, DEFAULT_PAUSE),
+            r#"-- Converted from a recorded canopy session.
+-- Recorded at 20x3.
+canopy.send_key("a")
+canopy.send_key("Ctrl+s")
+canopy.assert(
+    canopy.wait_for(function()
+        return canopy.screen_text():find("- buy milk", 1, true) ~= nil
+    end, 2000),
+    "checkpoint 1 timed out"
+)
+canopy.send_click(3, 1)
+canopy.send_scroll("Down", 3, 1)
+-- skipped mouse RightDown at 3, 1
+canopy.send_paste("abc")
+-- skipped key "+": key specification cannot be empty
+canopy.assert(
+    canopy.wait_for(function()
+        return canopy.screen_text():find("- \"eggs\"", 1, true) ~= nil
+    end, 2000),
+    "checkpoint 2 timed out"
+)
+"#
+        );
+    }
+
+    #[test]
+    fn luau_strings_escape_quotes_and_controls() {
+        assert_eq!(luau_string("a\"b\\c\n\u{1b}"), r#""a\"b\\c\n\u{1b}""#);
+    }
+}
@@ -1,17 +1,30 @@
+use std::sync::Arc;
+
+pub use subcell::SubCell;
+
 use super::termbuf::TermBuf;
 use crate::{
     core::text,
+    cursor::Cursor,
     error::Result,
     geom,
-    style::{Effect, ResolvedStyle, Style, StyleManager, StyleMap},
+    graphics::{ImagePlacement, Pixmap},
+    style::{Color, Effect, ResolvedStyle, Style, StyleManager, StyleMap},
 };
 
+/// Sub-cell glyph grids.
+mod subcell;
+
 /// The trait implemented by renderers.
 pub trait RenderBackend {
     /// Apply a style to the following text output
     fn style(&mut self, style: &ResolvedStyle) -> Result<()>;
     /// Output text to screen. This method is used for all text output.
     fn text(&mut self, loc: geom::Point, txt: &str) -> Result<()>;
+    /// Set the hyperlink target for the following text output, or clear it.
+    fn link(&mut self, _target: Option<&str>) -> Result<()> {
+        Ok(())
+    }
     /// Return true if the backend can shift characters within a line.
     fn supports_char_shift(&self) -> bool {
         false
@@ -30,12 +43,57 @@ pub trait RenderBackend {
     fn shift_lines(&mut self, _top: u32, _bottom: u32, _count: i32) -> Result<()> {
         Ok(())
     }
+    /// Return true if the backend drives the terminal's own cursor.
+    ///
+    /// Backends that return false get the cursor painted into the frame instead.
+    fn supports_cursor(&self) -> bool {
+        false
+    }
+    /// Place the terminal cursor after a frame, or hide it. The location is in screen coordinates.
+    fn cursor(&mut self, _cursor: Option<&Cursor>) -> Result<()> {
+        Ok(())
+    }
+    /// Return true if the backend draws images placed in the frame.
+    ///
+    /// Backends that return false show the text drawn beneath images instead.
+    fn supports_images(&self) -> bool {
+        false
+    }
+    /// Draw an image over its cells. The destination is in screen coordinates.
+    fn draw_image(&mut self, _image: &ImagePlacement) -> Result<()> {
+        Ok(())
+    }
+    /// Remove an image drawn by an earlier frame. The cells beneath it are rewritten afterwards.
+    fn erase_image(&mut self, _image: &ImagePlacement) -> Result<()> {
+        Ok(())
+    }
+    /// Remove every image before a full repaint.
+    fn erase_images(&mut self) -> Result<()> {
+        Ok(())
+    }
     /// Flush output to the terminal.
     fn flush(&mut self) -> Result<()>;
     /// Reset the backend to a clean state.
     fn reset(&mut self) -> Result<()> {
         Ok(())
     }
+    /// Forget cached terminal state after another program has used the terminal.
+    fn invalidate(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Build the OSC 8 sequence that opens a hyperlink, or closes it for `None`.
+///
+/// Control characters are dropped from the target so it cannot terminate the
+/// sequence early.
+pub(crate) fn hyperlink_sequence(target: Option<&str>) -> String {
+    let uri: String = target
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    format!("\x1b]8;;{uri}\x1b\\")
 }
 
 /// A render backend that discards all output.
@@ -223,6 +281,32 @@ impl<'a> Render<'a> {
         })
     }
 
+    /// Print text that links to `target`. The text is truncated or padded like
+    /// [`Render::text`], but only the cells covered by the text carry the link.
+    pub fn link(&mut self, style: &str, l: geom::Line, txt: &str, target: &str) -> Result<()> {
+        self.text(style, l, txt)?;
+        let width = u32::try_from(text::display_width(txt)).unwrap_or(u32::MAX);
+        self.set_link(
+            geom::Line::new(l.tl.x, l.tl.y, width.min(l.w)),
+            Some(target),
+        );
+        Ok(())
+    }
+
+    /// Attach a hyperlink target to the cells along a line, or clear it with
+    /// `None`. Writes out of bounds will be clipped.
+    pub fn set_link(&mut self, l: geom::Line, target: Option<&str>) {
+        let line_rect = geom::Rect::new(l.tl.x, l.tl.y, l.w, 1);
+        let Some(intersection) = line_rect.intersect(self.clip) else {
+            return;
+        };
+        let adjusted = geom::Line {
+            tl: self.translate_point(intersection.tl),
+            w: intersection.w,
+        };
+        self.buf.set_link(adjusted, target);
+    }
+
     /// Write a single cell with a resolved style.
     pub fn put_cell(&mut self, style: ResolvedStyle, p: geom::Point, ch: char) -> Result<()> {
         if self.clip.contains_point(p) {
@@ -251,6 +335,83 @@ impl<'a> Render<'a> {
         Ok(())
     }
 
+    /// Draw pixels at sub-cell resolution over a rectangle of cells.
+    ///
+    /// `pixel` receives coordinates relative to the rectangle, with `grid.size()` pixels per
+    /// cell, and returns `None` for background pixels. It is only called for visible cells.
+    /// Each cell gets the glyph and color pair that best shows its pixels.
+    pub fn subcells(
+        &mut self,
+        grid: SubCell,
+        rect: geom::Rect,
+        background: Color,
+        mut pixel: impl FnMut(u32, u32) -> Option<Color>,
+    ) -> Result<()> {
+        let Some(visible) = rect.intersect(self.clip) else {
+            return Ok(());
+        };
+        let size = grid.size();
+        let mut pixels = Vec::with_capacity((size.w * size.h) as usize);
+        for y in visible.tl.y..visible.tl.y + visible.h {
+            for x in visible.tl.x..visible.tl.x + visible.w {
+                let left = (x - rect.tl.x) * size.w;
+                let top = (y - rect.tl.y) * size.h;
+                pixels.clear();
+                for py in 0..size.h {
+                    for px in 0..size.w {
+                        pixels.push(pixel(left + px, top + py));
+                    }
+                }
+                let (ch, style) = grid.cell(&pixels, background);
+                self.put_cell(style, geom::Point { x, y }, ch)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Place part of an image over a rectangle of cells, scaled to fit.
+    ///
+    /// Draw a text rendition of the image into the same cells first: backends without a
+    /// graphics protocol show it, and so does every backend while another widget is drawn over
+    /// part of the image. The source rectangle is in image pixels and is cropped with the
+    /// destination when the destination is clipped.
+    pub fn image(
+        &mut self,
+        image: &Arc<Pixmap>,
+        source: geom::Rect,
+        dest: geom::Rect,
+    ) -> Result<()> {
+        let Some(source) = source.intersect(image.rect()) else {
+            return Ok(());
+        };
+        let Some(visible) = dest.intersect(self.clip) else {
+            return Ok(());
+        };
+        // Map a cell offset within the destination to a pixel offset within the source.
+        let scale = |cells: u32, pixels: u32, dest_cells: u32| {
+            let scaled = u64::from(cells) * u64::from(pixels) / u64::from(dest_cells.max(1));
+            u32::try_from(scaled).unwrap_or(pixels).min(pixels)
+        };
+        let left = scale(visible.tl.x - dest.tl.x, source.w, dest.w);
+        let top = scale(visible.tl.y - dest.tl.y, source.h, dest.h);
+        let right = scale(visible.tl.x + visible.w - dest.tl.x, source.w, dest.w);
+        let bottom = scale(visible.tl.y + visible.h - dest.tl.y, source.h, dest.h);
+        if right <= left || bottom <= top {
+            return Ok(());
+        }
+        self.buf.place_image(ImagePlacement {
+            image: Arc::clone(image),
+            source: geom::Rect::new(
+                source.tl.x + left,
+                source.tl.y + top,
+                right - left,
+                bottom - top,
+            ),
+            dest: self.translate_rect(visible),
+        });
+        Ok(())
+    }
+
     /// Translate a point from canvas coordinates to buffer coordinates.
     fn translate_point(&self, p: geom::Point) -> geom::Point {
         let x = i64::from(p.x) + self.origin.x;
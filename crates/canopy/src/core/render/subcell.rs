@@ -0,0 +1,179 @@
+//! Glyph grids that divide a terminal cell into smaller pixels.
+
+use crate::{
+    CommandEnum,
+    geom::Size,
+    style::{AttrSet, Color, ResolvedStyle},
+};
+
+/// Braille dot bit for each pixel of a 2x4 cell, in row-major order.
+const BRAILLE_DOTS: [u8; 8] = [0, 3, 1, 4, 2, 5, 6, 7];
+
+/// Quadrant glyphs indexed by pixel mask.
+const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// A glyph set that divides each cell into a grid of two-color pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, CommandEnum)]
+pub enum SubCell {
+    /// Upper and lower half blocks, 1x2 pixels per cell.
+    #[default]
+    HalfBlock,
+    /// Quadrant blocks, 2x2 pixels per cell.
+    Quadrant,
+    /// Sextant blocks from Symbols for Legacy Computing, 2x3 pixels per cell.
+    Sextant,
+    /// Braille patterns, 2x4 dots per cell.
+    Braille,
+}
+
+impl SubCell {
+    /// Every grid, from lowest to highest resolution.
+    pub const ALL: [Self; 4] = [
+        Self::HalfBlock,
+        Self::Quadrant,
+        Self::Sextant,
+        Self::Braille,
+    ];
+
+    /// Return the number of pixels across and down one cell.
+    pub fn size(self) -> Size {
+        match self {
+            Self::HalfBlock => Size::new(1, 2),
+            Self::Quadrant => Size::new(2, 2),
+            Self::Sextant => Size::new(2, 3),
+            Self::Braille => Size::new(2, 4),
+        }
+    }
+
+    /// Return the glyph that sets the foreground pixels in a mask.
+    ///
+    /// Bit `i` of the mask is the pixel at index `i` of the cell in row-major order. An empty
+    /// mask is a space.
+    pub fn glyph(self, mask: u8) -> char {
+        match self {
+            Self::HalfBlock => match mask & 0b11 {
+                0 => ' ',
+                1 => '▀',
+                2 => '▄',
+                _ => '█',
+            },
+            Self::Quadrant => QUADRANTS[usize::from(mask & 0xf)],
+            Self::Sextant => match mask & 0x3f {
+                0 => ' ',
+                21 => '▌',
+                42 => '▐',
+                63 => '█',
+                m => {
+                    // The block skips the two glyphs that already exist as half blocks.
+                    let skipped = u32::from(m > 21) + u32::from(m > 42);
+                    char::from_u32(0x1fb00 + u32::from(m) - 1 - skipped).unwrap_or(' ')
+                }
+            },
+            Self::Braille => {
+                if mask == 0 {
+                    return ' ';
+                }
+                let dots = (0..8)
+                    .filter(|i| mask & (1 << i) != 0)
+                    .fold(0u32, |dots, i| dots | (1 << BRAILLE_DOTS[i]));
+                char::from_u32(0x2800 + dots).unwrap_or(' ')
+            }
+        }
+    }
+
+    /// Choose the glyph and colors that best show one cell's pixels.
+    ///
+    /// Pixels are in row-major order and `None` marks background pixels, which take the
+    /// background color. The pixels are split into the foreground and background pair with the
+    /// least squared color error. A side made of one color keeps it exactly; a mixed side gets
+    /// the average. Background pixels stay on the background side, and braille keeps the
+    /// larger side as background so its dots draw the detail.
+    pub fn cell(self, pixels: &[Option<Color>], background: Color) -> (char, ResolvedStyle) {
+        let count = pixels.len().min(8);
+        let pixels = &pixels[..count];
+        let colors: Vec<Color> = pixels.iter().map(|p| p.unwrap_or(background)).collect();
+        let full = (1u16 << count) - 1;
+        let side = |mask: u16| (0..count).filter(move |i| mask & (1 << i) != 0);
+
+        let mut best = (f64::INFINITY, full);
+        // Pixel 0 is always foreground here; swapping sides gives the same split.
+        for mask in (1..=full).step_by(2) {
+            let error = split_error(&colors, side(mask)) + split_error(&colors, side(!mask & full));
+            if error < best.0 {
+                best = (error, mask);
+            }
+        }
+        let mut mask = best.1;
+
+        let has_background = |mask: u16| side(mask).any(|i| pixels[i].is_none());
+        let invert = match (has_background(mask), has_background(!mask & full)) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => self == Self::Braille && side(mask).count() * 2 > count,
+        };
+        if invert {
+            mask = !mask & full;
+        }
+
+        let fg = side_color(&colors, side(mask));
+        let bg = side_color(&colors, side(!mask & full));
+        let (ch, fg, bg) = match (fg, bg) {
+            (Some(fg), Some(bg)) if fg != bg => (self.glyph(mask as u8), fg, bg),
+            (Some(color), _) | (_, Some(color)) => (' ', color, color),
+            (None, None) => (' ', background, background),
+        };
+        (ch, ResolvedStyle::new(fg, bg, AttrSet::default()))
+    }
+}
+
+/// Return the RGB channels of a color as an array.
+fn channels(color: Color) -> [u8; 3] {
+    let (r, g, b) = color.rgb();
+    [r, g, b]
+}
+
+/// Return the summed squared distance of some pixels from their mean color.
+fn split_error(colors: &[Color], pixels: impl Iterator<Item = usize>) -> f64 {
+    let mut count = 0.0;
+    let mut sum = [0.0f64; 3];
+    let mut squares = 0.0;
+    for i in pixels {
+        for (total, channel) in sum.iter_mut().zip(channels(colors[i])) {
+            let value = f64::from(channel);
+            *total += value;
+            squares += value * value;
+        }
+        count += 1.0;
+    }
+    if count == 0.0 {
+        return 0.0;
+    }
+    squares - sum.iter().map(|total| total * total).sum::<f64>() / count
+}
+
+/// Return the color of some pixels: their shared color, their average, or `None` if empty.
+fn side_color(colors: &[Color], pixels: impl Iterator<Item = usize>) -> Option<Color> {
+    let mut first = None;
+    let mut mixed = false;
+    let mut count = 0u32;
+    let mut sum = [0u32; 3];
+    for i in pixels {
+        let color = colors[i];
+        match first {
+            None => first = Some(color),
+            Some(seen) if seen != color => mixed = true,
+            Some(_) => {}
+        }
+        for (total, channel) in sum.iter_mut().zip(channels(color)) {
+            *total += u32::from(channel);
+        }
+        count += 1;
+    }
+    if !mixed {
+        return first;
+    }
+    let [r, g, b] = sum.map(|total| u8::try_from(total / count).unwrap_or(u8::MAX));
+    Some(Color::Rgb { r, g, b })
+}
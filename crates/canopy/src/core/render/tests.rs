@@ -609,3 +609,94 @@ fn test_part_render_multiple_rectangles() {
         ));
     }
 }
+
+#[test]
+fn image_placements_crop_the_source_with_the_clip() {
+    let mut target = TestTarget::new(geom::Rect::new(5, 5, 10, 10));
+    let image = Arc::new(Pixmap::new(40, 20, vec![0; 40 * 20 * 4]).expect("pixmap should build"));
+
+    // The destination starts two cells left of and one row above the clip.
+    target
+        .render(|r| r.image(&image, image.rect(), geom::Rect::new(3, 4, 20, 10)))
+        .unwrap();
+
+    let placement = &target.buf.images[0];
+    assert_eq!(placement.dest, geom::Rect::new(0, 0, 10, 9));
+    assert_eq!(placement.source, geom::Rect::new(4, 2, 20, 18));
+    assert_eq!(target.buf.images().len(), 1);
+
+    // Drawing over a covered cell withdraws the image.
+    target
+        .render(|r| r.fill("default", geom::Rect::new(6, 6, 1, 1), '#'))
+        .unwrap();
+    assert!(target.buf.images().is_empty());
+}
+
+#[test]
+fn subcell_glyphs_follow_row_major_masks() {
+    assert_eq!(SubCell::HalfBlock.glyph(0b01), '▀');
+    assert_eq!(SubCell::HalfBlock.glyph(0b10), '▄');
+    assert_eq!(SubCell::Quadrant.glyph(0b0110), '▞');
+    assert_eq!(SubCell::Quadrant.glyph(0b1101), '▙');
+    assert_eq!(SubCell::Sextant.glyph(0b000001), '\u{1fb00}');
+    assert_eq!(SubCell::Sextant.glyph(0b010101), '▌');
+    assert_eq!(SubCell::Sextant.glyph(0b010110), '\u{1fb14}');
+    assert_eq!(SubCell::Sextant.glyph(0b111110), '\u{1fb3b}');
+    assert_eq!(SubCell::Braille.glyph(0b0000_0001), '⠁');
+    assert_eq!(SubCell::Braille.glyph(0b0000_0010), '⠈');
+    assert_eq!(SubCell::Braille.glyph(0b1100_0000), '⣀');
+    for grid in SubCell::ALL {
+        assert_eq!(grid.glyph(0), ' ');
+    }
+}
+
+#[test]
+fn subcell_cells_split_pixels_into_the_closest_pair() {
+    let red = Color::Rgb { r: 200, g: 0, b: 0 };
+    let dark = Color::Rgb { r: 40, g: 0, b: 0 };
+    let blue = Color::Blue;
+
+    // Two reds and two blues split exactly, keeping named colors.
+    let (ch, style) = SubCell::Quadrant.cell(&[Some(red), Some(blue), Some(blue), Some(red)], blue);
+    assert_eq!((ch, style.fg, style.bg), ('▚', red, blue));
+
+    // Similar colors share a side and are averaged.
+    let (ch, style) = SubCell::Quadrant.cell(
+        &[Some(red), Some(dark), Some(blue), Some(blue)],
+        Color::Black,
+    );
+    assert_eq!(ch, '▀');
+    assert_eq!(style.fg, Color::Rgb { r: 120, g: 0, b: 0 });
+    assert_eq!(style.bg, blue);
+
+    // Background pixels stay behind braille dots, and uniform cells are blank.
+    let mut dots = [None; 8];
+    dots[0] = Some(red);
+    dots[7] = Some(red);
+    let (ch, style) = SubCell::Braille.cell(&dots, Color::Black);
+    assert_eq!((ch, style.fg, style.bg), ('⢁', red, Color::Black));
+    let (ch, style) = SubCell::Braille.cell(&[None; 8], Color::Black);
+    assert_eq!((ch, style.bg), (' ', Color::Black));
+}
+
+#[test]
+fn subcells_sample_only_visible_cells() {
+    let mut target = TestTarget::new(geom::Rect::new(1, 0, 2, 1));
+    let mut calls = Vec::new();
+    target
+        .render(|r| {
+            r.subcells(
+                SubCell::Quadrant,
+                geom::Rect::new(0, 0, 3, 1),
+                Color::Black,
+                |x, y| {
+                    calls.push((x, y));
+                    (x % 2 == 0).then_some(Color::White)
+                },
+            )
+        })
+        .unwrap();
+    assert_eq!(calls.len(), 8);
+    assert!(calls.iter().all(|(x, _)| *x >= 2));
+    target.assert_matches(buf!("▌▌"));
+}
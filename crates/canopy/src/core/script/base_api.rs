@@ -18,17 +18,19 @@ use ruau::{
 };
 
 use super::{
-    ArgValue, Canopy, ChangeOutcome, CommandSet, Context, CoreContext, CoreViewContext, FocusScope,
-    NodeId, PathFilter, Pin, Point, RectI32, ReentrantCanopyGuard, Result, ViewContext,
-    available_bindings_to_arg, base_api, binding_info_to_arg, canopy_to_host, command_info_to_arg,
-    commands, defs, dispatch_command, dispatch_command_by_name, error, fixtures_to_arg,
-    host_return, host_value, inputmap, key, luau_global_owner_name, mouse, node_handle_type,
-    node_id_from_value, node_id_to_arg, node_info_to_arg, node_list_to_arg, owned_truthy,
-    owned_value_to_display, ret_arg, ret_none, ret_one, route_trace_to_arg,
-    scoped_value_to_display, scoped_value_to_string, screen_cells_to_arg, screen_text,
-    screen_text_for_rect, screen_to_arg, script_callback_label, script_journal_to_arg,
-    tree_node_to_arg, validate_node_handle, values_to_args, with_current_canopy, yield_now,
+    ArgValue, Canopy, ChangeOutcome, CommandSet, Context, CoreContext, CoreViewContext, Event,
+    ExportFormat, FocusScope, NodeId, PathFilter, Pin, Point, Rect, RectI32, ReentrantCanopyGuard,
+    Result, Size, ViewContext, available_bindings_to_arg, base_api, binding_info_to_arg,
+    canopy_to_host, command_info_to_arg, commands, defs, dispatch_command,
+    dispatch_command_by_name, error, fixtures_to_arg, host_return, host_value, inputmap, key,
+    luau_global_owner_name, mouse, node_handle_type, node_id_from_value, node_id_to_arg,
+    node_info_to_arg, node_list_to_arg, owned_truthy, owned_value_to_display, ret_arg, ret_none,
+    ret_one, route_trace_to_arg, scoped_value_to_display, scoped_value_to_string,
+    screen_cells_to_arg, screen_text, screen_text_for_rect, screen_to_arg, script_callback_label,
+    script_journal_to_arg, tree_node_to_arg, validate_node_handle, values_to_args,
+    with_current_canopy, yield_now,
 };
+use crate::poll::VirtualClock;
 
 /// The native implementation behind one base API function.
 enum Handler {
@@ -48,6 +50,8 @@ struct BaseFunction {
     signature: fn() -> FunctionSignature,
     /// Native host implementation.
     handler: Handler,
+    /// Whether the function changes app state, and so is refused to read-only evaluations.
+    mutates: bool,
 }
 
 /// Native functions exposed on the `canopy` library table.
@@ -57,12 +61,14 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
         docs: &["Return the root node."],
         signature: || FunctionSignature::new().ret(Type::named("NodeId")),
         handler: Handler::Sync(host_root),
+        mutates: false,
     },
     BaseFunction {
         name: "focused",
         docs: &["Return the currently focused node, or nil when nothing is focused."],
         signature: || FunctionSignature::new().ret(Type::named("NodeId").optional()),
         handler: Handler::Sync(host_focused),
+        mutates: false,
     },
     BaseFunction {
         name: "node_info",
@@ -73,6 +79,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeInfo"))
         },
         handler: Handler::Sync(host_node_info),
+        mutates: false,
     },
     BaseFunction {
         name: "find_node",
@@ -83,6 +90,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").optional())
         },
         handler: Handler::Sync(host_find_node),
+        mutates: false,
     },
     BaseFunction {
         name: "find_nodes",
@@ -93,6 +101,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").array())
         },
         handler: Handler::Sync(host_find_nodes),
+        mutates: false,
     },
     BaseFunction {
         name: "parent",
@@ -103,6 +112,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").optional())
         },
         handler: Handler::Sync(host_parent),
+        mutates: false,
     },
     BaseFunction {
         name: "children",
@@ -113,12 +123,14 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").array())
         },
         handler: Handler::Sync(host_children),
+        mutates: false,
     },
     BaseFunction {
         name: "tree",
         docs: &["Return a recursive snapshot of the entire tree rooted at `canopy.root()`."],
         signature: || FunctionSignature::new().ret(Type::named("TreeNode")),
         handler: Handler::Sync(host_tree),
+        mutates: false,
     },
     BaseFunction {
         name: "node_at",
@@ -130,6 +142,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").optional())
         },
         handler: Handler::Sync(host_node_at),
+        mutates: false,
     },
     BaseFunction {
         name: "set_focus",
@@ -140,18 +153,21 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Boolean)
         },
         handler: Handler::Sync(host_set_focus),
+        mutates: true,
     },
     BaseFunction {
         name: "focus_next",
         docs: &["Move focus to the next focusable node in global focus order."],
         signature: FunctionSignature::new,
         handler: Handler::Sync(host_focus_next),
+        mutates: true,
     },
     BaseFunction {
         name: "focus_prev",
         docs: &["Move focus to the previous focusable node in global focus order."],
         signature: FunctionSignature::new,
         handler: Handler::Sync(host_focus_prev),
+        mutates: true,
     },
     BaseFunction {
         name: "focus_dir",
@@ -160,6 +176,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
             FunctionSignature::new().param(("dir", Type::literals(["Up", "Down", "Left", "Right"])))
         },
         handler: Handler::Sync(host_focus_dir),
+        mutates: true,
     },
     BaseFunction {
         name: "send_key",
@@ -168,6 +185,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
         ],
         signature: || FunctionSignature::new().param(("key", Type::String)),
         handler: Handler::Sync(host_send_key),
+        mutates: true,
     },
     BaseFunction {
         name: "send_click",
@@ -178,6 +196,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .param(("y", Type::Number))
         },
         handler: Handler::Sync(host_send_click),
+        mutates: true,
     },
     BaseFunction {
         name: "send_scroll",
@@ -189,6 +208,42 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .param(("y", Type::Number))
         },
         handler: Handler::Sync(host_send_scroll),
+        mutates: true,
+    },
+    BaseFunction {
+        name: "send_paste",
+        docs: &["Inject a bracketed paste of text into the focused node."],
+        signature: || FunctionSignature::new().param(("text", Type::String)),
+        handler: Handler::Sync(host_send_paste),
+        mutates: true,
+    },
+    BaseFunction {
+        name: "resize",
+        docs: &["Resize the screen to a width and height in cells, as a terminal resize does."],
+        signature: || {
+            FunctionSignature::new()
+                .param(("width", Type::Number))
+                .param(("height", Type::Number))
+        },
+        handler: Handler::Sync(host_resize),
+        mutates: true,
+    },
+    BaseFunction {
+        name: "render",
+        docs: &["Lay out and render pending changes, as the event loop does after each input."],
+        signature: FunctionSignature::new,
+        handler: Handler::Sync(host_render),
+        mutates: false,
+    },
+    BaseFunction {
+        name: "advance_time",
+        docs: &[
+            "Advance the virtual clock by milliseconds, firing polls as their deadlines pass.",
+            "Errors unless the app runs on a virtual clock.",
+        ],
+        signature: || FunctionSignature::new().param(("ms", Type::Number)),
+        handler: Handler::Sync(host_advance_time),
+        mutates: true,
     },
     BaseFunction {
         name: "cmd",
@@ -200,6 +255,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Any)
         },
         handler: Handler::Sync(host_cmd),
+        mutates: true,
     },
     BaseFunction {
         name: "cmd_on",
@@ -212,6 +268,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Any)
         },
         handler: Handler::Sync(host_cmd_on),
+        mutates: true,
     },
     BaseFunction {
         name: "resolve",
@@ -222,60 +279,70 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("NodeId").optional())
         },
         handler: Handler::Sync(host_resolve),
+        mutates: false,
     },
     BaseFunction {
         name: "bindings",
         docs: &["Return the active binding table across all modes."],
         signature: || FunctionSignature::new().ret(Type::named("BindingInfo").array()),
         handler: Handler::Sync(host_bindings),
+        mutates: false,
     },
     BaseFunction {
         name: "commands",
         docs: &["Return structured metadata for all registered commands."],
         signature: || FunctionSignature::new().ret(Type::named("CommandInfo").array()),
         handler: Handler::Sync(host_commands),
+        mutates: false,
     },
     BaseFunction {
         name: "input_mode",
         docs: &["Return the active input mode. The default mode is the empty string."],
         signature: || FunctionSignature::new().ret(Type::String),
         handler: Handler::Sync(host_input_mode),
+        mutates: false,
     },
     BaseFunction {
         name: "set_mode",
         docs: &["Switch the active input mode. Passing the empty string returns to default mode."],
         signature: || FunctionSignature::new().param(("mode", Type::String)),
         handler: Handler::Sync(host_set_mode),
+        mutates: true,
     },
     BaseFunction {
         name: "push_mode",
         docs: &["Push an input mode above the current mode."],
         signature: || FunctionSignature::new().param(("mode", Type::String)),
         handler: Handler::Sync(host_push_mode),
+        mutates: true,
     },
     BaseFunction {
         name: "pop_mode",
         docs: &["Pop the top input mode and return the active mode after the pop."],
         signature: || FunctionSignature::new().ret(Type::String),
         handler: Handler::Sync(host_pop_mode),
+        mutates: true,
     },
     BaseFunction {
         name: "screen",
         docs: &["Return the rendered screen as rows of cell strings."],
         signature: || FunctionSignature::new().ret(Type::String.array().array()),
         handler: Handler::Sync(host_screen),
+        mutates: false,
     },
     BaseFunction {
         name: "screen_cells",
         docs: &["Return the rendered screen as rows of styled cell records."],
         signature: || FunctionSignature::new().ret(Type::named("ScreenCell").array().array()),
         handler: Handler::Sync(host_screen_cells),
+        mutates: false,
     },
     BaseFunction {
         name: "screen_text",
         docs: &["Return the rendered screen as newline-joined plain text."],
         signature: || FunctionSignature::new().ret(Type::String),
         handler: Handler::Sync(host_screen_text),
+        mutates: false,
     },
     BaseFunction {
         name: "screen_region",
@@ -289,6 +356,20 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::String)
         },
         handler: Handler::Sync(host_screen_region),
+        mutates: false,
+    },
+    BaseFunction {
+        name: "export_screen",
+        docs: &[
+            "Export the rendered screen as an SVG image, an HTML document or ANSI-escaped text.",
+        ],
+        signature: || {
+            FunctionSignature::new()
+                .param(("format", Type::literals(["svg", "html", "ansi"])))
+                .ret(Type::String)
+        },
+        handler: Handler::Sync(host_export_screen),
+        mutates: false,
     },
     BaseFunction {
         name: "node_region",
@@ -299,12 +380,14 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::String)
         },
         handler: Handler::Sync(host_node_region),
+        mutates: false,
     },
     BaseFunction {
         name: "route_trace",
         docs: &["Return the most recent input route trace."],
         signature: || FunctionSignature::new().ret(Type::named("RouteTraceEntry").array()),
         handler: Handler::Sync(host_route_trace),
+        mutates: false,
     },
     BaseFunction {
         name: "diagnostic_dump",
@@ -315,6 +398,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::String)
         },
         handler: Handler::Sync(host_diagnostic_dump),
+        mutates: false,
     },
     BaseFunction {
         name: "available_bindings",
@@ -325,18 +409,21 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::named("BindingSnapshot"))
         },
         handler: Handler::Sync(host_available_bindings),
+        mutates: false,
     },
     BaseFunction {
         name: "script_journal",
         docs: &["Return recorded script evaluations for replay and diagnostics."],
         signature: || FunctionSignature::new().ret(Type::named("ScriptJournalEntry").array()),
         handler: Handler::Sync(host_script_journal),
+        mutates: false,
     },
     BaseFunction {
         name: "api",
         docs: &["Return the generated Luau API definition for this app."],
         signature: || FunctionSignature::new().ret(Type::String),
         handler: Handler::Sync(host_api),
+        mutates: false,
     },
     BaseFunction {
         name: "bind",
@@ -349,6 +436,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Number)
         },
         handler: Handler::Sync(host_bind),
+        mutates: true,
     },
     BaseFunction {
         name: "bind_mouse",
@@ -361,6 +449,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Number)
         },
         handler: Handler::Sync(host_bind_mouse),
+        mutates: true,
     },
     BaseFunction {
         name: "unbind",
@@ -371,6 +460,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Boolean)
         },
         handler: Handler::Sync(host_unbind),
+        mutates: true,
     },
     BaseFunction {
         name: "unbind_key",
@@ -381,12 +471,14 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .param(("options", Type::named("UnbindSelector").optional()))
         },
         handler: Handler::Sync(host_unbind_key),
+        mutates: true,
     },
     BaseFunction {
         name: "clear_bindings",
         docs: &["Remove every binding from every mode."],
         signature: FunctionSignature::new,
         handler: Handler::Sync(host_clear_bindings),
+        mutates: true,
     },
     BaseFunction {
         name: "on_start",
@@ -395,12 +487,14 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
             FunctionSignature::new().param(("handler", Type::func(FunctionSignature::new())))
         },
         handler: Handler::Sync(host_on_start),
+        mutates: true,
     },
     BaseFunction {
         name: "log",
         docs: &["Append a log line to the evaluation result."],
         signature: || FunctionSignature::new().param(("message", Type::Any)),
         handler: Handler::Sync(host_log),
+        mutates: false,
     },
     BaseFunction {
         name: "assert",
@@ -411,6 +505,20 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .param(("message", Type::String.optional()))
         },
         handler: Handler::Sync(host_assert),
+        mutates: false,
+    },
+    BaseFunction {
+        name: "assert_snapshot",
+        docs: &[
+            "Fail the script when the screen, or a region of it, differs from a named snapshot file.",
+        ],
+        signature: || {
+            FunctionSignature::new()
+                .param(("name", Type::String))
+                .param(("region", Type::named("Rect").optional()))
+        },
+        handler: Handler::Sync(host_assert_snapshot),
+        mutates: false,
     },
     BaseFunction {
         name: "wait_for",
@@ -425,6 +533,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Boolean)
         },
         handler: Handler::Async(|| async_host_fn(wait_for_predicate)),
+        mutates: false,
     },
     BaseFunction {
         name: "wait_for_node",
@@ -436,6 +545,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Boolean)
         },
         handler: Handler::Async(|| async_host_fn(wait_for_node)),
+        mutates: false,
     },
     BaseFunction {
         name: "wait_for_screen_text",
@@ -447,6 +557,7 @@ const CANOPY_FUNCTIONS: &[BaseFunction] = &[
                 .ret(Type::Boolean)
         },
         handler: Handler::Async(|| async_host_fn(wait_for_screen_text)),
+        mutates: false,
     },
 ];
 
@@ -458,6 +569,19 @@ pub(super) fn register(builder: &mut module::Builder) {
             function.docs,
         );
         match function.handler {
+            Handler::Sync(handler) if function.mutates => {
+                let api = format!("canopy.{}", function.name);
+                builder.borrowed_function(
+                    function.name,
+                    binding,
+                    move |scope: &Scope<'_>, args: MultiValue<'_>| {
+                        with_current_canopy(scope, |canopy, _| {
+                            canopy.script_host.ensure_writable(&api)
+                        })?;
+                        handler(scope, args)
+                    },
+                );
+            }
             Handler::Sync(handler) => {
                 builder.borrowed_function(function.name, binding, handler);
             }
@@ -494,6 +618,19 @@ fn point_from_coords(x: i64, y: i64) -> Result<Point> {
     Ok(Point { x, y })
 }
 
+/// Convert script screen dimensions into a non-empty size.
+fn size_from_dims(width: i64, height: i64) -> Result<Size> {
+    let w = u32::try_from(width)
+        .ok()
+        .filter(|w| *w > 0)
+        .ok_or_else(|| error::Error::Script(format!("width must be > 0, got {width}")))?;
+    let h = u32::try_from(height)
+        .ok()
+        .filter(|h| *h > 0)
+        .ok_or_else(|| error::Error::Script(format!("height must be > 0, got {height}")))?;
+    Ok(Size::new(w, h))
+}
+
 /// Parsed options for script-created bindings.
 #[derive(Debug, Clone)]
 struct ScriptBindOptions {
@@ -770,13 +907,25 @@ where
         AsyncHostContext,
     ) -> Pin<Box<dyn Future<Output = StdResult<bool, RuntimeError>> + Send>>,
 {
-    let started = Instant::now();
+    let clock = ctx
+        .scope(|scope| {
+            let canopy = scope
+                .context_mut::<Canopy>()
+                .ok_or_else(|| RuntimeError::runtime("no active canopy context"))?;
+            Ok(canopy.virtual_clock())
+        })
+        .await?;
+    // Under a virtual clock the timeout is measured in virtual time, and waiting advances it.
+    let now = move || clock.as_ref().map_or_else(Instant::now, VirtualClock::now);
+    let started = now();
+    let limit = timeout_ms.and_then(|ms| started.checked_add(Duration::from_millis(ms)));
     loop {
         ctx.scope(|scope| {
             let mut canopy = scope
                 .context_mut::<Canopy>()
                 .ok_or_else(|| RuntimeError::runtime("no active canopy context"))?;
             canopy.service_automation();
+            canopy.service_tasks().map_err(|err| canopy_to_host(&err))?;
             Ok(())
         })
         .await?;
@@ -784,11 +933,23 @@ where
             return Ok(host_return(true));
         }
         if let Some(timeout_ms) = timeout_ms
-            && started.elapsed() >= Duration::from_millis(timeout_ms)
+            && now().saturating_duration_since(started) >= Duration::from_millis(timeout_ms)
         {
             return Err(wait_timeout(timeout_ms));
         }
-        yield_now().await;
+        let advanced = ctx
+            .scope(|scope| {
+                let mut canopy = scope
+                    .context_mut::<Canopy>()
+                    .ok_or_else(|| RuntimeError::runtime("no active canopy context"))?;
+                canopy
+                    .advance_time_toward(limit)
+                    .map_err(|err| canopy_to_host(&err))
+            })
+            .await?;
+        if !advanced {
+            yield_now().await;
+        }
     }
 }
 
@@ -870,6 +1031,9 @@ type HostHandler =
 /// Run an owner's default-bindings script inside the current live scope.
 fn run_default_bindings_in_scope(scope: &Scope<'_>, owner: &str) -> Result<()> {
     let run = with_current_canopy(scope, |canopy, _| {
+        canopy
+            .script_host
+            .ensure_writable(&format!("{owner}.default_bindings"))?;
         canopy.prepare_registered_default_bindings(owner)
     })?;
     let result = run.host.execute_in_scope(scope, run.root_id, run.script_id);
@@ -981,6 +1145,40 @@ fn host_assert<'s>(
     }
 }
 
+/// `canopy.assert_snapshot`: compare the screen against a snapshot file and record the result.
+fn host_assert_snapshot<'s>(
+    scope: &Scope<'s>,
+    args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    let mut args = ArgReader::new(args);
+    let name = args.string(scope)?;
+    let region = match args.opt_table(scope)? {
+        Some(table) => Some(rect_from_table(scope, &table)?),
+        None => None,
+    };
+    let (passed, message) = with_current_canopy(scope, |canopy, _| {
+        let region = match region {
+            Some(region) => {
+                canopy.refresh_snapshot()?;
+                let bounds = canopy.buf().map_or_else(Rect::zero, |buf| buf.rect());
+                Some(region.intersect_rect(bounds).unwrap_or_else(Rect::zero))
+            }
+            None => None,
+        };
+        let check = canopy.check_snapshot(&name, region)?;
+        let message = format!("snapshot {name:?} {check}");
+        canopy
+            .script_host
+            .push_assertion(check.passed(), message.clone());
+        Ok((check.passed(), message))
+    })?;
+    if passed {
+        Ok(ret_none())
+    } else {
+        Err(RuntimeError::runtime(message))
+    }
+}
+
 /// `canopy.root`: return the root node id.
 fn host_root<'s>(
     scope: &Scope<'s>,
@@ -1243,6 +1441,62 @@ fn host_send_scroll<'s>(
     })?;
     Ok(ret_none())
 }
+
+/// `canopy.send_paste`: inject a bracketed paste into the focused node.
+fn host_send_paste<'s>(
+    scope: &Scope<'s>,
+    args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    let mut args = ArgReader::new(args);
+    let text = args.string(scope)?;
+    with_current_canopy(scope, |canopy, _| {
+        let _reentrant = ReentrantCanopyGuard::push(canopy);
+        canopy.event(Event::Paste(text))
+    })?;
+    Ok(ret_none())
+}
+
+/// `canopy.resize`: resize the screen.
+fn host_resize<'s>(
+    scope: &Scope<'s>,
+    args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    let mut args = ArgReader::new(args);
+    let width = args.integer(scope)?;
+    let height = args.integer(scope)?;
+    with_current_canopy(scope, |canopy, _| {
+        let size = size_from_dims(width, height)?;
+        let _reentrant = ReentrantCanopyGuard::push(canopy);
+        canopy.event(Event::Resize(size))
+    })?;
+    Ok(ret_none())
+}
+
+/// `canopy.render`: lay out and render pending changes.
+fn host_render<'s>(
+    scope: &Scope<'s>,
+    _args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    with_current_canopy(scope, |canopy, _| canopy.refresh_snapshot())?;
+    Ok(ret_none())
+}
+
+/// `canopy.advance_time`: advance the virtual clock and fire due polls.
+fn host_advance_time<'s>(
+    scope: &Scope<'s>,
+    args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    let mut args = ArgReader::new(args);
+    let ms = args.integer(scope)?;
+    with_current_canopy(scope, |canopy, _| {
+        let ms = u64::try_from(ms)
+            .map_err(|_| error::Error::Script(format!("ms must be >= 0, got {ms}")))?;
+        let _reentrant = ReentrantCanopyGuard::push(canopy);
+        canopy.advance_time(Duration::from_millis(ms))
+    })?;
+    Ok(ret_none())
+}
+
 /// `canopy.bindings`: return the active binding table across all modes.
 fn host_bindings<'s>(
     scope: &Scope<'s>,
@@ -1450,6 +1704,41 @@ fn host_screen_text<'s>(
     Ok(ret_one(ScopedValue::String(scope.create_string(&text)?)))
 }
 
+/// `canopy.export_screen`: export the rendered screen in a named format.
+fn host_export_screen<'s>(
+    scope: &Scope<'s>,
+    args: MultiValue<'s>,
+) -> StdResult<MultiValue<'s>, RuntimeError> {
+    let mut args = ArgReader::new(args);
+    let format: ExportFormat = args
+        .string(scope)?
+        .parse()
+        .map_err(|err| canopy_to_host(&err))?;
+    let output = with_current_canopy(scope, |canopy, _| canopy.export_screen(format))?;
+    Ok(ret_one(ScopedValue::String(scope.create_string(&output)?)))
+}
+
+/// Read a `Rect` table as a signed screen rectangle.
+fn rect_from_table<'s>(scope: &Scope<'s>, table: &Table<'s>) -> StdResult<RectI32, RuntimeError> {
+    let field = |name: &str| -> StdResult<i64, RuntimeError> {
+        match table.get::<_, ScopedValue>(scope, name)? {
+            ScopedValue::Integer(value) => Ok(value),
+            ScopedValue::Number(value) if value.fract() == 0.0 => Ok(value as i64),
+            other => Err(RuntimeError::runtime(format!(
+                "region.{name}: expected integer, got {}",
+                other.type_name()
+            ))),
+        }
+    };
+    let (x, y, w, h) = (field("x")?, field("y")?, field("w")?, field("h")?);
+    Ok(RectI32::new(
+        i32::try_from(x).unwrap_or(if x < 0 { i32::MIN } else { i32::MAX }),
+        i32::try_from(y).unwrap_or(if y < 0 { i32::MIN } else { i32::MAX }),
+        u32::try_from(w.max(0)).unwrap_or(u32::MAX),
+        u32::try_from(h.max(0)).unwrap_or(u32::MAX),
+    ))
+}
+
 /// `canopy.screen_region`: return rendered plain text inside a screen rectangle.
 fn host_screen_region<'s>(
     scope: &Scope<'s>,
@@ -299,6 +299,12 @@ fn register_observation_info(builder: &mut module::Builder) {
                 .doc("Resolved text attributes such as bold or underline."),
             declaration::Field::new("continuation", declaration::Type::Boolean)
                 .doc("True when this cell continues a wide grapheme."),
+            declaration::Field::new("link", declaration::Type::String.optional())
+                .doc("Hyperlink target carried by this cell, if any."),
+            declaration::Field::new("underline_style", declaration::Type::String.optional())
+                .doc("Underline line style for underlined cells: single, double, curly, dotted or dashed."),
+            declaration::Field::new("underline_color", declaration::Type::String.optional())
+                .doc("Underline color as #rrggbb, if it differs from the text color."),
         ]),
     ));
     builder.alias(declaration::Alias::new(
@@ -69,6 +69,9 @@ pub(super) fn dispatch_command(
     allow_map_named: bool,
 ) -> Result<ArgValue> {
     with_current_canopy(scope, |canopy, _| {
+        canopy
+            .script_host
+            .ensure_writable(&format!("command {}", spec.id.0))?;
         let args = build_args_from_values(spec, values, allow_map_named).map_err(|message| {
             error::Error::from(commands::CommandError::conversion(format!(
                 "command {}: {message}",
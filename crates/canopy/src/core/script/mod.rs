@@ -41,8 +41,9 @@ use crate::{
         widget_access,
     },
     error::{self, Result},
-    event::{key, mouse},
-    geom::{Point, RectI32, Size},
+    event::{Event, key, mouse},
+    export::ExportFormat,
+    geom::{Point, Rect, RectI32, Size},
     path::PathFilter,
     style::{AttrSet, Color},
 };
@@ -418,6 +419,8 @@ struct LuauState {
     finalized: bool,
     /// Whether a top-level async eval is currently driving the retained VM.
     active_eval: bool,
+    /// Whether evaluations refuse APIs that mutate the app.
+    read_only: bool,
     /// Deferred hooks to execute after the first live render.
     on_start_hooks: Vec<LuauFunctionId>,
     /// Optional one-shot finalization failure used by deterministic tests.
@@ -651,6 +654,24 @@ impl LuauHost {
         Ok(())
     }
 
+    /// Refuse or allow APIs that mutate the app in the evaluations that follow.
+    pub fn set_read_only(&self, read_only: bool) {
+        self.state.borrow_mut().read_only = read_only;
+    }
+
+    /// Fail when a read-only evaluation calls an API that mutates the app.
+    pub(crate) fn ensure_writable(&self, api: &str) -> Result<()> {
+        if !self.state.borrow().read_only {
+            return Ok(());
+        }
+        Err(error::Error::ScriptStructured {
+            kind: error::ScriptErrorKind::ReadOnly,
+            command: None,
+            owner: None,
+            message: format!("{api} mutates the app and is not available to read-only scripts"),
+        })
+    }
+
     /// Mark the retained runtime as busy with a top-level async evaluation.
     fn begin_active_eval(&self) -> Result<ActiveEvalGuard> {
         let mut state = self.state.borrow_mut();
@@ -265,7 +265,7 @@ pub(super) fn screen_cells_to_arg(canopy: &mut Canopy) -> Result<ArgValue> {
 
 /// Convert one terminal cell into a scripting record.
 fn cell_to_arg(x: u32, y: u32, cell: &Cell) -> ArgValue {
-    ArgValue::Map(BTreeMap::from([
+    let mut map = BTreeMap::from([
         ("x".to_string(), ArgValue::UInt(u64::from(x))),
         ("y".to_string(), ArgValue::UInt(u64::from(y))),
         ("text".to_string(), ArgValue::String(cell.rendered_text())),
@@ -276,7 +276,20 @@ fn cell_to_arg(x: u32, y: u32, cell: &Cell) -> ArgValue {
             "continuation".to_string(),
             ArgValue::Bool(cell.continuation),
         ),
-    ]))
+    ]);
+    if let Some(link) = &cell.link {
+        map.insert("link".to_string(), ArgValue::String(link.to_string()));
+    }
+    if cell.style.attrs.underline {
+        map.insert(
+            "underline_style".to_string(),
+            ArgValue::String(cell.style.attrs.underline_style.name().to_string()),
+        );
+    }
+    if let Some(color) = cell.style.underline {
+        map.insert("underline_color".to_string(), color_to_arg(color));
+    }
+    ArgValue::Map(map)
 }
 
 /// Convert a color to a stable RGB string.
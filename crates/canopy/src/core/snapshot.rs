@@ -0,0 +1,893 @@
+//! Golden screen snapshots.
+//!
+//! A [`Snapshot`] records the rendered text of a screen region and, optionally, the style of
+//! every cell. Snapshots are stored as named `.snap` files under a [`SnapshotConfig`]
+//! directory and compared cell by cell, so a failure lists each changed cell with its expected
+//! and actual text or style.
+//!
+//! The file format is line based:
+//!
+//! ```text
+//! canopy snapshot 10x2
+//! --- text
+//! |hello     |
+//! |world     |
+//! --- style
+//! aaaaabbbbb
+//! aaaaaaaaaa
+//! --- legend
+//! a fg=#ffffff bg=#000000
+//! b fg=#ff0000 bg=#000000 bold
+//! ```
+//!
+//! Text rows are framed by `|` so that trailing spaces survive editors. The style and legend
+//! sections are optional; a snapshot without them compares text only. Update mode, enabled
+//! with the `CANOPY_UPDATE_SNAPSHOTS` environment variable, records missing snapshots and
+//! rewrites changed ones, keeping whichever layers the existing file had.
+
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io, iter,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    core::{termbuf::TermBuf, text},
+    error::{Error, Result},
+    geom::{Point, Rect, Size},
+    style::ResolvedStyle,
+};
+
+/// Environment variable that switches snapshot assertions into update mode.
+pub const UPDATE_ENV: &str = "CANOPY_UPDATE_SNAPSHOTS";
+
+/// File extension used for snapshot files.
+const EXTENSION: &str = "snap";
+
+/// Leading words of a snapshot file header, followed by the size.
+const HEADER: &str = "canopy snapshot";
+
+/// Section marker before the text rows.
+const TEXT_SECTION: &str = "--- text";
+
+/// Section marker before the style key rows.
+const STYLE_SECTION: &str = "--- style";
+
+/// Section marker before the style key legend.
+const LEGEND_SECTION: &str = "--- legend";
+
+/// Single-character style keys, used in order before falling back to Latin Extended letters.
+const STYLE_KEYS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Maximum number of differing cell runs listed in a diff report.
+const MAX_LISTED: usize = 40;
+
+/// A screen region captured from a buffer or read from a snapshot file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Region size in cells.
+    size: Size,
+    /// Rendered text of each cell in row-major order. Wide glyph continuations are empty.
+    cells: Vec<String>,
+    /// Style description of each cell in row-major order, when the style layer is recorded.
+    styles: Option<Vec<String>>,
+}
+
+impl Snapshot {
+    /// Capture a buffer, or a region of it, with its style layer. The region is clipped to the
+    /// buffer, and wide glyphs cut by the region edges are captured as spaces.
+    pub fn capture(buf: &TermBuf, region: Option<Rect>) -> Self {
+        let rect = match region {
+            Some(region) => region.intersect(buf.rect()).unwrap_or_else(Rect::zero),
+            None => buf.rect(),
+        };
+        let area = rect.w as usize * rect.h as usize;
+        let mut cells = Vec::with_capacity(area);
+        let mut styles = Vec::with_capacity(area);
+        for y in rect.tl.y..rect.tl.y + rect.h {
+            for x in rect.tl.x..rect.tl.x + rect.w {
+                let cell = buf
+                    .get(Point { x, y })
+                    .expect("snapshot region is clipped to the buffer");
+                let mut glyph = cell.rendered_text();
+                let cut_left = x == rect.tl.x && cell.continuation;
+                let cut_right = x + 1 == rect.tl.x + rect.w && text::grapheme_width(&glyph) > 1;
+                if cut_left || cut_right {
+                    glyph = String::from(" ");
+                }
+                cells.push(glyph);
+                styles.push(describe_style(&cell.style));
+            }
+        }
+        Self {
+            size: rect.expanse(),
+            cells,
+            styles: Some(styles),
+        }
+    }
+
+    /// Drop the style layer, so comparisons check text only.
+    pub fn without_styles(mut self) -> Self {
+        self.styles = None;
+        self
+    }
+
+    /// Return the region size in cells.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Does this snapshot record a style layer?
+    pub fn has_styles(&self) -> bool {
+        self.styles.is_some()
+    }
+
+    /// Return the text as newline-joined rows.
+    pub fn text(&self) -> String {
+        (0..self.size.h)
+            .map(|y| self.row_text(y))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Return the cells of one row.
+    fn row<'a, T>(&self, cells: &'a [T], y: u32) -> &'a [T] {
+        let width = self.size.w as usize;
+        let start = y as usize * width;
+        &cells[start..start + width]
+    }
+
+    /// Return the text of one row.
+    fn row_text(&self, y: u32) -> String {
+        self.row(&self.cells, y).concat()
+    }
+
+    /// Compare this expected snapshot against an actual one, returning `None` when they match.
+    ///
+    /// Text is always compared. Styles are compared when both snapshots record them.
+    pub fn diff(&self, actual: &Self) -> Option<SnapshotDiff> {
+        if self.size != actual.size {
+            return Some(SnapshotDiff {
+                expected: self.clone(),
+                actual: actual.clone(),
+                changes: Vec::new(),
+                cells: 0,
+            });
+        }
+        let styles = self.styles.as_ref().zip(actual.styles.as_ref());
+        let mut changes: Vec<Change> = Vec::new();
+        let mut cells = 0;
+        for y in 0..self.size.h {
+            for x in 0..self.size.w {
+                let index = (y * self.size.w + x) as usize;
+                let mut differs = false;
+                if self.cells[index] != actual.cells[index] {
+                    differs = true;
+                    Change::extend(
+                        &mut changes,
+                        Layer::Text,
+                        Point { x, y },
+                        &self.cells[index],
+                        &actual.cells[index],
+                    );
+                }
+                if let Some((expected, found)) = styles
+                    && expected[index] != found[index]
+                {
+                    differs = true;
+                    Change::extend(
+                        &mut changes,
+                        Layer::Style,
+                        Point { x, y },
+                        &expected[index],
+                        &found[index],
+                    );
+                }
+                cells += usize::from(differs);
+            }
+        }
+        (cells > 0).then(|| SnapshotDiff {
+            expected: self.clone(),
+            actual: actual.clone(),
+            changes,
+            cells,
+        })
+    }
+}
+
+impl fmt::Display for Snapshot {
+    /// Serialize the snapshot in the snapshot file format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER} {}x{}", self.size.w, self.size.h)?;
+        writeln!(f, "{TEXT_SECTION}")?;
+        for y in 0..self.size.h {
+            writeln!(f, "|{}|", self.row_text(y))?;
+        }
+        let Some(styles) = &self.styles else {
+            return Ok(());
+        };
+        let mut legend: Vec<&str> = Vec::new();
+        let mut keys: HashMap<&str, char> = HashMap::new();
+        writeln!(f, "{STYLE_SECTION}")?;
+        for y in 0..self.size.h {
+            for style in self.row(styles, y) {
+                let key = *keys.entry(style).or_insert_with(|| {
+                    legend.push(style);
+                    style_key(legend.len() - 1)
+                });
+                write!(f, "{key}")?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "{LEGEND_SECTION}")?;
+        for (index, style) in legend.iter().enumerate() {
+            writeln!(f, "{} {style}", style_key(index))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Snapshot {
+    type Err = Error;
+
+    /// Parse a snapshot from the snapshot file format.
+    fn from_str(source: &str) -> Result<Self> {
+        let mut lines = source.lines();
+        let size = lines
+            .next()
+            .and_then(|line| line.strip_prefix(HEADER))
+            .and_then(|size| size.trim().split_once('x'))
+            .and_then(|(w, h)| Some(Size::new(w.parse().ok()?, h.parse().ok()?)))
+            .ok_or_else(|| invalid("expected a `canopy snapshot WxH` header"))?;
+        expect_section(lines.next(), TEXT_SECTION)?;
+        let mut cells = Vec::with_capacity(size.w as usize * size.h as usize);
+        for y in 0..size.h {
+            let row = lines
+                .next()
+                .and_then(|line| line.strip_prefix('|'))
+                .and_then(|line| line.strip_suffix('|'))
+                .ok_or_else(|| invalid(format!("text row {y} is missing or not framed by `|`")))?;
+            parse_text_row(row, y, size.w, &mut cells)?;
+        }
+
+        let styles = match lines.next() {
+            None | Some("") => None,
+            Some(line) => {
+                expect_section(Some(line), STYLE_SECTION)?;
+                let rows: Vec<&str> = lines.by_ref().take(size.h as usize).collect();
+                expect_section(lines.next(), LEGEND_SECTION)?;
+                let mut legend = HashMap::new();
+                for line in lines.filter(|line| !line.is_empty()) {
+                    let mut chars = line.chars();
+                    let key = chars.next().expect("filtered lines are not empty");
+                    let style = chars
+                        .as_str()
+                        .strip_prefix(' ')
+                        .ok_or_else(|| invalid(format!("legend line `{line}` has no style")))?;
+                    legend.insert(key, style.to_string());
+                }
+                Some(parse_style_rows(&rows, size, &legend)?)
+            }
+        };
+        Ok(Self {
+            size,
+            cells,
+            styles,
+        })
+    }
+}
+
+/// Build an error for a malformed snapshot file.
+fn invalid(message: impl fmt::Display) -> Error {
+    Error::Invalid(format!("invalid snapshot: {message}"))
+}
+
+/// Check that a line is the expected section marker.
+fn expect_section(line: Option<&str>, section: &str) -> Result<()> {
+    match line {
+        Some(line) if line == section => Ok(()),
+        Some(line) => Err(invalid(format!("expected `{section}`, found `{line}`"))),
+        None => Err(invalid(format!("expected `{section}`, found end of file"))),
+    }
+}
+
+/// Split a text row into cells, adding an empty continuation after each wide glyph.
+fn parse_text_row(row: &str, y: u32, width: u32, cells: &mut Vec<String>) -> Result<()> {
+    let start = cells.len();
+    for grapheme in row.graphemes(true) {
+        cells.push(grapheme.to_string());
+        if text::grapheme_width(grapheme) > 1 {
+            cells.push(String::new());
+        }
+    }
+    let found = cells.len() - start;
+    if found != width as usize {
+        return Err(invalid(format!(
+            "text row {y} is {found} cells wide, expected {width}"
+        )));
+    }
+    Ok(())
+}
+
+/// Expand style key rows into per-cell style descriptions using the legend.
+fn parse_style_rows(
+    rows: &[&str],
+    size: Size,
+    legend: &HashMap<char, String>,
+) -> Result<Vec<String>> {
+    if rows.len() != size.h as usize {
+        return Err(invalid(format!(
+            "expected {} style rows, found {}",
+            size.h,
+            rows.len()
+        )));
+    }
+    let mut styles = Vec::with_capacity(size.w as usize * size.h as usize);
+    for (y, row) in rows.iter().enumerate() {
+        let start = styles.len();
+        for key in row.chars() {
+            let style = legend
+                .get(&key)
+                .ok_or_else(|| invalid(format!("style key `{key}` is not in the legend")))?;
+            styles.push(style.clone());
+        }
+        if styles.len() - start != size.w as usize {
+            return Err(invalid(format!(
+                "style row {y} is {} cells wide, expected {}",
+                styles.len() - start,
+                size.w
+            )));
+        }
+    }
+    Ok(styles)
+}
+
+/// Return the legend key for the style at an index in first-use order.
+fn style_key(index: usize) -> char {
+    STYLE_KEYS.get(index).map_or_else(
+        || {
+            u32::try_from(index - STYLE_KEYS.len())
+                .ok()
+                .and_then(|offset| char::from_u32(0x100 + offset))
+                .unwrap_or('?')
+        },
+        |key| char::from(*key),
+    )
+}
+
+/// Describe a resolved style as stable space-separated words.
+fn describe_style(style: &ResolvedStyle) -> String {
+    let mut words = vec![
+        format!("fg={}", style.fg.hex()),
+        format!("bg={}", style.bg.hex()),
+    ];
+    let attrs = style.attrs;
+    for (on, name) in [
+        (attrs.bold, "bold"),
+        (attrs.crossedout, "crossedout"),
+        (attrs.dim, "dim"),
+        (attrs.italic, "italic"),
+        (attrs.overline, "overline"),
+    ] {
+        if on {
+            words.push(name.to_string());
+        }
+    }
+    if attrs.underline {
+        words.push(format!("underline={}", attrs.underline_style.name()));
+    }
+    if let Some(color) = style.underline {
+        words.push(format!("underline_color={}", color.hex()));
+    }
+    words.join(" ")
+}
+
+/// Snapshot layer a change belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    /// Rendered cell text.
+    Text,
+    /// Cell style.
+    Style,
+}
+
+/// A run of adjacent changed cells in one row.
+///
+/// Text runs join every adjacent changed cell; style runs join adjacent cells with the same
+/// expected and actual styles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Change {
+    /// Layer that changed.
+    layer: Layer,
+    /// First cell of the run.
+    start: Point,
+    /// Number of cells in the run.
+    len: u32,
+    /// Expected text or style.
+    expected: String,
+    /// Actual text or style.
+    actual: String,
+}
+
+impl Change {
+    /// Record one changed cell, extending the previous run of the same layer when adjacent.
+    fn extend(changes: &mut Vec<Self>, layer: Layer, at: Point, expected: &str, actual: &str) {
+        let previous = changes
+            .iter_mut()
+            .rev()
+            .find(|change| change.layer == layer);
+        if let Some(change) = previous
+            && change.start.y == at.y
+            && change.start.x + change.len == at.x
+        {
+            match layer {
+                Layer::Text => {
+                    change.len += 1;
+                    change.expected.push_str(expected);
+                    change.actual.push_str(actual);
+                    return;
+                }
+                Layer::Style if change.expected == expected && change.actual == actual => {
+                    change.len += 1;
+                    return;
+                }
+                Layer::Style => {}
+            }
+        }
+        changes.push(Self {
+            layer,
+            start: at,
+            len: 1,
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        });
+    }
+
+    /// Does this run cover a column of a row?
+    fn covers(&self, x: u32, y: u32) -> bool {
+        self.start.y == y && (self.start.x..self.start.x + self.len).contains(&x)
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Point { x, y } = self.start;
+        if self.len == 1 {
+            write!(f, "row {y}, column {x}: ")?;
+        } else {
+            write!(f, "row {y}, columns {x}-{}: ", x + self.len - 1)?;
+        }
+        match self.layer {
+            Layer::Text => write!(f, "text {:?} -> {:?}", self.expected, self.actual),
+            Layer::Style => write!(f, "style {} -> {}", self.expected, self.actual),
+        }
+    }
+}
+
+/// A cell-level difference between an expected and an actual snapshot.
+///
+/// The [`Display`](fmt::Display) output shows the expected and actual rows with the changed
+/// columns marked, followed by a list of the changed cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Expected snapshot.
+    expected: Snapshot,
+    /// Actual snapshot.
+    actual: Snapshot,
+    /// Changed cell runs in row-major order, empty when the sizes differ.
+    changes: Vec<Change>,
+    /// Number of changed cells.
+    cells: usize,
+}
+
+impl SnapshotDiff {
+    /// Return the number of changed cells, or zero when the sizes differ.
+    pub fn changed_cells(&self) -> usize {
+        self.cells
+    }
+
+    /// Write the rows of both snapshots when their sizes differ.
+    fn fmt_resized(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (expected, actual) = (self.expected.size, self.actual.size);
+        writeln!(
+            f,
+            "size differs: expected {}x{}, actual {}x{}",
+            expected.w, expected.h, actual.w, actual.h
+        )?;
+        for y in 0..expected.h {
+            writeln!(f, "- {y:>3} |{}|", self.expected.row_text(y))?;
+        }
+        for y in 0..actual.h {
+            writeln!(f, "+ {y:>3} |{}|", self.actual.row_text(y))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.expected.size != self.actual.size {
+            return self.fmt_resized(f);
+        }
+        writeln!(f, "{} cell(s) differ", self.cells)?;
+        let size = self.expected.size;
+        for y in 0..size.h {
+            let changed: Vec<&Change> = self
+                .changes
+                .iter()
+                .filter(|change| change.start.y == y)
+                .collect();
+            let expected = self.expected.row_text(y);
+            if changed.is_empty() {
+                writeln!(f, "  {y:>3} |{expected}|")?;
+                continue;
+            }
+            let shown = if changed.iter().any(|change| change.layer == Layer::Text) {
+                writeln!(f, "- {y:>3} |{expected}|")?;
+                writeln!(f, "+ {y:>3} |{}|", self.actual.row_text(y))?;
+                &self.actual
+            } else {
+                writeln!(f, "~ {y:>3} |{expected}|")?;
+                &self.expected
+            };
+            let marks = markers(shown.row(&shown.cells, y), |x| {
+                changed.iter().any(|change| change.covers(x, y))
+            });
+            writeln!(f, "       {}", marks.trim_end())?;
+        }
+        for change in self.changes.iter().take(MAX_LISTED) {
+            writeln!(f, "{change}")?;
+        }
+        if self.changes.len() > MAX_LISTED {
+            writeln!(f, "... and {} more", self.changes.len() - MAX_LISTED)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build the marker line for a row, with `^` under every display column of a changed cell.
+///
+/// A wide glyph is marked across both of its columns when either of its cells changed.
+fn markers(row: &[String], changed: impl Fn(u32) -> bool) -> String {
+    let mut marks = String::new();
+    for (x, cell) in (0..).zip(row) {
+        let width = text::grapheme_width(cell);
+        if width == 0 {
+            continue;
+        }
+        let mark = if (x..x + width as u32).any(&changed) {
+            '^'
+        } else {
+            ' '
+        };
+        marks.extend(iter::repeat_n(mark, width));
+    }
+    marks
+}
+
+/// Result of checking a snapshot against its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotCheck {
+    /// The snapshot matched its file.
+    Matched,
+    /// Update mode wrote the snapshot to a new or changed file.
+    Updated(PathBuf),
+    /// The snapshot file does not exist.
+    Missing(PathBuf),
+    /// The snapshot differs from its file.
+    Mismatch {
+        /// Snapshot file path.
+        path: PathBuf,
+        /// Cell-level difference from the file.
+        diff: SnapshotDiff,
+    },
+}
+
+impl SnapshotCheck {
+    /// Did the check pass?
+    pub fn passed(&self) -> bool {
+        matches!(self, Self::Matched | Self::Updated(_))
+    }
+}
+
+impl fmt::Display for SnapshotCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Matched => write!(f, "matches"),
+            Self::Updated(path) => write!(f, "written to {}", path.display()),
+            Self::Missing(path) => write!(
+                f,
+                "is missing: {} does not exist; set {UPDATE_ENV}=1 to record it",
+                path.display()
+            ),
+            Self::Mismatch { path, diff } => {
+                write!(f, "does not match {}: {diff}", path.display())
+            }
+        }
+    }
+}
+
+/// Where snapshot files live and whether checks rewrite them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotConfig {
+    /// Directory holding snapshot files.
+    dir: PathBuf,
+    /// Whether checks record missing snapshots and rewrite changed ones.
+    update: bool,
+}
+
+impl SnapshotConfig {
+    /// Read snapshots from a directory, failing on mismatch.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            update: false,
+        }
+    }
+
+    /// Use the `snapshots` directory of the crate under test, as named by
+    /// `CARGO_MANIFEST_DIR`, with update mode taken from `CANOPY_UPDATE_SNAPSHOTS`.
+    ///
+    /// Without `CARGO_MANIFEST_DIR` the directory is relative to the working directory. Update
+    /// mode is on when the variable is set to anything other than an empty string or `0`.
+    pub fn from_env() -> Self {
+        let root = env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+        let update = env::var_os(UPDATE_ENV).is_some_and(|value| !value.is_empty() && value != "0");
+        Self::new(root.join("snapshots")).with_update(update)
+    }
+
+    /// Configure the snapshot directory.
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    /// Configure update mode.
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Return the snapshot directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Is update mode on?
+    pub fn update(&self) -> bool {
+        self.update
+    }
+
+    /// Return the file path for a snapshot name.
+    ///
+    /// Names may contain `/` to group snapshots in subdirectories, but must stay relative and
+    /// may not contain `..`.
+    pub fn path(&self, name: &str) -> Result<PathBuf> {
+        let relative = Path::new(name);
+        let valid = !name.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !valid {
+            return Err(Error::Invalid(format!("invalid snapshot name {name:?}")));
+        }
+        Ok(self.dir.join(format!("{name}.{EXTENSION}")))
+    }
+
+    /// Read a named snapshot, returning `None` when its file does not exist.
+    pub fn load(&self, name: &str) -> Result<Option<Snapshot>> {
+        let path = self.path(name)?;
+        match fs::read_to_string(&path) {
+            Ok(source) => source
+                .parse()
+                .map(Some)
+                .map_err(|err| Error::Invalid(format!("{}: {err}", path.display()))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::Invalid(format!(
+                "snapshot read failed: {}: {err}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Write a named snapshot, creating its directory if needed.
+    pub fn store(&self, name: &str, snapshot: &Snapshot) -> Result<PathBuf> {
+        let path = self.path(name)?;
+        let write = || -> io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, snapshot.to_string())
+        };
+        write().map_err(|err| {
+            Error::Invalid(format!("snapshot write failed: {}: {err}", path.display()))
+        })?;
+        Ok(path)
+    }
+
+    /// Check an actual snapshot against its named file.
+    ///
+    /// In update mode a missing file is recorded with both layers and a changed file is
+    /// rewritten with the layers it already had.
+    pub fn check(&self, name: &str, actual: &Snapshot) -> Result<SnapshotCheck> {
+        let expected = self.load(name)?;
+        let diff = match &expected {
+            Some(expected) => match expected.diff(actual) {
+                Some(diff) => Some(diff),
+                None => return Ok(SnapshotCheck::Matched),
+            },
+            None => None,
+        };
+        if self.update {
+            let stored = match &expected {
+                Some(expected) if !expected.has_styles() => actual.clone().without_styles(),
+                _ => actual.clone(),
+            };
+            return self.store(name, &stored).map(SnapshotCheck::Updated);
+        }
+        let path = self.path(name)?;
+        Ok(match diff {
+            Some(diff) => SnapshotCheck::Mismatch { path, diff },
+            None => SnapshotCheck::Missing(path),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geom::Line,
+        style::{Attr, AttrSet, Color},
+    };
+
+    fn plain() -> ResolvedStyle {
+        ResolvedStyle::new(Color::White, Color::Black, AttrSet::default())
+    }
+
+    fn buffer(rows: &[&str]) -> TermBuf {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0) as u32;
+        let mut buf = TermBuf::new(Size::new(width, rows.len() as u32), ' ', plain()).unwrap();
+        for (y, row) in rows.iter().enumerate() {
+            buf.text(&plain(), Line::new(0, y as u32, width), row)
+                .unwrap();
+        }
+        buf
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_the_file_format() {
+        let mut buf = buffer(&["hello ", "wide  "]);
+        let bold = ResolvedStyle::new(Color::Red, Color::Black, AttrSet::new(Attr::Bold));
+        buf.text(&bold, Line::new(4, 1, 2), "界").unwrap();
+        let snapshot = Snapshot::capture(&buf, None);
+        let file = snapshot.to_string();
+        assert_eq!(
+            file,
+            "canopy snapshot 6x2\n\
+             --- text\n\
+             |hello |\n\
+             |wide界|\n\
+             --- style\n\
+             aaaaaa\n\
+             aaaabb\n\
+             --- legend\n\
+             a fg=#ffffff bg=#000000\n\
+             b fg=#ff0000 bg=#000000 bold\n"
+        );
+        assert_eq!(file.parse::<Snapshot>().unwrap(), snapshot);
+
+        let text_only = snapshot.clone().without_styles();
+        assert_eq!(
+            text_only.to_string().parse::<Snapshot>().unwrap(),
+            text_only
+        );
+    }
+
+    #[test]
+    fn regions_are_clipped_to_the_buffer() {
+        let buf = buffer(&["abc", "def"]);
+        let snapshot = Snapshot::capture(&buf, Some(Rect::new(1, 1, 5, 5)));
+        assert_eq!(snapshot.size(), Size::new(2, 1));
+        assert_eq!(snapshot.text(), "ef");
+    }
+
+    #[test]
+    fn diff_markers_follow_display_columns() {
+        let expected = Snapshot::capture(&buffer(&["漢字ab"]), None);
+        let actual = Snapshot::capture(&buffer(&["漢字aX"]), None);
+        let diff = expected.diff(&actual).unwrap().to_string();
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[2], "+   0 |漢字aX  |");
+        assert_eq!(lines[3], format!("{}^", " ".repeat(12)));
+
+        let wide = Snapshot::capture(&buffer(&["ab漢字"]), None);
+        let diff = expected.diff(&wide).unwrap().to_string();
+        assert_eq!(diff.lines().nth(3), Some("       ^^^^^^"));
+    }
+
+    #[test]
+    fn diffs_list_text_and_style_changes() {
+        let expected = Snapshot::capture(&buffer(&["abcd", "efgh"]), None);
+        let mut buf = buffer(&["abXY", "efgh"]);
+        let red = ResolvedStyle::new(Color::Red, Color::Black, AttrSet::default());
+        buf.text(&red, Line::new(0, 1, 2), "ef").unwrap();
+        let actual = Snapshot::capture(&buf, None);
+
+        assert_eq!(expected.diff(&expected), None);
+        let diff = expected.diff(&actual).unwrap();
+        assert_eq!(diff.changed_cells(), 4);
+        assert_eq!(
+            diff.to_string(),
+            "4 cell(s) differ\n\
+             -   0 |abcd|\n\
+             +   0 |abXY|\n\
+             \x20        ^^\n\
+             ~   1 |efgh|\n\
+             \x20      ^^\n\
+             row 0, columns 2-3: text \"cd\" -> \"XY\"\n\
+             row 1, columns 0-1: style fg=#ffffff bg=#000000 -> fg=#ff0000 bg=#000000\n"
+        );
+        assert_eq!(
+            expected
+                .without_styles()
+                .diff(&actual)
+                .unwrap()
+                .changed_cells(),
+            2
+        );
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        for source in [
+            "",
+            "canopy snapshot 2\n--- text\n|ab|\n",
+            "canopy snapshot 2x1\n|ab|\n",
+            "canopy snapshot 2x1\n--- text\n|abc|\n",
+            "canopy snapshot 2x1\n--- text\nab\n",
+            "canopy snapshot 2x1\n--- text\n|ab|\n--- style\nab\n--- legend\na fg=#000000\n",
+        ] {
+            assert!(source.parse::<Snapshot>().is_err(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn checks_record_and_compare_snapshot_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SnapshotConfig::new(dir.path());
+        let first = Snapshot::capture(&buffer(&["one"]), None);
+        let second = Snapshot::capture(&buffer(&["two"]), None);
+
+        assert!(matches!(
+            config.check("screens/main", &first).unwrap(),
+            SnapshotCheck::Missing(_)
+        ));
+        let updating = config.clone().with_update(true);
+        assert!(matches!(
+            updating.check("screens/main", &first).unwrap(),
+            SnapshotCheck::Updated(_)
+        ));
+        assert!(dir.path().join("screens/main.snap").is_file());
+        assert_eq!(
+            config.check("screens/main", &first).unwrap(),
+            SnapshotCheck::Matched
+        );
+
+        let check = config.check("screens/main", &second).unwrap();
+        assert!(!check.passed());
+        assert!(check.to_string().contains("text \"one\" -> \"two\""));
+
+        config
+            .store("plain", &first.clone().without_styles())
+            .unwrap();
+        updating.check("plain", &second).unwrap();
+        assert!(!config.load("plain").unwrap().unwrap().has_styles());
+
+        assert!(config.path("../escape").is_err());
+        assert!(config.path("/abs").is_err());
+    }
+}
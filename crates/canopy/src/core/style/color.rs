@@ -103,6 +103,12 @@ const ANSI16: [(u8, u8, u8); 16] = [
 ];
 
 impl Color {
+    /// Format this color as a `#rrggbb` hex string.
+    pub fn hex(self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+
     /// Return this color's RGB channels.
     ///
     /// Named colors and ANSI-256 values use the standard palette mappings.
@@ -42,18 +42,24 @@ impl StyleEffect for ColorEffect {
             Self::ScaleBrightness(f) => {
                 style.fg = style.fg.map_colors(|c| c.scale_brightness(f));
                 style.bg = style.bg.map_colors(|c| c.scale_brightness(f));
+                style.underline = style
+                    .underline
+                    .map(|p| p.map_colors(|c| c.scale_brightness(f)));
             }
             Self::Saturation(f) => {
                 style.fg = style.fg.map_colors(|c| c.saturation(f));
                 style.bg = style.bg.map_colors(|c| c.saturation(f));
+                style.underline = style.underline.map(|p| p.map_colors(|c| c.saturation(f)));
             }
             Self::Invert => {
                 style.fg = style.fg.map_colors(Color::invert_rgb);
                 style.bg = style.bg.map_colors(Color::invert_rgb);
+                style.underline = style.underline.map(|p| p.map_colors(Color::invert_rgb));
             }
             Self::HueShift(d) => {
                 style.fg = style.fg.map_colors(|c| c.shift_hue(d));
                 style.bg = style.bg.map_colors(|c| c.shift_hue(d));
+                style.underline = style.underline.map(|p| p.map_colors(|c| c.shift_hue(d)));
             }
         }
         style
@@ -127,6 +133,7 @@ mod tests {
                 b: 20,
             }),
             attrs: AttrSet::default(),
+            underline: None,
         }
     }
 
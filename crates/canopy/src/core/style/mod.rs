@@ -26,8 +26,16 @@ pub enum Attr {
     Bold,
     /// Crossed out text.
     CrossedOut,
+    /// Curly underlined text.
+    CurlyUnderline,
+    /// Dashed underlined text.
+    DashedUnderline,
     /// Dim text.
     Dim,
+    /// Dotted underlined text.
+    DottedUnderline,
+    /// Double underlined text.
+    DoubleUnderline,
     /// Italic text.
     Italic,
     /// Overlined text.
@@ -36,6 +44,37 @@ pub enum Attr {
     Underline,
 }
 
+/// The line drawn for underlined text.
+///
+/// Terminals without extended underline support draw every style as a single underline.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum UnderlineStyle {
+    /// A single straight line.
+    #[default]
+    Single,
+    /// Two straight lines.
+    Double,
+    /// A wavy line, commonly used for diagnostics.
+    Curly,
+    /// A dotted line.
+    Dotted,
+    /// A dashed line.
+    Dashed,
+}
+
+impl UnderlineStyle {
+    /// Return the stable lowercase name of the style.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Single => "single",
+            Self::Double => "double",
+            Self::Curly => "curly",
+            Self::Dotted => "dotted",
+            Self::Dashed => "dashed",
+        }
+    }
+}
+
 /// A set of active text attributes.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct AttrSet {
@@ -51,6 +90,8 @@ pub struct AttrSet {
     pub overline: bool,
     /// Underline flag.
     pub underline: bool,
+    /// Line drawn when `underline` is set.
+    pub underline_style: UnderlineStyle,
 }
 
 impl Default for AttrSet {
@@ -63,6 +104,7 @@ impl Default for AttrSet {
             italic: false,
             overline: false,
             underline: false,
+            underline_style: UnderlineStyle::Single,
         }
     }
 }
@@ -79,11 +121,22 @@ impl AttrSet {
             Attr::Dim => self.dim = true,
             Attr::Italic => self.italic = true,
             Attr::CrossedOut => self.crossedout = true,
-            Attr::Underline => self.underline = true,
+            Attr::Underline => self = self.with_underline(UnderlineStyle::Single),
+            Attr::DoubleUnderline => self = self.with_underline(UnderlineStyle::Double),
+            Attr::CurlyUnderline => self = self.with_underline(UnderlineStyle::Curly),
+            Attr::DottedUnderline => self = self.with_underline(UnderlineStyle::Dotted),
+            Attr::DashedUnderline => self = self.with_underline(UnderlineStyle::Dashed),
             Attr::Overline => self.overline = true,
         };
         self
     }
+
+    /// Turn on underlining with a specific line style.
+    pub fn with_underline(mut self, style: UnderlineStyle) -> Self {
+        self.underline = true;
+        self.underline_style = style;
+        self
+    }
 }
 
 /// A gradient stop in a paint specification.
@@ -255,12 +308,25 @@ pub struct ResolvedStyle {
     pub bg: Color,
     /// Text attributes.
     pub attrs: AttrSet,
+    /// Underline color, or `None` to draw underlines in the foreground color.
+    pub underline: Option<Color>,
 }
 
 impl ResolvedStyle {
     /// Construct a resolved style from components.
     pub fn new(fg: Color, bg: Color, attrs: AttrSet) -> Self {
-        Self { fg, bg, attrs }
+        Self {
+            fg,
+            bg,
+            attrs,
+            underline: None,
+        }
+    }
+
+    /// Set the underline color.
+    pub fn with_underline(mut self, color: Option<Color>) -> Self {
+        self.underline = color;
+        self
     }
 }
 
@@ -273,6 +339,8 @@ pub struct Style {
     pub bg: Paint,
     /// Text attributes.
     pub attrs: AttrSet,
+    /// Underline paint, or `None` to draw underlines in the foreground color.
+    pub underline: Option<Paint>,
 }
 
 impl Style {
@@ -283,15 +351,19 @@ impl Style {
             self.bg.resolve(rect, point),
             self.attrs,
         )
+        .with_underline(self.underline.as_ref().map(|p| p.resolve(rect, point)))
     }
 
-    /// Resolve the style to a solid variant if both paints are solid.
+    /// Resolve the style to a solid variant if all paints are solid.
     pub fn resolve_solid(&self) -> Option<ResolvedStyle> {
-        Some(ResolvedStyle::new(
-            self.fg.solid_color()?,
-            self.bg.solid_color()?,
-            self.attrs,
-        ))
+        let underline = match &self.underline {
+            Some(paint) => Some(paint.solid_color()?),
+            None => None,
+        };
+        Some(
+            ResolvedStyle::new(self.fg.solid_color()?, self.bg.solid_color()?, self.attrs)
+                .with_underline(underline),
+        )
     }
 }
 
@@ -305,6 +377,12 @@ pub struct PartialStyle {
     pub bg: Option<Paint>,
     /// Optional attributes.
     pub attrs: Option<AttrSet>,
+    /// Optional underline paint.
+    ///
+    /// Unlike the other channels this may stay unset, and resolution stops once
+    /// the foreground, background and attributes are known. Set it on the same
+    /// rule as the underline attribute it colors.
+    pub underline: Option<Paint>,
 }
 
 /// A builder for creating reusable style specifications.
@@ -366,6 +444,12 @@ impl StyleBuilder {
         self.inner.attrs = Some(attrs);
         self
     }
+
+    /// Set the underline paint.
+    pub fn underline(mut self, paint: impl Into<Paint>) -> Self {
+        self.inner.underline = Some(paint.into());
+        self
+    }
 }
 
 impl From<StyleBuilder> for PartialStyle {
@@ -379,26 +463,31 @@ impl PartialStyle {
     pub fn fg(fg: impl Into<Paint>) -> Self {
         Self {
             fg: Some(fg.into()),
-            bg: None,
-            attrs: None,
+            ..Self::default()
         }
     }
 
     /// Create a new PartialStyle with only a background paint.
     pub fn bg(bg: impl Into<Paint>) -> Self {
         Self {
-            fg: None,
             bg: Some(bg.into()),
-            attrs: None,
+            ..Self::default()
         }
     }
 
     /// Create a new PartialStyle with only attributes.
     pub fn attrs(attrs: AttrSet) -> Self {
         Self {
-            fg: None,
-            bg: None,
             attrs: Some(attrs),
+            ..Self::default()
+        }
+    }
+
+    /// Create a new PartialStyle with only an underline paint.
+    pub fn underline(underline: impl Into<Paint>) -> Self {
+        Self {
+            underline: Some(underline.into()),
+            ..Self::default()
         }
     }
 
@@ -408,6 +497,7 @@ impl PartialStyle {
             fg: self.fg.clone().expect("foreground paint is set"),
             bg: self.bg.clone().expect("background paint is set"),
             attrs: self.attrs.expect("attributes are set"),
+            underline: self.underline.clone(),
         }
     }
 
@@ -429,6 +519,11 @@ impl PartialStyle {
             } else {
                 other.attrs
             },
+            underline: if self.underline.is_some() {
+                self.underline.clone()
+            } else {
+                other.underline.clone()
+            },
         }
     }
 
@@ -470,6 +565,7 @@ impl StyleMap {
                 fg: Some(Paint::Solid(Color::White)),
                 bg: Some(Paint::Solid(Color::Black)),
                 attrs: Some(AttrSet::default()),
+                underline: None,
             },
         );
         cs
@@ -554,6 +650,16 @@ impl<'a> StyleRules<'a> {
         self
     }
 
+    /// Set the underline paint for a path.
+    ///
+    /// If a rule already exists for this path, the underline paint is merged
+    /// with the existing style.
+    pub fn underline(mut self, path: &str, paint: impl Into<Paint>) -> Self {
+        let full_path = self.make_path(path);
+        self.merge_pending(full_path, PartialStyle::underline(paint));
+        self
+    }
+
     /// Set all attributes for a path.
     ///
     /// If a rule already exists for this path, the attributes are merged
@@ -771,6 +877,27 @@ mod tests {
         assert_eq!(resolved.attrs, AttrSet::new(Attr::Bold));
     }
 
+    #[test]
+    fn rules_set_underline_style_and_color() {
+        let mut style_map = StyleMap::new();
+        style_map
+            .rules()
+            .attr("diag/error", Attr::CurlyUnderline)
+            .underline("diag/error", Color::Red)
+            .apply();
+
+        let manager = StyleManager::new();
+        let resolved = manager
+            .get(&style_map, "diag/error")
+            .resolve_solid()
+            .expect("solid style");
+        assert!(resolved.attrs.underline);
+        assert_eq!(resolved.attrs.underline_style, UnderlineStyle::Curly);
+        assert_eq!(resolved.underline, Some(Color::Red));
+        let plain = manager.get(&style_map, "diag").resolve_solid();
+        assert_eq!(plain.and_then(|s| s.underline), None);
+    }
+
     #[test]
     fn rules_chain_sets_one_path_per_call() {
         let mut style_map = StyleMap::new();
@@ -798,19 +925,23 @@ mod tests {
         );
     }
 
-    /// Render a theme's complete rule set as sorted `path fg bg attrs` lines.
+    /// Render a theme's complete rule set as sorted `path fg bg attrs [underline]` lines.
     fn dump_theme(name: &str, map: &StyleMap) -> String {
         let mut lines: Vec<String> = map
             .styles
             .iter()
             .map(|(path, style)| {
-                format!(
+                let mut line = format!(
                     "/{} fg={:?} bg={:?} attrs={:?}",
                     path.join("/"),
                     style.fg,
                     style.bg,
                     style.attrs
-                )
+                );
+                if let Some(underline) = &style.underline {
+                    line.push_str(&format!(" underline={underline:?}"));
+                }
+                line
             })
             .collect();
         lines.sort();
@@ -840,6 +971,7 @@ mod tests {
             fg: Paint::solid(fg),
             bg: Paint::solid(bg),
             attrs: AttrSet::default(),
+            underline: None,
         }
     }
 
@@ -1163,6 +1295,7 @@ mod tests {
             fg: Paint::gradient(fg_spec.clone()),
             bg: Paint::gradient(bg_spec.clone()),
             attrs: AttrSet::default(),
+            underline: None,
         };
         let rect = geom::Rect::new(0, 0, 4, 4);
         let point = geom::Point { x: 1, y: 2 };
@@ -86,6 +86,14 @@ pub fn theme(p: &Palette) -> StyleMap {
         .fg("/yellow", p.yellow)
         .fg("/orange", p.orange)
         .fg("/black", p.black)
+        .fg("/chart/series/0", p.blue)
+        .fg("/chart/series/1", p.green)
+        .fg("/chart/series/2", p.orange)
+        .fg("/chart/series/3", p.magenta)
+        .fg("/chart/series/4", p.cyan)
+        .fg("/chart/series/5", p.yellow)
+        .fg("/chart/axis", p.frame)
+        .fg("/chart/gauge", p.accent)
         .attr("/text/bold", Attr::Bold)
         .attr("/text/italic", Attr::Italic)
         .attr("/text/underline", Attr::Underline)
@@ -115,12 +123,32 @@ pub fn theme(p: &Palette) -> StyleMap {
             "/editor/search/current",
             StyleBuilder::new().fg(p.bg).bg(p.orange),
         )
+        .style(
+            "/editor/bracket-match",
+            StyleBuilder::new().fg(p.bg).bg(p.cyan),
+        )
         .fg("/editor/line-number", p.line_number)
         .fg("/editor/line-number/current", p.accent)
         .style(
             "/editor/prompt",
             StyleBuilder::new().fg(p.fg).bg(p.panel_bg),
         )
+        .style(
+            "/terminal/prompt",
+            StyleBuilder::new().fg(p.fg).bg(p.panel_bg),
+        )
+        .style(
+            "/terminal/selection",
+            StyleBuilder::new().fg(p.fg).bg(p.selection_bg),
+        )
+        .style(
+            "/terminal/search/match",
+            StyleBuilder::new().fg(p.bg).bg(p.yellow),
+        )
+        .style(
+            "/terminal/search/current",
+            StyleBuilder::new().fg(p.bg).bg(p.orange),
+        )
         .style("/help/overlay", StyleBuilder::new().fg(p.fg).bg(p.bg))
         .style("/help/panel", StyleBuilder::new().fg(p.fg).bg(p.panel_bg))
         .style("/help/frame", StyleBuilder::new().bg(p.panel_bg))
@@ -0,0 +1,316 @@
+//! Background tasks owned by widget nodes.
+//!
+//! Widgets start work with [`Context::spawn`](crate::Context) or
+//! `spawn_blocking`. The work runs on a dedicated runtime thread, and its result
+//! is handed back to the owning widget on the UI thread after the run loop
+//! receives [`Event::Wake`]. Tasks are cancelled when their node unmounts or is
+//! removed, so a callback never runs against a widget that has gone away.
+
+use std::{
+    any::{Any, type_name, type_name_of_val},
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    sync::mpsc,
+    thread,
+};
+
+use futures::channel::mpsc::UnboundedSender;
+use tokio::{
+    runtime::{Builder as RuntimeBuilder, Handle},
+    sync::oneshot,
+    task::JoinHandle,
+};
+
+use crate::{
+    Context, NodeId,
+    error::{Error, Result},
+    event::Event,
+    widget::Widget,
+};
+
+/// Identifier for a background task spawned through a [`Context`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(u64);
+
+/// Type-erased task result carried back to the UI thread.
+pub type TaskOutput = Box<dyn Any + Send>;
+
+/// Type-erased callback that delivers a task result into its widget.
+pub type TaskCallback =
+    Box<dyn FnOnce(&mut dyn Widget, &mut dyn Context, TaskOutput) -> Result<()>>;
+
+/// Background work accepted by [`Context::spawn_task`].
+pub enum Task {
+    /// A future polled on the task runtime.
+    Future(Pin<Box<dyn Future<Output = TaskOutput> + Send>>),
+    /// A blocking closure run on the runtime's blocking pool.
+    Blocking(Box<dyn FnOnce() -> TaskOutput + Send>),
+}
+
+/// Wrap a typed delivery callback so it can be stored with a type-erased task.
+pub(crate) fn typed_callback<W, T>(
+    deliver: impl FnOnce(&mut W, &mut dyn Context, T) -> Result<()> + 'static,
+) -> TaskCallback
+where
+    W: Widget + 'static,
+    T: 'static,
+{
+    Box::new(move |widget, ctx, output| {
+        let actual = type_name_of_val(widget).to_string();
+        let any = widget as &mut dyn Any;
+        let widget = any.downcast_mut::<W>().ok_or_else(|| Error::TypeMismatch {
+            expected: type_name::<W>().to_string(),
+            actual,
+        })?;
+        let output = output
+            .downcast::<T>()
+            .map_err(|_| Error::Internal("task output type mismatch".into()))?;
+        deliver(widget, ctx, *output)
+    })
+}
+
+/// A spawned task awaiting delivery.
+struct PendingTask {
+    /// Node that owns the task.
+    node: NodeId,
+    /// Callback that receives the result.
+    deliver: TaskCallback,
+    /// Runtime handle used to abort the task.
+    join: JoinHandle<()>,
+}
+
+/// Dedicated thread driving the task runtime.
+struct Executor {
+    /// Handle used to spawn work onto the runtime.
+    handle: Handle,
+    /// Signal that stops the runtime thread.
+    shutdown: Option<oneshot::Sender<()>>,
+    /// Runtime thread, joined on drop.
+    worker: Option<thread::JoinHandle<()>>,
+}
+
+impl Executor {
+    /// Build the runtime and start its driver thread.
+    fn start() -> Result<Self> {
+        let runtime = RuntimeBuilder::new_current_thread()
+            .enable_time()
+            .build()
+            .map_err(|err| Error::RunLoop(format!("task runtime failed: {err}")))?;
+        let handle = runtime.handle().clone();
+        let (shutdown, stop) = oneshot::channel::<()>();
+        let worker = thread::Builder::new()
+            .name("canopy-tasks".into())
+            .spawn(move || {
+                let _stopped = runtime.block_on(stop);
+                // Blocking work cannot be interrupted, so don't wait for it.
+                runtime.shutdown_background();
+            })
+            .map_err(|err| Error::RunLoop(format!("task worker failed: {err}")))?;
+        Ok(Self {
+            handle,
+            shutdown: Some(shutdown),
+            worker: Some(worker),
+        })
+    }
+}
+
+impl Drop for Executor {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _worker_already_stopped = shutdown.send(());
+        }
+        if let Some(worker) = self.worker.take() {
+            let _worker_panicked = worker.join();
+        }
+    }
+}
+
+/// Registry of background tasks and their pending results.
+pub(crate) struct Tasks {
+    /// Last allocated task identifier.
+    next_id: u64,
+    /// Tasks that have not yet been delivered or cancelled.
+    pending: HashMap<TaskId, PendingTask>,
+    /// Sender cloned into each task to report its output.
+    results_tx: mpsc::Sender<(TaskId, TaskOutput)>,
+    /// Completed task outputs awaiting delivery.
+    results_rx: mpsc::Receiver<(TaskId, TaskOutput)>,
+    /// Event sender used to wake the run loop when a task completes.
+    wake_tx: Option<UnboundedSender<Event>>,
+    /// Runtime, started by the first spawn.
+    executor: Option<Executor>,
+}
+
+impl Tasks {
+    /// Construct an empty registry. The runtime starts lazily.
+    pub(crate) fn new() -> Self {
+        let (results_tx, results_rx) = mpsc::channel();
+        Self {
+            next_id: 0,
+            pending: HashMap::new(),
+            results_tx,
+            results_rx,
+            wake_tx: None,
+            executor: None,
+        }
+    }
+
+    /// Set the event sender used to wake the run loop on completion.
+    pub(crate) fn set_wake(&mut self, wake_tx: UnboundedSender<Event>) {
+        self.wake_tx = Some(wake_tx);
+    }
+
+    /// Start a task owned by a node.
+    pub(crate) fn spawn(
+        &mut self,
+        node: NodeId,
+        task: Task,
+        deliver: TaskCallback,
+    ) -> Result<TaskId> {
+        if self.executor.is_none() {
+            self.executor = Some(Executor::start()?);
+        }
+        let Some(executor) = &self.executor else {
+            return Err(Error::Internal("task runtime missing".into()));
+        };
+        self.next_id += 1;
+        let id = TaskId(self.next_id);
+        let results_tx = self.results_tx.clone();
+        let wake_tx = self.wake_tx.clone();
+        let report = move |output: TaskOutput| {
+            if results_tx.send((id, output)).is_ok()
+                && let Some(wake_tx) = wake_tx
+            {
+                let _receiver_closed = wake_tx.unbounded_send(Event::Wake);
+            }
+        };
+        let join = match task {
+            Task::Future(future) => executor.handle.spawn(async move { report(future.await) }),
+            Task::Blocking(work) => executor.handle.spawn_blocking(move || report(work())),
+        };
+        self.pending.insert(
+            id,
+            PendingTask {
+                node,
+                deliver,
+                join,
+            },
+        );
+        Ok(id)
+    }
+
+    /// Cancel a task, returning false if it was already delivered or cancelled.
+    pub(crate) fn cancel(&mut self, id: TaskId) -> bool {
+        let Some(task) = self.pending.remove(&id) else {
+            return false;
+        };
+        task.join.abort();
+        true
+    }
+
+    /// Return true if a task has not yet been delivered or cancelled.
+    pub(crate) fn is_pending(&self, id: TaskId) -> bool {
+        self.pending.contains_key(&id)
+    }
+
+    /// Return true if any task has not yet been delivered or cancelled.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Cancel every task owned by a node.
+    pub(crate) fn cancel_node(&mut self, node: NodeId) {
+        self.cancel_nodes(&HashSet::from([node]));
+    }
+
+    /// Cancel every task owned by any of the given nodes.
+    pub(crate) fn cancel_nodes(&mut self, nodes: &HashSet<NodeId>) {
+        self.pending.retain(|_, task| {
+            let keep = !nodes.contains(&task.node);
+            if !keep {
+                task.join.abort();
+            }
+            keep
+        });
+    }
+
+    /// Take completed tasks that are still registered, in completion order.
+    pub(crate) fn take_completed(&mut self) -> Vec<(NodeId, TaskCallback, TaskOutput)> {
+        let mut completed = Vec::new();
+        while let Ok((id, output)) = self.results_rx.try_recv() {
+            if let Some(task) = self.pending.remove(&id) {
+                completed.push((task.node, task.deliver, output));
+            }
+        }
+        completed
+    }
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        for task in self.pending.values() {
+            task.join.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{StreamExt, channel::mpsc::unbounded, executor::block_on};
+    use slotmap::SlotMap;
+
+    use super::*;
+
+    fn node_ids() -> (NodeId, NodeId) {
+        let mut map: SlotMap<NodeId, ()> = SlotMap::with_key();
+        (map.insert(()), map.insert(()))
+    }
+
+    fn wait_for_results(tasks: &mut Tasks, count: usize) -> Vec<(NodeId, TaskOutput)> {
+        let mut out = Vec::new();
+        while out.len() < count {
+            for (node, _, output) in tasks.take_completed() {
+                out.push((node, output));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        out
+    }
+
+    #[test]
+    fn completed_tasks_wake_the_loop_and_skip_cancelled_nodes() -> Result<()> {
+        let (first, second) = node_ids();
+        let (wake_tx, mut wake_rx) = unbounded();
+        let mut tasks = Tasks::new();
+        tasks.set_wake(wake_tx);
+
+        let (release, gate) = mpsc::channel::<()>();
+        let cancelled = tasks.spawn(
+            second,
+            Task::Blocking(Box::new(move || {
+                let _released = gate.recv();
+                Box::new(1u32) as TaskOutput
+            })),
+            Box::new(|_, _, _| Ok(())),
+        )?;
+        let kept = tasks.spawn(
+            first,
+            Task::Future(Box::pin(async { Box::new(2u32) as TaskOutput })),
+            Box::new(|_, _, _| Ok(())),
+        )?;
+        tasks.cancel_node(second);
+        release.send(()).expect("blocking task should be waiting");
+        assert!(!tasks.is_pending(cancelled));
+
+        let results = wait_for_results(&mut tasks, 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, first);
+        assert_eq!(results[0].1.downcast_ref::<u32>(), Some(&2));
+        assert!(!tasks.is_pending(kept));
+        assert!(matches!(block_on(wake_rx.next()), Some(Event::Wake)));
+        Ok(())
+    }
+}
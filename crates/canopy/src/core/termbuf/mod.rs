@@ -1,4 +1,4 @@
-use std::mem;
+use std::{mem, sync::Arc};
 
 use unicode_segmentation::UnicodeSegmentation;
 
@@ -7,6 +7,7 @@ use crate::{
     cursor,
     error::{Error, Result},
     geom::{Line, Point, Rect, Size},
+    graphics::ImagePlacement,
     render::RenderBackend,
     style::{Attr, ResolvedStyle},
 };
@@ -95,6 +96,10 @@ pub struct Cell {
     pub style: ResolvedStyle,
     /// True when this cell continues a wide glyph from the previous column.
     pub continuation: bool,
+    /// Hyperlink target carried by the cell, emitted as an OSC 8 link.
+    pub link: Option<Arc<str>>,
+    /// True when an image placed after the cell was written covers it.
+    pub image: bool,
 }
 
 impl Cell {
@@ -105,6 +110,8 @@ impl Cell {
             suffix: String::new(),
             style,
             continuation: false,
+            link: None,
+            image: false,
         }
     }
 
@@ -115,6 +122,8 @@ impl Cell {
             suffix: String::new(),
             style,
             continuation: false,
+            link: None,
+            image: false,
         }
     }
 
@@ -125,6 +134,8 @@ impl Cell {
             suffix: String::new(),
             style,
             continuation: true,
+            link: None,
+            image: false,
         }
     }
 
@@ -196,6 +207,8 @@ pub struct TermBuf {
     pub(crate) size: Size,
     /// Backing cell storage.
     pub(crate) cells: Vec<Cell>,
+    /// Images placed over the cells, in placement order.
+    pub(crate) images: Vec<ImagePlacement>,
 }
 
 impl TermBuf {
@@ -224,7 +237,11 @@ impl TermBuf {
             .try_reserve_exact(count)
             .map_err(|_| Error::RenderAllocation { cells: count })?;
         cells.resize(count, cell);
-        Ok(Self { size, cells })
+        Ok(Self {
+            size,
+            cells,
+            images: Vec::new(),
+        })
     }
 
     /// Return the buffer size.
@@ -314,6 +331,8 @@ impl TermBuf {
             suffix,
             style,
             continuation: false,
+            link: None,
+            image: false,
         };
         for offset in 1..width {
             self.cells[index + offset] = Cell::continuation(style);
@@ -347,6 +366,26 @@ impl TermBuf {
         Ok(())
     }
 
+    /// Attach a hyperlink target to the cells along a line, or clear it.
+    ///
+    /// The range expands to whole graphemes so a wide glyph is never partly
+    /// linked. Cells written afterwards start without a link.
+    pub fn set_link(&mut self, l: Line, target: Option<&str>) {
+        let Some(isec) = self.rect().intersect(l.rect()) else {
+            return;
+        };
+        let width = self.size.w as usize;
+        let row_start = isec.tl.y as usize * width;
+        let row = &self.cells[row_start..row_start + width];
+        let Some((start, end)) = grapheme_range(row, isec.tl.x as usize, isec.w as usize) else {
+            return;
+        };
+        let link: Option<Arc<str>> = target.map(Arc::from);
+        for cell in &mut self.cells[row_start + start..row_start + end] {
+            cell.link.clone_from(&link);
+        }
+    }
+
     /// Overlay a cursor on a cell by adjusting its style.
     pub fn overlay_cursor(&mut self, location: Point, shape: cursor::CursorShape) {
         let Some(idx) = self.idx(location) else {
@@ -377,6 +416,42 @@ impl TermBuf {
         }
     }
 
+    /// Place an image over a rectangle of cells, which keep their contents as a fallback.
+    ///
+    /// The placement is clipped to the buffer and replaces earlier images it overlaps. Writing
+    /// to a covered cell afterwards withdraws the image, so later widgets drawn over it stay
+    /// visible.
+    pub fn place_image(&mut self, mut placement: ImagePlacement) {
+        let Some(dest) = self.rect().intersect(placement.dest) else {
+            return;
+        };
+        placement.dest = dest;
+        self.images
+            .retain(|image| image.dest.intersect(dest).is_none());
+        for y in dest.tl.y..dest.tl.y + dest.h {
+            for x in dest.tl.x..dest.tl.x + dest.w {
+                if let Some(index) = self.idx(Point { x, y }) {
+                    self.cells[index].image = true;
+                }
+            }
+        }
+        self.images.push(placement);
+    }
+
+    /// Return the images whose cells have not been drawn over since they were placed.
+    pub fn images(&self) -> Vec<&ImagePlacement> {
+        self.images
+            .iter()
+            .filter(|image| {
+                let dest = image.dest;
+                (dest.tl.y..dest.tl.y + dest.h).all(|y| {
+                    (dest.tl.x..dest.tl.x + dest.w)
+                        .all(|x| self.get(Point { x, y }).is_some_and(|cell| cell.image))
+                })
+            })
+            .collect()
+    }
+
     /// Draw text clipped to the given line.
     pub fn text(&mut self, style: &ResolvedStyle, l: Line, txt: &str) -> Result<()> {
         self.text_with(l, txt, |_| *style)
@@ -520,6 +595,9 @@ impl TermBuf {
         if self.size != prev.size {
             return self.render(backend);
         }
+        if backend.supports_images() && !(self.images.is_empty() && prev.images.is_empty()) {
+            return self.diff_with_images(prev, backend);
+        }
         if backend.supports_line_shift() {
             let full = self.rect();
             if let Some(shift) = detect_row_shift_in_rect(self, prev, full, MAX_ROW_SHIFT) {
@@ -597,17 +675,46 @@ impl TermBuf {
         Ok(())
     }
 
+    /// Diff against a previous state on a backend that draws images.
+    ///
+    /// Cells under shown images are left alone, cells uncovered since the previous frame are
+    /// rewritten, and line shifting is skipped because it would move images with the text.
+    fn diff_with_images<R: RenderBackend>(&self, prev: &Self, backend: &mut R) -> Result<()> {
+        let shown = self.images();
+        let was_shown = prev.images();
+        let mut wrote = false;
+        for image in &was_shown {
+            if !shown.contains(image) {
+                backend.erase_image(image)?;
+                wrote = true;
+            }
+        }
+        wrote |= self.render_uncovered(Some(prev), &shown, &was_shown, backend)?;
+        for image in &shown {
+            if !was_shown.contains(image) {
+                backend.draw_image(image)?;
+                wrote = true;
+            }
+        }
+        if wrote {
+            backend.flush()?;
+        }
+        Ok(())
+    }
+
     /// Render this terminal buffer in full using the provided backend,
     /// batching runs of text with the same style.
     pub fn render<R: RenderBackend>(&self, backend: &mut R) -> Result<()> {
         self.validate_canonical()?;
-        let mut wrote = false;
-        let width = self.size.w as usize;
-        for y in 0..self.size.h {
-            let row_start = y as usize * width;
-            let row_end = row_start + width;
-            let row = &self.cells[row_start..row_end];
-            render_line_range(backend, row, y, 0, width)?;
+        let shown = if backend.supports_images() {
+            backend.erase_images()?;
+            self.images()
+        } else {
+            Vec::new()
+        };
+        let mut wrote = self.render_uncovered(None, &shown, &[], backend)?;
+        for image in &shown {
+            backend.draw_image(image)?;
             wrote = true;
         }
         if wrote {
@@ -615,6 +722,47 @@ impl TermBuf {
         }
         Ok(())
     }
+
+    /// Write the cells that are not under a shown image, skipping cells that match an
+    /// uncovered cell of the previous frame. Returns true when anything was written.
+    fn render_uncovered<R: RenderBackend>(
+        &self,
+        prev: Option<&Self>,
+        shown: &[&ImagePlacement],
+        was_shown: &[&ImagePlacement],
+        backend: &mut R,
+    ) -> Result<bool> {
+        let covered = |images: &[&ImagePlacement], p: Point| {
+            images.iter().any(|image| image.dest.contains_point(p))
+        };
+        let width = self.size.w as usize;
+        let mut wrote = false;
+        for y in 0..self.size.h {
+            let row_start = y as usize * width;
+            let row = &self.cells[row_start..row_start + width];
+            let stale = |x: usize| {
+                let p = Point { x: x as u32, y };
+                !covered(shown, p)
+                    && prev.is_none_or(|prev| {
+                        prev.cells[row_start + x] != row[x] || covered(was_shown, p)
+                    })
+            };
+            let mut x = 0usize;
+            while x < width {
+                if !stale(x) {
+                    x += 1;
+                    continue;
+                }
+                let start = x;
+                while x < width && stale(x) {
+                    x += 1;
+                }
+                render_line_range(backend, row, y, start, x - start)?;
+                wrote = true;
+            }
+        }
+        Ok(wrote)
+    }
 }
 
 /// Return the first cell of the grapheme containing the provided cell index.
@@ -867,7 +1015,7 @@ fn render_line_range<R: RenderBackend>(
     Ok(())
 }
 
-/// Render cells that share a style, splitting after wide graphemes.
+/// Render cells that share a style and link, splitting after wide graphemes.
 fn render_styled_cells<R: RenderBackend>(
     backend: &mut R,
     row: &[Cell],
@@ -876,6 +1024,7 @@ fn render_styled_cells<R: RenderBackend>(
     end: usize,
 ) -> Result<usize> {
     let style = &row[start].style;
+    let link = &row[start].link;
     let mut text = String::new();
     let mut x = start;
     let mut split_after_wide = false;
@@ -889,7 +1038,7 @@ fn render_styled_cells<R: RenderBackend>(
             }
             break;
         }
-        if cell.style != *style {
+        if cell.style != *style || cell.link != *link {
             break;
         }
 
@@ -900,6 +1049,7 @@ fn render_styled_cells<R: RenderBackend>(
     }
 
     backend.style(style)?;
+    backend.link(link.as_deref())?;
     if text.is_empty() {
         backend.text(Point { x: start as u32, y }, " ")?;
         return Ok(x.max(start + 1));
@@ -10,7 +10,8 @@ use crate::{
     buf,
     core::{testing::model::trace_result, text::grapheme_width},
     geom::Line,
-    style::{AttrSet, Color, PartialStyle, StyleBuilder},
+    graphics::Pixmap,
+    style::{AttrSet, Color, PartialStyle, StyleBuilder, UnderlineStyle},
     testing::buf::BufTest,
 };
 
@@ -19,6 +20,7 @@ fn def_style() -> ResolvedStyle {
         fg: Color::White,
         bg: Color::Black,
         attrs: AttrSet::default(),
+        underline: None,
     }
 }
 
@@ -243,6 +245,30 @@ impl RenderBackend for RecBackend {
     }
 }
 
+struct LinkBackend {
+    ops: Vec<String>,
+}
+
+impl RenderBackend for LinkBackend {
+    fn style(&mut self, _s: &ResolvedStyle) -> Result<()> {
+        Ok(())
+    }
+
+    fn text(&mut self, loc: Point, txt: &str) -> Result<()> {
+        self.ops.push(format!("text {} {} {}", loc.x, loc.y, txt));
+        Ok(())
+    }
+
+    fn link(&mut self, target: Option<&str>) -> Result<()> {
+        self.ops.push(format!("link {}", target.unwrap_or("-")));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
 struct ShiftBackend {
     shift: Option<i32>,
     text_ops: usize,
@@ -937,6 +963,36 @@ fn diff_style_changes() {
     assert_eq!(be.ops[3], "text 1 0 b");
 }
 
+#[test]
+fn diff_emits_underline_style_and_color_changes() {
+    let plain = def_style();
+    let curly = ResolvedStyle {
+        attrs: plain.attrs.with_underline(UnderlineStyle::Curly),
+        ..plain
+    };
+    let colored = curly.with_underline(Some(Color::Red));
+
+    let prev =
+        TermBuf::new(Size::new(3, 1), ' ', plain).expect("test render target should allocate");
+    let mut cur = prev.clone();
+    cur.fill(&curly, Rect::new(0, 0, 1, 1), 'a')
+        .expect("test buffer mutation should succeed");
+    cur.fill(&colored, Rect::new(1, 0, 1, 1), 'b')
+        .expect("test buffer mutation should succeed");
+
+    let mut be = RecBackend::new();
+    cur.diff(&prev, &mut be).unwrap();
+    assert_eq!(
+        be.ops,
+        vec![
+            format!("style {curly:?}"),
+            "text 0 0 a".to_string(),
+            format!("style {colored:?}"),
+            "text 1 0 b".to_string(),
+        ]
+    );
+}
+
 #[test]
 fn diff_multi_line() {
     let style = def_style();
@@ -953,6 +1009,40 @@ fn diff_multi_line() {
     assert_eq!(be.ops[1], "text 0 1 xx");
 }
 
+#[test]
+fn diff_treats_links_as_cell_identity() {
+    let style = def_style();
+    let mut prev =
+        TermBuf::new(Size::new(6, 1), ' ', style).expect("test render target should allocate");
+    prev.text(&style, Line::new(0, 0, 6), "ab界c")
+        .expect("test buffer mutation should succeed");
+    let mut cur = prev.clone();
+    cur.set_link(Line::new(1, 0, 2), Some("https://example.com"));
+
+    assert_eq!(
+        cur.get(Point { x: 1, y: 0 }).unwrap().link.as_deref(),
+        Some("https://example.com")
+    );
+    assert!(cur.get(Point { x: 3, y: 0 }).unwrap().link.is_some());
+    assert!(cur.get(Point { x: 4, y: 0 }).unwrap().link.is_none());
+
+    let mut be = LinkBackend { ops: Vec::new() };
+    cur.diff(&prev, &mut be).unwrap();
+    assert_eq!(
+        be.ops,
+        vec![
+            "link https://example.com",
+            "text 1 0 b",
+            "link https://example.com",
+            "text 2 0 界",
+        ]
+    );
+
+    cur.text(&style, Line::new(1, 0, 1), "b")
+        .expect("test buffer mutation should succeed");
+    assert!(cur.get(Point { x: 1, y: 0 }).unwrap().link.is_none());
+}
+
 #[test]
 fn render_whole_buffer() {
     let style = def_style();
@@ -1154,3 +1244,109 @@ fn contains_text_style_builders() {
     let italic_red = PartialStyle::from(StyleBuilder::new().fg(Color::Red).attr(Attr::Italic));
     assert!(!BufTest::new(&tb).contains_text_style("bold", &italic_red));
 }
+
+/// Backend that draws images and records both text and image operations.
+struct ImageBackend {
+    ops: Vec<String>,
+}
+
+impl RenderBackend for ImageBackend {
+    fn style(&mut self, _s: &ResolvedStyle) -> Result<()> {
+        Ok(())
+    }
+
+    fn text(&mut self, loc: Point, txt: &str) -> Result<()> {
+        self.ops.push(format!("text {} {} {}", loc.x, loc.y, txt));
+        Ok(())
+    }
+
+    fn supports_images(&self) -> bool {
+        true
+    }
+
+    fn draw_image(&mut self, image: &ImagePlacement) -> Result<()> {
+        let dest = image.dest;
+        self.ops.push(format!(
+            "draw {} {} {} {}",
+            dest.tl.x, dest.tl.y, dest.w, dest.h
+        ));
+        Ok(())
+    }
+
+    fn erase_image(&mut self, image: &ImagePlacement) -> Result<()> {
+        let dest = image.dest;
+        self.ops.push(format!(
+            "erase {} {} {} {}",
+            dest.tl.x, dest.tl.y, dest.w, dest.h
+        ));
+        Ok(())
+    }
+
+    fn erase_images(&mut self) -> Result<()> {
+        self.ops.push("erase all".into());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn diff_leaves_image_cells_alone_and_repaints_uncovered_cells() -> Result<()> {
+    let image = Arc::new(Pixmap::new(2, 2, vec![0; 16])?);
+    let place = |tb: &mut TermBuf, dest: Rect| {
+        tb.place_image(ImagePlacement {
+            image: Arc::clone(&image),
+            source: image.rect(),
+            dest,
+        });
+    };
+    let mut be = ImageBackend { ops: Vec::new() };
+
+    let mut first = buf_from_rows(&["abcd", "efgh"]);
+    place(&mut first, Rect::new(1, 0, 2, 2));
+    first.render(&mut be)?;
+    assert_eq!(
+        be.ops,
+        [
+            "erase all",
+            "text 0 0 a",
+            "text 3 0 d",
+            "text 0 1 e",
+            "text 3 1 h",
+            "draw 1 0 2 2"
+        ]
+    );
+
+    // Text changes beneath an unchanged image are not written.
+    be.ops.clear();
+    let mut second = buf_from_rows(&["aXYd", "efgh"]);
+    place(&mut second, Rect::new(1, 0, 2, 2));
+    second.diff(&first, &mut be)?;
+    assert!(be.ops.is_empty());
+
+    // Shrinking the image erases it, repaints the uncovered cells and draws it again.
+    be.ops.clear();
+    let mut third = buf_from_rows(&["aXYd", "efgh"]);
+    place(&mut third, Rect::new(1, 0, 1, 1));
+    third.diff(&second, &mut be)?;
+    assert_eq!(
+        be.ops,
+        ["erase 1 0 2 2", "text 2 0 Y", "text 1 1 fg", "draw 1 0 1 1"]
+    );
+
+    // Drawing over the image withdraws it and shows the text beneath.
+    be.ops.clear();
+    let mut fourth = buf_from_rows(&["aXYd", "efgh"]);
+    place(&mut fourth, Rect::new(1, 0, 1, 1));
+    fourth.text(&def_style(), Line::new(1, 0, 1), "Z")?;
+    fourth.diff(&third, &mut be)?;
+    assert_eq!(be.ops, ["erase 1 0 1 1", "text 1 0 Z"]);
+
+    // Backends without images only see the text.
+    let mut rec = RecBackend::new();
+    fourth.render(&mut rec)?;
+    assert!(rec.ops.iter().any(|op| op == "text 0 0 aZYd"));
+    Ok(())
+}
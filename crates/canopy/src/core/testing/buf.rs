@@ -134,7 +134,13 @@ impl<'a> BufTest<'a> {
                         };
                         let attr_matches =
                             style.attrs.is_none() || style.attrs == Some(cell.style.attrs);
-                        let style_matches = fg_matches && bg_matches && attr_matches;
+                        let underline_matches = match &style.underline {
+                            None => true,
+                            Some(Paint::Solid(color)) => Some(*color) == cell.style.underline,
+                            Some(Paint::Gradient(_)) => false,
+                        };
+                        let style_matches =
+                            fg_matches && bg_matches && attr_matches && underline_matches;
                         if style_matches {
                             c = true;
                         }
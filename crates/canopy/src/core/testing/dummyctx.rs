@@ -7,12 +7,15 @@ use crate::{
     commands::{ArgValue, CommandError, CommandInvocation, CommandScopeFrame, ListRowContext},
     core::{
         NodeId,
+        external::{External, ExternalOutput},
+        frame::FrameStats,
         help::BindingSnapshot,
         inputmap::{ExclusiveFrameToken, FrameworkBindingGroup},
         style::Effect,
+        task::{Task, TaskCallback, TaskId},
         view::View,
     },
-    error::Result,
+    error::{Error, Result},
     event::{Event, mouse::MouseEvent},
     geom::{Direction, Point},
     layout::Layout,
@@ -55,6 +58,10 @@ impl ViewContext for DummyContext {
         None
     }
 
+    fn frame_stats(&self) -> FrameStats {
+        FrameStats::default()
+    }
+
     fn node_type_id(&self, _node: NodeId) -> Option<TypeId> {
         None
     }
@@ -273,4 +280,26 @@ impl Context for DummyContext {
     fn request_diagnostic_dump(&mut self, _target: NodeId) {
         // DummyContext does not track diagnostic requests
     }
+
+    fn spawn_task(&mut self, _task: Task, _deliver: TaskCallback) -> Result<TaskId> {
+        Ok(TaskId::default())
+    }
+
+    fn cancel_task(&mut self, _task: TaskId) -> bool {
+        false
+    }
+
+    fn task_pending(&self, _task: TaskId) -> bool {
+        false
+    }
+
+    fn suspend(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn run_external(&mut self, _external: External) -> Result<ExternalOutput> {
+        Err(Error::InvalidOperation(
+            "DummyContext cannot run external programs".into(),
+        ))
+    }
 }
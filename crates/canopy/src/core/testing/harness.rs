@@ -1,15 +1,15 @@
-use std::any::Any;
+use std::{any::Any, time::Duration};
 
 use super::buf::BufTest;
 use crate::{
-    Canopy, Context, Loader, NodeId, ViewContext,
+    Canopy, Context, Loader, NodeId, ViewContext, VirtualClock,
     core::{
         context::{CoreContext, CoreViewContext},
         termbuf::TermBuf,
     },
     error::Result,
     event::{key, mouse},
-    geom::Size,
+    geom::{Rect, Size},
     layout::Sizing,
     render::NopBackend,
     widget::Widget,
@@ -33,6 +33,8 @@ pub struct HarnessBuilder<W> {
     root: W,
     /// View size for the harness.
     size: Size,
+    /// Whether poll timing runs on a virtual clock.
+    virtual_clock: bool,
 }
 
 impl<W: Widget + Loader + 'static> HarnessBuilder<W> {
@@ -41,6 +43,7 @@ impl<W: Widget + Loader + 'static> HarnessBuilder<W> {
         Self {
             root,
             size: Size::new(100, 100),
+            virtual_clock: false,
         }
     }
 
@@ -50,10 +53,19 @@ impl<W: Widget + Loader + 'static> HarnessBuilder<W> {
         self
     }
 
+    /// Run poll timing on a virtual clock, advanced with [`Harness::advance`].
+    pub fn virtual_clock(mut self) -> Self {
+        self.virtual_clock = true;
+        self
+    }
+
     /// Build the harness with the configured settings.
     pub fn build(self) -> Result<Harness> {
         let render = NopBackend::new();
         let mut canopy = Canopy::new();
+        if self.virtual_clock {
+            canopy.use_virtual_clock(VirtualClock::new());
+        }
 
         <W as Loader>::load(&mut canopy)?;
         canopy.finalize_api()?;
@@ -138,6 +150,14 @@ impl Harness {
         self.canopy.render(&mut self.backend)
     }
 
+    /// Advance the virtual clock, firing polls as their deadlines pass, and render.
+    ///
+    /// Fails unless the harness was built with [`HarnessBuilder::virtual_clock`].
+    pub fn advance(&mut self, duration: Duration) -> Result<()> {
+        self.canopy.advance_time(duration)?;
+        self.canopy.render(&mut self.backend)
+    }
+
     /// Execute a script on the app under test.
     pub fn script(&mut self, script: &str) -> Result<()> {
         let script_id = self.canopy.script_host.compile(script)?;
@@ -198,6 +218,24 @@ impl Harness {
         BufTest::new(self.buf())
     }
 
+    /// Assert that the screen matches a named snapshot file, panicking with a cell-level diff
+    /// on mismatch.
+    ///
+    /// Snapshot files default to the `snapshots` directory of the crate under test. Set
+    /// `CANOPY_UPDATE_SNAPSHOTS=1` to record missing snapshots and rewrite changed ones.
+    pub fn assert_snapshot(&mut self, name: &str) {
+        self.assert_snapshot_region(name, None);
+    }
+
+    /// Assert that a region of the screen matches a named snapshot file.
+    pub fn assert_snapshot_region(&mut self, name: &str, region: Option<Rect>) {
+        let check = self
+            .canopy
+            .check_snapshot(name, region)
+            .unwrap_or_else(|err| panic!("snapshot {name:?}: {err}"));
+        assert!(check.passed(), "snapshot {name:?} {check}");
+    }
+
     /// Find all nodes whose paths match the filter, relative to the root.
     pub fn find_nodes(&self, path_filter: &str) -> Vec<NodeId> {
         let ctx = CoreViewContext::new(&self.canopy.core, self.root);
@@ -207,10 +245,12 @@ impl Harness {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
     use crate::{
         ViewContext, derive_commands, error::Result, geom::Line, layout::Layout, render::Render,
-        state::NodeName, widget::Widget,
+        snapshot::SnapshotConfig, state::NodeName, widget::Widget,
     };
 
     struct TestNode;
@@ -251,6 +291,41 @@ mod tests {
         assert!(h.tbuf().contains_text("test"));
     }
 
+    #[test]
+    fn test_harness_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut h = Harness::builder(TestNode::new())
+            .size(10, 2)
+            .build()
+            .unwrap();
+        h.canopy
+            .set_snapshot_config(SnapshotConfig::new(dir.path()).with_update(true));
+        h.render().unwrap();
+        h.assert_snapshot("node");
+        h.assert_snapshot_region("word", Some(Rect::new(0, 0, 4, 1)));
+        let file = fs::read_to_string(dir.path().join("word.snap")).unwrap();
+        assert!(file.starts_with("canopy snapshot 4x1\n--- text\n|test|\n--- style\naaaa\n"));
+
+        h.canopy
+            .set_snapshot_config(SnapshotConfig::new(dir.path()));
+        h.assert_snapshot("node");
+        h.assert_snapshot_region("word", Some(Rect::new(0, 0, 4, 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot \"missing\" is missing")]
+    fn test_harness_missing_snapshot_panics() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut h = Harness::builder(TestNode::new())
+            .size(10, 2)
+            .build()
+            .unwrap();
+        h.canopy
+            .set_snapshot_config(SnapshotConfig::new(dir.path()));
+        h.render().unwrap();
+        h.assert_snapshot("missing");
+    }
+
     #[test]
     fn test_harness_builder() {
         let mut h = Harness::builder(TestNode::new())
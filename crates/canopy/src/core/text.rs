@@ -94,11 +94,31 @@ fn tab_width(column: usize, tab_stop: usize) -> usize {
 }
 
 /// Return the display width of a string in terminal cells.
-#[cfg(test)]
 pub fn display_width(s: &str) -> usize {
     s.graphemes(true).map(grapheme_width).sum()
 }
 
+/// Escape text for XML and HTML attributes and content.
+///
+/// Control characters other than newlines and tabs, which XML 1.0 cannot hold, become
+/// replacement characters.
+pub fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' | '\r' | '\t' => out.push(ch),
+            ch if ch.is_control() => out.push(char::REPLACEMENT_CHARACTER),
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
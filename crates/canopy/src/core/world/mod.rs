@@ -3,13 +3,18 @@
     reason = "Core methods are split by arena, layout, and dispatch concerns."
 )]
 
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{cell::RefCell, collections::HashSet, io, mem, rc::Rc};
 
 use slotmap::SlotMap;
 
 use self::focus::FocusRecoveryHint;
 use super::{
+    backend::TerminalHandle,
+    coverage::CoverageHits,
+    external,
+    frame::FrameHistory,
     inputmap::{ExclusiveFrameToken, InputMap},
+    task::Tasks,
     widget_access::{WidgetMutGuard, WidgetReadGuard, WidgetSlotGuard},
 };
 use crate::{
@@ -64,8 +69,20 @@ pub struct Core {
     pub(crate) input_map: InputMap,
     /// Command scope stack for injection.
     command_scope: Vec<CommandScopeFrame>,
+    /// Command and binding hits, while coverage recording is on.
+    pub(crate) coverage: Option<CoverageHits>,
     /// Pending diagnostic dump request.
     pub(crate) pending_diagnostic_dump: Option<NodeId>,
+    /// Background tasks owned by nodes.
+    pub(crate) tasks: Tasks,
+    /// Timing of recently rendered frames.
+    pub(crate) frames: FrameHistory,
+    /// Terminal session that can be lent to external programs, when one is running.
+    pub(crate) terminal: Option<TerminalHandle>,
+    /// Whether the next frame must redraw every cell.
+    pub(crate) repaint_requested: bool,
+    /// Stops the process for a suspend. Tests replace this to avoid stopping themselves.
+    pub(crate) stop_process: fn() -> io::Result<()>,
 }
 
 /// Journal for one outermost tree edit and all nested edits it performs.
@@ -188,7 +205,13 @@ impl Core {
             commands: CommandSet::new(),
             input_map: InputMap::new(),
             command_scope: Vec::new(),
+            coverage: None,
             pending_diagnostic_dump: None,
+            tasks: Tasks::new(),
+            frames: FrameHistory::default(),
+            terminal: None,
+            repaint_requested: false,
+            stop_process: external::stop_process,
         }
     }
 
@@ -204,6 +227,25 @@ impl Core {
         self.exit_requested.take()
     }
 
+    /// Lend the terminal to a closure and repaint every cell afterwards.
+    ///
+    /// Without a terminal session the closure runs directly. The crossterm backend reports the
+    /// terminal size as a resize when it takes the terminal back, so the repaint uses any size
+    /// change made in the meantime.
+    pub(crate) fn with_terminal_released<R>(&mut self, f: impl FnOnce() -> R) -> Result<R> {
+        let out = match &self.terminal {
+            Some(terminal) => terminal.release_while(f)?,
+            None => f(),
+        };
+        self.repaint_requested = true;
+        Ok(out)
+    }
+
+    /// Take and clear any pending full-repaint request.
+    pub(crate) fn take_repaint_request(&mut self) -> bool {
+        mem::take(&mut self.repaint_requested)
+    }
+
     /// Request a diagnostic dump for a target node.
     pub(crate) fn request_diagnostic_dump(&mut self, target: NodeId) {
         self.pending_diagnostic_dump = Some(target);
@@ -296,6 +296,7 @@ impl Core {
             };
             let mut ctx = CoreContext::new(self, mounted.node_id);
             widget.on_unmount(&mut ctx);
+            self.tasks.cancel_node(mounted.node_id);
         }
         self.rolling_back_tree_edit = false;
     }
@@ -311,6 +312,7 @@ impl Core {
         }
         let widget = Rc::clone(&node.widget);
         self.with_widget_ctx(node_id, |widget, ctx| widget.on_unmount(ctx))?;
+        self.tasks.cancel_node(node_id);
         if let Some(node) = self.nodes.get_mut(node_id) {
             node.mounted = false;
         }
@@ -985,6 +987,7 @@ impl Core {
 
     /// Clear auxiliary targets that point into a removed set.
     fn clear_removed_targets(&mut self, removed: &HashSet<NodeId>) {
+        self.tasks.cancel_nodes(removed);
         if self
             .pending_diagnostic_dump
             .is_some_and(|target| removed.contains(&target))
@@ -36,14 +36,16 @@ pub use core::testing;
 // Stable app-author surface.
 pub use core::{
     AutomationCallback, AutomationHandle, BindingId, BindingOwner, BindingPhase, BindingScope,
-    BindingTarget, Canopy, ChangeOutcome, ChildKey, Context, ExclusiveFrameToken, Fixture,
-    FixtureInfo, FocusScope, FrameworkBindingGroup, InputSpec, KeyedChildren, Loader, NodeId, Path,
-    PathFilter, RemovePolicy, RoutePhase, RouteTraceEntry, ScriptApiState, ScriptJournalEntry,
-    ScriptModuleRoots, Slot, TypedId, ViewContext,
+    BindingTarget, Canopy, ChangeOutcome, ChildKey, Context, ExclusiveFrameToken, External,
+    ExternalOutput, Fixture, FixtureInfo, FocusScope, FramePolicy, FrameStats, FrameTiming,
+    FrameworkBindingGroup, InputSpec, KeyedChildren, Loader, NodeId, Path, PathFilter,
+    RemovePolicy, RoutePhase, RouteTraceEntry, ScriptApiState, ScriptJournalEntry,
+    ScriptModuleRoots, Slot, TaskId, TerminalOptions, TypedId, ViewContext, VirtualClock,
 };
 // App-author modules used by widget implementations and derive output.
 pub use core::{
-    commands, cursor, error, event, help, path, render, script, state, style, text, view,
+    commands, coverage, cursor, error, event, export, graphics, help, observe, path, recording,
+    render, script, snapshot, state, style, task, text, view,
 };
 
 /// Crossterm terminal run-loop integration.
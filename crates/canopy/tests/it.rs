@@ -4,12 +4,18 @@
 //! Luau-bearing library. Keeping a single root here keeps that to one link step. Cargo resolves
 //! a test root's modules against `tests/` itself, so each module names its path explicitly.
 
+/// Virtual clock integration tests.
+#[path = "it/clock.rs"]
+mod clock;
 /// Command dispatch, argument, and error integration tests.
 #[path = "it/commands.rs"]
 mod commands;
 /// Helpers shared by more than one integration module.
 #[path = "it/common.rs"]
 mod common;
+/// Command and binding coverage integration tests.
+#[path = "it/coverage.rs"]
+mod coverage;
 /// Focus traversal integration tests.
 #[path = "it/focus.rs"]
 mod focus;
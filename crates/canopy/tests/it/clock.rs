@@ -0,0 +1,126 @@
+//! Virtual clock integration tests.
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use canopy::{
+        Canopy, Context, Loader, ViewContext, Widget, command,
+        commands::ArgValue,
+        derive_commands,
+        error::{Error, Result},
+        geom::Line,
+        render::Render,
+        testing::harness::Harness,
+    };
+
+    /// Widget that counts polls on a fixed interval.
+    struct Ticker {
+        /// Polls received so far, including the initial poll.
+        ticks: i64,
+    }
+
+    #[derive_commands]
+    impl Ticker {
+        fn new() -> Self {
+            Self { ticks: 0 }
+        }
+
+        #[command]
+        fn ticks(&self) -> i64 {
+            self.ticks
+        }
+    }
+
+    impl Widget for Ticker {
+        fn render(&mut self, frame: &mut Render, _ctx: &dyn ViewContext) -> Result<()> {
+            frame.text("default", Line::new(0, 0, 8), &self.ticks.to_string())?;
+            Ok(())
+        }
+
+        fn poll(&mut self, _ctx: &mut dyn Context) -> Option<Duration> {
+            self.ticks += 1;
+            Some(Duration::from_millis(100))
+        }
+    }
+
+    impl Loader for Ticker {
+        fn load(c: &mut Canopy) -> Result<()> {
+            c.add_commands::<Self>()
+        }
+    }
+
+    fn ticker_harness() -> Result<Harness> {
+        let mut harness = Harness::builder(Ticker::new())
+            .size(8, 1)
+            .virtual_clock()
+            .build()?;
+        harness.render()?;
+        Ok(harness)
+    }
+
+    fn ticks(harness: &mut Harness) -> i64 {
+        harness.with_root_widget::<Ticker, _>(|ticker| ticker.ticks)
+    }
+
+    fn virtual_now(harness: &Harness) -> Instant {
+        harness
+            .canopy
+            .virtual_clock()
+            .expect("virtual clock installed")
+            .now()
+    }
+
+    #[test]
+    fn advance_fires_each_poll_deadline_in_the_window() -> Result<()> {
+        let mut harness = ticker_harness()?;
+        let start = virtual_now(&harness);
+        assert_eq!(ticks(&mut harness), 1);
+
+        harness.advance(Duration::from_millis(250))?;
+        assert_eq!(ticks(&mut harness), 3);
+        assert!(harness.tbuf().contains_text("3"));
+
+        harness.advance(Duration::from_millis(49))?;
+        assert_eq!(ticks(&mut harness), 3);
+        harness.advance(Duration::from_millis(1))?;
+        assert_eq!(ticks(&mut harness), 4);
+        assert_eq!(virtual_now(&harness) - start, Duration::from_millis(300));
+        Ok(())
+    }
+
+    #[test]
+    fn advance_requires_a_virtual_clock() -> Result<()> {
+        let mut harness = Harness::builder(Ticker::new()).size(8, 1).build()?;
+        harness.render()?;
+        assert!(matches!(
+            harness.advance(Duration::from_millis(10)),
+            Err(Error::Invalid(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn luau_advance_time_and_waits_use_virtual_time() -> Result<()> {
+        let mut harness = ticker_harness()?;
+        let start = virtual_now(&harness);
+
+        harness.script("canopy.advance_time(100)")?;
+        assert_eq!(ticks(&mut harness), 2);
+
+        let value = harness.canopy.eval_script_value(
+            r#"return canopy.wait_for(function() return canopy.cmd("ticker::ticks") >= 6 end, 60000)"#,
+        )?;
+        assert_eq!(value, ArgValue::Bool(true));
+        assert_eq!(ticks(&mut harness), 6);
+        assert_eq!(virtual_now(&harness) - start, Duration::from_millis(500));
+
+        let error = harness
+            .canopy
+            .eval_script_value("return canopy.wait_for(function() return false end, 60000)")
+            .expect_err("wait should time out in virtual time");
+        assert!(matches!(error, Error::ScriptTimeout { timeout_ms: 60_000 }));
+        assert_eq!(virtual_now(&harness) - start, Duration::from_millis(60_500));
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+//! Command and binding coverage integration tests.
+
+#[cfg(test)]
+mod tests {
+    use canopy::{
+        Canopy, Loader, ViewContext, Widget, command, derive_commands, error::Result, geom::Line,
+        render::Render, testing::harness::Harness,
+    };
+
+    /// Widget with one command a binding exercises and one nothing exercises.
+    struct Counter {
+        /// Current count.
+        count: i64,
+    }
+
+    #[derive_commands]
+    impl Counter {
+        fn new() -> Self {
+            Self { count: 0 }
+        }
+
+        #[command]
+        fn bump(&mut self) {
+            self.count += 1;
+        }
+
+        #[command]
+        fn reset(&mut self) {
+            self.count = 0;
+        }
+    }
+
+    impl Widget for Counter {
+        fn render(&mut self, frame: &mut Render, _ctx: &dyn ViewContext) -> Result<()> {
+            frame.text("default", Line::new(0, 0, 8), &self.count.to_string())?;
+            Ok(())
+        }
+    }
+
+    impl Loader for Counter {
+        fn load(c: &mut Canopy) -> Result<()> {
+            c.add_commands::<Self>()
+        }
+    }
+
+    fn counter_harness() -> Result<Harness> {
+        let mut harness = Harness::builder(Counter::new()).size(8, 1).build()?;
+        harness.render()?;
+        harness.canopy.eval_script(
+            r#"canopy.bind("b", { description = "Bump the counter" }, function() counter.bump() end)"#,
+        )?;
+        Ok(harness)
+    }
+
+    #[test]
+    fn coverage_counts_dispatched_commands_and_fired_bindings() -> Result<()> {
+        let mut harness = counter_harness()?;
+        harness.canopy.start_coverage();
+        harness.key('b')?;
+        harness.key('b')?;
+
+        let hits = harness.canopy.take_coverage().expect("coverage recording");
+        assert_eq!(hits.commands.get("counter::bump"), Some(&2));
+        assert!(!hits.commands.contains_key("counter::reset"));
+        let binding = hits
+            .bindings
+            .values()
+            .find(|hit| hit.description == "Bump the counter")
+            .expect("binding hit");
+        assert_eq!(binding.count, 2);
+
+        assert!(harness.canopy.take_coverage().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn coverage_is_off_until_started() -> Result<()> {
+        let mut harness = counter_harness()?;
+        harness.key('b')?;
+        assert!(harness.canopy.take_coverage().is_none());
+
+        harness.canopy.start_coverage();
+        let hits = harness.canopy.take_coverage().expect("coverage recording");
+        assert!(hits.commands.is_empty());
+        assert!(hits.bindings.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn catalog_lists_commands_by_owner_and_bindings_by_key() -> Result<()> {
+        let harness = counter_harness()?;
+        let catalog = harness.canopy.coverage_catalog();
+
+        for id in ["counter::bump", "counter::reset"] {
+            let entry = catalog
+                .commands
+                .iter()
+                .find(|entry| entry.id == id)
+                .expect("registered command");
+            assert_eq!(entry.owner, "counter");
+        }
+        let binding = catalog
+            .bindings
+            .iter()
+            .find(|entry| entry.description == "Bump the counter")
+            .expect("registered binding");
+        assert_eq!(binding.key, "default:b");
+        assert_eq!(binding.target, "script");
+        assert!(!binding.framework);
+        Ok(())
+    }
+}
@@ -11,9 +11,11 @@ mod tests {
         derive_commands,
         error::{Error, Result, ScriptErrorKind},
         event::{Event, key::Key, mouse},
+        export::ExportFormat,
         geom::{Line, Size},
         layout::Layout,
         render::Render,
+        snapshot::SnapshotConfig,
         testing::{backend::TestRender, harness::Harness},
     };
     use serde::{Deserialize, Serialize};
@@ -63,6 +65,10 @@ mod tests {
                     self.value = 22;
                     Ok(EventOutcome::Handle)
                 }
+                Event::Paste(text) => {
+                    self.value = i32::try_from(text.len()).unwrap_or(i32::MAX);
+                    Ok(EventOutcome::Handle)
+                }
                 _ => Ok(EventOutcome::Ignore),
             }
         }
@@ -931,6 +937,43 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn read_only_scripts_inspect_but_cannot_mutate() -> Result<()> {
+        let mut harness = Harness::builder(ScriptTarget::new()).size(10, 1).build()?;
+        harness.canopy.set_script_read_only(true);
+        assert_eq!(
+            harness
+                .canopy
+                .eval_script_value("return canopy.input_mode()")?,
+            ArgValue::String("default".into())
+        );
+
+        for script in [
+            "script_target.set(3)",
+            r#"canopy.cmd("script_target::set", 3)"#,
+            r#"canopy.send_key("x")"#,
+            r#"canopy.set_mode("insert")"#,
+        ] {
+            let err = harness
+                .script(script)
+                .expect_err("read-only scripts cannot mutate");
+            let Error::ScriptStructured { kind, .. } = err else {
+                panic!("expected structured script error, got {err:?}");
+            };
+            assert_eq!(kind, ScriptErrorKind::ReadOnly);
+        }
+        harness.with_root_widget::<ScriptTarget, _>(|target| {
+            assert_eq!(target.value, 0);
+        });
+
+        harness.canopy.set_script_read_only(false);
+        harness.script("script_target.set(3)")?;
+        harness.with_root_widget::<ScriptTarget, _>(|target| {
+            assert_eq!(target.value, 3);
+        });
+        Ok(())
+    }
+
     #[test]
     fn command_discovery_reports_contract_and_availability() -> Result<()> {
         let mut harness = Harness::builder(ScriptTarget::new()).size(10, 1).build()?;
@@ -1052,6 +1095,94 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn luau_snapshot_assertions_record_and_compare_files() -> Result<()> {
+        let dir = test_dir();
+        let mut harness = Harness::builder(ApiRoot).size(20, 5).build()?;
+        harness.render()?;
+        let corner = r#"canopy.assert_snapshot("corner", { x = 0, y = 0, w = 8, h = 1 })"#;
+
+        harness
+            .canopy
+            .set_snapshot_config(SnapshotConfig::new(dir.path()).with_update(true));
+        harness.canopy.eval_script(corner)?;
+        assert!(dir.path().join("corner.snap").is_file());
+        harness.canopy.take_script_assertions();
+
+        harness
+            .canopy
+            .set_snapshot_config(SnapshotConfig::new(dir.path()));
+        harness.canopy.eval_script(corner)?;
+        assert!(harness.canopy.take_script_assertions()[0].passed);
+
+        let error = harness
+            .canopy
+            .eval_script(r#"canopy.assert_snapshot("corner")"#)
+            .unwrap_err();
+        assert!(error.to_string().contains("size differs"));
+        let assertions = harness.canopy.take_script_assertions();
+        assert!(!assertions[0].passed);
+        assert!(assertions[0].message.contains("expected 8x1, actual 20x5"));
+
+        let error = harness
+            .canopy
+            .eval_script(r#"canopy.assert_snapshot("missing")"#)
+            .unwrap_err();
+        assert!(error.to_string().contains("is missing"));
+        Ok(())
+    }
+
+    #[test]
+    fn luau_export_screen_returns_documents() -> Result<()> {
+        let mut harness = Harness::builder(ApiRoot).size(20, 5).build()?;
+        harness.render()?;
+
+        for format in ExportFormat::ALL {
+            let expected = harness.canopy.export_screen(format)?;
+            let value = harness
+                .canopy
+                .eval_script_value(&format!("return canopy.export_screen(\"{format}\")"))?;
+            assert_eq!(value, ArgValue::String(expected));
+        }
+
+        let error = harness
+            .canopy
+            .eval_script(r#"canopy.export_screen("png")"#)
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown export format"));
+        Ok(())
+    }
+
+    #[test]
+    fn luau_paste_resize_and_render_drive_the_app() -> Result<()> {
+        let mut harness = Harness::builder(ApiRoot).size(20, 5).build()?;
+        harness.render()?;
+
+        harness.script(
+            r#"
+            local leaves = canopy.find_nodes("api_root/api_leaf")
+            canopy.set_focus(leaves[2])
+            canopy.send_paste("hello")
+            canopy.assert(
+                canopy.cmd_on(leaves[2], "api_leaf::get") == 5,
+                "send_paste should reach the focused node"
+            )
+            canopy.resize(12, 3)
+            canopy.render()
+            local rows = canopy.screen()
+            canopy.assert(#rows == 3, "resize should change the screen height")
+            canopy.assert(#rows[1] == 12, "resize should change the screen width")
+        "#,
+        )?;
+
+        let error = harness
+            .canopy
+            .eval_script("canopy.resize(0, 3)")
+            .unwrap_err();
+        assert!(error.to_string().contains("width must be > 0"));
+        Ok(())
+    }
+
     #[test]
     fn on_start_hooks_run_after_first_render() -> Result<()> {
         let mut harness = Harness::builder(ScriptTarget::new()).size(10, 1).build()?;
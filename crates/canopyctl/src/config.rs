@@ -20,6 +20,8 @@ struct FileConfig {
     smoke: Option<SmokeSection>,
     /// `canopyctl mcp` server settings.
     mcp: Option<McpSection>,
+    /// `canopyctl watch` settings.
+    watch: Option<WatchSection>,
 }
 
 /// `[app]` section from `.canopyctl.toml`.
@@ -46,6 +48,8 @@ struct SmokeSection {
     fail_fast: Option<bool>,
     /// Default per-script timeout in milliseconds.
     timeout_ms: Option<u64>,
+    /// Default number of headless app processes evaluating scripts at once.
+    jobs: Option<usize>,
 }
 
 /// `[mcp]` section from `.canopyctl.toml`.
@@ -55,6 +59,15 @@ struct McpSection {
     idle_shutdown_after_secs: Option<u64>,
 }
 
+/// `[watch]` section from `.canopyctl.toml`.
+#[derive(Debug, Default, Clone, Deserialize)]
+struct WatchSection {
+    /// App sources or binaries whose changes rerun the whole suite.
+    paths: Option<Vec<PathBuf>>,
+    /// Polling interval in milliseconds.
+    poll_ms: Option<u64>,
+}
+
 /// Loaded config together with the directory it resolves paths against.
 #[derive(Debug, Clone)]
 pub struct LoadedConfig {
@@ -192,6 +205,53 @@ impl LoadedConfig {
                 .unwrap_or(false)
     }
 
+    /// Resolve the effective number of parallel smoke jobs, at least one.
+    pub fn smoke_jobs(&self, override_jobs: Option<usize>) -> usize {
+        override_jobs
+            .or_else(|| self.file.smoke.as_ref().and_then(|smoke| smoke.jobs))
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    /// Resolve the app paths `canopyctl watch` watches besides the suite.
+    ///
+    /// Explicit paths win, then `[watch].paths`. With neither, the headless program is
+    /// watched when it names a file on disk rather than a command on `PATH`.
+    pub fn watch_paths(
+        &self,
+        override_paths: &[PathBuf],
+        headless: &ResolvedCommand,
+    ) -> Vec<PathBuf> {
+        let configured = self
+            .file
+            .watch
+            .as_ref()
+            .and_then(|watch| watch.paths.as_ref());
+        if !override_paths.is_empty() {
+            override_paths
+                .iter()
+                .map(|path| self.resolve_path(path))
+                .collect()
+        } else if let Some(configured) = configured {
+            configured
+                .iter()
+                .map(|path| self.resolve_path(path))
+                .collect()
+        } else {
+            headless.program_path().into_iter().collect()
+        }
+    }
+
+    /// Resolve the effective `canopyctl watch` polling interval.
+    pub fn watch_poll_interval(&self, override_poll_ms: Option<u64>) -> Duration {
+        Duration::from_millis(
+            override_poll_ms
+                .or_else(|| self.file.watch.as_ref().and_then(|watch| watch.poll_ms))
+                .unwrap_or(500)
+                .max(10),
+        )
+    }
+
     /// Resolve the effective MCP idle timeout.
     pub fn idle_shutdown_after(&self) -> Duration {
         Duration::from_secs(
@@ -224,6 +284,21 @@ impl ResolvedCommand {
         Ok(Self { argv, cwd, env })
     }
 
+    /// Return the program as a file path, when it names an existing file rather than a command
+    /// looked up on `PATH`.
+    pub fn program_path(&self) -> Option<PathBuf> {
+        let program = Path::new(&self.argv[0]);
+        if program.components().count() < 2 {
+            return None;
+        }
+        let path = if program.is_absolute() {
+            program.to_path_buf()
+        } else {
+            self.cwd.join(program)
+        };
+        path.is_file().then_some(path)
+    }
+
     /// Convert the resolved command into a `tokio::process::Command`.
     pub fn to_command(&self) -> Command {
         let mut command = Command::new(&self.argv[0]);
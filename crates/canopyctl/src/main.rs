@@ -7,29 +7,47 @@ mod config;
 mod replay;
 /// MCP client sessions and the manager shared by the CLI and the proxy server.
 mod session;
+/// File watching and the status board for `canopyctl watch`.
+mod watch;
 
 use std::{
     fmt::Display,
     fs,
-    path::{Path, PathBuf},
+    io::{self, Write},
+    path::{self, Path, PathBuf},
     process::{Stdio, exit, id},
-    sync::Arc,
+    sync::{Arc, OnceLock},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{Context, Result, bail};
+use canopy::{
+    coverage::CoverageCatalog,
+    export::ExportFormat,
+    recording::{RECORD_ENV, input_log_path, luau_script, read_input_log},
+};
 use canopy_mcp::{
-    ApplyFixtureRequest, ScriptEvalRequest, SuiteConfig, discover_scripts, fixture_for_script,
-    json_tool_result,
+    ApplyFixtureRequest, CoverageReport, FuzzConfig, ReportFormat, ScriptEvalOutcome,
+    ScriptEvalRequest, ScriptQueue, ScriptResult, ScriptStatus, ScriptTiming, SuiteConfig,
+    SuiteResult, discover_scripts, fixture_for_script, json_tool_result, resources::SCREEN_URI,
+    snapshot_dir_for_script,
 };
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde_json::Value as JsonValue;
 use tmcp::{ToolError, ToolResult, mcp_server, schema::CallToolResult, tool_params};
-use tokio::{net::UnixStream, sync::Mutex, time::sleep};
+use tokio::{
+    net::UnixStream,
+    signal,
+    sync::{Mutex, mpsc},
+    task::JoinSet,
+    time::sleep,
+};
 
 use crate::{
-    config::LoadedConfig,
+    config::{LoadedConfig, ResolvedCommand},
     replay::{load_replay_journal, replay_entry_from_eval, write_replay_journal},
-    session::{Session, SessionManager},
+    session::{Observer, Session, SessionManager},
+    watch::{Rerun, StatusBoard, Watcher, classify},
 };
 
 /// Top-level CLI arguments.
@@ -62,6 +80,16 @@ enum Commands {
     /// Print the rendered `.d.luau` API from a headless app instance.
     #[command(alias = "script-api")]
     Api(SpawnArgs),
+    /// Export the rendered screen of a headless or live app as SVG, HTML or ANSI text.
+    Screenshot(ScreenshotArgs),
+    /// Convert a recorded input log into a Luau smoke script.
+    Transcribe(TranscribeArgs),
+    /// Drive a headless app with random input and shrink the first failure to a Luau repro.
+    Fuzz(FuzzArgs),
+    /// Rerun smoke scripts on a warm headless app whenever scripts or app files change.
+    Watch(WatchArgs),
+    /// Follow the screen of a live app as a read-only observer.
+    Observe(ObserveArgs),
 }
 
 /// Arguments for `canopyctl run`.
@@ -70,6 +98,51 @@ struct RunArgs {
     /// Optional fixture to apply after the live UDS server is ready.
     #[arg(long)]
     fixture: Option<String>,
+    /// Record the session to an asciicast file, with an input log beside it.
+    #[arg(long)]
+    record: Option<PathBuf>,
+    /// Command override passed after `--`.
+    #[arg(last = true)]
+    command: Vec<String>,
+}
+
+/// Arguments for `canopyctl transcribe`.
+#[derive(Args)]
+struct TranscribeArgs {
+    /// Input log, or the asciicast file it was recorded beside.
+    recording: PathBuf,
+    /// Write the script to a file instead of stdout.
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+    /// Minimum pause in milliseconds before an input that earns a screen checkpoint.
+    #[arg(long, default_value_t = 500)]
+    pause_ms: u64,
+}
+
+/// Arguments for `canopyctl fuzz`.
+#[derive(Args)]
+struct FuzzArgs {
+    /// Seed for the run; a clock-derived seed is used and printed when omitted.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Number of input sequences to run, each against a fresh app instance.
+    #[arg(long)]
+    iterations: Option<usize>,
+    /// Number of actions in each input sequence.
+    #[arg(long)]
+    steps: Option<usize>,
+    /// Optional fixture to apply before each sequence.
+    #[arg(long)]
+    fixture: Option<String>,
+    /// Command id the fuzzer must never dispatch; may be repeated.
+    #[arg(long = "exclude")]
+    exclude_commands: Vec<String>,
+    /// Optional per-action timeout override in milliseconds.
+    #[arg(long)]
+    step_timeout_ms: Option<u64>,
+    /// Write the Luau repro of a failure to a file instead of stdout.
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
     /// Command override passed after `--`.
     #[arg(last = true)]
     command: Vec<String>,
@@ -97,6 +170,58 @@ struct SmokeArgs {
     /// Optional per-script timeout override in milliseconds.
     #[arg(long)]
     timeout_ms: Option<u64>,
+    /// Record missing snapshots and rewrite changed ones instead of failing.
+    #[arg(long)]
+    update_snapshots: bool,
+    /// Run only scripts whose fixture or suite-relative path matches; may be repeated.
+    #[arg(long = "filter")]
+    filters: Vec<String>,
+    /// Number of headless app processes evaluating scripts at once.
+    #[arg(short = 'j', long)]
+    jobs: Option<usize>,
+    /// Write a JUnit XML report to a path.
+    #[arg(long)]
+    junit: Option<PathBuf>,
+    /// Write a TAP report to a path.
+    #[arg(long)]
+    tap: Option<PathBuf>,
+    /// Write a JSON lines report, one record per script, to a path.
+    #[arg(long)]
+    jsonl: Option<PathBuf>,
+    /// Print which registered commands and bindings the suite never exercised.
+    #[arg(long)]
+    coverage: bool,
+    /// Write the coverage report as JSON to a path.
+    #[arg(long)]
+    coverage_json: Option<PathBuf>,
+    /// Command override passed after `--`.
+    #[arg(last = true)]
+    command: Vec<String>,
+}
+
+/// Arguments for `canopyctl watch`.
+#[derive(Args)]
+struct WatchArgs {
+    /// Optional explicit script paths relative to the suite root.
+    scripts: Vec<PathBuf>,
+    /// Optional suite directory override.
+    #[arg(long)]
+    suite: Option<PathBuf>,
+    /// Run only scripts whose fixture or suite-relative path matches; may be repeated.
+    #[arg(long = "filter")]
+    filters: Vec<String>,
+    /// App source or binary path whose changes rerun every script; may be repeated.
+    #[arg(long = "path")]
+    paths: Vec<PathBuf>,
+    /// Poll for changes instead of using native file events.
+    #[arg(long)]
+    poll: bool,
+    /// Polling interval override in milliseconds.
+    #[arg(long)]
+    poll_ms: Option<u64>,
+    /// Optional per-script timeout override in milliseconds.
+    #[arg(long)]
+    timeout_ms: Option<u64>,
     /// Command override passed after `--`.
     #[arg(last = true)]
     command: Vec<String>,
@@ -146,6 +271,57 @@ struct EvalArgs {
     command: Vec<String>,
 }
 
+/// Arguments for `canopyctl screenshot`.
+#[derive(Args)]
+struct ScreenshotArgs {
+    /// Output format.
+    #[arg(long, value_enum, ignore_case = true, default_value_t = ScreenshotFormat::Svg)]
+    format: ScreenshotFormat,
+    /// Write the export to a file instead of stdout.
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+    /// Capture a live app over its Unix-domain socket instead of spawning a headless one.
+    #[arg(long)]
+    socket: Option<PathBuf>,
+    /// Optional fixture to apply before capturing.
+    #[arg(long)]
+    fixture: Option<String>,
+    /// Optional Luau source to run before capturing.
+    #[arg(long)]
+    script: Option<String>,
+    /// Command override passed after `--`.
+    #[arg(last = true)]
+    command: Vec<String>,
+}
+
+/// Screen export formats accepted by `canopyctl screenshot`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ScreenshotFormat {
+    /// A standalone SVG image.
+    Svg,
+    /// A standalone HTML document.
+    Html,
+    /// Text with ANSI escape sequences.
+    Ansi,
+}
+
+impl From<ScreenshotFormat> for ExportFormat {
+    fn from(format: ScreenshotFormat) -> Self {
+        match format {
+            ScreenshotFormat::Svg => Self::Svg,
+            ScreenshotFormat::Html => Self::Html,
+            ScreenshotFormat::Ansi => Self::Ansi,
+        }
+    }
+}
+
+/// Arguments for `canopyctl observe`.
+#[derive(Args)]
+struct ObserveArgs {
+    /// Unix-domain socket of the live app.
+    socket: PathBuf,
+}
+
 /// MCP proxy server implementation for `canopyctl mcp`.
 #[derive(Clone)]
 struct CanopyctlMcpServer {
@@ -161,6 +337,8 @@ struct CanopyctlMcpServer {
 struct ConnectRequest {
     /// Unix-domain socket path to connect to.
     socket: String,
+    /// Join as a read-only observer, leaving the controller role to another client.
+    observe: Option<bool>,
 }
 
 #[mcp_server]
@@ -175,7 +353,10 @@ impl CanopyctlMcpServer {
     async fn connect(&self, params: ConnectRequest) -> ToolResult<CallToolResult> {
         self.touch().await;
         self.sessions
-            .connect_live(Path::new(&params.socket))
+            .connect_live(
+                Path::new(&params.socket),
+                params.observe.unwrap_or_default(),
+            )
             .await
             .map_err(tool_error)?;
         Ok(CallToolResult::new().with_text_content("connected"))
@@ -250,6 +431,11 @@ async fn main() -> Result<()> {
         Commands::Fixtures(args) => fixtures_command(config, args).await,
         Commands::Eval(args) => eval_command(config, args).await,
         Commands::Api(args) => api_command(config, args).await,
+        Commands::Screenshot(args) => screenshot_command(config, args).await,
+        Commands::Transcribe(args) => transcribe_command(&args),
+        Commands::Fuzz(args) => fuzz_command(config, args).await,
+        Commands::Watch(args) => watch_command(config, args).await,
+        Commands::Observe(args) => observe_command(args).await,
     }
 }
 
@@ -262,6 +448,11 @@ async fn run_command(config: LoadedConfig, args: RunArgs) -> Result<()> {
     command.stdin(Stdio::inherit());
     command.stdout(Stdio::inherit());
     command.stderr(Stdio::inherit());
+    if let Some(record) = &args.record {
+        let record =
+            path::absolute(record).with_context(|| format!("resolve {}", record.display()))?;
+        command.env(RECORD_ENV, record);
+    }
 
     eprintln!("{}", socket_path.display());
     let mut child = command.spawn().context("spawn interactive app")?;
@@ -279,51 +470,287 @@ async fn run_command(config: LoadedConfig, args: RunArgs) -> Result<()> {
     Ok(())
 }
 
+/// Scripts and settings shared by the workers of one smoke run.
+struct SmokeRun {
+    /// Suite root used to derive fixtures and test names.
+    suite_dir: PathBuf,
+    /// Scripts left to run, in discovery order.
+    queue: ScriptQueue,
+    /// Per-script timeout in milliseconds.
+    timeout_ms: Option<u64>,
+    /// Whether to record and rewrite snapshots instead of failing.
+    update_snapshots: bool,
+    /// Whether scripts record the commands and bindings they exercise.
+    coverage: bool,
+    /// Registered commands and bindings, fetched by the first worker when recording coverage.
+    catalog: OnceLock<CoverageCatalog>,
+}
+
 /// Execute `canopyctl smoke`.
+///
+/// Scripts are handed out to a pool of headless app processes, one script at a time. Results
+/// print as they finish; reports list them in discovery order.
 async fn smoke_command(config: LoadedConfig, args: SmokeArgs) -> Result<()> {
     let command = config.headless_command(&args.command)?;
-    let session = Session::spawn_headless(&command).await?;
     let suite_dir = config.smoke_suite_dir(args.suite.as_deref());
     let mut suite = SuiteConfig::new(&suite_dir);
     suite.scripts = args.scripts;
+    suite.filters = args.filters;
     let scripts = discover_scripts(&suite)?;
-    let timeout_ms = config.smoke_timeout_ms(args.timeout_ms);
-    let fail_fast = config.smoke_fail_fast(args.fail_fast);
+    let jobs = config.smoke_jobs(args.jobs).min(scripts.len());
+    let run = Arc::new(SmokeRun {
+        suite_dir: suite_dir.clone(),
+        queue: ScriptQueue::new(scripts, config.smoke_fail_fast(args.fail_fast)),
+        timeout_ms: config.smoke_timeout_ms(args.timeout_ms),
+        update_snapshots: args.update_snapshots,
+        coverage: args.coverage || args.coverage_json.is_some(),
+        catalog: OnceLock::new(),
+    });
 
-    let mut failed = 0usize;
-    for path in scripts {
+    let (result_tx, mut result_rx) = mpsc::unbounded_channel();
+    let mut workers = JoinSet::new();
+    for _ in 0..jobs {
+        workers.spawn(smoke_worker(
+            command.clone(),
+            Arc::clone(&run),
+            result_tx.clone(),
+        ));
+    }
+    drop(result_tx);
+
+    let mut slots = vec![None; run.queue.len()];
+    while let Some((index, result)) = result_rx.recv().await {
+        print_smoke_result(&suite_dir, &result);
+        slots[index] = Some(result);
+    }
+    while let Some(joined) = workers.join_next().await {
+        joined.context("smoke worker panicked")??;
+    }
+
+    let result = SuiteResult {
+        scripts: slots.into_iter().flatten().collect(),
+    };
+    let reports = [
+        (args.junit, ReportFormat::Junit),
+        (args.tap, ReportFormat::Tap),
+        (args.jsonl, ReportFormat::Jsonl),
+    ];
+    for (path, format) in reports {
+        if let Some(path) = path {
+            format
+                .write(&path, &suite_dir, &result)
+                .with_context(|| format!("write {}", path.display()))?;
+        }
+    }
+    if let Some(catalog) = run.catalog.get() {
+        let report = CoverageReport::from_suite(catalog, &result);
+        if args.coverage {
+            print!("\n{}", report.render_table());
+        }
+        if let Some(path) = &args.coverage_json {
+            report
+                .write_json(path)
+                .with_context(|| format!("write {}", path.display()))?;
+        }
+    }
+
+    let failed = result
+        .scripts
+        .iter()
+        .filter(|script| script.status == ScriptStatus::Failed)
+        .count();
+    if failed > 0 {
+        bail!("{failed} smoke script(s) failed");
+    }
+    Ok(())
+}
+
+/// Evaluate smoke scripts on one headless app process until the queue is drained.
+async fn smoke_worker(
+    command: ResolvedCommand,
+    run: Arc<SmokeRun>,
+    results: mpsc::UnboundedSender<(usize, ScriptResult)>,
+) -> Result<()> {
+    let session = Session::spawn_headless(&command).await?;
+    let outcome = smoke_worker_loop(&session, &run, &results).await;
+    if outcome.is_err() {
+        run.queue.stop();
+    }
+    session.shutdown().await;
+    outcome
+}
+
+/// Take scripts from the shared queue and send back each result.
+async fn smoke_worker_loop(
+    session: &Session,
+    run: &SmokeRun,
+    results: &mpsc::UnboundedSender<(usize, ScriptResult)>,
+) -> Result<()> {
+    if run.coverage && run.catalog.get().is_none() {
+        let catalog = session.coverage_catalog().await?;
+        run.catalog.get_or_init(|| catalog);
+    }
+    while let Some((index, path)) = run.queue.next_script() {
         let source =
-            fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
-        let script_fixture = fixture_for_script(&suite_dir, &path);
+            fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        let fixture = fixture_for_script(&run.suite_dir, path);
         let started = Instant::now();
         let outcome = session
             .eval(ScriptEvalRequest {
                 script: source,
-                fixture: script_fixture.clone(),
-                timeout_ms,
+                fixture: fixture.clone(),
+                timeout_ms: run.timeout_ms,
+                snapshot_dir: Some(snapshot_dir_for_script(path).to_string_lossy().to_string()),
+                update_snapshots: run.update_snapshots.then_some(true),
+                coverage: run.coverage.then_some(true),
             })
             .await?;
-        let elapsed = started.elapsed().as_millis();
-        let fixture = script_fixture.as_deref().unwrap_or("-");
-        let test_name = smoke_test_name(&suite_dir, &path);
-        if outcome.success {
-            println!("PASS fixture={fixture} test={test_name} ({elapsed}ms)");
-        } else {
-            failed += 1;
-            println!("FAIL fixture={fixture} test={test_name} ({elapsed}ms)");
-            if let Some(error) = outcome.error {
-                println!("  {}", error.message);
+        let mut result = ScriptResult::from_outcome(path.to_path_buf(), fixture, outcome);
+        result.elapsed_ms = started.elapsed().as_millis() as u64;
+        run.queue.finish(&result);
+        if results.send((index, result)).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Print the PASS or FAIL line for one smoke script.
+fn print_smoke_result(suite_dir: &Path, result: &ScriptResult) {
+    let fixture = result.fixture.as_deref().unwrap_or("-");
+    let test_name = smoke_test_name(suite_dir, &result.path);
+    let elapsed = result.elapsed_ms;
+    if result.status == ScriptStatus::Passed {
+        println!("PASS fixture={fixture} test={test_name} ({elapsed}ms)");
+    } else {
+        println!("FAIL fixture={fixture} test={test_name} ({elapsed}ms)");
+        if let Some(message) = &result.message {
+            println!("  {message}");
+        }
+    }
+}
+
+/// Execute `canopyctl watch`.
+///
+/// Scripts run one at a time on a single warm headless session. A change to an app source or
+/// binary restarts the session and reruns every script; a script change reruns that script.
+async fn watch_command(config: LoadedConfig, args: WatchArgs) -> Result<()> {
+    let command = config.headless_command(&args.command)?;
+    let suite_dir = config.smoke_suite_dir(args.suite.as_deref());
+    let mut suite = SuiteConfig::new(&suite_dir);
+    suite.scripts = args.scripts;
+    suite.filters = args.filters;
+    suite.timeout_ms = config.smoke_timeout_ms(args.timeout_ms);
+    let mut roots = vec![suite_dir.clone()];
+    roots.extend(config.watch_paths(&args.paths, &command));
+    let interval = config.watch_poll_interval(args.poll_ms);
+    let watcher = if args.poll {
+        Watcher::polling(roots, interval)
+    } else {
+        Watcher::native(roots, interval)
+    };
+    let sessions = SessionManager::new(config).with_headless_command(args.command);
+
+    let outcome = tokio::select! {
+        outcome = watch_loop(&sessions, &suite, watcher) => outcome,
+        signal = signal::ctrl_c() => signal.context("listen for Ctrl-C"),
+    };
+    sessions.disconnect().await?;
+    outcome
+}
+
+/// Run the suite, then rerun whatever each batch of changes affects, until interrupted.
+async fn watch_loop(
+    sessions: &SessionManager,
+    suite: &SuiteConfig,
+    mut watcher: Watcher,
+) -> Result<()> {
+    let suite_dir = &suite.suite_dir;
+    let mut board = StatusBoard::default();
+    let mut rerun = Rerun::All;
+    board.set_note(watcher.describe());
+    loop {
+        match discover_scripts(suite) {
+            Ok(scripts) => {
+                for path in board.queue(scripts, &rerun) {
+                    board.draw(suite_dir);
+                    board.record(watch_script(sessions, suite, path).await);
+                }
             }
-            if fail_fast {
-                break;
+            Err(error) => {
+                board.clear();
+                board.set_note(error.to_string());
             }
         }
-    }
+        board.draw(suite_dir);
 
-    if failed > 0 {
-        bail!("{failed} smoke script(s) failed");
+        let changed = loop {
+            let changed = watcher.next_changes().await;
+            if let Some(next) = classify(suite_dir, &changed) {
+                rerun = next;
+                break changed;
+            }
+        };
+        if rerun == Rerun::All {
+            sessions.disconnect().await?;
+        }
+        let names: Vec<_> = changed
+            .iter()
+            .map(|path| {
+                path.strip_prefix(suite_dir)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        board.set_note(format!("changed: {}", names.join(", ")));
     }
-    Ok(())
+}
+
+/// Evaluate one script on the warm session.
+///
+/// A session failure is reported as the script's result and drops the session, so the next
+/// script starts a fresh one.
+async fn watch_script(
+    sessions: &SessionManager,
+    suite: &SuiteConfig,
+    path: PathBuf,
+) -> ScriptResult {
+    let fixture = fixture_for_script(&suite.suite_dir, &path);
+    let started = Instant::now();
+    let outcome = match fs::read_to_string(&path) {
+        Ok(source) => {
+            let request = ScriptEvalRequest {
+                script: source,
+                fixture: fixture.clone(),
+                timeout_ms: suite.timeout_ms,
+                snapshot_dir: Some(snapshot_dir_for_script(&path).to_string_lossy().to_string()),
+                update_snapshots: None,
+                coverage: None,
+            };
+            match sessions.eval(request).await {
+                Ok(outcome) => outcome,
+                Err(error) => {
+                    let _ignored = sessions.disconnect().await;
+                    ScriptEvalOutcome::error_only(
+                        "session",
+                        format!("{error:#}"),
+                        Vec::new(),
+                        ScriptTiming::zero(),
+                    )
+                }
+            }
+        }
+        Err(error) => ScriptEvalOutcome::error_only(
+            "io",
+            format!("read {}: {error}", path.display()),
+            Vec::new(),
+            ScriptTiming::zero(),
+        ),
+    };
+    let mut result = ScriptResult::from_outcome(path, fixture, outcome);
+    result.elapsed_ms = started.elapsed().as_millis() as u64;
+    result
 }
 
 /// Execute `canopyctl replay`.
@@ -350,6 +777,9 @@ async fn replay_command(config: LoadedConfig, args: ReplayArgs) -> Result<()> {
                 script: script.to_string(),
                 fixture: args.fixture.clone(),
                 timeout_ms: args.timeout_ms,
+                snapshot_dir: None,
+                update_snapshots: None,
+                coverage: None,
             })
             .await?;
         if outcome.success {
@@ -422,6 +852,9 @@ async fn eval_command(config: LoadedConfig, args: EvalArgs) -> Result<()> {
             script: script.clone(),
             fixture: args.fixture,
             timeout_ms: args.timeout_ms,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
         })
         .await?;
     println!("{}", serde_json::to_string_pretty(&outcome)?);
@@ -442,6 +875,150 @@ async fn api_command(config: LoadedConfig, args: SpawnArgs) -> Result<()> {
     Ok(())
 }
 
+/// Execute `canopyctl screenshot`.
+async fn screenshot_command(config: LoadedConfig, args: ScreenshotArgs) -> Result<()> {
+    let format = ExportFormat::from(args.format);
+    let session = match &args.socket {
+        Some(socket) => Session::connect_live(socket).await?,
+        None => Session::spawn_headless(&config.headless_command(&args.command)?).await?,
+    };
+    let outcome = session
+        .eval(ScriptEvalRequest {
+            script: screenshot_script(args.script.as_deref(), format),
+            fixture: args.fixture,
+            timeout_ms: None,
+            snapshot_dir: None,
+            update_snapshots: None,
+            coverage: None,
+        })
+        .await?;
+    session.shutdown().await;
+    let Some(JsonValue::String(output)) = outcome.value else {
+        match outcome.error {
+            Some(error) => bail!("screenshot failed: {}", error.message),
+            None => bail!("screenshot failed: export returned no text"),
+        }
+    };
+    match args.output {
+        Some(path) => fs::write(&path, output).with_context(|| format!("write {}", path.display())),
+        None => {
+            print!("{output}");
+            Ok(())
+        }
+    }
+}
+
+/// Execute `canopyctl observe`.
+///
+/// Joins the live session as an observer, so another client keeps the controller role, and
+/// redraws the terminal each time the app reports a new screen, until interrupted.
+async fn observe_command(args: ObserveArgs) -> Result<()> {
+    let mut observer = Observer::connect(&args.socket).await?;
+    tokio::select! {
+        outcome = observe_loop(&mut observer) => outcome,
+        signal = signal::ctrl_c() => signal.context("listen for Ctrl-C"),
+    }
+}
+
+/// Follow the live screen resource and print each new frame.
+async fn observe_loop(observer: &mut Observer) -> Result<()> {
+    observer.subscribe(SCREEN_URI).await?;
+    loop {
+        let screen = observer.read(SCREEN_URI).await?;
+        let mut stdout = io::stdout().lock();
+        let _ignored = write!(stdout, "\x1b[2J\x1b[H{screen}");
+        let _ignored = stdout.flush();
+        drop(stdout);
+        if !observer.changed(SCREEN_URI).await {
+            bail!("live session closed");
+        }
+    }
+}
+
+/// Execute `canopyctl transcribe`.
+fn transcribe_command(args: &TranscribeArgs) -> Result<()> {
+    let log = if args.recording.extension().is_some_and(|ext| ext == "cast") {
+        input_log_path(&args.recording)
+    } else {
+        args.recording.clone()
+    };
+    let records = read_input_log(&log)?;
+    let script = luau_script(&records, Duration::from_millis(args.pause_ms));
+    match &args.output {
+        Some(path) => fs::write(path, script).with_context(|| format!("write {}", path.display())),
+        None => {
+            print!("{script}");
+            Ok(())
+        }
+    }
+}
+
+/// Execute `canopyctl fuzz`.
+async fn fuzz_command(config: LoadedConfig, args: FuzzArgs) -> Result<()> {
+    let seed = match args.seed {
+        Some(seed) => seed,
+        None => clock_seed()?,
+    };
+    let mut fuzz = FuzzConfig::new(seed);
+    fuzz.iterations = args.iterations.unwrap_or(fuzz.iterations);
+    fuzz.steps = args.steps.unwrap_or(fuzz.steps);
+    fuzz.step_timeout_ms = args.step_timeout_ms.unwrap_or(fuzz.step_timeout_ms);
+    fuzz.fixture = args.fixture;
+    fuzz.exclude_commands = args.exclude_commands;
+
+    println!("fuzzing with seed {seed}");
+    let session = Session::spawn_headless(&config.headless_command(&args.command)?).await?;
+    let report = session.fuzz(fuzz).await;
+    session.shutdown().await;
+    let report = report?;
+    let Some(failure) = report.failure else {
+        println!(
+            "PASS {} iterations, {} actions",
+            report.iterations, report.actions
+        );
+        return Ok(());
+    };
+    println!(
+        "FAIL iteration {} {}: {}",
+        failure.iteration,
+        failure.kind.as_str(),
+        failure.message
+    );
+    println!(
+        "  shrunk {} actions to {}",
+        failure.original_steps,
+        failure.actions.len()
+    );
+    match &args.output {
+        Some(path) => {
+            fs::write(path, &failure.repro).with_context(|| format!("write {}", path.display()))?;
+            println!("  repro written to {}", path.display());
+        }
+        None => print!("{}", failure.repro),
+    }
+    bail!("fuzzing failed with seed {seed}");
+}
+
+/// Derive a fuzzing seed from the system clock.
+fn clock_seed() -> Result<u64> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system clock before unix epoch")?
+        .as_nanos();
+    Ok(nanos as u64)
+}
+
+/// Build the Luau source that runs an optional setup script and exports the screen.
+///
+/// The setup script runs in its own block so that a trailing `return` stays valid.
+fn screenshot_script(setup: Option<&str>, format: ExportFormat) -> String {
+    let export = format!("return canopy.export_screen(\"{format}\")");
+    match setup {
+        Some(setup) => format!("do\n{setup}\nend\n{export}"),
+        None => export,
+    }
+}
+
 /// Execute `canopyctl mcp`.
 async fn mcp_command(config: LoadedConfig) -> Result<()> {
     let sessions = Arc::new(SessionManager::new(config.clone()));
@@ -510,6 +1087,18 @@ mod tests {
     use super::*;
     use crate::replay::ReplayInput;
 
+    #[test]
+    fn screenshot_script_wraps_setup_in_a_block() {
+        assert_eq!(
+            screenshot_script(None, ExportFormat::Html),
+            r#"return canopy.export_screen("html")"#
+        );
+        assert_eq!(
+            screenshot_script(Some("canopy.send_key(\"j\")\nreturn"), ExportFormat::Svg),
+            "do\ncanopy.send_key(\"j\")\nreturn\nend\nreturn canopy.export_screen(\"svg\")"
+        );
+    }
+
     #[test]
     fn replay_input_accepts_object_journal() -> Result<()> {
         let parsed = serde_json::from_str::<ReplayInput>(
@@ -3,13 +3,20 @@
 use std::path::Path;
 
 use anyhow::{Context, Result, anyhow};
-use canopy::FixtureInfo;
-use canopy_mcp::{ApplyFixtureRequest, BootstrapResponse, ScriptEvalOutcome, ScriptEvalRequest};
-use tmcp::Client;
+use async_trait::async_trait;
+use canopy::{FixtureInfo, coverage::CoverageCatalog};
+use canopy_mcp::{
+    ApplyFixtureRequest, BootstrapResponse, FuzzConfig, FuzzReport, ScriptEvalOutcome,
+    ScriptEvalRequest, SessionRole,
+};
+use tmcp::{
+    Client, ClientCtx, ClientHandler,
+    schema::{ResourceContents, ServerNotification},
+};
 use tokio::{
     net::UnixStream,
     process::Child,
-    sync::{MappedMutexGuard, Mutex, MutexGuard},
+    sync::{MappedMutexGuard, Mutex, MutexGuard, mpsc},
 };
 
 use crate::config::{LoadedConfig, ResolvedCommand};
@@ -102,11 +109,32 @@ impl Session {
         Ok(self.client.call_tool_structured("bootstrap", ()).await?)
     }
 
+    /// Run a fuzzing campaign inside the headless app process.
+    pub async fn fuzz(&self, config: FuzzConfig) -> Result<FuzzReport> {
+        Ok(self.client.call_tool_structured("fuzz", config).await?)
+    }
+
     /// Request the fixture catalog.
     pub async fn fixtures(&self) -> Result<Vec<FixtureInfo>> {
         Ok(self.client.call_tool_structured("fixtures", ()).await?)
     }
 
+    /// Request every command and binding the app registers.
+    pub async fn coverage_catalog(&self) -> Result<CoverageCatalog> {
+        Ok(self
+            .client
+            .call_tool_structured("coverage_catalog", ())
+            .await?)
+    }
+
+    /// Give up the controller role of a live session, leaving this client a read-only observer.
+    pub async fn release_control(&self) -> Result<SessionRole> {
+        Ok(self
+            .client
+            .call_tool_structured("release_control", ())
+            .await?)
+    }
+
     /// Apply or remember a fixture for the session.
     pub async fn apply_fixture(&mut self, name: String) -> Result<()> {
         match self.kind {
@@ -124,10 +152,92 @@ impl Session {
     }
 }
 
+/// Read-only client of a live app that follows its resources instead of evaluating scripts.
+///
+/// Nothing it does reaches the app's script journal, so watching a session leaves the
+/// controller's eval history intact.
+pub struct Observer {
+    /// Connected MCP client.
+    client: Client<ResourceUpdates>,
+    /// URIs of subscribed resources the app reported as changed.
+    updates: mpsc::UnboundedReceiver<String>,
+}
+
+impl Observer {
+    /// Connect to a live UDS MCP server and give up the controller role.
+    pub async fn connect(socket: &Path) -> Result<Self> {
+        let stream = UnixStream::connect(socket)
+            .await
+            .with_context(|| format!("connect to {}", socket.display()))?;
+        let (reader, writer) = stream.into_split();
+        let (updates_tx, updates) = mpsc::unbounded_channel();
+        let mut client =
+            Client::with_handler(CLIENT_NAME, CLIENT_VERSION, ResourceUpdates(updates_tx));
+        client.connect_stream(reader, writer).await?;
+        let _role: SessionRole = client.call_tool_structured("release_control", ()).await?;
+        Ok(Self { client, updates })
+    }
+
+    /// Ask the app to report changes to a resource.
+    pub async fn subscribe(&self, uri: &str) -> Result<()> {
+        Ok(self.client.subscribe_resource(uri).await?)
+    }
+
+    /// Read the text of a resource.
+    pub async fn read(&self, uri: &str) -> Result<String> {
+        let result = self.client.read_resource(uri).await?;
+        result
+            .contents
+            .into_iter()
+            .find_map(|contents| match contents {
+                ResourceContents::Text(text) => Some(text.text),
+                ResourceContents::Blob(_) => None,
+            })
+            .ok_or_else(|| anyhow!("{uri} returned no text"))
+    }
+
+    /// Wait until the app reports a change to `uri`, folding in any reports already queued.
+    ///
+    /// Returns false once the connection closes.
+    pub async fn changed(&mut self, uri: &str) -> bool {
+        loop {
+            let Some(updated) = self.updates.recv().await else {
+                return false;
+            };
+            let mut hit = updated == uri;
+            while let Ok(next) = self.updates.try_recv() {
+                hit |= next == uri;
+            }
+            if hit {
+                return true;
+            }
+        }
+    }
+}
+
+/// Client handler that forwards resource-updated notifications to an [`Observer`].
+struct ResourceUpdates(mpsc::UnboundedSender<String>);
+
+#[async_trait]
+impl ClientHandler for ResourceUpdates {
+    async fn notification(
+        &self,
+        _context: &ClientCtx,
+        notification: ServerNotification,
+    ) -> tmcp::Result<()> {
+        if let ServerNotification::ResourceUpdated { uri } = notification {
+            let _receiver_closed = self.0.send(uri);
+        }
+        Ok(())
+    }
+}
+
 /// Shared session manager used by the CLI and proxy MCP server.
 pub struct SessionManager {
     /// Loaded CLI configuration.
     config: LoadedConfig,
+    /// Headless command override; empty uses `[app].headless`.
+    headless_override: Vec<String>,
     /// Current session, if any.
     state: Mutex<Option<Session>>,
 }
@@ -137,17 +247,29 @@ impl SessionManager {
     pub fn new(config: LoadedConfig) -> Self {
         Self {
             config,
+            headless_override: Vec::new(),
             state: Mutex::new(None),
         }
     }
 
+    /// Spawn headless sessions from a command override instead of `[app].headless`.
+    pub fn with_headless_command(mut self, command: Vec<String>) -> Self {
+        self.headless_override = command;
+        self
+    }
+
     /// Connect to a live UDS session, replacing any existing session.
-    pub async fn connect_live(&self, socket: &Path) -> Result<()> {
+    ///
+    /// An observer gives up the controller role straight away, so its scripts stay read-only.
+    pub async fn connect_live(&self, socket: &Path, observe: bool) -> Result<()> {
         let previous = self.take_session().await;
         if let Some(previous) = previous {
             previous.shutdown().await;
         }
         let session = Session::connect_live(socket).await?;
+        if observe {
+            session.release_control().await?;
+        }
         *self.state.lock().await = Some(session);
         Ok(())
     }
@@ -189,7 +311,7 @@ impl SessionManager {
     async fn session(&self) -> Result<MappedMutexGuard<'_, Session>> {
         let mut state = self.state.lock().await;
         if state.is_none() {
-            let command = self.config.headless_command(&[])?;
+            let command = self.config.headless_command(&self.headless_override)?;
             *state = Some(Session::spawn_headless(&command).await?);
         }
         Ok(MutexGuard::map(state, |state| {
@@ -0,0 +1,430 @@
+//! File watching and the status board for `canopyctl watch`.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, IsTerminal, Write},
+    mem,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use canopy_mcp::{ScriptResult, ScriptStatus, script_relative_name};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as _, recommended_watcher};
+use tokio::{
+    sync::mpsc,
+    time::{sleep, timeout},
+};
+
+/// Delay that lets a burst of native file events settle before rescanning.
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// Size and modification time of one watched file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    /// Last modification time, when the platform reports one.
+    modified: Option<SystemTime>,
+    /// File length in bytes.
+    len: u64,
+}
+
+/// Every file under a set of watched roots, with its stamp.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, FileStamp>);
+
+impl Snapshot {
+    /// Scan roots recursively. Missing roots are skipped, so they are picked up once created.
+    /// Hidden entries and `target` directories below a root are not scanned.
+    pub fn scan(roots: &[PathBuf]) -> Self {
+        let mut files = BTreeMap::new();
+        for root in roots {
+            scan_path(root, &mut files);
+        }
+        Self(files)
+    }
+
+    /// Return the paths added, removed or modified between this snapshot and `next`, sorted.
+    pub fn changed_paths(&self, next: &Self) -> Vec<PathBuf> {
+        let mut changed: Vec<_> = next
+            .0
+            .iter()
+            .filter(|(path, stamp)| self.0.get(*path) != Some(*stamp))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.0
+                    .keys()
+                    .filter(|path| !next.0.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        changed
+    }
+}
+
+/// Record a file, or every file below a directory.
+fn scan_path(path: &Path, files: &mut BTreeMap<PathBuf, FileStamp>) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if !metadata.is_dir() {
+        files.insert(
+            path.to_path_buf(),
+            FileStamp {
+                modified: metadata.modified().ok(),
+                len: metadata.len(),
+            },
+        );
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !name.starts_with('.') && name != "target" {
+            scan_path(&entry.path(), files);
+        }
+    }
+}
+
+/// Reports file changes under a set of roots.
+///
+/// Changes are found by comparing snapshots, so polling alone is enough. Native file events,
+/// when available, only wake the watcher before the next poll.
+pub struct Watcher {
+    /// Watched files and directories.
+    roots: Vec<PathBuf>,
+    /// Files as of the last reported change.
+    snapshot: Snapshot,
+    /// Time between polls.
+    interval: Duration,
+    /// Native watcher, kept alive for its events, and the channel the events arrive on.
+    native: Option<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)>,
+}
+
+impl Watcher {
+    /// Watch roots by polling only.
+    pub fn polling(roots: Vec<PathBuf>, interval: Duration) -> Self {
+        let snapshot = Snapshot::scan(&roots);
+        Self {
+            roots,
+            snapshot,
+            interval,
+            native: None,
+        }
+    }
+
+    /// Watch roots with native file events, falling back to polling when they are
+    /// unavailable.
+    pub fn native(roots: Vec<PathBuf>, interval: Duration) -> Self {
+        let mut watcher = Self::polling(roots, interval);
+        watcher.native = native_events(&watcher.roots).ok();
+        watcher
+    }
+
+    /// Describe how changes are detected, for the status board.
+    pub fn describe(&self) -> String {
+        let mode = if self.native.is_some() {
+            "native events"
+        } else {
+            "polling"
+        };
+        format!(
+            "watching {} path(s) with {mode}, every {}ms",
+            self.roots.len(),
+            self.interval.as_millis()
+        )
+    }
+
+    /// Wait until at least one watched file changes and return the changed paths.
+    pub async fn next_changes(&mut self) -> Vec<PathBuf> {
+        loop {
+            self.wait().await;
+            let next = Snapshot::scan(&self.roots);
+            let changed = self.snapshot.changed_paths(&next);
+            self.snapshot = next;
+            if !changed.is_empty() {
+                return changed;
+            }
+        }
+    }
+
+    /// Sleep until the next poll, or until native events arrive and settle.
+    async fn wait(&mut self) {
+        let Some((_, events)) = &mut self.native else {
+            sleep(self.interval).await;
+            return;
+        };
+        match timeout(self.interval, events.recv()).await {
+            Ok(Some(())) => {
+                sleep(SETTLE).await;
+                while events.try_recv().is_ok() {}
+            }
+            Ok(None) => self.native = None,
+            Err(_elapsed) => {}
+        }
+    }
+}
+
+/// Start a native watcher over the roots that exist, signalling a channel on every event.
+fn native_events(
+    roots: &[PathBuf],
+) -> notify::Result<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher = recommended_watcher(move |event: notify::Result<Event>| {
+        if event.is_ok() {
+            let _ignored = tx.send(());
+        }
+    })?;
+    for root in roots.iter().filter(|root| root.exists()) {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+    }
+    Ok((watcher, rx))
+}
+
+/// Scripts to rerun after a change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rerun {
+    /// The app changed: restart the session and rerun every script.
+    All,
+    /// Only these suite scripts were added, removed or modified.
+    Scripts(Vec<PathBuf>),
+}
+
+/// Decide what a set of changed paths reruns, or `None` when nothing needs to.
+///
+/// Paths outside the suite directory are app sources or binaries. Inside it, only `.luau`
+/// scripts count; snapshots and other files are ignored.
+pub fn classify(suite_dir: &Path, changed: &[PathBuf]) -> Option<Rerun> {
+    if changed.iter().any(|path| !path.starts_with(suite_dir)) {
+        return Some(Rerun::All);
+    }
+    let scripts: Vec<_> = changed
+        .iter()
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "luau")
+        })
+        .cloned()
+        .collect();
+    (!scripts.is_empty()).then_some(Rerun::Scripts(scripts))
+}
+
+/// Latest result of every script in the suite, in path order.
+#[derive(Debug, Default)]
+pub struct StatusBoard {
+    /// Latest result per script; `None` while it waits to run.
+    scripts: BTreeMap<PathBuf, Option<ScriptResult>>,
+    /// Line shown under the totals, such as what triggered the last run.
+    note: String,
+}
+
+impl StatusBoard {
+    /// Replace the script list with the current suite and mark the scripts to rerun as
+    /// pending. Results are kept for unchanged scripts; new scripts always run. Returns the
+    /// scripts to run, in path order.
+    pub fn queue(&mut self, scripts: Vec<PathBuf>, rerun: &Rerun) -> Vec<PathBuf> {
+        let previous = mem::take(&mut self.scripts);
+        let mut targets = Vec::new();
+        for script in scripts {
+            let kept = match rerun {
+                Rerun::All => None,
+                Rerun::Scripts(changed) if changed.contains(&script) => None,
+                Rerun::Scripts(_) => previous.get(&script).cloned().flatten(),
+            };
+            if kept.is_none() {
+                targets.push(script.clone());
+            }
+            self.scripts.insert(script, kept);
+        }
+        targets.sort();
+        targets
+    }
+
+    /// Drop every script, as when the suite cannot be discovered.
+    pub fn clear(&mut self) {
+        self.scripts.clear();
+    }
+
+    /// Record a finished script.
+    pub fn record(&mut self, result: ScriptResult) {
+        self.scripts.insert(result.path.clone(), Some(result));
+    }
+
+    /// Set the line shown under the totals.
+    pub fn set_note(&mut self, note: impl Into<String>) {
+        self.note = note.into();
+    }
+
+    /// Render one line per script with any failure message, then the totals and note.
+    pub fn render(&self, suite_dir: &Path) -> String {
+        let mut out = String::new();
+        let (mut passed, mut failed, mut pending) = (0, 0, 0);
+        for (path, result) in &self.scripts {
+            let name = script_relative_name(suite_dir, path);
+            match result {
+                None => {
+                    pending += 1;
+                    out.push_str(&format!("....  {name}\n"));
+                }
+                Some(result) if result.status == ScriptStatus::Passed => {
+                    passed += 1;
+                    out.push_str(&format!("PASS  {name}  {}ms\n", result.elapsed_ms));
+                }
+                Some(result) => {
+                    failed += 1;
+                    out.push_str(&format!("FAIL  {name}  {}ms\n", result.elapsed_ms));
+                    if let Some(message) = &result.message {
+                        out.push_str(&format!("      {message}\n"));
+                    }
+                }
+            }
+        }
+        out.push_str(&format!(
+            "\n{passed} passed, {failed} failed, {pending} pending\n"
+        ));
+        if !self.note.is_empty() {
+            out.push_str(&self.note);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Print the board, clearing the terminal first when stdout is one.
+    pub fn draw(&self, suite_dir: &Path) {
+        let mut stdout = io::stdout().lock();
+        let clear = if stdout.is_terminal() {
+            "\x1b[2J\x1b[H"
+        } else {
+            ""
+        };
+        let _ignored = write!(stdout, "{clear}{}", self.render(suite_dir));
+        let _ignored = stdout.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use canopy_mcp::{ScriptEvalOutcome, ScriptTaskState, ScriptTiming};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn suite() -> TempDir {
+        let dir = tempfile::tempdir().expect("create test directory");
+        fs::create_dir_all(dir.path().join("with_items")).expect("create fixture directory");
+        fs::write(dir.path().join("bootstrap.luau"), "return true").expect("write script");
+        fs::write(dir.path().join("with_items/nav.luau"), "return true").expect("write script");
+        dir
+    }
+
+    fn result(path: &Path, success: bool) -> ScriptResult {
+        let outcome = if success {
+            ScriptEvalOutcome {
+                success: true,
+                state: ScriptTaskState::Completed,
+                value: None,
+                logs: Vec::new(),
+                assertions: Vec::new(),
+                diagnostics: Vec::new(),
+                timing: ScriptTiming::zero(),
+                error: None,
+                coverage: None,
+            }
+        } else {
+            ScriptEvalOutcome::error_only("runtime", "boom", Vec::new(), ScriptTiming::zero())
+        };
+        ScriptResult::from_outcome(path.to_path_buf(), None, outcome)
+    }
+
+    #[test]
+    fn snapshots_report_added_modified_and_removed_files() {
+        let dir = suite();
+        let roots = [dir.path().to_path_buf()];
+        let before = Snapshot::scan(&roots);
+        assert!(before.changed_paths(&Snapshot::scan(&roots)).is_empty());
+
+        let bootstrap = dir.path().join("bootstrap.luau");
+        let nav = dir.path().join("with_items/nav.luau");
+        let added = dir.path().join("added.luau");
+        fs::write(&bootstrap, "return false -- longer").expect("modify script");
+        fs::remove_file(&nav).expect("remove script");
+        fs::write(&added, "").expect("add script");
+        fs::create_dir_all(dir.path().join(".hidden")).expect("create hidden directory");
+        fs::write(dir.path().join(".hidden/skip.luau"), "").expect("write hidden script");
+
+        assert_eq!(
+            before.changed_paths(&Snapshot::scan(&roots)),
+            vec![added, bootstrap, nav]
+        );
+    }
+
+    #[test]
+    fn classify_reruns_scripts_or_everything() {
+        let suite_dir = Path::new("/app/smoke");
+        let script = suite_dir.join("with_items/nav.luau");
+        let snapshot = suite_dir.join("with_items/nav.snapshots/screen.txt");
+
+        assert_eq!(classify(suite_dir, &[snapshot.clone()]), None);
+        assert_eq!(
+            classify(suite_dir, &[script.clone(), snapshot]),
+            Some(Rerun::Scripts(vec![script.clone()]))
+        );
+        assert_eq!(
+            classify(suite_dir, &[script, PathBuf::from("/app/target/debug/app")]),
+            Some(Rerun::All)
+        );
+    }
+
+    #[test]
+    fn board_keeps_unchanged_results_and_runs_new_scripts() {
+        let suite_dir = Path::new("/app/smoke");
+        let first = suite_dir.join("a.luau");
+        let second = suite_dir.join("b.luau");
+        let third = suite_dir.join("c.luau");
+        let mut board = StatusBoard::default();
+
+        let targets = board.queue(vec![first.clone(), second.clone()], &Rerun::All);
+        assert_eq!(targets, vec![first.clone(), second.clone()]);
+        board.record(result(&first, true));
+        board.record(result(&second, false));
+
+        let changed = Rerun::Scripts(vec![second.clone()]);
+        let targets = board.queue(vec![first, second.clone(), third.clone()], &changed);
+        assert_eq!(targets, vec![second, third]);
+
+        board.set_note("changed: b.luau");
+        let rendered = board.render(suite_dir);
+        assert!(rendered.starts_with("PASS  a.luau  0ms\n....  b.luau\n....  c.luau\n"));
+        assert!(rendered.ends_with("1 passed, 0 failed, 2 pending\nchanged: b.luau\n"));
+    }
+
+    #[test]
+    fn board_shows_failure_messages() {
+        let suite_dir = Path::new("/app/smoke");
+        let script = suite_dir.join("with_items/nav.luau");
+        let mut board = StatusBoard::default();
+        board.queue(vec![script.clone()], &Rerun::All);
+        board.record(result(&script, false));
+        assert_eq!(
+            board.render(suite_dir),
+            "FAIL  with_items/nav.luau  0ms\n      boom\n\n0 passed, 1 failed, 0 pending\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn polling_watcher_reports_changes() {
+        let dir = suite();
+        let mut watcher =
+            Watcher::polling(vec![dir.path().to_path_buf()], Duration::from_millis(10));
+        let script = dir.path().join("bootstrap.luau");
+        fs::write(&script, "return 1 + 1").expect("modify script");
+
+        let changed = timeout(Duration::from_secs(5), watcher.next_changes())
+            .await
+            .expect("change reported");
+        assert_eq!(changed, vec![script]);
+    }
+}
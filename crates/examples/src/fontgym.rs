@@ -665,13 +665,13 @@ impl Widget for FontGymInput {
     }
 
     fn cursor(&self) -> Option<Cursor> {
-        Some(Cursor {
-            location: Point {
+        Some(Cursor::new(
+            Point {
                 x: self.cursor as u32,
                 y: 0,
             },
-            shape: CursorShape::Block,
-        })
+            CursorShape::Block,
+        ))
     }
 
     fn render(&mut self, rndr: &mut Render, ctx: &dyn ViewContext) -> Result<()> {
@@ -11,6 +11,9 @@ end)
 canopy.bind("o", { path = "image_view/", description = "Zoom out" }, function()
     image_view.zoom("Out")
 end)
+canopy.bind("m", { path = "image_view/", description = "Next glyph mode" }, function()
+    image_view.cycle_mode()
+end)
 canopy.bind("h", { path = "image_view/", description = "Pan left" }, function()
     image_view.pan("Left")
 end)
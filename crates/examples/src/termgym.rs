@@ -459,6 +459,7 @@ impl Loader for TermGym {
     fn load(c: &mut Canopy) -> Result<()> {
         c.add_commands::<Self>()?;
         c.add_commands::<List<TermEntry>>()?;
+        c.add_commands::<Terminal>()?;
         Ok(())
     }
 }